@@ -0,0 +1,253 @@
+//! Manifests of generated files for skipping regeneration and detecting stale files.
+//!
+//! An [`ArtifactManifest`] records the version of this crate, the commit of the definitions
+//! repository and a hash of every generated file:
+//!
+//! ```text
+//! VERSION 0.1.0
+//! COMMIT 8d3c6b1
+//! FILE 6057eddda3d2b0d2 Map.json
+//! FILE 9f4b0b22c3f4d1a9 Spell.json
+//! ```
+//!
+//! Hashes are 64 bit FNV-1a of the file contents.
+//! They detect changed files but are not cryptographic.
+
+use crate::error::ArtifactManifestError;
+use crate::types::{fnv1a, FNV_OFFSET_BASIS};
+use crate::writer::Writer;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Hashes of generated files together with what they were generated from.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
+pub struct ArtifactManifest {
+    /// Version of this crate the files were generated with.
+    pub version: String,
+    /// Commit of the definitions repository the files were generated from, if known.
+    pub commit: Option<String>,
+    /// Hash of the contents keyed by file name.
+    pub files: BTreeMap<String, u64>,
+}
+
+/// Differences between two [`ArtifactManifest`]s found with [`ArtifactManifest::compare`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
+pub struct ArtifactChanges {
+    /// Whether the crate version or commit differ.
+    pub source_changed: bool,
+    /// Files only in the newer manifest.
+    pub added: Vec<String>,
+    /// Files only in the older manifest.
+    pub removed: Vec<String>,
+    /// Files in both manifests with different hashes.
+    pub changed: Vec<String>,
+}
+
+impl ArtifactChanges {
+    /// Whether the manifests describe the same files generated from the same source.
+    pub fn is_unchanged(&self) -> bool {
+        !self.source_changed
+            && self.added.is_empty()
+            && self.removed.is_empty()
+            && self.changed.is_empty()
+    }
+}
+
+impl ArtifactManifest {
+    /// Creates an empty manifest for the current crate version and `commit`.
+    pub fn new(commit: Option<&str>) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            commit: commit.map(|a| a.to_string()),
+            files: BTreeMap::new(),
+        }
+    }
+
+    /// Creates a manifest for files keyed by name,
+    /// like the ones from [`DbdCollection::to_html_site`](crate::DbdCollection::to_html_site).
+    pub fn from_outputs(commit: Option<&str>, outputs: &BTreeMap<String, String>) -> Self {
+        let mut s = Self::new(commit);
+
+        for (name, contents) in outputs {
+            s.insert(name, contents);
+        }
+
+        s
+    }
+
+    /// Adds the hash of `contents` for `name`, replacing any previous hash.
+    pub fn insert(&mut self, name: impl Into<String>, contents: &str) -> Option<u64> {
+        self.files.insert(name.into(), hash(contents.as_bytes()))
+    }
+
+    /// Finds the differences from `self` to the `newer` manifest.
+    pub fn compare(&self, newer: &ArtifactManifest) -> ArtifactChanges {
+        let mut changes = ArtifactChanges {
+            source_changed: self.version != newer.version || self.commit != newer.commit,
+            ..Default::default()
+        };
+
+        for (name, hash) in &newer.files {
+            match self.files.get(name) {
+                None => changes.added.push(name.clone()),
+                Some(old) if old != hash => changes.changed.push(name.clone()),
+                Some(_) => {}
+            }
+        }
+
+        changes.removed = self
+            .files
+            .keys()
+            .filter(|a| !newer.files.contains_key(*a))
+            .cloned()
+            .collect();
+
+        changes
+    }
+
+    /// Finds the files in `dir` that are missing or do not match their hash, sorted by name.
+    ///
+    /// # Errors
+    ///
+    /// Errors if a file exists but can not be read.
+    pub fn stale_files(&self, dir: &Path) -> std::io::Result<Vec<String>> {
+        let mut stale = Vec::new();
+
+        for (name, expected) in &self.files {
+            let actual = match std::fs::read(dir.join(name)) {
+                Ok(contents) => Some(hash(&contents)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+                Err(e) => return Err(e),
+            };
+
+            if actual != Some(*expected) {
+                stale.push(name.clone());
+            }
+        }
+
+        Ok(stale)
+    }
+
+    /// Parse the format described in the [module documentation](crate::artifacts).
+    ///
+    /// # Errors
+    ///
+    /// Errors if a line is not a valid `VERSION`, `COMMIT` or `FILE` line, or if `VERSION` is missing.
+    pub fn parse(contents: &str) -> Result<Self, ArtifactManifestError> {
+        let mut version = None;
+        let mut commit = None;
+        let mut files = BTreeMap::new();
+
+        for (i, line) in contents.lines().enumerate() {
+            let err = || ArtifactManifestError::InvalidLine {
+                line: i + 1,
+                contents: line.to_string(),
+            };
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            if let Some(v) = line.strip_prefix("VERSION ") {
+                version = Some(v.trim().to_string());
+            } else if let Some(c) = line.strip_prefix("COMMIT ") {
+                commit = Some(c.trim().to_string());
+            } else if let Some(file) = line.strip_prefix("FILE ") {
+                let (hash, name) = file.split_once(' ').ok_or_else(err)?;
+                let hash = u64::from_str_radix(hash, 16).map_err(|_| err())?;
+                files.insert(name.to_string(), hash);
+            } else {
+                return Err(err());
+            }
+        }
+
+        let version = version.ok_or(ArtifactManifestError::MissingVersion)?;
+
+        Ok(Self {
+            version,
+            commit,
+            files,
+        })
+    }
+
+    /// Loads and parses a manifest written with [`ArtifactManifest::to_manifest_string`].
+    ///
+    /// # Errors
+    ///
+    /// Errors if the file can not be read.
+    /// The inner result errors in the same way as [`ArtifactManifest::parse`].
+    pub fn load(path: &Path) -> std::io::Result<Result<Self, ArtifactManifestError>> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(Self::parse(&contents))
+    }
+
+    /// Write the manifest in the format described in the [module documentation](crate::artifacts).
+    pub fn to_manifest_string(&self) -> String {
+        let mut s = Writer::new();
+
+        s.wln(format!("VERSION {}", self.version));
+        if let Some(commit) = &self.commit {
+            s.wln(format!("COMMIT {}", commit));
+        }
+        for (name, hash) in &self.files {
+            s.wln(format!("FILE {:016x} {}", hash, name));
+        }
+
+        s.inner
+    }
+}
+
+const fn hash(contents: &[u8]) -> u64 {
+    fnv1a(FNV_OFFSET_BASIS, contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::artifacts::ArtifactManifest;
+    use crate::error::ArtifactManifestError;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn artifact_manifest() {
+        let mut outputs = BTreeMap::new();
+        outputs.insert("Map.json".to_string(), "{}".to_string());
+        outputs.insert("Spell.json".to_string(), "[]".to_string());
+
+        let old = ArtifactManifest::from_outputs(Some("8d3c6b1"), &outputs);
+        let parsed = ArtifactManifest::parse(&old.to_manifest_string()).unwrap();
+        assert_eq!(parsed, old);
+        assert!(old.compare(&parsed).is_unchanged());
+
+        outputs.remove("Spell.json");
+        outputs.insert("Map.json".to_string(), "{ }".to_string());
+        outputs.insert("Item.json".to_string(), "{}".to_string());
+        let new = ArtifactManifest::from_outputs(Some("8d3c6b1"), &outputs);
+
+        let changes = old.compare(&new);
+        assert!(!changes.source_changed);
+        assert_eq!(changes.added, vec!["Item.json"]);
+        assert_eq!(changes.removed, vec!["Spell.json"]);
+        assert_eq!(changes.changed, vec!["Map.json"]);
+
+        let changes = old.compare(&ArtifactManifest::from_outputs(
+            None,
+            &old.files
+                .keys()
+                .map(|a| (a.clone(), String::new()))
+                .collect(),
+        ));
+        assert!(changes.source_changed);
+
+        assert_eq!(
+            ArtifactManifest::parse("FILE 00 Map.json\n"),
+            Err(ArtifactManifestError::MissingVersion)
+        );
+        assert_eq!(
+            ArtifactManifest::parse("VERSION 0.1.0\nFILE xyz Map.json\n"),
+            Err(ArtifactManifestError::InvalidLine {
+                line: 2,
+                contents: "FILE xyz Map.json".to_string()
+            })
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use crate::Version;
+
+/// Builds of well known client releases, so build numbers do not have to be written by hand.
+///
+/// Requires the `builds` feature.
+///
+/// ```rust
+/// use wowdbdefs_rs::{Builds, Version};
+///
+/// assert_eq!(Builds::WRATH_3_3_5A, Version::new(3, 3, 5, 12340));
+/// assert_eq!(Builds::get("wrath_3_3_5a"), Some(Builds::WRATH_3_3_5A));
+/// assert_eq!(Builds::name(&Version::new(1, 12, 1, 5875)), Some("CLASSIC_1_12_1"));
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Builds;
+
+impl Builds {
+    /// 0.5.3.3368, the most complete alpha client.
+    pub const ALPHA_0_5_3: Version = Version::new(0, 5, 3, 3368);
+    /// 1.12.1.5875, the last common patch of the original game.
+    pub const CLASSIC_1_12_1: Version = Version::new(1, 12, 1, 5875);
+    /// 1.12.2.6005.
+    pub const CLASSIC_1_12_2: Version = Version::new(1, 12, 2, 6005);
+    /// 2.4.3.8606, the last patch of The Burning Crusade.
+    pub const TBC_2_4_3: Version = Version::new(2, 4, 3, 8606);
+    /// 3.3.5.12340, the last patch of Wrath of the Lich King.
+    pub const WRATH_3_3_5A: Version = Version::new(3, 3, 5, 12340);
+    /// 4.3.4.15595, the last patch of Cataclysm.
+    pub const CATA_4_3_4: Version = Version::new(4, 3, 4, 15595);
+    /// 5.4.8.18414, the last patch of Mists of Pandaria.
+    pub const MOP_5_4_8: Version = Version::new(5, 4, 8, 18414);
+    /// 6.2.4.21742, a late patch of Warlords of Draenor.
+    pub const WOD_6_2_4: Version = Version::new(6, 2, 4, 21742);
+    /// 7.3.5.26972, a late patch of Legion.
+    pub const LEGION_7_3_5: Version = Version::new(7, 3, 5, 26972);
+
+    /// Every build constant with its name, sorted by version.
+    pub const ALL: &'static [(&'static str, Version)] = &[
+        ("ALPHA_0_5_3", Self::ALPHA_0_5_3),
+        ("CLASSIC_1_12_1", Self::CLASSIC_1_12_1),
+        ("CLASSIC_1_12_2", Self::CLASSIC_1_12_2),
+        ("TBC_2_4_3", Self::TBC_2_4_3),
+        ("WRATH_3_3_5A", Self::WRATH_3_3_5A),
+        ("CATA_4_3_4", Self::CATA_4_3_4),
+        ("MOP_5_4_8", Self::MOP_5_4_8),
+        ("WOD_6_2_4", Self::WOD_6_2_4),
+        ("LEGION_7_3_5", Self::LEGION_7_3_5),
+    ];
+
+    /// Build of the constant named `name`, ignoring ASCII case.
+    pub fn get(name: &str) -> Option<Version> {
+        Self::ALL
+            .iter()
+            .find(|(a, _)| a.eq_ignore_ascii_case(name))
+            .map(|(_, version)| *version)
+    }
+
+    /// Name of the constant for `version`, if any.
+    pub fn name(version: &Version) -> Option<&'static str> {
+        Self::ALL
+            .iter()
+            .find(|(_, a)| a == version)
+            .map(|(name, _)| *name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Builds;
+
+    #[test]
+    fn builds_sorted() {
+        assert!(Builds::ALL.windows(2).all(|a| a[0].1 < a[1].1));
+        assert_eq!(Builds::get("TBC_2_4_3"), Some(Builds::TBC_2_4_3));
+        assert!(Builds::get("WRATH").is_none());
+    }
+}
@@ -0,0 +1,664 @@
+//! Single file bundles of the definitions needed for a specific client.
+//!
+//! A bundle is the `.dbd` contents of every table written after a `TABLE` line with the table name:
+//!
+//! ```text
+//! TABLE Map
+//! COLUMNS
+//! int ID
+//!
+//! BUILD 3.3.5.12340
+//! $id$ID<32>
+//!
+//! TABLE AreaTable
+//! COLUMNS
+//! ...
+//! ```
+//!
+//! Bundles for a single build written with [`DbdCollection::to_build_bundle`] start with a header
+//! of the build and an index of the line number of every `TABLE` line, starting at 1:
+//!
+//! ```text
+//! BUNDLE 3.3.5.12340
+//! INDEX AreaTable 4
+//! INDEX Map 13
+//! TABLE AreaTable
+//! ...
+//! ```
+//!
+//! [`BundleReader`] uses the index to parse only the tables that are used.
+//!
+//! Bundles written with [`DbdCollection::to_build_bundle_with_options`] can also record the
+//! commit of the definitions repository they were made from and checksums of the contents:
+//!
+//! ```text
+//! BUNDLE 3.3.5.12340
+//! COMMIT 8d3c6b1
+//! HASH 6d1a445076af2537
+//! INDEX Map 5 6057eddda3d2b0d2
+//! TABLE Map
+//! ...
+//! ```
+//!
+//! Checksums are 64 bit FNV-1a of the lines, each followed by `\n`.
+//! The checksum of a table covers its `TABLE` line and contents and the `HASH` line covers every other line of the bundle.
+//! They detect corrupted and truncated bundles but are not cryptographic,
+//! so use [`BundleReader::verify_signature`] with a detached signature to detect tampering.
+
+use crate::error::{BundleError, BundleVerificationError, ConversionError, LoadError};
+use crate::parser::{parse_file, string_to_version};
+use crate::types::{fnv1a, FNV_OFFSET_BASIS};
+use crate::{write_to_file, DbdCollection, DbdFile, Version, VersionSet};
+use std::collections::BTreeMap;
+
+const TABLE_PREFIX: &str = "TABLE ";
+const BUNDLE_PREFIX: &str = "BUNDLE ";
+const INDEX_PREFIX: &str = "INDEX ";
+const COMMIT_PREFIX: &str = "COMMIT ";
+const HASH_PREFIX: &str = "HASH ";
+
+/// Options for [`DbdCollection::to_build_bundle_with_options`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub struct BundleOptions<'a> {
+    /// Write a checksum for every table and a hash of the entire bundle.
+    pub checksums: bool,
+    /// Commit of the definitions repository the bundle is made from, written as a `COMMIT` line.
+    pub commit: Option<&'a str>,
+}
+
+impl<'a> BundleOptions<'a> {
+    /// Creates options without checksums or commit.
+    pub const fn new() -> Self {
+        Self {
+            checksums: false,
+            commit: None,
+        }
+    }
+}
+
+/// Verifies detached signatures of bundles for [`BundleReader::verify_signature`].
+///
+/// Implement this with the signature scheme used for distributing the bundles.
+pub trait SignatureVerifier {
+    /// Returns true if `signature` is valid for `contents`.
+    fn verify(&self, contents: &[u8], signature: &[u8]) -> bool;
+}
+
+impl<T: SignatureVerifier + ?Sized> SignatureVerifier for &T {
+    fn verify(&self, contents: &[u8], signature: &[u8]) -> bool {
+        (**self).verify(contents, signature)
+    }
+}
+
+impl DbdCollection {
+    /// Creates a collection with only what is needed for `versions`.
+    ///
+    /// Definitions are trimmed with [`DbdCollection::subset`] and comments are removed
+    /// to keep the bundle small.
+    /// Columns not used by any remaining definition are left out when written with
+    /// [`DbdCollection::to_bundle`].
+    pub fn bundle_for(&self, versions: &VersionSet) -> DbdCollection {
+        let mut collection = self.subset(versions);
+
+        for file in collection.files.values_mut() {
+            for definition in &mut file.definitions {
+                for entry in &mut definition.entries {
+                    entry.comment = None;
+                    entry.column_comment = None;
+                }
+            }
+        }
+
+        collection
+    }
+
+    /// Writes every file of the collection into a single bundle, sorted by table name.
+    ///
+    /// Load the bundle again with [`load_bundle_from_string`].
+    ///
+    /// # Errors
+    ///
+    /// Errors if a file can not be converted with [`DbdFile::into_raw`](crate::DbdFile::into_raw).
+    pub fn to_bundle(&self) -> Result<String, ConversionError> {
+        Ok(self.bundle_tables()?.into_iter().map(|(_, a)| a).collect())
+    }
+
+    /// Writes the definitions needed for `version` into a single bundle with a header and an index.
+    ///
+    /// The header is the build followed by the line number of every `TABLE` line, starting at 1:
+    ///
+    /// ```text
+    /// BUNDLE 3.3.5.12340
+    /// INDEX AreaTable 4
+    /// INDEX Map 13
+    /// TABLE AreaTable
+    /// ...
+    /// ```
+    ///
+    /// The collection is trimmed with [`DbdCollection::bundle_for`] first.
+    /// Read the bundle with [`BundleReader`] or [`load_bundle_from_string`].
+    ///
+    /// # Errors
+    ///
+    /// Errors if a file can not be converted with [`DbdFile::into_raw`].
+    pub fn to_build_bundle(&self, version: &Version) -> Result<String, ConversionError> {
+        self.to_build_bundle_with_options(version, &BundleOptions::new())
+    }
+
+    /// Same as [`DbdCollection::to_build_bundle`] but with a `COMMIT` line and checksums
+    /// depending on `options`, see [`BundleOptions`].
+    ///
+    /// # Errors
+    ///
+    /// Errors if a file can not be converted with [`DbdFile::into_raw`].
+    pub fn to_build_bundle_with_options(
+        &self,
+        version: &Version,
+        options: &BundleOptions,
+    ) -> Result<String, ConversionError> {
+        let bundle = self.bundle_for(&(*version).into());
+        let tables = bundle.bundle_tables()?;
+
+        let mut header = vec![format!("{}{}", BUNDLE_PREFIX, version)];
+        if let Some(commit) = options.commit {
+            header.push(format!("{}{}", COMMIT_PREFIX, commit));
+        }
+
+        let mut line = header.len() + tables.len() + usize::from(options.checksums) + 1;
+        for (table, contents) in &tables {
+            let mut index = format!("{}{} {}", INDEX_PREFIX, table, line);
+            if options.checksums {
+                index.push_str(&format!(" {:016x}", checksum(contents.lines())));
+            }
+            header.push(index);
+            line += contents.lines().count();
+        }
+
+        if options.checksums {
+            let lines = header
+                .iter()
+                .map(String::as_str)
+                .chain(tables.iter().flat_map(|(_, a)| a.lines()));
+            let hash = format!("{}{:016x}", HASH_PREFIX, checksum(lines));
+            header.insert(1 + usize::from(options.commit.is_some()), hash);
+        }
+
+        let mut s = String::new();
+        for line in header {
+            s.push_str(&line);
+            s.push('\n');
+        }
+        for (_, contents) in tables {
+            s.push_str(&contents);
+        }
+
+        Ok(s)
+    }
+
+    fn bundle_tables(&self) -> Result<Vec<(&str, String)>, ConversionError> {
+        let mut tables = Vec::with_capacity(self.files.len());
+
+        for (table, file) in &self.files {
+            let raw = file.clone().into_raw()?;
+            tables.push((
+                table.as_str(),
+                format!("{}{}\n{}", TABLE_PREFIX, table, write_to_file(&raw)),
+            ));
+        }
+
+        Ok(tables)
+    }
+}
+
+/// Load a bundle written by [`DbdCollection::to_bundle`].
+///
+/// Lines before the first `TABLE` line are ignored.
+/// Line numbers of parse errors are for the entire bundle.
+///
+/// # Errors
+///
+/// Errors if a table can not be parsed or converted.
+pub fn load_bundle_from_string(contents: &str) -> Result<DbdCollection, LoadError> {
+    let mut collection = DbdCollection::new();
+    let mut table: Option<(String, usize, String)> = None;
+
+    let mut insert = |table: Option<(String, usize, String)>| -> Result<(), LoadError> {
+        if let Some((name, offset, contents)) = table {
+            collection.insert(load_table(&name, offset, &contents)?);
+        }
+
+        Ok(())
+    };
+
+    for (i, line) in contents.lines().enumerate() {
+        if let Some(name) = line.strip_prefix(TABLE_PREFIX) {
+            insert(table.take())?;
+            table = Some((name.trim().to_string(), i + 1, String::new()));
+        } else if let Some((_, _, contents)) = &mut table {
+            contents.push_str(line);
+            contents.push('\n');
+        }
+    }
+    insert(table)?;
+
+    Ok(collection)
+}
+
+fn checksum<'a>(lines: impl Iterator<Item = &'a str>) -> u64 {
+    lines.fold(FNV_OFFSET_BASIS, |hash, line| {
+        fnv1a(fnv1a(hash, line.as_bytes()), b"\n")
+    })
+}
+
+fn load_table(name: &str, offset: usize, contents: &str) -> Result<DbdFile, LoadError> {
+    let file = format!("{}.dbd", name);
+
+    let raw = parse_file(contents, file.clone()).map_err(|mut error| {
+        error.line += offset;
+        LoadError::Parse {
+            file: file.clone(),
+            error,
+        }
+    })?;
+
+    raw.into_proper()
+        .map_err(|error| LoadError::Conversion { file, error })
+}
+
+/// Reader for bundles written by [`DbdCollection::to_build_bundle`] that only parses the tables that are used.
+///
+/// ```rust
+/// use wowdbdefs_rs::{load_file_from_string, BundleReader, DbdCollection, Version};
+///
+/// let file = load_file_from_string("COLUMNS\nint ID\n\nBUILD 3.3.5.12340\n$id$ID<32>\n", "Map.dbd")
+///     .unwrap()
+///     .into_proper()
+///     .unwrap();
+/// let bundle = DbdCollection::from_files(vec![file])
+///     .to_build_bundle(&Version::new(3, 3, 5, 12340))
+///     .unwrap();
+///
+/// let reader = BundleReader::new(&bundle).unwrap();
+/// assert_eq!(reader.build(), Version::new(3, 3, 5, 12340));
+/// assert!(reader.load_table("Map").unwrap().is_ok());
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BundleReader<'a> {
+    contents: &'a str,
+    build: Version,
+    commit: Option<&'a str>,
+    hash: Option<(usize, u64)>,
+    index: BTreeMap<&'a str, (usize, Option<u64>)>,
+}
+
+impl<'a> BundleReader<'a> {
+    /// Reads the header and index of `contents`.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the bundle does not start with a `BUNDLE` line
+    /// or a `COMMIT`, `HASH` or `INDEX` line is not valid.
+    pub fn new(contents: &'a str) -> Result<Self, BundleError> {
+        let invalid = |line: &str, line_count: usize| BundleError::InvalidHeader {
+            line: line_count,
+            contents: line.to_string(),
+        };
+
+        let parse_checksum = |s: &str| u64::from_str_radix(s, 16).ok().filter(|_| s.len() == 16);
+
+        let mut lines = contents.lines().enumerate().peekable();
+
+        let first = lines.next().map_or("", |(_, a)| a);
+        let build = match first.strip_prefix(BUNDLE_PREFIX) {
+            Some(build) => string_to_version(build.trim(), 1, BUNDLE_PREFIX.len())
+                .map_err(|_| invalid(first, 1))?,
+            None => return Err(invalid(first, 1)),
+        };
+
+        let mut commit = None;
+        if let Some((_, line)) = lines.peek() {
+            if let Some(c) = line.strip_prefix(COMMIT_PREFIX) {
+                commit = Some(c.trim());
+                lines.next();
+            }
+        }
+
+        let mut hash = None;
+        if let Some((i, line)) = lines.peek().copied() {
+            if let Some(h) = line.strip_prefix(HASH_PREFIX) {
+                match parse_checksum(h.trim()) {
+                    Some(h) => hash = Some((i, h)),
+                    None => return Err(invalid(line, i + 1)),
+                }
+                lines.next();
+            }
+        }
+
+        let mut index = BTreeMap::new();
+        for (i, line) in lines {
+            let entry = match line.strip_prefix(INDEX_PREFIX) {
+                Some(entry) => entry,
+                None => break,
+            };
+
+            let mut parts = entry.split_whitespace();
+            let table = parts.next();
+            let start = parts.next().map(str::parse::<usize>);
+            let checksum = parts.next().map(parse_checksum);
+
+            match (table, start, checksum, parts.next()) {
+                (Some(table), Some(Ok(start)), None, None) if start > i + 1 => {
+                    index.insert(table, (start, None));
+                }
+                (Some(table), Some(Ok(start)), Some(Some(checksum)), None) if start > i + 1 => {
+                    index.insert(table, (start, Some(checksum)));
+                }
+                _ => return Err(invalid(line, i + 1)),
+            }
+        }
+
+        Ok(Self {
+            contents,
+            build,
+            commit,
+            hash,
+            index,
+        })
+    }
+
+    /// Build the bundle was written for.
+    pub const fn build(&self) -> Version {
+        self.build
+    }
+
+    /// Commit of the definitions repository the bundle was made from, if written.
+    pub const fn commit(&self) -> Option<&'a str> {
+        self.commit
+    }
+
+    /// Hash of the entire bundle from the `HASH` line, if written.
+    pub fn hash(&self) -> Option<u64> {
+        self.hash.map(|(_, hash)| hash)
+    }
+
+    /// Names of every table in the bundle, sorted.
+    pub fn tables(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.index.keys().copied()
+    }
+
+    /// Parses a single table, or [`None`] if it is not in the bundle.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the table can not be parsed or converted,
+    /// or if the index does not point to the `TABLE` line of the table.
+    pub fn load_table(&self, table: &str) -> Option<Result<DbdFile, LoadError>> {
+        let (start, _) = *self.index.get(table)?;
+        let end = self.end_of(start);
+
+        let mut lines = self.contents.lines().skip(start - 1);
+        let header = lines.next().unwrap_or("");
+        if header.strip_prefix(TABLE_PREFIX).map(str::trim) != Some(table) {
+            return Some(Err(LoadError::Bundle(BundleError::InvalidIndex {
+                table: table.to_string(),
+                line: start,
+            })));
+        }
+
+        let mut contents = String::new();
+        for line in lines.take(end - start - 1) {
+            contents.push_str(line);
+            contents.push('\n');
+        }
+
+        Some(load_table(table, start, &contents))
+    }
+
+    /// Verifies the `HASH` line and the checksum of every table.
+    ///
+    /// The checksums only detect corrupted or truncated bundles,
+    /// use [`BundleReader::verify_signature`] to detect tampering.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the bundle has no `HASH` line or a checksum does not match.
+    pub fn verify(&self) -> Result<(), BundleVerificationError> {
+        let (hash_line, expected) = self.hash.ok_or(BundleVerificationError::NoChecksums)?;
+
+        let lines = self
+            .contents
+            .lines()
+            .enumerate()
+            .filter(|(i, _)| *i != hash_line)
+            .map(|(_, a)| a);
+        let actual = checksum(lines);
+        if actual != expected {
+            return Err(BundleVerificationError::ContentHash { expected, actual });
+        }
+
+        for (table, (start, expected)) in &self.index {
+            let expected = match expected {
+                Some(expected) => *expected,
+                None => return Err(BundleVerificationError::NoChecksums),
+            };
+
+            let lines = self
+                .contents
+                .lines()
+                .skip(start - 1)
+                .take(self.end_of(*start) - start);
+            let actual = checksum(lines);
+            if actual != expected {
+                return Err(BundleVerificationError::TableChecksum {
+                    table: table.to_string(),
+                    expected,
+                    actual,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Verifies a detached `signature` of the entire bundle with `verifier`.
+    ///
+    /// The signature covers the bundle exactly as it was passed to [`BundleReader::new`].
+    ///
+    /// # Errors
+    ///
+    /// Errors if `verifier` does not accept the signature.
+    pub fn verify_signature(
+        &self,
+        signature: &[u8],
+        verifier: impl SignatureVerifier,
+    ) -> Result<(), BundleVerificationError> {
+        if verifier.verify(self.contents.as_bytes(), signature) {
+            Ok(())
+        } else {
+            Err(BundleVerificationError::InvalidSignature)
+        }
+    }
+
+    /// Line number after the last line of the table starting at `start`.
+    fn end_of(&self, start: usize) -> usize {
+        self.index
+            .values()
+            .map(|(a, _)| *a)
+            .filter(|a| *a > start)
+            .min()
+            .unwrap_or(usize::MAX)
+    }
+
+    /// Parses every table in the bundle.
+    ///
+    /// # Errors
+    ///
+    /// Errors if a table can not be loaded with [`BundleReader::load_table`].
+    pub fn load_all(&self) -> Result<DbdCollection, LoadError> {
+        let mut collection = DbdCollection::new();
+
+        for table in self.tables() {
+            if let Some(file) = self.load_table(table) {
+                collection.insert(file?);
+            }
+        }
+
+        Ok(collection)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::{BundleError, BundleVerificationError, LoadError};
+    use crate::{
+        load_bundle_from_string, load_file_from_string, BundleOptions, BundleReader, DbdCollection,
+        SignatureVerifier, Version,
+    };
+
+    #[test]
+    fn bundle_round_trip() {
+        let files = vec![
+            (
+                "Map.dbd",
+                "COLUMNS\nint ID // Column comment\nint Retail\n\nBUILD 3.3.5.12340\n$id$ID<32> // Entry comment\n\nBUILD 9.0.1.36216\n$id$ID<32>\nRetail<32>\n",
+            ),
+            ("Spell.dbd", "COLUMNS\nint ID\n\nBUILD 1.12.1.5875\n$id$ID<32>\n"),
+        ]
+        .into_iter()
+        .map(|(name, contents)| {
+            load_file_from_string(contents, name)
+                .unwrap()
+                .into_proper()
+                .unwrap()
+        });
+
+        let bundle =
+            DbdCollection::from_files(files).bundle_for(&Version::new(3, 3, 5, 12340).into());
+        assert_eq!(bundle.len(), 1);
+
+        let written = bundle.to_bundle().unwrap();
+        assert_eq!(
+            written,
+            "TABLE Map\nCOLUMNS\nint ID\n\nBUILD 3.3.5.12340\n$id$ID<32>\n\n"
+        );
+        assert_eq!(load_bundle_from_string(&written).unwrap(), bundle);
+    }
+
+    #[test]
+    fn build_bundle() {
+        let files = vec![
+            (
+                "Map.dbd",
+                "COLUMNS\nint ID\n\nBUILD 3.3.5.12340\n$id$ID<32>\n",
+            ),
+            (
+                "AreaTable.dbd",
+                "COLUMNS\nint ID\nint<Map::ID> MapID\n\nBUILD 3.3.5.12340\n$id$ID<32>\nMapID<32>\n",
+            ),
+        ]
+        .into_iter()
+        .map(|(name, contents)| {
+            load_file_from_string(contents, name)
+                .unwrap()
+                .into_proper()
+                .unwrap()
+        });
+        let collection = DbdCollection::from_files(files);
+        let version = Version::new(3, 3, 5, 12340);
+
+        let written = collection.to_build_bundle(&version).unwrap();
+        assert_eq!(
+            written,
+            "BUNDLE 3.3.5.12340\nINDEX AreaTable 4\nINDEX Map 13\nTABLE AreaTable\nCOLUMNS\nint ID\nint<Map::ID> MapID\n\nBUILD 3.3.5.12340\n$id$ID<32>\nMapID<32>\n\nTABLE Map\nCOLUMNS\nint ID\n\nBUILD 3.3.5.12340\n$id$ID<32>\n\n"
+        );
+
+        let reader = BundleReader::new(&written).unwrap();
+        assert_eq!(reader.build(), version);
+        assert_eq!(
+            reader.tables().collect::<Vec<_>>(),
+            vec!["AreaTable", "Map"]
+        );
+        assert_eq!(
+            &reader.load_table("Map").unwrap().unwrap(),
+            collection.get("Map").unwrap()
+        );
+        assert!(reader.load_table("Spell").is_none());
+        assert_eq!(reader.load_all().unwrap(), collection);
+        assert_eq!(load_bundle_from_string(&written).unwrap(), collection);
+
+        assert_eq!(
+            BundleReader::new("TABLE Map\n"),
+            Err(BundleError::InvalidHeader {
+                line: 1,
+                contents: "TABLE Map".to_string()
+            })
+        );
+        assert!(BundleReader::new("BUNDLE 3.3.5\n").is_err());
+        assert!(matches!(
+            BundleReader::new("BUNDLE 3.3.5.12340\nINDEX Map\n"),
+            Err(BundleError::InvalidHeader { line: 2, .. })
+        ));
+        let wrong = BundleReader::new("BUNDLE 3.3.5.12340\nINDEX Map 3\n").unwrap();
+        assert_eq!(
+            wrong.load_table("Map"),
+            Some(Err(LoadError::Bundle(BundleError::InvalidIndex {
+                table: "Map".to_string(),
+                line: 3
+            })))
+        );
+    }
+
+    #[test]
+    fn bundle_checksums() {
+        struct Equal;
+        impl SignatureVerifier for Equal {
+            fn verify(&self, contents: &[u8], signature: &[u8]) -> bool {
+                contents == signature
+            }
+        }
+
+        let file = load_file_from_string(
+            "COLUMNS\nint ID\n\nBUILD 3.3.5.12340\n$id$ID<32>\n",
+            "Map.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+        let collection = DbdCollection::from_files(vec![file]);
+        let version = Version::new(3, 3, 5, 12340);
+
+        let options = BundleOptions {
+            checksums: true,
+            commit: Some("8d3c6b1"),
+        };
+        let written = collection
+            .to_build_bundle_with_options(&version, &options)
+            .unwrap();
+        assert!(written.starts_with(
+            "BUNDLE 3.3.5.12340\nCOMMIT 8d3c6b1\nHASH 6d1a445076af2537\nINDEX Map 5 6057eddda3d2b0d2\nTABLE Map\n"
+        ));
+
+        let reader = BundleReader::new(&written).unwrap();
+        assert_eq!(reader.commit(), Some("8d3c6b1"));
+        assert!(reader.verify().is_ok());
+        assert_eq!(reader.load_all().unwrap(), collection);
+        assert!(reader.verify_signature(written.as_bytes(), Equal).is_ok());
+        assert_eq!(
+            reader.verify_signature(b"", Equal),
+            Err(BundleVerificationError::InvalidSignature)
+        );
+
+        let tampered = written.replace("ID<32>", "ID<16>");
+        assert!(matches!(
+            BundleReader::new(&tampered).unwrap().verify(),
+            Err(BundleVerificationError::ContentHash { .. })
+        ));
+
+        let unchecked = collection.to_build_bundle(&version).unwrap();
+        assert_eq!(
+            BundleReader::new(&unchecked).unwrap().verify(),
+            Err(BundleVerificationError::NoChecksums)
+        );
+        assert!(BundleReader::new("BUNDLE 3.3.5.12340\nHASH 12\n").is_err());
+    }
+}
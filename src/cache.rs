@@ -0,0 +1,136 @@
+use crate::{DbdCollection, Definition, Version};
+use std::collections::{BTreeMap, HashMap};
+
+/// Bounded least recently used cache of definition lookups for a [`DbdCollection`].
+///
+/// [`DbdFile::specific_version`](crate::DbdFile::specific_version) searches every definition of a file,
+/// so services answering many lookups for the same tables and versions can use this to
+/// make repeated lookups constant time.
+/// Lookups for versions without a definition are cached as well.
+#[derive(Debug, Clone)]
+pub struct DefinitionCache {
+    collection: DbdCollection,
+    capacity: usize,
+    entries: HashMap<(String, Version), (Option<usize>, u64)>,
+    recency: BTreeMap<u64, (String, Version)>,
+    tick: u64,
+}
+
+impl DefinitionCache {
+    /// Creates a cache that keeps at most `capacity` lookups.
+    ///
+    /// A `capacity` of 0 disables caching.
+    pub fn new(collection: DbdCollection, capacity: usize) -> Self {
+        Self {
+            collection,
+            capacity,
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            tick: 0,
+        }
+    }
+
+    /// Finds the definition of `table`, without `.dbd`, for `version`.
+    pub fn get(&mut self, table: &str, version: &Version) -> Option<&Definition> {
+        let index = self.index(table, version)?;
+
+        self.collection
+            .get(table)
+            .map(|file| &file.definitions[index])
+    }
+
+    fn index(&mut self, table: &str, version: &Version) -> Option<usize> {
+        let key = (table.to_string(), *version);
+        self.tick += 1;
+
+        if let Some((index, tick)) = self.entries.get_mut(&key) {
+            self.recency.remove(tick);
+            *tick = self.tick;
+            self.recency.insert(self.tick, key);
+
+            return *index;
+        }
+
+        let index = self.collection.get(table).and_then(|file| {
+            file.definitions
+                .iter()
+                .position(|a| a.versions.contains(version))
+        });
+
+        if self.capacity == 0 {
+            return index;
+        }
+
+        if self.entries.len() >= self.capacity {
+            let oldest = self.recency.keys().next().copied();
+            if let Some(oldest) = oldest {
+                if let Some(key) = self.recency.remove(&oldest) {
+                    self.entries.remove(&key);
+                }
+            }
+        }
+
+        self.entries.insert(key.clone(), (index, self.tick));
+        self.recency.insert(self.tick, key);
+
+        index
+    }
+
+    /// Amount of cached lookups.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if there are no cached lookups.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Removes all cached lookups.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+    }
+
+    /// The collection lookups are made in.
+    pub const fn collection(&self) -> &DbdCollection {
+        &self.collection
+    }
+
+    /// Returns the collection, discarding the cache.
+    pub fn into_inner(self) -> DbdCollection {
+        self.collection
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{load_file_from_string, DbdCollection, DefinitionCache, Version};
+
+    #[test]
+    fn least_recently_used() {
+        let map = load_file_from_string(
+            "COLUMNS\nint ID\n\nBUILD 1.12.1.5875\n$id$ID<32>\n\nBUILD 3.3.5.12340\nID<32>\n",
+            "Map.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+        let mut cache = DefinitionCache::new(DbdCollection::from_files(vec![map]), 2);
+
+        let vanilla = Version::new(1, 12, 1, 5875);
+        let wrath = Version::new(3, 3, 5, 12340);
+
+        assert!(cache.get("Map", &vanilla).unwrap().entries[0].primary_key);
+        assert!(!cache.get("Map", &wrath).unwrap().entries[0].primary_key);
+        assert!(cache.get("Map", &vanilla).is_some());
+        assert!(cache.get("Map", &Version::new(2, 4, 3, 8606)).is_none());
+        assert!(cache.get("Missing", &vanilla).is_none());
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get("Map", &wrath).is_some());
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+}
@@ -0,0 +1,41 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Token for cooperatively cancelling long running operations from another thread.
+///
+/// Clones share the same state, so cancelling any clone cancels every operation using the token.
+/// Operations check the token between files or tables and stop as soon as possible after
+/// [`CancelToken::cancel`] is called, see [`load_directory_with_cancel`](crate::load_directory_with_cancel)
+/// and [`DbdCollection::lints_with_cancel`](crate::DbdCollection::lints_with_cancel).
+///
+/// ```rust
+/// use wowdbdefs_rs::CancelToken;
+///
+/// let token = CancelToken::new();
+/// let handle = token.clone();
+/// assert!(!token.is_cancelled());
+///
+/// handle.cancel();
+/// assert!(token.is_cancelled());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+    /// Creates a token that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels every operation using the token or a clone of it.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true if [`CancelToken::cancel`] has been called.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,343 @@
+//! Summaries of the changes between two versions of the definitions.
+//!
+//! ```rust
+//! # use std::path::Path;
+//! # use wowdbdefs_rs::changelog::generate_changelog;
+//! # fn t(old: &Path, new: &Path) -> Result<(), Box<dyn std::error::Error>> {
+//! let changelog = generate_changelog(old, new)??;
+//! println!("{}", changelog.to_markdown());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::LoadError;
+use crate::mapping::{map_columns, MatchReason};
+use crate::writer::Writer;
+use crate::{load_directory, DbdCollection, DbdFile, Type, VersionSet};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// Changes between two versions of a collection.
+///
+/// Created from [`Changelog::between`] or [`generate_changelog`].
+/// All table and column names are sorted.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
+pub struct Changelog {
+    /// Tables that only exist in the new collection, without `.dbd`.
+    pub added_tables: Vec<String>,
+    /// Tables that only exist in the old collection, without `.dbd`.
+    pub removed_tables: Vec<String>,
+    /// Versions documented by any table in the new collection that were not documented by that table before.
+    pub new_versions: VersionSet,
+    /// Changes in tables that exist in both collections.
+    ///
+    /// Tables without changes are not included.
+    pub tables: Vec<TableChanges>,
+}
+
+/// Changes in a single table.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
+pub struct TableChanges {
+    /// Name of the table, without `.dbd`.
+    pub table: String,
+    /// Versions that are newly documented.
+    pub new_versions: VersionSet,
+    /// Columns that did not exist before.
+    pub added_columns: Vec<String>,
+    /// Columns that no longer exist.
+    pub removed_columns: Vec<String>,
+    /// Columns that were likely renamed, as the old name followed by the new name.
+    ///
+    /// Found with [`map_columns`] on definitions with overlapping versions.
+    /// Renamed columns are not included in [`TableChanges::added_columns`] or [`TableChanges::removed_columns`].
+    pub renamed_columns: Vec<(String, String)>,
+    /// Columns that exist in both but with different types.
+    pub changed_columns: Vec<String>,
+}
+
+impl TableChanges {
+    fn is_empty(&self) -> bool {
+        self.new_versions.is_empty()
+            && self.added_columns.is_empty()
+            && self.removed_columns.is_empty()
+            && self.renamed_columns.is_empty()
+            && self.changed_columns.is_empty()
+    }
+}
+
+impl Changelog {
+    /// Finds the changes from `old` to `new`.
+    pub fn between(old: &DbdCollection, new: &DbdCollection) -> Self {
+        let mut changelog = Self::default();
+
+        for (table, file) in &new.files {
+            let old_file = if let Some(old_file) = old.get(table) {
+                old_file
+            } else {
+                changelog.added_tables.push(table.clone());
+                changelog.new_versions = changelog.new_versions.union(&versions(file));
+                continue;
+            };
+
+            let changes = table_changes(table, old_file, file);
+            if !changes.is_empty() {
+                changelog.new_versions = changelog.new_versions.union(&changes.new_versions);
+                changelog.tables.push(changes);
+            }
+        }
+
+        for table in old.files.keys() {
+            if new.get(table).is_none() {
+                changelog.removed_tables.push(table.clone());
+            }
+        }
+
+        changelog
+    }
+
+    /// Returns true if there are no changes.
+    pub fn is_empty(&self) -> bool {
+        self.added_tables.is_empty()
+            && self.removed_tables.is_empty()
+            && self.new_versions.is_empty()
+            && self.tables.is_empty()
+    }
+
+    /// Renders the changes as Markdown.
+    pub fn to_markdown(&self) -> String {
+        let mut s = Writer::new();
+        s.wln("# Changelog");
+
+        if self.is_empty() {
+            s.newline();
+            s.wln("No changes.");
+            return s.inner;
+        }
+
+        write_list(&mut s, "New tables", &self.added_tables);
+        write_list(&mut s, "Removed tables", &self.removed_tables);
+        write_list(&mut s, "New builds", &version_strings(&self.new_versions));
+
+        if !self.tables.is_empty() {
+            s.newline();
+            s.wln("## Changed tables");
+
+            for table in &self.tables {
+                s.newline();
+                s.wln(format!("### `{}`", table.table));
+                s.newline();
+
+                write_line(&mut s, "New builds", &version_strings(&table.new_versions));
+                write_line(&mut s, "Added columns", &table.added_columns);
+                write_line(&mut s, "Removed columns", &table.removed_columns);
+                if !table.renamed_columns.is_empty() {
+                    let renames: Vec<String> = table
+                        .renamed_columns
+                        .iter()
+                        .map(|(old, new)| format!("`{}` -> `{}`", old, new))
+                        .collect();
+                    s.wln(format!("* Renamed columns: {}", renames.join(", ")));
+                }
+                write_line(&mut s, "Changed columns", &table.changed_columns);
+            }
+        }
+
+        s.inner
+    }
+}
+
+/// Load the `.dbd` files of two directories, for example two checkouts of the `WoWDBDefs` repository,
+/// and find the changes from `old_dir` to `new_dir`.
+///
+/// # Errors
+///
+/// The function has two error types:
+///
+/// * [`std::io::Error`], for errors in reading the directories or files.
+/// * [`LoadError`], for errors in parsing or converting a `.dbd` file.
+///
+pub fn generate_changelog(
+    old_dir: &Path,
+    new_dir: &Path,
+) -> std::io::Result<Result<Changelog, LoadError>> {
+    let old = match load_directory(old_dir)? {
+        Ok(old) => old,
+        Err(e) => return Ok(Err(e)),
+    };
+    let new = match load_directory(new_dir)? {
+        Ok(new) => new,
+        Err(e) => return Ok(Err(e)),
+    };
+
+    Ok(Ok(Changelog::between(&old, &new)))
+}
+
+fn versions(file: &DbdFile) -> VersionSet {
+    file.definitions
+        .iter()
+        .fold(VersionSet::new(), |acc, a| acc.union(&a.versions))
+}
+
+fn column_types(file: &DbdFile) -> BTreeMap<&str, BTreeSet<&Type>> {
+    let mut columns: BTreeMap<&str, BTreeSet<&Type>> = BTreeMap::new();
+
+    for definition in &file.definitions {
+        for entry in &definition.entries {
+            columns
+                .entry(entry.name.as_str())
+                .or_default()
+                .insert(&entry.ty);
+        }
+    }
+
+    columns
+}
+
+fn table_changes(table: &str, old: &DbdFile, new: &DbdFile) -> TableChanges {
+    let old_columns = column_types(old);
+    let new_columns = column_types(new);
+
+    let mut changes = TableChanges {
+        table: table.to_string(),
+        new_versions: versions(new).difference(&versions(old)),
+        ..TableChanges::default()
+    };
+
+    for (name, types) in &new_columns {
+        match old_columns.get(name) {
+            None => changes.added_columns.push(name.to_string()),
+            Some(old_types) if old_types != types => {
+                changes.changed_columns.push(name.to_string());
+            }
+            Some(_) => {}
+        }
+    }
+
+    for name in old_columns.keys() {
+        if !new_columns.contains_key(name) {
+            changes.removed_columns.push(name.to_string());
+        }
+    }
+
+    find_renames(&mut changes, old, new);
+
+    changes
+}
+
+/// Moves columns that were removed and added under a different name in the same position
+/// from [`TableChanges::removed_columns`] and [`TableChanges::added_columns`] to [`TableChanges::renamed_columns`].
+fn find_renames(changes: &mut TableChanges, old: &DbdFile, new: &DbdFile) {
+    for old_definition in &old.definitions {
+        for new_definition in &new.definitions {
+            if !old_definition.versions.overlaps(&new_definition.versions) {
+                continue;
+            }
+
+            for pair in map_columns(old_definition, new_definition).pairs {
+                if pair.reason == MatchReason::Name {
+                    continue;
+                }
+
+                let removed = changes.removed_columns.iter().position(|a| a == &pair.from);
+                let added = changes.added_columns.iter().position(|a| a == &pair.to);
+
+                if let (Some(removed), Some(added)) = (removed, added) {
+                    changes.removed_columns.remove(removed);
+                    changes.added_columns.remove(added);
+                    changes.renamed_columns.push((pair.from, pair.to));
+                }
+            }
+        }
+    }
+
+    changes.renamed_columns.sort();
+}
+
+fn version_strings(versions: &VersionSet) -> Vec<String> {
+    versions
+        .intervals()
+        .iter()
+        .map(|a| {
+            if a.from == a.to {
+                a.from.to_string()
+            } else {
+                format!("{}-{}", a.from, a.to)
+            }
+        })
+        .collect()
+}
+
+fn write_list(s: &mut Writer, title: &str, values: &[String]) {
+    if values.is_empty() {
+        return;
+    }
+
+    s.newline();
+    s.wln(format!("## {}", title));
+    s.newline();
+
+    for value in values {
+        s.wln(format!("* `{}`", value));
+    }
+}
+
+fn write_line(s: &mut Writer, title: &str, values: &[String]) {
+    if values.is_empty() {
+        return;
+    }
+
+    let values: Vec<String> = values.iter().map(|a| format!("`{}`", a)).collect();
+    s.wln(format!("* {}: {}", title, values.join(", ")));
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::changelog::Changelog;
+    use crate::{load_file_from_string, DbdCollection, DbdFile};
+
+    fn file(name: &str, contents: &str) -> DbdFile {
+        load_file_from_string(contents, name)
+            .unwrap()
+            .into_proper()
+            .unwrap()
+    }
+
+    #[test]
+    fn changelog() {
+        let old = DbdCollection::from_files(vec![
+            file(
+                "Map.dbd",
+                "COLUMNS\nint ID\nint Unk0\nint Flags\n\nBUILD 1.12.1.5875\n$id$ID<32>\nUnk0<32>\nFlags<32>\n",
+            ),
+            file("Old.dbd", "COLUMNS\nint ID\n\nBUILD 1.12.1.5875\n$id$ID<32>\n"),
+        ]);
+        let new = DbdCollection::from_files(vec![
+            file(
+                "Map.dbd",
+                "COLUMNS\nint ID\nint Expansion\nint Flags\n\nBUILD 1.12.1.5875, 2.4.3.8606\n$id$ID<32>\nExpansion<32>\nFlags<u32>\n",
+            ),
+            file("Spell.dbd", "COLUMNS\nint ID\n\nBUILD 3.3.5.12340\n$id$ID<32>\n"),
+        ]);
+
+        let changelog = Changelog::between(&old, &new);
+        assert_eq!(changelog.added_tables, vec!["Spell"]);
+        assert_eq!(changelog.removed_tables, vec!["Old"]);
+        assert_eq!(changelog.tables.len(), 1);
+
+        let map = &changelog.tables[0];
+        assert!(map.added_columns.is_empty());
+        assert!(map.removed_columns.is_empty());
+        assert_eq!(
+            map.renamed_columns,
+            vec![("Unk0".to_string(), "Expansion".to_string())]
+        );
+        assert_eq!(map.changed_columns, vec!["Flags"]);
+
+        let markdown = changelog.to_markdown();
+        assert!(markdown.contains("## New builds\n\n* `2.4.3.8606`\n* `3.3.5.12340`\n"));
+        assert!(markdown.contains("### `Map`\n\n* New builds: `2.4.3.8606`\n"));
+        assert!(markdown.contains("* Renamed columns: `Unk0` -> `Expansion`\n"));
+
+        assert!(Changelog::between(&new, &new).is_empty());
+    }
+}
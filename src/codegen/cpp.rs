@@ -0,0 +1,212 @@
+//! Modern C++ struct generation.
+//!
+//! Generates a packed struct with the same layout as a record of the `DBC` file using
+//! the fixed width integer types and `std::array`, followed by a `static_assert` of the record size.
+//! Strings are written as `std::uint32_t` offsets into the string block
+//! and `locstring`s as an array with a string for every locale of the version followed by a flags field if the version has one.
+//!
+//! Integer columns with an attached [`enum`](crate::enums) or with values listed in their comment,
+//! for example `// 0: none, 1: party`, are written as `enum class`es.
+
+use crate::codegen::naming::{CppNames, NameKind, NameMapper};
+use crate::codegen::{entry_enum, layout_definition, write_doc_lines, DocOptions};
+use crate::enums::EnumDefinition;
+use crate::error::CodegenError;
+use crate::writer::Writer;
+use crate::{DbdFile, ScalarType, Version};
+
+const fn cpp_type(ty: ScalarType) -> (&'static str, usize) {
+    match ty {
+        ScalarType::Int8 => ("std::int8_t", 1),
+        ScalarType::Int16 => ("std::int16_t", 2),
+        ScalarType::Int32 => ("std::int32_t", 4),
+        ScalarType::Int64 => ("std::int64_t", 8),
+        ScalarType::UInt8 => ("std::uint8_t", 1),
+        ScalarType::UInt16 => ("std::uint16_t", 2),
+        ScalarType::UInt32 => ("std::uint32_t", 4),
+        ScalarType::UInt64 => ("std::uint64_t", 8),
+        ScalarType::Float => ("float", 4),
+        ScalarType::LocString | ScalarType::String => ("std::uint32_t", 4),
+    }
+}
+
+fn write_enum(s: &mut Writer, e: &EnumDefinition, ty: &str, names: &impl NameMapper) {
+    s.wln(format!(
+        "enum class {} : {}",
+        names.map(NameKind::Type, &e.name),
+        ty
+    ));
+    s.wln("{");
+    for value in &e.values {
+        let declaration = format!(
+            "{} = {},",
+            names.map(NameKind::Field, &value.name),
+            value.value
+        );
+
+        if let Some(comment) = &value.comment {
+            s.wln(format!("    {:<56}// {}", declaration, comment));
+        } else {
+            s.wln(format!("    {}", declaration));
+        }
+    }
+    s.wln("};");
+    s.newline();
+}
+
+/// Generate a struct for the definition of `file` valid for `version`.
+///
+/// # Errors
+///
+/// Errors if there is no definition for `version`.
+pub fn generate(file: &DbdFile, version: &Version) -> Result<String, CodegenError> {
+    generate_with_names(file, version, &CppNames)
+}
+
+/// Same as [`generate`] but with the identifiers from `names` instead of [`CppNames`].
+///
+/// # Errors
+///
+/// Same as [`generate`].
+pub fn generate_with_names(
+    file: &DbdFile,
+    version: &Version,
+    names: &impl NameMapper,
+) -> Result<String, CodegenError> {
+    generate_with_options(file, version, names, &DocOptions::new())
+}
+
+/// Same as [`generate_with_names`] but also writes comments according to `docs`.
+///
+/// # Errors
+///
+/// Same as [`generate`].
+pub fn generate_with_options(
+    file: &DbdFile,
+    version: &Version,
+    names: &impl NameMapper,
+    docs: &DocOptions,
+) -> Result<String, CodegenError> {
+    let definition = layout_definition(file, version)?;
+    let table = file.table_name();
+    let name = names.map(NameKind::Type, table);
+
+    let mut s = Writer::new();
+    s.wln("#include <array>");
+    s.wln("#include <cstdint>");
+    s.newline();
+
+    let mut enums: Vec<String> = Vec::new();
+    for entry in &definition.entries {
+        if let Some(e) = entry_enum(table, entry) {
+            if !enums.contains(&e.name) {
+                write_enum(&mut s, &e, cpp_type(entry.ty.scalar()).0, names);
+                enums.push(e.name);
+            }
+        }
+    }
+
+    s.wln("#pragma pack(push, 1)");
+    s.wln(format!("struct {}Entry", name));
+    s.wln("{");
+
+    let mut size = 0;
+    for entry in &definition.entries {
+        let field = names.map(NameKind::Field, &entry.name);
+        let scalar = entry.ty.scalar();
+        let (ty, ty_size) = cpp_type(scalar);
+        let ty = entry_enum(table, entry)
+            .map_or_else(|| ty.to_string(), |e| names.map(NameKind::Type, &e.name));
+
+        write_doc_lines(&mut s, "    //", &docs.lines(entry));
+
+        let count = entry.ty.array_width();
+        if let ScalarType::LocString = scalar {
+            let strings = version.locstring_strings();
+
+            for i in 0..count.unwrap_or(1) {
+                let field = match count {
+                    Some(_) => format!("{}_{}", field, i),
+                    None => field.clone(),
+                };
+
+                if strings == 1 {
+                    s.wln(format!("    {} {};", ty, field));
+                } else {
+                    s.wln(format!("    std::array<{}, {}> {};", ty, strings, field));
+                }
+                size += strings * ty_size;
+
+                if version.locstring_has_flags() {
+                    s.wln(format!("    std::uint32_t {}_flags;", field));
+                    size += 4;
+                }
+            }
+
+            continue;
+        }
+
+        match count {
+            Some(count) => {
+                s.wln(format!("    std::array<{}, {}> {};", ty, count, field));
+                size += count * ty_size;
+            }
+            None => {
+                s.wln(format!("    {} {};", ty, field));
+                size += ty_size;
+            }
+        }
+    }
+
+    s.wln("};");
+    s.wln("#pragma pack(pop)");
+    s.newline();
+    s.wln(format!("static_assert(sizeof({}Entry) == {});", name, size));
+
+    Ok(s.inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codegen::cpp::generate;
+    use crate::enums::parse_enums;
+    use crate::{load_file_from_string, Version};
+
+    const CONTENTS: &str = "COLUMNS
+int ID
+string Directory
+locstring MapName_lang
+float Corpse
+int InstanceType // 0: none, 1: party, 2: raid
+int PvP
+
+BUILD 1.12.1.5875
+$id$ID<32>
+Directory
+MapName_lang
+Corpse[2]
+InstanceType<u8>
+PvP<u32>
+";
+
+    #[test]
+    fn struct_and_enums() {
+        let mut file = load_file_from_string(CONTENTS, "Map.dbd")
+            .unwrap()
+            .into_proper()
+            .unwrap();
+        file.attach_enums(
+            &parse_enums("ENUM PvpType Map::PvP\n0 Normal\n1 Pvp // World PvP\n").unwrap(),
+        );
+
+        let s = generate(&file, &Version::new(1, 12, 1, 5875)).unwrap();
+        assert!(s.contains("enum class MapInstanceType : std::uint8_t\n{\n    None = 0,\n    Party = 1,\n    Raid = 2,\n};\n"));
+        assert!(s.contains("enum class PvpType : std::uint32_t\n{\n    Normal = 0,\n"));
+        assert!(
+            s.contains("struct MapEntry\n{\n    std::int32_t ID;\n    std::uint32_t Directory;\n")
+        );
+        assert!(s.contains("    std::array<std::uint32_t, 8> MapName_lang;\n    std::uint32_t MapName_lang_flags;\n"));
+        assert!(s.contains("    std::array<float, 2> Corpse;\n    MapInstanceType InstanceType;\n    PvpType PvP;\n"));
+        assert!(s.ends_with("static_assert(sizeof(MapEntry) == 57);\n"));
+    }
+}
@@ -0,0 +1,216 @@
+//! Field numbers that stay the same when definitions change.
+//!
+//! Wire formats like [protobuf](https://protobuf.dev) and [FlatBuffers](https://flatbuffers.dev) identify fields by number,
+//! so numbering fields by their position in the definition breaks existing data
+//! as soon as a column is inserted upstream.
+//! [`FieldIds`] records the number of every column and is kept next to the generated code.
+//!
+//! The file format has a single column per line, as the table name, column name and id.
+//! Empty lines and lines starting with `#` are ignored:
+//!
+//! ```text
+//! # Field ids
+//! Map ID 0
+//! Map Directory 1
+//! ```
+//!
+//! Ids follow the rules of [FlatBuffers](https://flatbuffers.dev/flatbuffers_guide_writing_schema.html): they start at 0 and every table uses every id up to its highest one.
+//! Columns that are removed keep their id so it is never used by a different column,
+//! see [`flatbuffers::generate_with_field_ids`](crate::codegen::flatbuffers::generate_with_field_ids).
+
+use crate::enums::ColumnReference;
+use crate::error::FieldIdsError;
+use crate::DbdFile;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+
+/// Persisted field number of every column, see the [module documentation](self).
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
+pub struct FieldIds {
+    tables: BTreeMap<String, BTreeMap<String, u32>>,
+}
+
+impl FieldIds {
+    /// Creates an empty mapping.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses the file format described in the [module documentation](self).
+    ///
+    /// # Errors
+    ///
+    /// Errors if a line does not have a table, a column and an id,
+    /// if two columns of a table have the same id, or if an id of a table is skipped.
+    pub fn parse(contents: &str) -> Result<Self, FieldIdsError> {
+        let mut s = Self::new();
+
+        for (i, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = || FieldIdsError::InvalidLine {
+                line: i + 1,
+                contents: line.to_string(),
+            };
+
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let (table, column, id) = match parts.as_slice() {
+                [table, column, id] => (*table, *column, id.parse::<u32>().map_err(|_| invalid())?),
+                _ => return Err(invalid()),
+            };
+            let columns = s.tables.entry(table.to_string()).or_default();
+            if columns.values().any(|a| *a == id) {
+                return Err(FieldIdsError::DuplicateId {
+                    column: ColumnReference::new(table, column),
+                    id,
+                });
+            }
+            columns.insert(column.to_string(), id);
+        }
+
+        for (table, columns) in &s.tables {
+            let mut ids: Vec<u32> = columns.values().copied().collect();
+            ids.sort_unstable();
+
+            if let Some(id) = (0..).zip(ids).find_map(|(a, b)| (a != b).then(|| a)) {
+                return Err(FieldIdsError::MissingId {
+                    table: table.clone(),
+                    id,
+                });
+            }
+        }
+
+        Ok(s)
+    }
+
+    /// Reads and parses a field id file.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the file can not be read or parsed.
+    pub fn load(path: &Path) -> std::io::Result<Result<Self, FieldIdsError>> {
+        Ok(Self::parse(&std::fs::read_to_string(path)?))
+    }
+
+    /// Writes the field ids to a file, sorted by table and id.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the file can not be written.
+    pub fn write(&self, path: &Path) -> std::io::Result<()> {
+        std::fs::write(path, self.to_string())
+    }
+
+    /// Id of `column` in `table`, if it has been assigned.
+    pub fn get(&self, table: &str, column: &str) -> Option<u32> {
+        self.tables.get(table)?.get(column).copied()
+    }
+
+    /// Columns of `table` ordered by id, or an empty list if the table has no ids.
+    ///
+    /// The id of a column is its index in the list.
+    pub fn columns(&self, table: &str) -> Vec<&str> {
+        let mut columns: Vec<(&str, u32)> = self
+            .tables
+            .get(table)
+            .map(|a| {
+                a.iter()
+                    .map(|(column, id)| (column.as_str(), *id))
+                    .collect()
+            })
+            .unwrap_or_default();
+        columns.sort_by_key(|(_, id)| *id);
+
+        columns.into_iter().map(|(column, _)| column).collect()
+    }
+
+    /// Assigns ids to every column of `file` that does not have one.
+    ///
+    /// New columns are numbered after the highest id of the table in the order they first appear in the definitions.
+    /// Existing ids are never changed.
+    pub fn assign(&mut self, file: &DbdFile) {
+        let columns = self
+            .tables
+            .entry(file.table_name().to_string())
+            .or_default();
+        let mut next = columns.values().max().map_or(0, |a| a + 1);
+
+        for definition in &file.definitions {
+            for entry in &definition.entries {
+                if !columns.contains_key(&entry.name) {
+                    columns.insert(entry.name.clone(), next);
+                    next += 1;
+                }
+            }
+        }
+    }
+}
+
+impl Display for FieldIds {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (table, columns) in &self.tables {
+            let mut columns: Vec<(&String, &u32)> = columns.iter().collect();
+            columns.sort_by_key(|(_, id)| **id);
+
+            for (column, id) in columns {
+                writeln!(f, "{} {} {}", table, column, id)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codegen::field_ids::FieldIds;
+    use crate::error::FieldIdsError;
+    use crate::load_file_from_string;
+
+    #[test]
+    fn stable_ids() {
+        let mut ids =
+            FieldIds::parse("# Field ids\n\nMap ID 0\nMap Directory 1\nMap Removed 2\n").unwrap();
+
+        let file = load_file_from_string(
+            "COLUMNS\nint ID\nint Inserted\nstring Directory\n\nBUILD 1.12.1.5875\n$id$ID<32>\nInserted<32>\nDirectory\n",
+            "Map.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+        ids.assign(&file);
+
+        assert_eq!(ids.get("Map", "Directory"), Some(1));
+        assert_eq!(ids.get("Map", "Inserted"), Some(3));
+        assert_eq!(
+            ids.columns("Map"),
+            vec!["ID", "Directory", "Removed", "Inserted"]
+        );
+        assert_eq!(
+            ids.to_string(),
+            "Map ID 0\nMap Directory 1\nMap Removed 2\nMap Inserted 3\n"
+        );
+        assert_eq!(FieldIds::parse(&ids.to_string()).unwrap(), ids);
+
+        assert!(matches!(
+            FieldIds::parse("Map ID -1"),
+            Err(FieldIdsError::InvalidLine { line: 1, .. })
+        ));
+        assert!(matches!(
+            FieldIds::parse("Map ID 0\nMap Other 0"),
+            Err(FieldIdsError::DuplicateId { id: 0, .. })
+        ));
+        assert_eq!(
+            FieldIds::parse("Map ID 0\nMap Other 2"),
+            Err(FieldIdsError::MissingId {
+                table: "Map".to_string(),
+                id: 1
+            })
+        );
+    }
+}
@@ -0,0 +1,345 @@
+//! [FlatBuffers](https://flatbuffers.dev) schema generation.
+//!
+//! Generates a `.fbs` schema with a table for the rows of a definition
+//! and a root table with a vector of every row:
+//!
+//! ```text
+//! attribute "foreign_key";
+//!
+//! table Map {
+//!   id:int (key);
+//!   parent_map_id:int (foreign_key: "Map::ID");
+//!   corpse:[float];
+//! }
+//!
+//! table MapTable {
+//!   rows:[Map];
+//! }
+//!
+//! root_type MapTable;
+//! ```
+//!
+//! Arrays are written as vectors and `locstring`s as a vector with a string for every locale.
+//! Foreign keys are written as the integer id with a `foreign_key` attribute and the primary key with the `key` attribute.
+//!
+//! Fields are written in the order of the definition, so data written with a schema for one build
+//! can not be read with a schema for another.
+//! [`generate_with_field_ids`] writes fields in the order of [`FieldIds`] with an explicit `id` attribute instead,
+//! and keeps columns that are not in the definition as `deprecated` fields.
+//! Attached [`enums`](crate::enums) and [`overrides`](crate::overrides) are not used.
+
+use crate::codegen::field_ids::FieldIds;
+use crate::codegen::naming::{FlatBuffersNames, NameKind, NameMapper};
+use crate::codegen::{definition, write_doc_lines, DocOptions};
+use crate::error::CodegenError;
+use crate::writer::Writer;
+use crate::{DbdFile, Definition, Entry, ScalarType, Version};
+
+const fn scalar_type(ty: ScalarType) -> &'static str {
+    match ty {
+        ScalarType::Int8 => "byte",
+        ScalarType::Int16 => "short",
+        ScalarType::Int32 => "int",
+        ScalarType::Int64 => "long",
+        ScalarType::UInt8 => "ubyte",
+        ScalarType::UInt16 => "ushort",
+        ScalarType::UInt32 => "uint",
+        ScalarType::UInt64 => "ulong",
+        ScalarType::Float => "float",
+        ScalarType::String => "string",
+        ScalarType::LocString => "[string]",
+    }
+}
+
+fn field_type(entry: &Entry) -> Result<String, CodegenError> {
+    let ty = entry.ty.scalar();
+
+    Ok(match (ty, entry.ty.array_width()) {
+        (ScalarType::LocString, Some(_)) => {
+            return Err(CodegenError::UnsupportedType {
+                entry: entry.name.clone(),
+                ty: entry.ty.clone(),
+            });
+        }
+        (ty, Some(_)) => format!("[{}]", scalar_type(ty)),
+        (ty, None) => scalar_type(ty).to_string(),
+    })
+}
+
+/// Generate a schema for the definition of `file` valid for `version`.
+///
+/// # Errors
+///
+/// Errors if there is no definition for `version` or if an entry is an array of `locstring`s,
+/// since vectors of vectors are not supported.
+pub fn generate(file: &DbdFile, version: &Version) -> Result<String, CodegenError> {
+    generate_with_names(file, version, &FlatBuffersNames)
+}
+
+/// Same as [`generate`] but with the identifiers from `names` instead of [`FlatBuffersNames`].
+///
+/// # Errors
+///
+/// Same as [`generate`].
+pub fn generate_with_names(
+    file: &DbdFile,
+    version: &Version,
+    names: &impl NameMapper,
+) -> Result<String, CodegenError> {
+    generate_with_options(file, version, names, &DocOptions::new())
+}
+
+/// Same as [`generate_with_names`] but also writes comments according to `docs`.
+///
+/// # Errors
+///
+/// Same as [`generate`].
+pub fn generate_with_options(
+    file: &DbdFile,
+    version: &Version,
+    names: &impl NameMapper,
+    docs: &DocOptions,
+) -> Result<String, CodegenError> {
+    generate_schema(file, version, names, docs, None)
+}
+
+/// Same as [`generate_with_options`] but writes every field with its id from `ids`.
+///
+/// Columns with an id that are not in the definition are written as `deprecated` fields
+/// so the ids of the table stay contiguous.
+///
+/// # Errors
+///
+/// Same as [`generate`], and if an entry of the definition does not have an id in `ids`,
+/// see [`FieldIds::assign`].
+pub fn generate_with_field_ids(
+    file: &DbdFile,
+    version: &Version,
+    names: &impl NameMapper,
+    docs: &DocOptions,
+    ids: &FieldIds,
+) -> Result<String, CodegenError> {
+    generate_schema(file, version, names, docs, Some(ids))
+}
+
+fn generate_schema(
+    file: &DbdFile,
+    version: &Version,
+    names: &impl NameMapper,
+    docs: &DocOptions,
+    ids: Option<&FieldIds>,
+) -> Result<String, CodegenError> {
+    let definition = definition(file, version)?;
+    let name = names.map(NameKind::Type, file.table_name());
+
+    let mut s = Writer::new();
+
+    if definition
+        .entries
+        .iter()
+        .any(|a| a.ty.foreign_key().is_some())
+    {
+        s.wln("attribute \"foreign_key\";");
+        s.newline();
+    }
+
+    let fields = match ids {
+        Some(ids) => field_order(file, definition, ids)?,
+        None => definition.entries.iter().map(Field::Entry).collect(),
+    };
+
+    s.wln(format!("table {} {{", name));
+    for (id, field) in fields.into_iter().enumerate() {
+        let entry = match field {
+            Field::Entry(entry) => entry,
+            Field::Deprecated(column) => {
+                s.wln(format!(
+                    "  {}:{} (id: {}, deprecated);",
+                    names.map(NameKind::Field, column),
+                    deprecated_type(file, column),
+                    id
+                ));
+                continue;
+            }
+        };
+        let ty = field_type(entry)?;
+
+        let mut attributes = Vec::new();
+        if ids.is_some() {
+            attributes.push(format!("id: {}", id));
+        }
+        if entry.primary_key && entry.ty.array_width().is_none() {
+            attributes.push("key".to_string());
+        }
+        if let Some(key) = entry.ty.foreign_key() {
+            attributes.push(format!("foreign_key: \"{}::{}\"", key.database, key.column));
+        }
+        let attributes = if attributes.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", attributes.join(", "))
+        };
+
+        write_doc_lines(&mut s, "  ///", &docs.lines(entry));
+        s.wln(format!(
+            "  {}:{}{};",
+            names.map(NameKind::Field, &entry.name),
+            ty,
+            attributes
+        ));
+    }
+    s.wln("}");
+    s.newline();
+
+    s.wln(format!("table {}Table {{", name));
+    s.wln(format!("  rows:[{}];", name));
+    s.wln("}");
+    s.newline();
+
+    s.wln(format!("root_type {}Table;", name));
+
+    Ok(s.inner)
+}
+
+enum Field<'a> {
+    Entry(&'a Entry),
+    Deprecated(&'a str),
+}
+
+/// Fields of `definition` ordered by id, with columns that are not in the definition as [`Field::Deprecated`].
+fn field_order<'a>(
+    file: &DbdFile,
+    definition: &'a Definition,
+    ids: &'a FieldIds,
+) -> Result<Vec<Field<'a>>, CodegenError> {
+    if let Some(entry) = definition
+        .entries
+        .iter()
+        .find(|a| ids.get(file.table_name(), &a.name).is_none())
+    {
+        return Err(CodegenError::MissingFieldId(entry.name.clone()));
+    }
+
+    Ok(ids
+        .columns(file.table_name())
+        .into_iter()
+        .map(
+            |column| match definition.entries.iter().find(|a| a.name == column) {
+                Some(entry) => Field::Entry(entry),
+                None => Field::Deprecated(column),
+            },
+        )
+        .collect())
+}
+
+/// Type of the column in another definition, since deprecated fields still need a valid type.
+fn deprecated_type(file: &DbdFile, column: &str) -> String {
+    file.definitions
+        .iter()
+        .flat_map(|a| &a.entries)
+        .filter(|a| a.name == column)
+        .find_map(|a| field_type(a).ok())
+        .unwrap_or_else(|| scalar_type(ScalarType::UInt8).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codegen::field_ids::FieldIds;
+    use crate::codegen::flatbuffers::{generate, generate_with_field_ids};
+    use crate::codegen::naming::FlatBuffersNames;
+    use crate::codegen::DocOptions;
+    use crate::error::CodegenError;
+    use crate::{load_file_from_string, Version};
+
+    const CONTENTS: &str = "COLUMNS
+int ID
+string Directory
+locstring MapName_lang
+int<Map::ID> ParentMapID
+float Corpse
+int Type
+
+BUILD 1.12.1.5875
+$id$ID<32>
+Directory
+MapName_lang
+ParentMapID<u16>
+Corpse[2]
+Type<u8>
+
+BUILD 0.5.3.3368
+$id$ID<32>
+MapName_lang[2]
+";
+
+    #[test]
+    fn schema() {
+        let file = load_file_from_string(CONTENTS, "Map.dbd")
+            .unwrap()
+            .into_proper()
+            .unwrap();
+
+        assert_eq!(
+            generate(&file, &Version::new(1, 12, 1, 5875)).unwrap(),
+            "attribute \"foreign_key\";
+
+table Map {
+  id:int (key);
+  directory:string;
+  map_name_lang:[string];
+  parent_map_id:ushort (foreign_key: \"Map::ID\");
+  corpse:[float];
+  type:ubyte;
+}
+
+table MapTable {
+  rows:[Map];
+}
+
+root_type MapTable;
+"
+        );
+
+        assert!(generate(&file, &Version::new(0, 5, 3, 3368)).is_err());
+    }
+
+    #[test]
+    fn schema_with_field_ids() {
+        let file = load_file_from_string(CONTENTS, "Map.dbd")
+            .unwrap()
+            .into_proper()
+            .unwrap();
+        let version = Version::new(1, 12, 1, 5875);
+
+        let mut ids =
+            FieldIds::parse("Map ID 0\nMap Type 1\nMap Removed 2\nMap MapName_lang 3\n").unwrap();
+        assert_eq!(
+            generate_with_field_ids(&file, &version, &FlatBuffersNames, &DocOptions::new(), &ids),
+            Err(CodegenError::MissingFieldId("Directory".to_string()))
+        );
+
+        ids.assign(&file);
+        assert_eq!(
+            generate_with_field_ids(&file, &version, &FlatBuffersNames, &DocOptions::new(), &ids)
+                .unwrap(),
+            "attribute \"foreign_key\";
+
+table Map {
+  id:int (id: 0, key);
+  type:ubyte (id: 1);
+  removed:ubyte (id: 2, deprecated);
+  map_name_lang:[string] (id: 3);
+  directory:string (id: 4);
+  parent_map_id:ushort (id: 5, foreign_key: \"Map::ID\");
+  corpse:[float] (id: 6);
+}
+
+table MapTable {
+  rows:[Map];
+}
+
+root_type MapTable;
+"
+        );
+    }
+}
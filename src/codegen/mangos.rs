@@ -0,0 +1,324 @@
+//! [cMaNGOS](https://github.com/cmangos) and [vMaNGOS](https://github.com/vmangos/core) `DBC` struct and format string generation.
+//!
+//! The generated struct is packed in the same order as the `DBC` file
+//! and the format string uses the characters from the `DBCFileLoader`:
+//!
+//! * `n`, the primary key.
+//! * `i`, a 32 bit integer.
+//! * `b`, an 8 bit integer.
+//! * `f`, a float.
+//! * `s`, a string.
+//!
+//! Attached [`enums`](crate::enums) are written as C++ enums before the struct,
+//! with the integer type of the first column that uses them as the underlying type.
+//! Enum columns use the enum as the type while flag columns, and enum columns with a different integer type,
+//! keep the integer type so the struct matches the record.
+//!
+//! [`TypeOverride::Custom`] columns use the custom type and other overrides are written as comments.
+
+use crate::codegen::naming::{CppNames, NameKind, NameMapper};
+use crate::codegen::{layout_definition, write_doc_lines, DocOptions};
+use crate::enums::{EnumDefinition, EnumKind};
+use crate::error::CodegenError;
+use crate::overrides::TypeOverride;
+use crate::writer::Writer;
+use crate::{DbdFile, ScalarType, Version};
+use std::collections::BTreeMap;
+
+/// Generate the struct and format string for the definition of `file` valid for `version`.
+///
+/// `locstring`s are expanded into the amount of strings used by `version` followed by a flags field.
+///
+/// # Errors
+///
+/// Errors with [`CodegenError::NoDefinitionForVersion`] if there is no definition for `version`,
+/// with [`CodegenError::FilteredDefinition`] if the definition has filtered entries,
+/// or with [`CodegenError::UnsupportedType`] if an entry has a type the `DBCFileLoader` can not read,
+/// for example 16 or 64 bit integers.
+pub fn generate(file: &DbdFile, version: &Version) -> Result<String, CodegenError> {
+    generate_with_names(file, version, &CppNames)
+}
+
+/// Same as [`generate`] but with the identifiers from `names` instead of [`CppNames`].
+///
+/// # Errors
+///
+/// Same as [`generate`].
+pub fn generate_with_names(
+    file: &DbdFile,
+    version: &Version,
+    names: &impl NameMapper,
+) -> Result<String, CodegenError> {
+    generate_with_options(file, version, names, &DocOptions::new())
+}
+
+/// Same as [`generate_with_names`] but also writes comments according to `docs`.
+///
+/// # Errors
+///
+/// Same as [`generate`].
+pub fn generate_with_options(
+    file: &DbdFile,
+    version: &Version,
+    names: &impl NameMapper,
+    docs: &DocOptions,
+) -> Result<String, CodegenError> {
+    let definition = layout_definition(file, version)?;
+    let name = names.map(NameKind::Type, file.table_name());
+
+    let mut s = Writer::new();
+    let mut format = String::with_capacity(definition.entries.len());
+    let mut index = 0_usize;
+
+    let mut enum_types = BTreeMap::new();
+    for entry in &definition.entries {
+        if let (Some(e), Some((cpp_ty, _))) = (&entry.enumeration, cpp_type(entry.ty.scalar())) {
+            if entry.ty.scalar().is_integer() && !enum_types.contains_key(e.name.as_str()) {
+                write_enum(&mut s, e, cpp_ty);
+                enum_types.insert(e.name.as_str(), cpp_ty);
+            }
+        }
+    }
+
+    s.wln(format!("struct {}Entry", name));
+    s.wln("{");
+
+    for entry in &definition.entries {
+        let array_size = entry.ty.array_width();
+        let ty = entry.ty.scalar();
+        let field = names.map(NameKind::Field, &entry.name);
+        write_doc_lines(&mut s, "    //", &docs.lines(entry));
+
+        if let ScalarType::LocString = ty {
+            let strings = version.locstring_strings();
+            let count = strings * array_size.unwrap_or(1);
+
+            let declaration = if strings == 1 {
+                field_name(&field, array_size, "char*")
+            } else {
+                format!("char* {}[{}];", field, count)
+            };
+            write_field(&mut s, &declaration, index, count);
+            index += count;
+            format.push_str(&"s".repeat(count));
+
+            if version.locstring_has_flags() {
+                write_field(&mut s, &format!("uint32 {}_flags;", field), index, 1);
+                index += 1;
+                format.push('i');
+            }
+
+            continue;
+        }
+
+        let (cpp_ty, c) = match cpp_type(ty) {
+            Some(v) => v,
+            None => {
+                return Err(CodegenError::UnsupportedType {
+                    entry: entry.name.clone(),
+                    ty: entry.ty.clone(),
+                });
+            }
+        };
+        let c = if entry.primary_key && entry.inline {
+            'n'
+        } else {
+            c
+        };
+
+        let count = array_size.unwrap_or(1);
+        let (declaration_ty, comment) = match (&entry.type_override, &entry.enumeration) {
+            (Some(TypeOverride::Custom(name)), _) => (name.as_str(), None),
+            (Some(TypeOverride::Bool), _) => (cpp_ty, Some("bool".to_string())),
+            (Some(TypeOverride::Path), _) => (cpp_ty, Some("path".to_string())),
+            (Some(TypeOverride::Bitmask(name)), _) => {
+                (cpp_ty, Some(format!("bitmask of {}", name)))
+            }
+            (None, Some(e)) if entry.ty.scalar().is_integer() => match e.kind {
+                EnumKind::Enum if enum_types.get(e.name.as_str()) == Some(&cpp_ty) => {
+                    (e.name.as_str(), None)
+                }
+                EnumKind::Enum | EnumKind::Flags => (cpp_ty, Some(e.name.clone())),
+            },
+            _ => (cpp_ty, None),
+        };
+        let declaration = field_name(&field, array_size, declaration_ty);
+        let declaration = if let Some(comment) = comment {
+            format!("{:<40}// {}", declaration, comment)
+        } else {
+            declaration
+        };
+        write_field(&mut s, &declaration, index, count);
+        index += count;
+        for _ in 0..count {
+            format.push(c);
+        }
+    }
+
+    s.wln("};");
+    s.newline();
+    s.wln(format!("char const {}Entryfmt[] = \"{}\";", name, format));
+
+    Ok(s.inner)
+}
+
+const fn cpp_type(ty: ScalarType) -> Option<(&'static str, char)> {
+    Some(match ty {
+        ScalarType::Int8 => ("int8", 'b'),
+        ScalarType::UInt8 => ("uint8", 'b'),
+        ScalarType::Int32 => ("int32", 'i'),
+        ScalarType::UInt32 => ("uint32", 'i'),
+        ScalarType::Float => ("float", 'f'),
+        ScalarType::String => ("char*", 's'),
+        _ => return None,
+    })
+}
+
+fn write_enum(s: &mut Writer, e: &EnumDefinition, cpp_ty: &str) {
+    s.wln(format!("enum {} : {}", e.name, cpp_ty));
+    s.wln("{");
+
+    for value in &e.values {
+        let declaration = match e.kind {
+            EnumKind::Enum => format!("{} = {},", value.name, value.value),
+            EnumKind::Flags => format!("{} = 0x{:X},", value.name, value.value),
+        };
+
+        if let Some(comment) = &value.comment {
+            s.wln(format!("    {:<56}// {}", declaration, comment));
+        } else {
+            s.wln(format!("    {}", declaration));
+        }
+    }
+
+    s.wln("};");
+    s.newline();
+}
+
+fn field_name(name: &str, array_size: Option<usize>, ty: &str) -> String {
+    if let Some(array_size) = array_size {
+        format!("{} {}[{}];", ty, name, array_size)
+    } else {
+        format!("{} {};", ty, name)
+    }
+}
+
+fn write_field(s: &mut Writer, declaration: &str, index: usize, count: usize) {
+    let index = if count == 1 {
+        index.to_string()
+    } else {
+        format!("{}-{}", index, index + count - 1)
+    };
+
+    s.wln(format!("    {:<56}// {}", declaration, index));
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codegen::mangos::{generate, generate_with_options};
+    use crate::codegen::naming::CppNames;
+    use crate::codegen::DocOptions;
+    use crate::enums::parse_enums;
+    use crate::overrides::TypeOverrides;
+    use crate::{load_file_from_string, Version};
+
+    const CONTENTS: &str = "COLUMNS
+int ID
+string Directory
+locstring MapName_lang
+float Corpse
+int Unused // Always [[Zero|0]]
+
+BUILD 1.12.1.5875
+$id$ID<32>
+Directory
+MapName_lang
+Corpse[2]
+Unused<u8>
+
+BUILD 3.3.5.12340
+$id$ID<32>
+Unused<16>
+";
+
+    #[test]
+    fn struct_and_format() {
+        let file = load_file_from_string(CONTENTS, "Map.dbd")
+            .unwrap()
+            .into_proper()
+            .unwrap();
+        let s = generate(&file, &Version::new(1, 12, 1, 5875)).unwrap();
+
+        assert!(s.contains("struct MapEntry"));
+        assert!(s.contains("char* MapName_lang[8];"));
+        assert!(s.contains("// 2-9"));
+        assert!(s.contains("uint32 MapName_lang_flags;"));
+        assert!(s.contains("char const MapEntryfmt[] = \"nsssssssssiffb\";"));
+
+        assert!(generate(&file, &Version::new(3, 3, 5, 12340)).is_err());
+    }
+
+    #[test]
+    fn enums() {
+        let mut file = load_file_from_string(CONTENTS, "Map.dbd")
+            .unwrap()
+            .into_proper()
+            .unwrap();
+        file.attach_enums(
+            &parse_enums("ENUM MapType Map::Unused\n0 Common\n1 Instance // Dungeons\n").unwrap(),
+        );
+        let s = generate(&file, &Version::new(1, 12, 1, 5875)).unwrap();
+
+        assert!(s.starts_with("enum MapType : uint8\n{\n    Common = 0,\n"));
+        assert!(s.contains("Instance = 1,"));
+        assert!(s.contains("    MapType Unused;"));
+    }
+
+    #[test]
+    fn enum_underlying_type() {
+        let mut file = load_file_from_string(
+            "COLUMNS\nint ID\nint Type\nint Other\n\nBUILD 1.12.1.5875\n$id$ID<32>\nType<32>\nOther<u8>\n",
+            "Map.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+        file.attach_enums(&parse_enums("ENUM MapType Map::Type, Map::Other\n0 Common\n").unwrap());
+        let s = generate(&file, &Version::new(1, 12, 1, 5875)).unwrap();
+
+        assert!(s.starts_with("enum MapType : int32\n"));
+        assert!(s.contains("    MapType Type;"));
+        assert!(s.contains(&format!("    {:<40}// MapType", "uint8 Other;")));
+    }
+
+    #[test]
+    fn type_overrides() {
+        let file = load_file_from_string(CONTENTS, "Map.dbd")
+            .unwrap()
+            .into_proper_with_overrides(
+                &TypeOverrides::parse("Map::Unused custom MapType\nMap::Directory path\n").unwrap(),
+            )
+            .unwrap();
+        let s = generate(&file, &Version::new(1, 12, 1, 5875)).unwrap();
+
+        assert!(s.contains("    MapType Unused;"));
+        assert!(s.contains(&format!("    {:<40}// path", "char* Directory;")));
+    }
+
+    #[test]
+    fn comments() {
+        let file = load_file_from_string(CONTENTS, "Map.dbd")
+            .unwrap()
+            .into_proper()
+            .unwrap();
+        let version = Version::new(1, 12, 1, 5875);
+        assert!(!generate(&file, &version).unwrap().contains("Always"));
+
+        let docs = DocOptions {
+            strip_wiki_links: true,
+            ..DocOptions::all()
+        };
+        let s = generate_with_options(&file, &version, &CppNames, &docs).unwrap();
+        assert!(s.contains("    // Always 0\n    uint8 Unused;"));
+    }
+}
@@ -0,0 +1,291 @@
+//! Code generation from parsed definitions.
+//!
+//! Every backend generates code for the [`Definition`] of a [`DbdFile`] that is valid for a specific [`Version`].
+//!
+//! The [`wow_dbc`], [`zig`], [`cpp`], [`trinitycore`] and [`mangos`] backends describe the exact record layout,
+//! so they return [`CodegenError::FilteredDefinition`] for definitions where entries were removed with
+//! [`DbdFile::filter_entries`].
+//!
+//! Every backend has a `generate_with_names` function that takes a [`NameMapper`](naming::NameMapper)
+//! for customizing the identifiers in the generated code, see [`naming`],
+//! and a `generate_with_options` function that also takes [`DocOptions`] for writing comments.
+
+use crate::comment_hints::CommentHint;
+use crate::enums::{EnumDefinition, EnumKind, EnumValue};
+use crate::error::CodegenError;
+use crate::writer::Writer;
+use crate::{DbdFile, Definition, Entry, Version};
+
+pub mod cpp;
+pub mod field_ids;
+pub mod flatbuffers;
+pub mod mangos;
+pub mod naming;
+pub mod sql;
+pub mod trinitycore;
+pub mod wow_dbc;
+pub mod zig;
+
+pub(crate) fn definition<'a>(
+    file: &'a DbdFile,
+    version: &Version,
+) -> Result<&'a Definition, CodegenError> {
+    file.specific_version(version)
+        .ok_or(CodegenError::NoDefinitionForVersion(*version))
+}
+
+/// Same as [`definition`], but for backends whose output must match the record layout exactly.
+pub(crate) fn layout_definition<'a>(
+    file: &'a DbdFile,
+    version: &Version,
+) -> Result<&'a Definition, CodegenError> {
+    let definition = definition(file, version)?;
+
+    if definition.is_filtered() {
+        return Err(CodegenError::FilteredDefinition(*version));
+    }
+
+    Ok(definition)
+}
+
+/// Enum for an integer `entry` of `table`, either attached with [`enums`](crate::enums)
+/// or from the values listed in its comments, see [`CommentHint::EnumValues`].
+///
+/// Enums from comments are named after the table and column and the values are converted into identifiers.
+/// Only [`EnumKind::Enum`] is returned since flags can not be named from comments.
+pub(crate) fn entry_enum(table: &str, entry: &Entry) -> Option<EnumDefinition> {
+    if !entry.ty.scalar().is_integer() {
+        return None;
+    }
+
+    if let Some(e) = &entry.enumeration {
+        return if e.kind == EnumKind::Enum {
+            Some(e.clone())
+        } else {
+            None
+        };
+    }
+
+    let values = entry.comment_hints().into_iter().find_map(|a| match a {
+        CommentHint::EnumValues(values) => Some(values),
+        _ => None,
+    })?;
+
+    let mut e = EnumDefinition {
+        name: format!("{}{}", table, entry.name),
+        kind: EnumKind::Enum,
+        columns: Vec::new(),
+        values: Vec::with_capacity(values.len()),
+    };
+    for (value, name) in values {
+        let name = value_identifier(&name);
+        if !e.values.iter().any(|a| a.name == name || a.value == value) {
+            e.values.push(EnumValue {
+                name,
+                value,
+                comment: None,
+            });
+        }
+    }
+
+    Some(e)
+}
+
+/// Converts free text like `party or raid` into `PartyOrRaid`.
+fn value_identifier(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+
+    for word in s.split(|c: char| !c.is_ascii_alphanumeric()) {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            out.push(first.to_ascii_uppercase());
+            out.extend(chars);
+        }
+    }
+
+    if out.chars().next().map_or(true, |a| a.is_ascii_digit()) {
+        out.insert_str(0, "Value");
+    }
+
+    out
+}
+
+/// Options for writing comments from `.dbd` files as comments in generated code.
+///
+/// The default writes no comments.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub struct DocOptions {
+    /// Write [`Entry::column_comment`], the comment under `COLUMNS`.
+    pub column_comments: bool,
+    /// Write [`Entry::comment`], the comment on the entry in the definition.
+    pub entry_comments: bool,
+    /// Replace wiki links like `[[Page]]` and `[[Page|text]]` with their text.
+    pub strip_wiki_links: bool,
+    /// Maximum width of a line of comment text, not including the comment marker and indentation.
+    ///
+    /// `None` writes every comment on a single line.
+    pub wrap_width: Option<usize>,
+}
+
+impl DocOptions {
+    /// Creates options that write no comments.
+    pub const fn new() -> Self {
+        Self {
+            column_comments: false,
+            entry_comments: false,
+            strip_wiki_links: false,
+            wrap_width: None,
+        }
+    }
+
+    /// Creates options that write every comment as it is in the `.dbd` file.
+    pub const fn all() -> Self {
+        Self {
+            column_comments: true,
+            entry_comments: true,
+            strip_wiki_links: false,
+            wrap_width: None,
+        }
+    }
+
+    /// Lines of comment text for `entry`.
+    ///
+    /// The column comment is written before the entry comment with an empty line between them.
+    pub fn lines(&self, entry: &Entry) -> Vec<String> {
+        let comments = [
+            (self.column_comments, &entry.column_comment),
+            (self.entry_comments, &entry.comment),
+        ];
+
+        let mut lines = Vec::new();
+        for comment in comments
+            .iter()
+            .filter(|(include, _)| *include)
+            .filter_map(|(_, comment)| comment.as_ref())
+        {
+            let comment = if self.strip_wiki_links {
+                strip_wiki_links(comment)
+            } else {
+                comment.clone()
+            };
+
+            if !lines.is_empty() {
+                lines.push(String::new());
+            }
+            match self.wrap_width {
+                Some(width) => lines.extend(wrap(&comment, width)),
+                None => lines.push(comment),
+            }
+        }
+
+        lines
+    }
+}
+
+/// Writes `lines` with `prefix` before every line, without trailing whitespace for empty lines.
+pub(crate) fn write_doc_lines(s: &mut Writer, prefix: &str, lines: &[String]) {
+    for line in lines {
+        if line.is_empty() {
+            s.wln(prefix);
+        } else {
+            s.wln(format!("{} {}", prefix, line));
+        }
+    }
+}
+
+fn strip_wiki_links(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(start) = rest.find("[[") {
+        let end = match rest[start..].find("]]") {
+            Some(end) => start + end,
+            None => break,
+        };
+
+        out.push_str(&rest[..start]);
+        let link = &rest[start + 2..end];
+        out.push_str(link.rsplit('|').next().unwrap_or(link));
+        rest = &rest[end + 2..];
+    }
+
+    out.push_str(rest);
+    out
+}
+
+fn wrap(s: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+
+    for word in s.split_whitespace() {
+        if !line.is_empty() && line.len() + 1 + word.len() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+
+    if !line.is_empty() {
+        lines.push(line);
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codegen::{entry_enum, DocOptions};
+    use crate::{load_file_from_string, DbdFile};
+
+    #[test]
+    fn doc_lines() {
+        let file: DbdFile = load_file_from_string(
+            "COLUMNS\nint Flags // See [[Map#Flags|map flags]] and [[AreaTable]]\n\nBUILD 1.12.1.5875\nFlags<32> // Only set for raids\n",
+            "Map.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+        let entry = &file.definitions[0].entries[0];
+
+        assert!(DocOptions::new().lines(entry).is_empty());
+        assert_eq!(
+            DocOptions::all().lines(entry),
+            vec![
+                "See [[Map#Flags|map flags]] and [[AreaTable]]",
+                "",
+                "Only set for raids"
+            ]
+        );
+
+        let options = DocOptions {
+            entry_comments: false,
+            strip_wiki_links: true,
+            wrap_width: Some(20),
+            ..DocOptions::all()
+        };
+        assert_eq!(options.lines(entry), vec!["See map flags and", "AreaTable"]);
+    }
+
+    #[test]
+    fn enum_from_comment() {
+        let file: DbdFile = load_file_from_string(
+            "COLUMNS\nint InstanceType // 0: none, 1: party or raid, 2: 3 players\nfloat Radius // 0: none, 1: all\n\nBUILD 1.12.1.5875\nInstanceType<8>\nRadius\n",
+            "Map.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+        let entries = &file.definitions[0].entries;
+
+        let e = entry_enum("Map", &entries[0]).unwrap();
+        assert_eq!(e.name, "MapInstanceType");
+        assert_eq!(
+            e.values.iter().map(|a| a.name.as_str()).collect::<Vec<_>>(),
+            vec!["None", "PartyOrRaid", "Value3Players"]
+        );
+        assert!(entry_enum("Map", &entries[1]).is_none());
+    }
+}
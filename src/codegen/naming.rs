@@ -0,0 +1,428 @@
+//! Identifiers used in generated code.
+//!
+//! Every backend passes table, column and flag names through a [`NameMapper`] before writing them.
+//! [`RustNames`], [`CppNames`], [`ZigNames`] and [`FlatBuffersNames`] are the defaults for the backends
+//! and [`NameOverrides`] replaces individual names on top of any other mapper.
+//!
+//! ```rust
+//! use wowdbdefs_rs::codegen::naming::{NameKind, NameMapper, NameOverrides, RustNames};
+//!
+//! let names = NameOverrides::new(RustNames).with(NameKind::Field, "ID", "entry");
+//! assert_eq!(names.map(NameKind::Field, "ID"), "entry");
+//! assert_eq!(names.map(NameKind::Field, "UIMapID"), "ui_map_id");
+//! assert_eq!(names.map(NameKind::Field, "Type"), "ty");
+//! ```
+
+use std::collections::BTreeMap;
+
+/// What a name is used for in generated code.
+#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum NameKind {
+    /// Type named after a table, used as the base of for example row and key types.
+    Type,
+    /// Field of a column or getter of a flag.
+    Field,
+    /// Module or file named after a table.
+    Module,
+    /// Constant named after a table.
+    Constant,
+}
+
+/// Converts names from `.dbd` files into identifiers for generated code.
+pub trait NameMapper {
+    /// Identifier for `name` used as `kind`.
+    ///
+    /// `name` is a table name without `.dbd`, a column name or a flag name.
+    fn map(&self, kind: NameKind, name: &str) -> String;
+}
+
+impl<T: NameMapper + ?Sized> NameMapper for &T {
+    fn map(&self, kind: NameKind, name: &str) -> String {
+        (**self).map(kind, name)
+    }
+}
+
+/// Keywords that can not be used as identifiers in Rust.
+pub const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while",
+];
+
+/// Keywords that can not be used as identifiers in C++.
+pub const CPP_KEYWORDS: &[&str] = &[
+    "alignas",
+    "alignof",
+    "and",
+    "asm",
+    "auto",
+    "bool",
+    "break",
+    "case",
+    "catch",
+    "char",
+    "class",
+    "const",
+    "constexpr",
+    "continue",
+    "default",
+    "delete",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "explicit",
+    "export",
+    "extern",
+    "false",
+    "float",
+    "for",
+    "friend",
+    "goto",
+    "if",
+    "inline",
+    "int",
+    "long",
+    "mutable",
+    "namespace",
+    "new",
+    "noexcept",
+    "not",
+    "nullptr",
+    "operator",
+    "or",
+    "private",
+    "protected",
+    "public",
+    "register",
+    "return",
+    "short",
+    "signed",
+    "sizeof",
+    "static",
+    "struct",
+    "switch",
+    "template",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typedef",
+    "typename",
+    "union",
+    "unsigned",
+    "using",
+    "virtual",
+    "void",
+    "volatile",
+    "while",
+    "xor",
+];
+
+/// Keywords and type names that can not be used as identifiers in [FlatBuffers](https://flatbuffers.dev) schemas.
+pub const FLATBUFFERS_KEYWORDS: &[&str] = &[
+    "attribute",
+    "bool",
+    "byte",
+    "double",
+    "enum",
+    "false",
+    "file_extension",
+    "file_identifier",
+    "float",
+    "include",
+    "int",
+    "long",
+    "namespace",
+    "root_type",
+    "rpc_service",
+    "short",
+    "string",
+    "struct",
+    "table",
+    "true",
+    "ubyte",
+    "uint",
+    "ulong",
+    "union",
+    "ushort",
+];
+
+/// Keywords that can not be used as identifiers in [Zig](https://ziglang.org) without `@""`.
+pub const ZIG_KEYWORDS: &[&str] = &[
+    "addrspace",
+    "align",
+    "allowzero",
+    "and",
+    "anyframe",
+    "anytype",
+    "asm",
+    "async",
+    "await",
+    "break",
+    "callconv",
+    "catch",
+    "comptime",
+    "const",
+    "continue",
+    "defer",
+    "else",
+    "enum",
+    "errdefer",
+    "error",
+    "export",
+    "extern",
+    "fn",
+    "for",
+    "if",
+    "inline",
+    "linksection",
+    "noalias",
+    "noinline",
+    "nosuspend",
+    "opaque",
+    "or",
+    "orelse",
+    "packed",
+    "pub",
+    "resume",
+    "return",
+    "struct",
+    "suspend",
+    "switch",
+    "test",
+    "threadlocal",
+    "try",
+    "type",
+    "union",
+    "unreachable",
+    "usingnamespace",
+    "var",
+    "volatile",
+    "while",
+];
+
+/// Rust naming conventions.
+///
+/// Types are `PascalCase`, fields and modules are `snake_case` and constants are `SCREAMING_SNAKE_CASE`.
+/// Fields named `type` become `ty` and other keywords are written as raw identifiers.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub struct RustNames;
+
+impl NameMapper for RustNames {
+    fn map(&self, kind: NameKind, name: &str) -> String {
+        let name = match kind {
+            NameKind::Type => to_pascal_case(name),
+            NameKind::Field | NameKind::Module => to_snake_case(name),
+            NameKind::Constant => to_screaming_snake_case(name),
+        };
+
+        match name.as_str() {
+            "type" => "ty".to_string(),
+            "crate" | "self" | "Self" | "super" => format!("{}_", name),
+            n if RUST_KEYWORDS.contains(&n) => format!("r#{}", name),
+            _ => name,
+        }
+    }
+}
+
+/// C++ naming conventions of the emulator cores.
+///
+/// Types, fields and modules keep the names from the `.dbd` files
+/// and constants are `SCREAMING_SNAKE_CASE`.
+/// Keywords get a `_` suffix.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub struct CppNames;
+
+impl NameMapper for CppNames {
+    fn map(&self, kind: NameKind, name: &str) -> String {
+        let name = match kind {
+            NameKind::Type | NameKind::Field | NameKind::Module => name.to_string(),
+            NameKind::Constant => to_screaming_snake_case(name),
+        };
+
+        if CPP_KEYWORDS.contains(&name.as_str()) {
+            format!("{}_", name)
+        } else {
+            name
+        }
+    }
+}
+
+/// [FlatBuffers](https://flatbuffers.dev) naming conventions.
+///
+/// Tables are `PascalCase`, fields are `snake_case` and constants are `SCREAMING_SNAKE_CASE`.
+/// Keywords get a `_` suffix.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub struct FlatBuffersNames;
+
+impl NameMapper for FlatBuffersNames {
+    fn map(&self, kind: NameKind, name: &str) -> String {
+        let name = match kind {
+            NameKind::Type => to_pascal_case(name),
+            NameKind::Field | NameKind::Module => to_snake_case(name),
+            NameKind::Constant => to_screaming_snake_case(name),
+        };
+
+        if FLATBUFFERS_KEYWORDS.contains(&name.as_str()) {
+            format!("{}_", name)
+        } else {
+            name
+        }
+    }
+}
+
+/// [Zig](https://ziglang.org) naming conventions.
+///
+/// Types are `PascalCase`, fields and modules are `snake_case` and constants are `SCREAMING_SNAKE_CASE`.
+/// Keywords are written as `@"name"`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub struct ZigNames;
+
+impl NameMapper for ZigNames {
+    fn map(&self, kind: NameKind, name: &str) -> String {
+        let name = match kind {
+            NameKind::Type => to_pascal_case(name),
+            NameKind::Field | NameKind::Module => to_snake_case(name),
+            NameKind::Constant => to_screaming_snake_case(name),
+        };
+
+        if ZIG_KEYWORDS.contains(&name.as_str()) {
+            format!("@\"{}\"", name)
+        } else {
+            name
+        }
+    }
+}
+
+/// [`NameMapper`] that replaces individual names and uses another mapper for the rest.
+///
+/// Replacements are written as is, without any case conversion or keyword escaping.
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct NameOverrides<M> {
+    inner: M,
+    overrides: BTreeMap<(NameKind, String), String>,
+}
+
+impl<M: NameMapper> NameOverrides<M> {
+    /// Creates overrides on top of `inner` without any replacements.
+    pub fn new(inner: M) -> Self {
+        Self {
+            inner,
+            overrides: BTreeMap::new(),
+        }
+    }
+
+    /// Replaces `name` used as `kind` with `replacement`.
+    pub fn with(
+        mut self,
+        kind: NameKind,
+        name: impl Into<String>,
+        replacement: impl Into<String>,
+    ) -> Self {
+        self.insert(kind, name, replacement);
+        self
+    }
+
+    /// Replaces `name` used as `kind` with `replacement`.
+    pub fn insert(
+        &mut self,
+        kind: NameKind,
+        name: impl Into<String>,
+        replacement: impl Into<String>,
+    ) {
+        self.overrides
+            .insert((kind, name.into()), replacement.into());
+    }
+}
+
+impl<M: NameMapper> NameMapper for NameOverrides<M> {
+    fn map(&self, kind: NameKind, name: &str) -> String {
+        match self.overrides.get(&(kind, name.to_string())) {
+            Some(replacement) => replacement.clone(),
+            None => self.inner.map(kind, name),
+        }
+    }
+}
+
+/// Converts `PascalCase` names like `MapName_lang` into `map_name_lang`.
+pub fn to_snake_case(s: &str) -> String {
+    let chars: Vec<char> = s.chars().collect();
+    let mut out = String::with_capacity(s.len() + 4);
+
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() && i != 0 {
+            let previous = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).map_or(false, |c| c.is_ascii_lowercase());
+
+            if previous.is_ascii_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_ascii_uppercase() && next_is_lower)
+            {
+                out.push('_');
+            }
+        }
+
+        out.push(c.to_ascii_lowercase());
+    }
+
+    out
+}
+
+/// Converts names like `MapName_lang` into `MAP_NAME_LANG`.
+pub fn to_screaming_snake_case(s: &str) -> String {
+    to_snake_case(s).to_ascii_uppercase()
+}
+
+/// Converts names like `MapName_lang` into `MapNameLang`.
+///
+/// Names that are already `PascalCase` are unchanged.
+pub fn to_pascal_case(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+
+    for part in s.split('_') {
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            out.push(first.to_ascii_uppercase());
+            out.extend(chars);
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codegen::naming::{
+        to_pascal_case, to_snake_case, CppNames, NameKind, NameMapper, RustNames, ZigNames,
+    };
+
+    #[test]
+    fn snake_case() {
+        assert_eq!(to_snake_case("MapName_lang"), "map_name_lang");
+        assert_eq!(to_snake_case("UIMapID"), "ui_map_id");
+        assert_eq!(to_snake_case("Field_9_0_1_034"), "field_9_0_1_034");
+        assert_eq!(to_snake_case("ID"), "id");
+    }
+
+    #[test]
+    fn pascal_case() {
+        assert_eq!(
+            to_pascal_case("SpellItemEnchantment"),
+            "SpellItemEnchantment"
+        );
+        assert_eq!(to_pascal_case("MapName_lang"), "MapNameLang");
+        assert_eq!(to_pascal_case("map"), "Map");
+    }
+
+    #[test]
+    fn keywords() {
+        assert_eq!(RustNames.map(NameKind::Field, "Loop"), "r#loop");
+        assert_eq!(RustNames.map(NameKind::Field, "Self"), "self_");
+        assert_eq!(RustNames.map(NameKind::Module, "Map"), "map");
+        assert_eq!(CppNames.map(NameKind::Field, "class"), "class_");
+        assert_eq!(CppNames.map(NameKind::Constant, "SpellItem"), "SPELL_ITEM");
+        assert_eq!(ZigNames.map(NameKind::Field, "Type"), "@\"type\"");
+    }
+}
@@ -0,0 +1,304 @@
+//! SQL migration scripts between the definitions of two builds.
+//!
+//! Every table is assumed to have a column for every entry, with arrays written as a column for every element
+//! named after the entry and the index, like `Corpse_0` and `Corpse_1`:
+//!
+//! ```text
+//! ALTER TABLE `Map` DROP COLUMN `Unk0`;
+//! ALTER TABLE `Map` RENAME COLUMN `MapName` TO `MapName_lang`;
+//! ALTER TABLE `Map` MODIFY COLUMN `Flags` INT UNSIGNED NOT NULL DEFAULT 0;
+//! ALTER TABLE `Map` ADD COLUMN `Corpse_2` FLOAT NOT NULL DEFAULT 0;
+//! ```
+//!
+//! The migration goes through every definition between the two builds,
+//! and columns are paired between consecutive definitions with [`map_columns`] so renamed columns keep their data.
+//! For every step columns are dropped first, then renamed, then changed and then added in the order of the new definition.
+
+use crate::codegen::definition;
+use crate::error::CodegenError;
+use crate::mapping::map_columns;
+use crate::writer::Writer;
+use crate::{DbdFile, Definition, ScalarType, Version};
+
+/// SQL dialect of a migration script.
+#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum SqlDialect {
+    /// [MySQL](https://www.mysql.com) and [MariaDB](https://mariadb.org), used by the emulator cores.
+    MySql,
+    /// [PostgreSQL](https://www.postgresql.org).
+    Postgres,
+    /// [SQLite](https://www.sqlite.org).
+    ///
+    /// The type of a column can not be changed,
+    /// so changed columns are written as comments that need to be migrated by hand.
+    Sqlite,
+}
+
+impl SqlDialect {
+    fn quote(self, name: &str) -> String {
+        match self {
+            SqlDialect::MySql => format!("`{}`", name.replace('`', "``")),
+            SqlDialect::Postgres | SqlDialect::Sqlite => {
+                format!("\"{}\"", name.replace('"', "\"\""))
+            }
+        }
+    }
+
+    const fn column_type(self, ty: ScalarType) -> &'static str {
+        match self {
+            SqlDialect::MySql => match ty {
+                ScalarType::Int8 => "TINYINT",
+                ScalarType::Int16 => "SMALLINT",
+                ScalarType::Int32 => "INT",
+                ScalarType::Int64 => "BIGINT",
+                ScalarType::UInt8 => "TINYINT UNSIGNED",
+                ScalarType::UInt16 => "SMALLINT UNSIGNED",
+                ScalarType::UInt32 => "INT UNSIGNED",
+                ScalarType::UInt64 => "BIGINT UNSIGNED",
+                ScalarType::Float => "FLOAT",
+                ScalarType::LocString | ScalarType::String => "TEXT",
+            },
+            SqlDialect::Postgres => match ty {
+                ScalarType::Int8 | ScalarType::Int16 | ScalarType::UInt8 => "SMALLINT",
+                ScalarType::Int32 | ScalarType::UInt16 => "INTEGER",
+                ScalarType::Int64 | ScalarType::UInt32 => "BIGINT",
+                ScalarType::UInt64 => "NUMERIC(20)",
+                ScalarType::Float => "REAL",
+                ScalarType::LocString | ScalarType::String => "TEXT",
+            },
+            SqlDialect::Sqlite => match ty {
+                ScalarType::Float => "REAL",
+                ScalarType::LocString | ScalarType::String => "TEXT",
+                _ => "INTEGER",
+            },
+        }
+    }
+}
+
+/// Columns of `definition` with their type, arrays expanded into a column for every element.
+fn columns(definition: &Definition) -> Vec<(String, ScalarType)> {
+    columns_with_names(definition, |name| name)
+}
+
+/// Same as [`columns`] but with the entries named by `name`.
+fn columns_with_names<'a>(
+    definition: &'a Definition,
+    name: impl Fn(&'a str) -> &'a str,
+) -> Vec<(String, ScalarType)> {
+    let mut columns = Vec::new();
+
+    for entry in &definition.entries {
+        let ty = entry.ty.scalar();
+        let name = name(&entry.name);
+        match entry.ty.array_width() {
+            Some(width) => columns.extend((0..width).map(|i| (format!("{}_{}", name, i), ty))),
+            None => columns.push((name.to_string(), ty)),
+        }
+    }
+
+    columns
+}
+
+fn column_definition(dialect: SqlDialect, name: &str, ty: ScalarType) -> String {
+    let default = match ty {
+        ScalarType::LocString | ScalarType::String => "''",
+        _ => "0",
+    };
+
+    format!(
+        "{} {} NOT NULL DEFAULT {}",
+        dialect.quote(name),
+        dialect.column_type(ty),
+        default
+    )
+}
+
+/// Generate `ALTER TABLE` statements that change the table of `file` from the definition valid for `from`
+/// to the definition valid for `to`, going through every definition in between.
+///
+/// Returns an empty string if the columns are the same.
+///
+/// # Errors
+///
+/// Errors if there is no definition for `from` or `to`.
+pub fn generate_migration(
+    file: &DbdFile,
+    from: &Version,
+    to: &Version,
+    dialect: SqlDialect,
+) -> Result<String, CodegenError> {
+    let first = definition(file, from)?;
+    let last = definition(file, to)?;
+
+    let mut steps = if from <= to {
+        file.definitions_between(from, to)
+    } else {
+        let mut steps = file.definitions_between(to, from);
+        steps.reverse();
+        steps
+    };
+    steps.retain(|a| !std::ptr::eq(*a, first) && !std::ptr::eq(*a, last));
+    steps.insert(0, first);
+    steps.push(last);
+
+    let table = dialect.quote(file.table_name());
+    let mut s = Writer::new();
+
+    for step in steps.windows(2) {
+        if !std::ptr::eq(step[0], step[1]) {
+            migration_step(&mut s, &table, step[0], step[1], dialect);
+        }
+    }
+
+    Ok(s.inner)
+}
+
+fn migration_step(
+    s: &mut Writer,
+    table: &str,
+    from: &Definition,
+    to: &Definition,
+    dialect: SqlDialect,
+) {
+    let mapping = map_columns(from, to);
+
+    let original = columns(from);
+    let old = columns_with_names(from, |name| mapping.to_column(name).unwrap_or(name));
+    let new = columns(to);
+
+    for ((original, _), (name, _)) in original.iter().zip(&old) {
+        if !new.iter().any(|(a, _)| a == name) {
+            s.wln(format!(
+                "ALTER TABLE {} DROP COLUMN {};",
+                table,
+                dialect.quote(original)
+            ));
+        }
+    }
+
+    for ((original, _), (name, _)) in original.iter().zip(&old) {
+        if original != name && new.iter().any(|(a, _)| a == name) {
+            s.wln(format!(
+                "ALTER TABLE {} RENAME COLUMN {} TO {};",
+                table,
+                dialect.quote(original),
+                dialect.quote(name)
+            ));
+        }
+    }
+
+    for (name, ty) in &new {
+        let old_ty = match old.iter().find(|(a, _)| a == name) {
+            Some((_, old_ty)) => old_ty,
+            None => continue,
+        };
+        if dialect.column_type(*old_ty) == dialect.column_type(*ty) {
+            continue;
+        }
+
+        match dialect {
+            SqlDialect::MySql => s.wln(format!(
+                "ALTER TABLE {} MODIFY COLUMN {};",
+                table,
+                column_definition(dialect, name, *ty)
+            )),
+            SqlDialect::Postgres => s.wln(format!(
+                "ALTER TABLE {} ALTER COLUMN {} TYPE {};",
+                table,
+                dialect.quote(name),
+                dialect.column_type(*ty)
+            )),
+            SqlDialect::Sqlite => s.wln(format!(
+                "-- Change {} of {} from {} to {} by hand.",
+                dialect.quote(name),
+                table,
+                dialect.column_type(*old_ty),
+                dialect.column_type(*ty)
+            )),
+        }
+    }
+
+    for (name, ty) in &new {
+        if !old.iter().any(|(a, _)| a == name) {
+            s.wln(format!(
+                "ALTER TABLE {} ADD COLUMN {};",
+                table,
+                column_definition(dialect, name, *ty)
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codegen::sql::{generate_migration, SqlDialect};
+    use crate::{load_file_from_string, Version};
+
+    #[test]
+    fn migration() {
+        let file = load_file_from_string(
+            "COLUMNS\nint ID\nint Flags\nint Unk0\nfloat Corpse\nstring Directory\n\nBUILD 1.12.1.5875\n$id$ID<32>\nFlags<32>\nUnk0<32>\nCorpse[2]\n\nBUILD 3.3.5.12340\n$id$ID<32>\nFlags<u32>\nCorpse[3]\nDirectory\n",
+            "Map.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+        let vanilla = Version::new(1, 12, 1, 5875);
+        let wrath = Version::new(3, 3, 5, 12340);
+
+        assert_eq!(
+            generate_migration(&file, &vanilla, &wrath, SqlDialect::MySql).unwrap(),
+            "ALTER TABLE `Map` DROP COLUMN `Unk0`;
+ALTER TABLE `Map` MODIFY COLUMN `Flags` INT UNSIGNED NOT NULL DEFAULT 0;
+ALTER TABLE `Map` ADD COLUMN `Corpse_2` FLOAT NOT NULL DEFAULT 0;
+ALTER TABLE `Map` ADD COLUMN `Directory` TEXT NOT NULL DEFAULT '';
+"
+        );
+        assert_eq!(
+            generate_migration(&file, &wrath, &vanilla, SqlDialect::Postgres).unwrap(),
+            "ALTER TABLE \"Map\" DROP COLUMN \"Corpse_2\";
+ALTER TABLE \"Map\" DROP COLUMN \"Directory\";
+ALTER TABLE \"Map\" ALTER COLUMN \"Flags\" TYPE INTEGER;
+ALTER TABLE \"Map\" ADD COLUMN \"Unk0\" INTEGER NOT NULL DEFAULT 0;
+"
+        );
+        assert!(
+            generate_migration(&file, &vanilla, &vanilla, SqlDialect::Sqlite)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(generate_migration(
+            &file,
+            &vanilla,
+            &Version::new(2, 4, 3, 8606),
+            SqlDialect::Sqlite
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn migration_through_renames() {
+        let file = load_file_from_string(
+            "COLUMNS\nint ID\nint Unk0\nint AreaID\nint<Map::ID> MapID\nint Flags\n\nBUILD 1.12.1.5875\n$id$ID<32>\nUnk0<32>\nFlags<32>\n\nBUILD 2.4.3.8606\n$id$ID<32>\nAreaID<32>\nFlags<32>\n\nBUILD 3.3.5.12340\n$id$ID<32>\nMapID<32>\nAreaID<32>\nFlags<32>\n",
+            "AreaTrigger.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+        let vanilla = Version::new(1, 12, 1, 5875);
+        let wrath = Version::new(3, 3, 5, 12340);
+
+        assert_eq!(
+            generate_migration(&file, &vanilla, &wrath, SqlDialect::MySql).unwrap(),
+            "ALTER TABLE `AreaTrigger` RENAME COLUMN `Unk0` TO `AreaID`;
+ALTER TABLE `AreaTrigger` ADD COLUMN `MapID` INT NOT NULL DEFAULT 0;
+"
+        );
+        assert_eq!(
+            generate_migration(&file, &wrath, &vanilla, SqlDialect::Sqlite).unwrap(),
+            "ALTER TABLE \"AreaTrigger\" DROP COLUMN \"MapID\";
+ALTER TABLE \"AreaTrigger\" RENAME COLUMN \"AreaID\" TO \"Unk0\";
+"
+        );
+    }
+}
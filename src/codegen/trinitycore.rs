@@ -0,0 +1,232 @@
+//! [TrinityCore](https://github.com/TrinityCore/TrinityCore) `DB2Meta` and `DB2LoadInfo` generation.
+
+use crate::codegen::naming::{CppNames, NameKind, NameMapper};
+use crate::codegen::{layout_definition, write_doc_lines, DocOptions};
+use crate::error::CodegenError;
+use crate::writer::Writer;
+use crate::{DbdFile, Entry, ScalarType, Version};
+
+struct Field {
+    name: String,
+    format: &'static str,
+    signed: bool,
+    is_string: bool,
+    array_size: usize,
+    inline: bool,
+    doc: Vec<String>,
+}
+
+fn field(entry: &Entry, names: &impl NameMapper, docs: &DocOptions) -> Field {
+    let ty = entry.ty.scalar();
+    let (format, signed) = match ty {
+        ScalarType::Int8 => ("FT_BYTE", true),
+        ScalarType::UInt8 => ("FT_BYTE", false),
+        ScalarType::Int16 => ("FT_SHORT", true),
+        ScalarType::UInt16 => ("FT_SHORT", false),
+        ScalarType::Int32 => ("FT_INT", true),
+        ScalarType::UInt32 => ("FT_INT", false),
+        ScalarType::Int64 => ("FT_LONG", true),
+        ScalarType::UInt64 => ("FT_LONG", false),
+        ScalarType::Float => ("FT_FLOAT", true),
+        ScalarType::LocString => ("FT_STRING", true),
+        ScalarType::String => ("FT_STRING_NOT_LOCALIZED", true),
+    };
+
+    Field {
+        name: names.map(NameKind::Field, &entry.name),
+        format,
+        signed,
+        is_string: matches!(ty, ScalarType::LocString | ScalarType::String),
+        array_size: entry.ty.array_width().unwrap_or(1),
+        inline: entry.inline,
+        doc: docs.lines(entry),
+    }
+}
+
+/// Generate the `DB2Meta` and `DB2LoadInfo` structs for the definition of `file` valid for `version`.
+///
+/// The `.dbd` format does not contain the `FileDataID` of the table so it must be provided through `file_data_id`.
+/// `-1` is used if it is not known.
+/// If the definition has more than one layout the lowest is used.
+///
+/// # Errors
+///
+/// Errors with [`CodegenError::NoDefinitionForVersion`] if there is no definition for `version`,
+/// or with [`CodegenError::FilteredDefinition`] if the definition has filtered entries.
+pub fn generate(
+    file: &DbdFile,
+    version: &Version,
+    file_data_id: Option<i32>,
+) -> Result<String, CodegenError> {
+    generate_with_names(file, version, file_data_id, &CppNames)
+}
+
+/// Same as [`generate`] but with the identifiers from `names` instead of [`CppNames`].
+///
+/// # Errors
+///
+/// Same as [`generate`].
+pub fn generate_with_names(
+    file: &DbdFile,
+    version: &Version,
+    file_data_id: Option<i32>,
+    names: &impl NameMapper,
+) -> Result<String, CodegenError> {
+    generate_with_options(file, version, file_data_id, names, &DocOptions::new())
+}
+
+/// Same as [`generate_with_names`] but also writes comments according to `docs`.
+///
+/// # Errors
+///
+/// Same as [`generate`].
+pub fn generate_with_options(
+    file: &DbdFile,
+    version: &Version,
+    file_data_id: Option<i32>,
+    names: &impl NameMapper,
+    docs: &DocOptions,
+) -> Result<String, CodegenError> {
+    let definition = layout_definition(file, version)?;
+    let name = names.map(NameKind::Type, file.table_name());
+
+    let mut fields = Vec::with_capacity(definition.entries.len());
+    for entry in &definition.entries {
+        fields.push(field(entry, names, docs));
+    }
+
+    let file_fields: Vec<&Field> = fields.iter().filter(|a| a.inline).collect();
+
+    let index_field = definition
+        .entries
+        .iter()
+        .filter(|a| a.inline)
+        .position(|a| a.primary_key)
+        .map_or(-1, |a| a as i64);
+    let parent_index_field = match definition.entries.iter().find(|a| a.relation) {
+        Some(relation) if relation.inline => definition
+            .entries
+            .iter()
+            .filter(|a| a.inline)
+            .position(|a| a.relation)
+            .map_or(-1, |a| a as i64),
+        Some(_) => file_fields.len() as i64,
+        None => -1,
+    };
+    let layout = definition.layouts.iter().next().map_or(0, |a| a.inner);
+
+    let mut s = Writer::new();
+
+    s.wln(format!("struct {}Meta", name));
+    s.wln("{");
+    s.wln("    static DB2Meta const* Instance()");
+    s.wln("    {");
+    s.wln(format!(
+        "        static DB2MetaField const fields[{}] =",
+        file_fields.len()
+    ));
+    s.wln("        {");
+    for f in &file_fields {
+        s.wln(format!(
+            "            {{ {}, {}, {} }},",
+            f.format, f.array_size, f.signed
+        ));
+    }
+    s.wln("        };");
+    s.wln(format!(
+        "        static DB2Meta const instance({}, {}, {}, {}, 0x{:08X}, fields, {});",
+        file_data_id.unwrap_or(-1),
+        index_field,
+        fields.len(),
+        file_fields.len(),
+        layout,
+        parent_index_field,
+    ));
+    s.wln("        return &instance;");
+    s.wln("    }");
+    s.wln("};");
+    s.newline();
+
+    let load_fields: Vec<(String, &Field, bool)> = fields
+        .iter()
+        .flat_map(|f| {
+            if f.array_size == 1 {
+                vec![(f.name.clone(), f, true)]
+            } else {
+                (1..=f.array_size)
+                    .map(|i| (format!("{}{}", f.name, i), f, i == 1))
+                    .collect()
+            }
+        })
+        .collect();
+
+    s.wln(format!("struct {}LoadInfo", name));
+    s.wln("{");
+    s.wln(format!(
+        "    static constexpr DB2FieldMeta Fields[{}] =",
+        load_fields.len()
+    ));
+    s.wln("    {");
+    for (field_name, f, first) in &load_fields {
+        if *first {
+            write_doc_lines(&mut s, "        //", &f.doc);
+        }
+        s.wln(format!(
+            "        {{ {}, {}, \"{}\" }},",
+            f.signed && !f.is_string,
+            f.format,
+            field_name
+        ));
+    }
+    s.wln("    };");
+    s.newline();
+    s.wln(format!(
+        "    static constexpr DB2LoadInfo Instance{{ Fields, {}, &{}Meta::Instance, HOTFIX_SEL_{} }};",
+        load_fields.len(),
+        name,
+        names.map(NameKind::Constant, file.table_name()),
+    ));
+    s.wln("};");
+
+    Ok(s.inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codegen::trinitycore::generate;
+    use crate::{load_file_from_string, Version};
+
+    const CONTENTS: &str = "COLUMNS
+int ID
+string Name
+locstring Title_lang
+int<Map::ID> MapID
+int Flags
+
+LAYOUT 11A7EB3A
+BUILD 9.0.1.36216
+$noninline,id$ID<32>
+Name
+Title_lang
+Flags<u16>[2]
+$noninline,relation$MapID<32>
+";
+
+    #[test]
+    fn meta_and_load_info() {
+        let file = load_file_from_string(CONTENTS, "AreaTable.dbd")
+            .unwrap()
+            .into_proper()
+            .unwrap();
+        let s = generate(&file, &Version::new(9, 0, 1, 36216), Some(1353545)).unwrap();
+
+        assert!(s.contains("struct AreaTableMeta"));
+        assert!(s.contains("static DB2MetaField const fields[3] ="));
+        assert!(s.contains("{ FT_SHORT, 2, false },"));
+        assert!(s.contains("instance(1353545, -1, 5, 3, 0x11A7EB3A, fields, 3);"));
+        assert!(s.contains("{ false, FT_SHORT, \"Flags2\" },"));
+        assert!(
+            s.contains("Instance{ Fields, 6, &AreaTableMeta::Instance, HOTFIX_SEL_AREA_TABLE };")
+        );
+    }
+}
@@ -0,0 +1,1011 @@
+//! [`wow_dbc`](https://github.com/gtker/wow_dbc) table generation.
+//!
+//! Generates a module for a single table containing the table, row and primary key types
+//! as well as the `DbcTable` and `Indexable` implementations.
+//! The module is expected to be placed in the expansion module of `wow_dbc` that matches the version,
+//! for example `vanilla_tables::map` for `Map.dbd` and `1.12.1.5875`.
+//!
+//! Attached [`enums`](crate::enums) are generated as Rust enums with `TryFrom` implementations
+//! and flags as newtypes with a getter for every flag.
+//!
+//! [`TypeOverride::Bool`] columns are generated as `bool` and [`TypeOverride::Custom`] columns
+//! use the custom type, which must be `Copy` and convertible from and into the integer type.
+//!
+//! [`generate_conversion`] generates conversions between the rows of two expansions.
+
+use crate::codegen::naming::{NameKind, NameMapper, RustNames};
+use crate::codegen::{layout_definition, write_doc_lines, DocOptions};
+use crate::enums::{EnumDefinition, EnumKind};
+use crate::error::CodegenError;
+use crate::overrides::TypeOverride;
+use crate::writer::Writer;
+use crate::{DbdFile, Definition, ScalarType, Version};
+use std::collections::BTreeSet;
+
+enum Kind {
+    Primitive(&'static str),
+    String,
+    LocString(&'static str),
+    ForeignKey { table: String, ty: &'static str },
+    PrimaryKey(&'static str),
+    Enum { name: String, ty: &'static str },
+    Flags { name: String, ty: &'static str },
+    Bool(&'static str),
+    Custom { name: String, ty: &'static str },
+}
+
+struct Field {
+    name: String,
+    kind: Kind,
+    array_size: Option<usize>,
+    doc: Vec<String>,
+}
+
+/// Strings are stored as `u32` offsets into the string block.
+const fn primitive(ty: ScalarType) -> (&'static str, usize) {
+    match ty {
+        ScalarType::Int8 => ("i8", 1),
+        ScalarType::Int16 => ("i16", 2),
+        ScalarType::Int32 => ("i32", 4),
+        ScalarType::Int64 => ("i64", 8),
+        ScalarType::UInt8 => ("u8", 1),
+        ScalarType::UInt16 => ("u16", 2),
+        ScalarType::UInt32 => ("u32", 4),
+        ScalarType::UInt64 => ("u64", 8),
+        ScalarType::Float => ("f32", 4),
+        ScalarType::String | ScalarType::LocString => ("u32", 4),
+    }
+}
+
+const fn expansion_module(version: &Version) -> Option<&'static str> {
+    Some(match version.major {
+        1 => "vanilla_tables",
+        2 => "tbc_tables",
+        3 => "wrath_tables",
+        _ => return None,
+    })
+}
+
+impl Field {
+    fn rust_type(&self, table: &str) -> String {
+        let ty = match &self.kind {
+            Kind::Primitive(ty) => ty.to_string(),
+            Kind::String => "String".to_string(),
+            Kind::LocString(ty) => ty.to_string(),
+            Kind::ForeignKey { table, .. } => format!("{}Key", table),
+            Kind::PrimaryKey(_) => format!("{}Key", table),
+            Kind::Enum { name, .. } | Kind::Flags { name, .. } | Kind::Custom { name, .. } => {
+                name.clone()
+            }
+            Kind::Bool(_) => "bool".to_string(),
+        };
+
+        if let Some(array_size) = self.array_size {
+            format!("[{}; {}]", ty, array_size)
+        } else {
+            ty
+        }
+    }
+
+    fn read(&self, table: &str) -> String {
+        match &self.kind {
+            Kind::Primitive(ty) => format!("crate::util::read_{}_le(chunk)?", ty),
+            Kind::String => "{\n                let s = crate::util::get_string_as_vec(chunk, &string_block)?;\n                String::from_utf8(s)?\n            }".to_string(),
+            Kind::LocString("LocalizedString") => {
+                "crate::util::read_localized_string(chunk, &string_block)?".to_string()
+            }
+            Kind::LocString(_) => {
+                "crate::util::read_extended_localized_string(chunk, &string_block)?".to_string()
+            }
+            Kind::ForeignKey { table, ty } => {
+                format!("{}Key::new(crate::util::read_{}_le(chunk)?.into())", table, ty)
+            }
+            Kind::PrimaryKey(ty) => {
+                format!("{}Key::new(crate::util::read_{}_le(chunk)?)", table, ty)
+            }
+            Kind::Enum { name, ty } => {
+                format!("{}::try_from(crate::util::read_{}_le(chunk)?)?", name, ty)
+            }
+            Kind::Flags { name, ty } => {
+                format!("{}::new(crate::util::read_{}_le(chunk)?)", name, ty)
+            }
+            Kind::Bool(ty) => format!("crate::util::read_{}_le(chunk)? != 0", ty),
+            Kind::Custom { name, ty } => {
+                format!("{}::from(crate::util::read_{}_le(chunk)?)", name, ty)
+            }
+        }
+    }
+
+    fn write(&self, s: &mut Writer, value: &str, indent: &str) {
+        match &self.kind {
+            Kind::Primitive(_) => {
+                s.wln(format!("{}b.write_all(&{}.to_le_bytes())?;", indent, value));
+            }
+            Kind::String => {
+                s.wln(format!("{}if !{}.is_empty() {{", indent, value));
+                s.wln(format!(
+                    "{}    b.write_all(&(string_index as u32).to_le_bytes())?;",
+                    indent
+                ));
+                s.wln(format!(
+                    "{}    string_index += {}.len() + 1;",
+                    indent, value
+                ));
+                s.wln(format!("{}}} else {{", indent));
+                s.wln(format!(
+                    "{}    b.write_all(&(0_u32).to_le_bytes())?;",
+                    indent
+                ));
+                s.wln(format!("{}}}", indent));
+            }
+            Kind::LocString(_) => {
+                s.wln(format!(
+                    "{}b.write_all(&{}.string_indices_as_array(&mut string_index))?;",
+                    indent, value
+                ));
+            }
+            Kind::ForeignKey { ty, .. } => {
+                s.wln(format!(
+                    "{}b.write_all(&({}.id as {}).to_le_bytes())?;",
+                    indent, value, ty
+                ));
+            }
+            Kind::PrimaryKey(_) => {
+                s.wln(format!(
+                    "{}b.write_all(&{}.id.to_le_bytes())?;",
+                    indent, value
+                ));
+            }
+            Kind::Enum { ty, .. } => {
+                s.wln(format!(
+                    "{}b.write_all(&({}.as_int() as {}).to_le_bytes())?;",
+                    indent, value, ty
+                ));
+            }
+            Kind::Flags { .. } => {
+                s.wln(format!(
+                    "{}b.write_all(&{}.as_int().to_le_bytes())?;",
+                    indent, value
+                ));
+            }
+            Kind::Bool(ty) | Kind::Custom { ty, .. } => {
+                s.wln(format!(
+                    "{}b.write_all(&{}::from({}).to_le_bytes())?;",
+                    indent, ty, value
+                ));
+            }
+        }
+    }
+
+    fn string_block(&self, s: &mut Writer, value: &str, indent: &str) {
+        match &self.kind {
+            Kind::String => {
+                s.wln(format!(
+                    "{}if !{v}.is_empty() {{ b.write_all({v}.as_bytes())?; b.write_all(&[0])?; }}",
+                    indent,
+                    v = value
+                ));
+            }
+            Kind::LocString(_) => {
+                s.wln(format!("{}{}.string_block_as_array(b)?;", indent, value));
+            }
+            _ => {}
+        }
+    }
+
+    fn string_block_size(&self, s: &mut Writer, value: &str, indent: &str) {
+        match &self.kind {
+            Kind::String => {
+                s.wln(format!(
+                    "{}if !{v}.is_empty() {{ sum += {v}.len() + 1; }}",
+                    indent,
+                    v = value
+                ));
+            }
+            Kind::LocString(_) => {
+                s.wln(format!("{}sum += {}.string_block_size();", indent, value));
+            }
+            _ => {}
+        }
+    }
+
+    const fn has_strings(&self) -> bool {
+        matches!(self.kind, Kind::String | Kind::LocString(_))
+    }
+}
+
+fn write_enum(s: &mut Writer, e: &EnumDefinition, ty: &str) {
+    s.wln("#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]");
+    s.wln(format!("pub enum {} {{", e.name));
+    for value in &e.values {
+        if let Some(comment) = &value.comment {
+            s.wln(format!("    /// {}", comment));
+        }
+        s.wln(format!("    {},", value.name));
+    }
+    s.wln("}");
+    s.newline();
+
+    s.wln(format!("impl TryFrom<{}> for {} {{", ty, e.name));
+    s.wln("    type Error = crate::InvalidEnumError;");
+    s.wln(format!(
+        "    fn try_from(value: {}) -> Result<Self, Self::Error> {{",
+        ty
+    ));
+    s.wln("        Ok(match value {");
+    for value in &e.values {
+        s.wln(format!(
+            "            {} => Self::{},",
+            value.value, value.name
+        ));
+    }
+    s.wln(format!(
+        "            val => return Err(crate::InvalidEnumError::new(\"{}\", val as i64)),",
+        e.name
+    ));
+    s.wln("        })");
+    s.wln("    }");
+    s.wln("}");
+    s.newline();
+
+    s.wln(format!("impl {} {{", e.name));
+    s.wln(format!("    pub const fn as_int(&self) -> {} {{", ty));
+    s.wln("        match self {");
+    for value in &e.values {
+        s.wln(format!(
+            "            Self::{} => {},",
+            value.name, value.value
+        ));
+    }
+    s.wln("        }");
+    s.wln("    }");
+    s.wln("}");
+
+    if let Some(first) = e.values.first() {
+        s.newline();
+        s.wln(format!("impl Default for {} {{", e.name));
+        s.wln("    fn default() -> Self {");
+        s.wln(format!("        Self::{}", first.name));
+        s.wln("    }");
+        s.wln("}");
+    }
+}
+
+fn write_flags(s: &mut Writer, e: &EnumDefinition, ty: &str, names: &impl NameMapper) {
+    s.wln("#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]");
+    s.wln(format!("pub struct {} {{", e.name));
+    s.wln(format!("    value: {},", ty));
+    s.wln("}");
+    s.newline();
+
+    s.wln(format!("impl {} {{", e.name));
+    s.wln(format!(
+        "    pub const fn new(value: {}) -> Self {{ Self {{ value }} }}",
+        ty
+    ));
+    s.newline();
+    s.wln(format!(
+        "    pub const fn as_int(&self) -> {} {{ self.value }}",
+        ty
+    ));
+    for value in &e.values {
+        s.newline();
+        if let Some(comment) = &value.comment {
+            s.wln(format!("    /// {}", comment));
+        }
+        s.wln(format!(
+            "    pub const fn {}(&self) -> bool {{ (self.value & 0x{:X}) != 0 }}",
+            names.map(NameKind::Field, &value.name),
+            value.value
+        ));
+    }
+    s.wln("}");
+}
+
+struct Fields<'a> {
+    fields: Vec<Field>,
+    record_size: usize,
+    field_count: usize,
+    primary_key: Option<(String, &'static str)>,
+    imports: BTreeSet<String>,
+    enums: Vec<(&'a EnumDefinition, &'static str)>,
+}
+
+fn fields<'a>(
+    table: &str,
+    definition: &'a Definition,
+    version: &Version,
+    names: &impl NameMapper,
+    docs: &DocOptions,
+) -> Result<Fields<'a>, CodegenError> {
+    let module = expansion_module(version).ok_or(CodegenError::UnsupportedVersion(*version))?;
+
+    let mut fields = Vec::with_capacity(definition.entries.len());
+    let mut record_size = 0_usize;
+    let mut field_count = 0_usize;
+    let mut primary_key = None;
+    let mut imports = BTreeSet::new();
+    let mut enums: Vec<(&EnumDefinition, &'static str)> = Vec::new();
+
+    for entry in &definition.entries {
+        let array_size = entry.ty.array_width();
+
+        let (kind, size, count) = match (entry.ty.scalar(), entry.ty.foreign_key()) {
+            (ScalarType::LocString, _) => {
+                let strings = version.locstring_strings();
+                let ty = if strings == 8 {
+                    "LocalizedString"
+                } else {
+                    "ExtendedLocalizedString"
+                };
+                imports.insert(format!("crate::{}", ty));
+
+                (Kind::LocString(ty), (strings + 1) * 4, strings + 1)
+            }
+            (ScalarType::String, _) => (Kind::String, 4, 1),
+            (ty, Some(key)) => {
+                let (ty, size) = primitive(ty);
+                if key.database != table {
+                    imports.insert(format!(
+                        "crate::{}::{}::{}Key",
+                        module,
+                        names.map(NameKind::Module, &key.database),
+                        names.map(NameKind::Type, &key.database),
+                    ));
+                }
+
+                (
+                    Kind::ForeignKey {
+                        table: names.map(NameKind::Type, &key.database),
+                        ty,
+                    },
+                    size,
+                    1,
+                )
+            }
+            (scalar, None) => {
+                let (ty, size) = primitive(scalar);
+                let is_float = matches!(scalar, ScalarType::Float);
+
+                let kind = if entry.primary_key {
+                    primary_key = Some((names.map(NameKind::Field, &entry.name), ty));
+                    Kind::PrimaryKey(ty)
+                } else if let (Some(TypeOverride::Bool), false) = (&entry.type_override, is_float) {
+                    Kind::Bool(ty)
+                } else if let Some(TypeOverride::Custom(name)) = &entry.type_override {
+                    Kind::Custom {
+                        name: name.clone(),
+                        ty,
+                    }
+                } else if let (Some(e), false) = (&entry.enumeration, is_float) {
+                    if !enums.iter().any(|(a, _)| a.name == e.name) {
+                        enums.push((e, ty));
+                    }
+
+                    match e.kind {
+                        EnumKind::Enum => Kind::Enum {
+                            name: e.name.clone(),
+                            ty,
+                        },
+                        EnumKind::Flags => Kind::Flags {
+                            name: e.name.clone(),
+                            ty,
+                        },
+                    }
+                } else {
+                    Kind::Primitive(ty)
+                };
+
+                (kind, size, 1)
+            }
+        };
+
+        record_size += size * array_size.unwrap_or(1);
+        field_count += count * array_size.unwrap_or(1);
+
+        let mut doc = docs.lines(entry);
+        let type_doc = match &entry.type_override {
+            Some(TypeOverride::Path) => Some("Path.".to_string()),
+            Some(TypeOverride::Bitmask(e)) => Some(format!("Bitmask of [`{}`].", e)),
+            _ => None,
+        };
+        if let Some(type_doc) = type_doc {
+            if !doc.is_empty() {
+                doc.push(String::new());
+            }
+            doc.push(type_doc);
+        }
+
+        fields.push(Field {
+            name: names.map(NameKind::Field, &entry.name),
+            kind,
+            array_size,
+            doc,
+        });
+    }
+
+    Ok(Fields {
+        fields,
+        record_size,
+        field_count,
+        primary_key,
+        imports,
+        enums,
+    })
+}
+
+/// Generate a `wow_dbc` module for the definition of `file` valid for `version`.
+///
+/// # Errors
+///
+/// Errors with [`CodegenError::NoDefinitionForVersion`] if there is no definition for `version`,
+/// with [`CodegenError::FilteredDefinition`] if the definition has filtered entries,
+/// or with [`CodegenError::UnsupportedVersion`] if `version` is not vanilla, TBC or Wrath.
+pub fn generate(file: &DbdFile, version: &Version) -> Result<String, CodegenError> {
+    generate_with_names(file, version, &RustNames)
+}
+
+/// Same as [`generate`] but with the identifiers from `names` instead of [`RustNames`].
+///
+/// # Errors
+///
+/// Same as [`generate`].
+pub fn generate_with_names(
+    file: &DbdFile,
+    version: &Version,
+    names: &impl NameMapper,
+) -> Result<String, CodegenError> {
+    generate_with_options(file, version, names, &DocOptions::new())
+}
+
+/// Same as [`generate_with_names`] but also writes comments according to `docs`.
+///
+/// # Errors
+///
+/// Same as [`generate`].
+pub fn generate_with_options(
+    file: &DbdFile,
+    version: &Version,
+    names: &impl NameMapper,
+    docs: &DocOptions,
+) -> Result<String, CodegenError> {
+    let definition = layout_definition(file, version)?;
+    let table = file.table_name();
+    let name = names.map(NameKind::Type, table);
+    let Fields {
+        fields,
+        record_size,
+        field_count,
+        primary_key,
+        imports,
+        enums,
+    } = fields(table, definition, version, names, docs)?;
+
+    let mut s = Writer::new();
+
+    s.wln("use crate::header::{HEADER_SIZE, DbcHeader, parse_header};");
+    if primary_key.is_some() {
+        s.wln("use crate::{DbcTable, Indexable};");
+    } else {
+        s.wln("use crate::DbcTable;");
+    }
+    for import in &imports {
+        s.wln(format!("use {};", import));
+    }
+    s.wln("use std::io::Write;");
+    s.newline();
+
+    s.wln("#[derive(Debug, Clone, PartialEq, PartialOrd)]");
+    s.wln(format!("pub struct {} {{", name));
+    s.wln(format!("    pub rows: Vec<{}Row>,", name));
+    s.wln("}");
+    s.newline();
+
+    s.wln(format!("impl DbcTable for {} {{", name));
+    s.wln(format!("    type Row = {}Row;", name));
+    s.newline();
+    s.wln(format!(
+        "    fn filename() -> &'static str {{ \"{}.dbc\" }}",
+        table
+    ));
+    s.newline();
+    s.wln("    fn rows(&self) -> &[Self::Row] { &self.rows }");
+    s.wln("    fn rows_mut(&mut self) -> &mut [Self::Row] { &mut self.rows }");
+    s.newline();
+    s.wln("    fn read(b: &mut impl std::io::Read) -> Result<Self, crate::DbcError> {");
+    s.wln("        let mut header = [0_u8; HEADER_SIZE];");
+    s.wln("        b.read_exact(&mut header)?;");
+    s.wln("        let header = parse_header(&header)?;");
+    s.newline();
+    s.wln(format!(
+        "        if header.record_size != {} {{",
+        record_size
+    ));
+    s.wln("            return Err(crate::DbcError::InvalidHeader(");
+    s.wln("                crate::InvalidHeaderError::RecordSize {");
+    s.wln(format!("                    expected: {},", record_size));
+    s.wln("                    actual: header.record_size,");
+    s.wln("                },");
+    s.wln("            ));");
+    s.wln("        }");
+    s.newline();
+    s.wln(format!(
+        "        if header.field_count != {} {{",
+        field_count
+    ));
+    s.wln("            return Err(crate::DbcError::InvalidHeader(");
+    s.wln("                crate::InvalidHeaderError::FieldCount {");
+    s.wln(format!("                    expected: {},", field_count));
+    s.wln("                    actual: header.field_count,");
+    s.wln("                },");
+    s.wln("            ));");
+    s.wln("        }");
+    s.newline();
+    s.wln("        let mut r = vec![0_u8; (header.record_count * header.record_size) as usize];");
+    s.wln("        b.read_exact(&mut r)?;");
+    s.wln("        let mut string_block = vec![0_u8; header.string_block_size as usize];");
+    s.wln("        b.read_exact(&mut string_block)?;");
+    s.newline();
+    s.wln("        let mut rows = Vec::with_capacity(header.record_count as usize);");
+    s.newline();
+    s.wln("        for mut chunk in r.chunks(header.record_size as usize) {");
+    s.wln("            let chunk = &mut chunk;");
+    s.newline();
+    for field in &fields {
+        if let Some(array_size) = field.array_size {
+            s.wln(format!("            let {} = {{", field.name));
+            s.wln(format!(
+                "                let mut arr = Vec::with_capacity({});",
+                array_size
+            ));
+            s.wln(format!("                for _ in 0..{} {{", array_size));
+            s.wln(format!(
+                "                    arr.push({});",
+                field.read(&name)
+            ));
+            s.wln("                }");
+            s.newline();
+            s.wln("                arr.try_into().unwrap()");
+            s.wln("            };");
+        } else {
+            s.wln(format!(
+                "            let {} = {};",
+                field.name,
+                field.read(&name)
+            ));
+        }
+        s.newline();
+    }
+    s.wln(format!("            rows.push({}Row {{", name));
+    for field in &fields {
+        s.wln(format!("                {},", field.name));
+    }
+    s.wln("            });");
+    s.wln("        }");
+    s.newline();
+    s.wln(format!("        Ok({} {{ rows, }})", name));
+    s.wln("    }");
+    s.newline();
+    s.wln("    fn write(&self, b: &mut impl Write) -> Result<(), std::io::Error> {");
+    s.wln("        let header = DbcHeader {");
+    s.wln("            record_count: self.rows.len() as u32,");
+    s.wln(format!("            field_count: {},", field_count));
+    s.wln(format!("            record_size: {},", record_size));
+    s.wln("            string_block_size: self.string_block_size(),");
+    s.wln("        };");
+    s.newline();
+    s.wln("        b.write_all(&header.write_header())?;");
+    s.newline();
+    if fields.iter().any(Field::has_strings) {
+        s.wln("        let mut string_index = 1;");
+    }
+    s.wln("        for row in &self.rows {");
+    for (i, field) in fields.iter().enumerate() {
+        if i != 0 {
+            s.newline();
+        }
+
+        if field.array_size.is_some() {
+            s.wln(format!("            for i in &row.{} {{", field.name));
+            let value = match field.kind {
+                Kind::Bool(_) | Kind::Custom { .. } => "*i",
+                _ => "i",
+            };
+            field.write(&mut s, value, "                ");
+            s.wln("            }");
+        } else {
+            field.write(&mut s, &format!("row.{}", field.name), "            ");
+        }
+    }
+    s.wln("        }");
+    s.newline();
+    s.wln("        self.write_string_block(b)?;");
+    s.newline();
+    s.wln("        Ok(())");
+    s.wln("    }");
+    s.wln("}");
+    s.newline();
+
+    if let Some((key_name, key_ty)) = &primary_key {
+        s.wln(format!("impl Indexable for {} {{", name));
+        s.wln(format!("    type PrimaryKey = {}Key;", name));
+        s.wln("    fn get(&self, key: impl TryInto<Self::PrimaryKey>) -> Option<&Self::Row> {");
+        s.wln("        let key = key.try_into().ok()?;");
+        s.wln(format!(
+            "        self.rows.iter().find(|a| a.{}.id == key.id)",
+            key_name
+        ));
+        s.wln("    }");
+        s.newline();
+        s.wln("    fn get_mut(&mut self, key: impl TryInto<Self::PrimaryKey>) -> Option<&mut Self::Row> {");
+        s.wln("        let key = key.try_into().ok()?;");
+        s.wln(format!(
+            "        self.rows.iter_mut().find(|a| a.{}.id == key.id)",
+            key_name
+        ));
+        s.wln("    }");
+        s.wln("}");
+        s.newline();
+
+        s.wln("#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]");
+        s.wln(format!("pub struct {}Key {{", name));
+        s.wln(format!("    pub id: {}", key_ty));
+        s.wln("}");
+        s.newline();
+        s.wln(format!("impl {}Key {{", name));
+        s.wln(format!(
+            "    pub const fn new(id: {}) -> Self {{ Self {{ id }} }}",
+            key_ty
+        ));
+        s.wln("}");
+        s.newline();
+        s.wln(format!("impl From<{}> for {}Key {{", key_ty, name));
+        s.wln(format!(
+            "    fn from(v: {}) -> Self {{ Self::new(v) }}",
+            key_ty
+        ));
+        s.wln("}");
+        s.newline();
+    }
+
+    s.wln(format!("impl {} {{", name));
+    s.wln("    fn write_string_block(&self, b: &mut impl Write) -> Result<(), std::io::Error> {");
+    s.wln("        b.write_all(&[0])?;");
+    s.newline();
+    s.wln("        for row in &self.rows {");
+    for field in fields.iter().filter(|a| a.has_strings()) {
+        if field.array_size.is_some() {
+            s.wln(format!("            for s in &row.{} {{", field.name));
+            field.string_block(&mut s, "s", "                ");
+            s.wln("            }");
+        } else {
+            field.string_block(&mut s, &format!("row.{}", field.name), "            ");
+        }
+    }
+    s.wln("        }");
+    s.newline();
+    s.wln("        Ok(())");
+    s.wln("    }");
+    s.newline();
+    s.wln("    fn string_block_size(&self) -> u32 {");
+    s.wln("        let mut sum = 1;");
+    s.wln("        for row in &self.rows {");
+    for field in fields.iter().filter(|a| a.has_strings()) {
+        if field.array_size.is_some() {
+            s.wln(format!("            for s in &row.{} {{", field.name));
+            field.string_block_size(&mut s, "s", "                ");
+            s.wln("            }");
+        } else {
+            field.string_block_size(&mut s, &format!("row.{}", field.name), "            ");
+        }
+    }
+    s.wln("        }");
+    s.newline();
+    s.wln("        sum as u32");
+    s.wln("    }");
+    s.wln("}");
+    s.newline();
+
+    s.wln("#[derive(Debug, Clone, PartialEq, PartialOrd)]");
+    s.wln(format!("pub struct {}Row {{", name));
+    for field in &fields {
+        write_doc_lines(&mut s, "    ///", &field.doc);
+        s.wln(format!(
+            "    pub {}: {},",
+            field.name,
+            field.rust_type(&name)
+        ));
+    }
+    s.wln("}");
+
+    for (e, ty) in enums {
+        s.newline();
+        match e.kind {
+            EnumKind::Enum => write_enum(&mut s, e, ty),
+            EnumKind::Flags => write_flags(&mut s, e, ty, names),
+        }
+    }
+
+    Ok(s.inner)
+}
+
+/// Expression that converts `value` of the `old` field into the type of the `new` field,
+/// or `None` if it can not be converted.
+fn convert(old: &Field, new: &Field, module: &str, value: &str) -> Option<String> {
+    let cast = |old: &str, new: &str| {
+        if old == new {
+            String::new()
+        } else {
+            format!(" as {}", new)
+        }
+    };
+
+    Some(match (&old.kind, &new.kind) {
+        (Kind::Primitive(a), Kind::Primitive(b)) => format!("{}{}", value, cast(a, b)),
+        (Kind::String, Kind::String) | (Kind::Bool(_), Kind::Bool(_)) => value.to_string(),
+        (Kind::LocString(a), Kind::LocString(b)) if a == b => value.to_string(),
+        (Kind::Custom { name: a, .. }, Kind::Custom { name: b, .. }) if a == b => value.to_string(),
+        (Kind::PrimaryKey(a), Kind::PrimaryKey(b)) if a == b => format!("{}.id.into()", value),
+        (Kind::PrimaryKey(_), Kind::PrimaryKey(b)) => format!("({}.id as {}).into()", value, b),
+        (Kind::ForeignKey { table: a, .. }, Kind::ForeignKey { table: b, .. }) if a == b => {
+            format!("{}.id.into()", value)
+        }
+        (Kind::Enum { ty: a, .. }, Kind::Enum { name, ty: b }) => format!(
+            "{}::{}::try_from({}.as_int(){}).unwrap_or_default()",
+            module,
+            name,
+            value,
+            cast(a, b)
+        ),
+        (Kind::Flags { ty: a, .. }, Kind::Flags { name, ty: b }) => format!(
+            "{}::{}::new({}.as_int(){})",
+            module,
+            name,
+            value,
+            cast(a, b)
+        ),
+        _ => return None,
+    })
+}
+
+/// Generate a `From` implementation that converts rows of the `wow_dbc` module for `from`
+/// into rows of the module for `to`, see [`generate`].
+///
+/// Fields with the same name are copied and converted into the new type where possible,
+/// integers with `as` and enums with `TryFrom`, falling back to the default value.
+/// New fields and fields that can not be converted get the default value,
+/// and removed fields are listed in a comment.
+///
+/// # Errors
+///
+/// Errors if [`generate`] would fail for `from` or `to`,
+/// or if both versions use the same expansion module since the row types would be the same.
+pub fn generate_conversion(
+    file: &DbdFile,
+    from: &Version,
+    to: &Version,
+) -> Result<String, CodegenError> {
+    generate_conversion_with_names(file, from, to, &RustNames)
+}
+
+/// Same as [`generate_conversion`] but with the identifiers from `names` instead of [`RustNames`].
+///
+/// # Errors
+///
+/// Same as [`generate_conversion`].
+pub fn generate_conversion_with_names(
+    file: &DbdFile,
+    from: &Version,
+    to: &Version,
+    names: &impl NameMapper,
+) -> Result<String, CodegenError> {
+    let table = file.table_name();
+    let name = names.map(NameKind::Type, table);
+    let docs = DocOptions::new();
+
+    let old = fields(table, layout_definition(file, from)?, from, names, &docs)?.fields;
+    let new = fields(table, layout_definition(file, to)?, to, names, &docs)?.fields;
+
+    let module = |version: &Version| {
+        expansion_module(version)
+            .map(|a| format!("crate::{}::{}", a, names.map(NameKind::Module, table)))
+    };
+    let old_module = module(from).ok_or(CodegenError::UnsupportedVersion(*from))?;
+    let new_module = module(to).ok_or(CodegenError::UnsupportedVersion(*to))?;
+    if old_module == new_module {
+        return Err(CodegenError::UnsupportedVersion(*to));
+    }
+
+    let mut s = Writer::new();
+
+    s.wln(format!(
+        "impl From<{m}::{n}Row> for {}::{n}Row {{",
+        new_module,
+        m = old_module,
+        n = name
+    ));
+    s.wln(format!(
+        "    fn from(v: {}::{}Row) -> Self {{",
+        old_module, name
+    ));
+    for field in old.iter().filter(|a| !new.iter().any(|b| b.name == a.name)) {
+        s.wln(format!("        // Removed: {}", field.name));
+    }
+    s.wln("        Self {");
+    for field in &new {
+        let old_field = match old.iter().find(|a| a.name == field.name) {
+            Some(old_field) => old_field,
+            None => {
+                s.wln(format!("            {}: Default::default(),", field.name));
+                continue;
+            }
+        };
+
+        let value = format!("v.{}", field.name);
+        let converted = match (old_field.array_size, field.array_size) {
+            (None, None) => convert(old_field, field, &new_module, &value),
+            (Some(a), Some(b)) if a == b => convert(old_field, field, &new_module, "a").map(|a| {
+                if a == "a" {
+                    value.clone()
+                } else {
+                    format!("{}.map(|a| {})", value, a)
+                }
+            }),
+            _ => None,
+        };
+
+        match converted {
+            Some(converted) => s.wln(format!("            {}: {},", field.name, converted)),
+            None => s.wln(format!(
+                "            {}: Default::default(), // Can not convert {} into {}",
+                field.name,
+                old_field.rust_type(&name),
+                field.rust_type(&name)
+            )),
+        }
+    }
+    s.wln("        }");
+    s.wln("    }");
+    s.wln("}");
+
+    Ok(s.inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codegen::naming::{NameKind, NameOverrides, RustNames};
+    use crate::codegen::wow_dbc::{generate, generate_conversion, generate_with_names};
+    use crate::enums::parse_enums;
+    use crate::overrides::TypeOverrides;
+    use crate::{load_file_from_string, Version};
+
+    const CONTENTS: &str = "COLUMNS
+int ID
+string Directory
+locstring MapName_lang
+int<AreaTable::ID> AreaTableID
+float Corpse
+int Type
+
+BUILD 1.12.1.5875
+$id$ID<32>
+Directory
+MapName_lang
+AreaTableID<32>
+Corpse[2]
+Type<u8>
+";
+
+    #[test]
+    fn vanilla_table() {
+        let file = load_file_from_string(CONTENTS, "Map.dbd")
+            .unwrap()
+            .into_proper()
+            .unwrap();
+        let s = generate(&file, &Version::new(1, 12, 1, 5875)).unwrap();
+
+        assert!(s.contains("use crate::vanilla_tables::area_table::AreaTableKey;"));
+        assert!(s.contains("use crate::LocalizedString;"));
+        assert!(s.contains("if header.record_size != 57 {"));
+        assert!(s.contains("if header.field_count != 15 {"));
+        assert!(s.contains("    pub id: MapKey,"));
+        assert!(s.contains("    pub corpse: [f32; 2],"));
+        assert!(s.contains("    pub ty: u8,"));
+        assert!(s.contains("self.rows.iter().find(|a| a.id.id == key.id)"));
+        assert!(s.contains("if !row.directory.is_empty() { b.write_all(row.directory.as_bytes())?; b.write_all(&[0])?; }\n"));
+        assert!(s.contains("if !row.directory.is_empty() { sum += row.directory.len() + 1; }\n"));
+        assert!(s.contains(
+            "            row.map_name_lang.string_block_as_array(b)?;\n        }\n\n        Ok(())"
+        ));
+
+        assert!(generate(&file, &Version::new(4, 3, 4, 15595)).is_err());
+    }
+
+    #[test]
+    fn type_overrides() {
+        let file = load_file_from_string(CONTENTS, "Map.dbd")
+            .unwrap()
+            .into_proper_with_overrides(
+                &TypeOverrides::parse(
+                    "Map::Type bool\nMap::Directory path\nMap::Corpse custom Coordinate\n",
+                )
+                .unwrap(),
+            )
+            .unwrap();
+        let s = generate(&file, &Version::new(1, 12, 1, 5875)).unwrap();
+
+        assert!(s.contains("    pub ty: bool,"));
+        assert!(s.contains("let ty = crate::util::read_u8_le(chunk)? != 0;"));
+        assert!(s.contains("b.write_all(&u8::from(row.ty).to_le_bytes())?;"));
+        assert!(s.contains("    /// Path.\n    pub directory: String,"));
+        assert!(s.contains("    pub corpse: [Coordinate; 2],"));
+        assert!(s.contains("b.write_all(&f32::from(*i).to_le_bytes())?;"));
+    }
+
+    #[test]
+    fn enums() {
+        let mut file = load_file_from_string(CONTENTS, "Map.dbd")
+            .unwrap()
+            .into_proper()
+            .unwrap();
+        file.attach_enums(
+            &parse_enums("FLAGS MapType Map::Type\n0x1 IsInstance\n0x10 NoCorpse // Unused\n")
+                .unwrap(),
+        );
+        let s = generate(&file, &Version::new(1, 12, 1, 5875)).unwrap();
+
+        assert!(s.contains("    pub ty: MapType,"));
+        assert!(s.contains("let ty = MapType::new(crate::util::read_u8_le(chunk)?);"));
+        assert!(s.contains("b.write_all(&row.ty.as_int().to_le_bytes())?;"));
+        assert!(s.contains("pub struct MapType {\n    value: u8,\n}"));
+        assert!(s.contains("    /// Unused\n    pub const fn no_corpse(&self) -> bool { (self.value & 0x10) != 0 }"));
+    }
+
+    #[test]
+    fn conversion() {
+        let file = load_file_from_string(
+            "COLUMNS\nint ID\nint Flags\nint Unk0\nlocstring Name_lang\nfloat Corpse\nint<AreaTable::ID> AreaTableID\nint Expansion\n\nBUILD 1.12.1.5875\n$id$ID<32>\nFlags<32>\nUnk0<32>\nName_lang\nCorpse[2]\nAreaTableID<32>\n\nBUILD 3.3.5.12340\n$id$ID<32>\nFlags<u16>\nName_lang\nCorpse[2]\nAreaTableID<32>\nExpansion<8>\n",
+            "Map.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+        let vanilla = Version::new(1, 12, 1, 5875);
+        let wrath = Version::new(3, 3, 5, 12340);
+
+        assert_eq!(
+            generate_conversion(&file, &vanilla, &wrath).unwrap(),
+            "impl From<crate::vanilla_tables::map::MapRow> for crate::wrath_tables::map::MapRow {
+    fn from(v: crate::vanilla_tables::map::MapRow) -> Self {
+        // Removed: unk0
+        Self {
+            id: v.id.id.into(),
+            flags: v.flags as u16,
+            name_lang: Default::default(), // Can not convert LocalizedString into ExtendedLocalizedString
+            corpse: v.corpse,
+            area_table_id: v.area_table_id.id.into(),
+            expansion: Default::default(),
+        }
+    }
+}
+"
+        );
+        assert!(generate_conversion(&file, &vanilla, &vanilla).is_err());
+    }
+
+    #[test]
+    fn name_overrides() {
+        let file = load_file_from_string(CONTENTS, "Map.dbd")
+            .unwrap()
+            .into_proper()
+            .unwrap();
+        let names = NameOverrides::new(RustNames)
+            .with(NameKind::Field, "Type", "map_type")
+            .with(NameKind::Module, "AreaTable", "area");
+        let s = generate_with_names(&file, &Version::new(1, 12, 1, 5875), &names).unwrap();
+
+        assert!(s.contains("use crate::vanilla_tables::area::AreaTableKey;"));
+        assert!(s.contains("    pub map_type: u8,"));
+        assert!(s.contains("fn filename() -> &'static str { \"Map.dbc\" }"));
+    }
+}
@@ -0,0 +1,198 @@
+//! [Zig](https://ziglang.org) `packed struct` generation.
+//!
+//! Generates a `packed struct` with the same layout as a record of the `DBC` file.
+//! Packed structs can not contain arrays, so arrays are written as a field for every element
+//! with the index appended to the name, and `locstring`s as a field for every string of the version
+//! followed by a flags field if the version has one.
+//! Strings are written as `u32` offsets into the string block.
+//!
+//! Integer columns with an attached [`enum`](crate::enums) or with values listed in their comment,
+//! for example `// 0: none, 1: party`, are written as non-exhaustive enums.
+
+use crate::codegen::naming::{NameKind, NameMapper, ZigNames};
+use crate::codegen::{entry_enum, layout_definition, write_doc_lines, DocOptions};
+use crate::enums::EnumDefinition;
+use crate::error::CodegenError;
+use crate::writer::Writer;
+use crate::{DbdFile, ScalarType, Version};
+
+const fn zig_type(ty: ScalarType) -> &'static str {
+    match ty {
+        ScalarType::Int8 => "i8",
+        ScalarType::Int16 => "i16",
+        ScalarType::Int32 => "i32",
+        ScalarType::Int64 => "i64",
+        ScalarType::UInt8 => "u8",
+        ScalarType::UInt16 => "u16",
+        ScalarType::UInt32 => "u32",
+        ScalarType::UInt64 => "u64",
+        ScalarType::Float => "f32",
+        ScalarType::LocString | ScalarType::String => "u32",
+    }
+}
+
+fn write_enum(s: &mut Writer, e: &EnumDefinition, ty: &str, names: &impl NameMapper) {
+    s.wln(format!(
+        "pub const {} = enum({}) {{",
+        names.map(NameKind::Type, &e.name),
+        ty
+    ));
+    for value in &e.values {
+        if let Some(comment) = &value.comment {
+            s.wln(format!("    /// {}", comment));
+        }
+        s.wln(format!(
+            "    {} = {},",
+            names.map(NameKind::Field, &value.name),
+            value.value
+        ));
+    }
+    s.wln("    _,");
+    s.wln("};");
+    s.newline();
+}
+
+/// Generate a `packed struct` for the definition of `file` valid for `version`.
+///
+/// # Errors
+///
+/// Errors if there is no definition for `version`.
+pub fn generate(file: &DbdFile, version: &Version) -> Result<String, CodegenError> {
+    generate_with_names(file, version, &ZigNames)
+}
+
+/// Same as [`generate`] but with the identifiers from `names` instead of [`ZigNames`].
+///
+/// # Errors
+///
+/// Same as [`generate`].
+pub fn generate_with_names(
+    file: &DbdFile,
+    version: &Version,
+    names: &impl NameMapper,
+) -> Result<String, CodegenError> {
+    generate_with_options(file, version, names, &DocOptions::new())
+}
+
+/// Same as [`generate_with_names`] but also writes comments according to `docs`.
+///
+/// # Errors
+///
+/// Same as [`generate`].
+pub fn generate_with_options(
+    file: &DbdFile,
+    version: &Version,
+    names: &impl NameMapper,
+    docs: &DocOptions,
+) -> Result<String, CodegenError> {
+    let definition = layout_definition(file, version)?;
+    let table = file.table_name();
+
+    let mut s = Writer::new();
+
+    let mut enums: Vec<String> = Vec::new();
+    for entry in &definition.entries {
+        if let Some(e) = entry_enum(table, entry) {
+            if !enums.contains(&e.name) {
+                write_enum(&mut s, &e, zig_type(entry.ty.scalar()), names);
+                enums.push(e.name);
+            }
+        }
+    }
+
+    s.wln(format!(
+        "pub const {} = packed struct {{",
+        names.map(NameKind::Type, table)
+    ));
+
+    for entry in &definition.entries {
+        let name = names.map(NameKind::Field, &entry.name);
+        let scalar = entry.ty.scalar();
+        let ty = entry_enum(table, entry).map_or_else(
+            || zig_type(scalar).to_string(),
+            |e| names.map(NameKind::Type, &e.name),
+        );
+
+        write_doc_lines(&mut s, "    ///", &docs.lines(entry));
+
+        let count = entry.ty.array_width();
+        let strings = version.locstring_strings();
+        match (scalar, count) {
+            (ScalarType::LocString, count) => {
+                for i in 0..count.unwrap_or(1) {
+                    let name = match count {
+                        Some(_) => format!("{}_{}", name, i),
+                        None => name.clone(),
+                    };
+                    if strings == 1 {
+                        s.wln(format!("    {}: u32,", name));
+                    } else {
+                        for j in 0..strings {
+                            s.wln(format!("    {}_{}: u32,", name, j));
+                        }
+                    }
+                    if version.locstring_has_flags() {
+                        s.wln(format!("    {}_flags: u32,", name));
+                    }
+                }
+            }
+            (_, Some(count)) => {
+                for i in 0..count {
+                    s.wln(format!("    {}_{}: {},", name, i, ty));
+                }
+            }
+            (_, None) => s.wln(format!("    {}: {},", name, ty)),
+        }
+    }
+
+    s.wln("};");
+
+    Ok(s.inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codegen::zig::generate;
+    use crate::{load_file_from_string, Version};
+
+    const CONTENTS: &str = "COLUMNS
+int ID
+string Directory
+locstring MapName_lang
+float Corpse
+int InstanceType // 0: none, 1: party, 2: raid
+int Type
+
+BUILD 1.12.1.5875
+$id$ID<32>
+Directory
+MapName_lang
+Corpse[2]
+InstanceType<u8>
+Type<16>
+
+BUILD 4.3.4.15595
+$id$ID<32>
+MapName_lang
+";
+
+    #[test]
+    fn packed_struct() {
+        let file = load_file_from_string(CONTENTS, "Map.dbd")
+            .unwrap()
+            .into_proper()
+            .unwrap();
+
+        let s = generate(&file, &Version::new(1, 12, 1, 5875)).unwrap();
+        assert!(s.starts_with(
+            "pub const MapInstanceType = enum(u8) {\n    none = 0,\n    party = 1,\n    raid = 2,\n    _,\n};\n"
+        ));
+        assert!(s.contains("    directory: u32,\n    map_name_lang_0: u32,\n"));
+        assert!(s.contains("    map_name_lang_7: u32,\n    map_name_lang_flags: u32,\n"));
+        assert!(s.contains("    corpse_0: f32,\n    corpse_1: f32,\n"));
+        assert!(s.contains("    instance_type: MapInstanceType,\n    @\"type\": i16,\n};"));
+
+        let s = generate(&file, &Version::new(4, 3, 4, 15595)).unwrap();
+        assert!(s.contains("    map_name_lang: u32,\n};"));
+    }
+}
@@ -0,0 +1,102 @@
+use crate::manifest::ManifestEntry;
+use crate::{DbdFile, Layout};
+use std::collections::BTreeMap;
+
+/// Collection of parsed and validated files, for example an entire `definitions` directory.
+///
+/// Created from [`load_directory`](crate::load_directory), [`load_repository`](crate::load_repository)
+/// or [`DbdCollection::from_files`].
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct DbdCollection {
+    /// Files keyed by table name, without `.dbd`.
+    pub files: BTreeMap<String, DbdFile>,
+    /// Entries of `manifest.json` keyed by table name.
+    ///
+    /// Only set by [`load_repository`](crate::load_repository).
+    pub manifest: BTreeMap<String, ManifestEntry>,
+}
+
+impl DbdCollection {
+    /// Creates an empty collection.
+    pub fn new() -> Self {
+        Self {
+            files: BTreeMap::new(),
+            manifest: BTreeMap::new(),
+        }
+    }
+
+    /// Creates a collection from already loaded files.
+    ///
+    /// Files with the same table name replace previous ones.
+    pub fn from_files(files: impl IntoIterator<Item = DbdFile>) -> Self {
+        let mut s = Self::new();
+
+        for file in files {
+            s.insert(file);
+        }
+
+        s
+    }
+
+    /// Inserts a file, returning the previous file with the same table name, if any.
+    pub fn insert(&mut self, file: DbdFile) -> Option<DbdFile> {
+        self.files.insert(file.table_name().to_string(), file)
+    }
+
+    /// Finds the file for `table`, without `.dbd`.
+    pub fn get(&self, table: &str) -> Option<&DbdFile> {
+        self.files.get(table)
+    }
+
+    /// Finds the file for `table`, without `.dbd`.
+    pub fn get_mut(&mut self, table: &str) -> Option<&mut DbdFile> {
+        self.files.get_mut(table)
+    }
+
+    /// Iterates over all files, sorted by table name.
+    pub fn iter(&self) -> impl Iterator<Item = &DbdFile> {
+        self.files.values()
+    }
+
+    /// Amount of files in the collection.
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Finds the `manifest.json` entry for `table`, without `.dbd`.
+    pub fn manifest_entry(&self, table: &str) -> Option<&ManifestEntry> {
+        self.manifest.get(table)
+    }
+
+    /// Finds the hash of the name of `table`, without `.dbd`, as used in `.db2` headers and hotfixes.
+    ///
+    /// The hash is taken from [`DbdCollection::manifest`].
+    pub fn table_hash(&self, table: &str) -> Option<u32> {
+        self.manifest_entry(table)?.table_hash
+    }
+
+    /// Finds the name of the table with the name hash `hash` in [`DbdCollection::manifest`].
+    ///
+    /// This is the reverse of [`DbdCollection::table_hash`].
+    pub fn table_name_from_hash(&self, hash: u32) -> Option<&str> {
+        self.manifest
+            .values()
+            .find(|a| a.table_hash == Some(hash))
+            .map(|a| a.table_name.as_str())
+    }
+
+    /// Returns true if the collection has no files.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Finds the [`DbdFile::latest_layout`] of every table, keyed by table name.
+    ///
+    /// Tables without any layouts are not included.
+    pub fn latest_layouts(&self) -> BTreeMap<&str, &Layout> {
+        self.files
+            .iter()
+            .filter_map(|(table, file)| Some((table.as_str(), file.latest_layout()?)))
+            .collect()
+    }
+}
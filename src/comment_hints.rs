@@ -0,0 +1,158 @@
+//! Classification of entry and column comments into semantic hints.
+//!
+//! Comments are free text, but often follow a few conventions:
+//!
+//! * `// reference to World\Map\` points to a path in the game files.
+//! * `// 0: none, 1: party, 2: raid` lists the meaning of values.
+//! * `// in yards` gives the unit of the value.
+//!
+//! Classification is opt-in through [`Entry::comment_hints`] and never changes the parsed comments.
+
+use crate::Entry;
+
+/// Units recognized by [`CommentHint::classify`], matched as whole words without regard to case.
+const UNITS: &[&str] = &[
+    "ms",
+    "milliseconds",
+    "seconds",
+    "sec",
+    "minutes",
+    "hours",
+    "yards",
+    "yd",
+    "degrees",
+    "radians",
+    "percent",
+    "%",
+];
+
+/// Semantic hint found in a comment.
+///
+/// Created from [`CommentHint::classify`] and [`Entry::comment_hints`].
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum CommentHint {
+    /// Reference to a path in the game files, for example `World\Map\`.
+    FilePath(String),
+    /// Meaning of specific values, for example `0: none, 1: party`.
+    ///
+    /// Only parts of the comment in the form `<value>: <name>` or `<value> = <name>` are included.
+    EnumValues(Vec<(i64, String)>),
+    /// Unit of the value, for example `yards`. Always lowercase.
+    Unit(String),
+    /// Comment that does not match any of the other hints.
+    Text(String),
+}
+
+impl CommentHint {
+    /// Classifies a single comment, without the leading `//`.
+    ///
+    /// Enum values are checked first, then file paths, then units.
+    /// At least two values are required for [`CommentHint::EnumValues`].
+    pub fn classify(comment: &str) -> CommentHint {
+        let comment = comment.trim();
+
+        let values = enum_values(comment);
+        if values.len() >= 2 {
+            return CommentHint::EnumValues(values);
+        }
+
+        if let Some(path) = comment.split_whitespace().find(|a| a.contains('\\')) {
+            return CommentHint::FilePath(path.to_string());
+        }
+
+        let lowercase = comment.to_lowercase();
+        if let Some(unit) = lowercase
+            .split(|c: char| c.is_whitespace() || c == '(' || c == ')' || c == ',')
+            .find(|a| UNITS.contains(a))
+        {
+            return CommentHint::Unit(unit.to_string());
+        }
+
+        CommentHint::Text(comment.to_string())
+    }
+}
+
+fn enum_values(comment: &str) -> Vec<(i64, String)> {
+    comment
+        .split([',', ';'])
+        .filter_map(|part| {
+            let (value, name) = part.split_once([':', '='])?;
+            let value = value.split_whitespace().last()?;
+
+            let value = if let Some(hex) = value.strip_prefix("0x") {
+                i64::from_str_radix(hex, 16).ok()?
+            } else {
+                value.parse().ok()?
+            };
+
+            let name = name.trim();
+            if name.is_empty() {
+                return None;
+            }
+
+            Some((value, name.to_string()))
+        })
+        .collect()
+}
+
+impl Entry {
+    /// Classifies [`Entry::comment`] and [`Entry::column_comment`] with [`CommentHint::classify`],
+    /// in that order.
+    ///
+    /// Comments that are not set are skipped.
+    pub fn comment_hints(&self) -> Vec<CommentHint> {
+        self.comment
+            .iter()
+            .chain(self.column_comment.iter())
+            .map(|a| CommentHint::classify(a))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::comment_hints::CommentHint;
+    use crate::load_file_from_string;
+
+    #[test]
+    fn classify() {
+        assert_eq!(
+            CommentHint::classify(" reference to World\\Map\\ [...]"),
+            CommentHint::FilePath("World\\Map\\".to_string())
+        );
+        assert_eq!(
+            CommentHint::classify("Integer 0: none, 1: party, 2: raid, >=5: none"),
+            CommentHint::EnumValues(vec![
+                (0, "none".to_string()),
+                (1, "party".to_string()),
+                (2, "raid".to_string()),
+            ])
+        );
+        assert_eq!(
+            CommentHint::classify("Radius in Yards"),
+            CommentHint::Unit("yards".to_string())
+        );
+        assert_eq!(
+            CommentHint::classify("Parent map"),
+            CommentHint::Text("Parent map".to_string())
+        );
+
+        let file = load_file_from_string(
+            "COLUMNS\nint Cooldown // Cooldown (ms)\n\nBUILD 1.12.1.5875\nCooldown<32> // 0x1: Global, 0x2: Category\n",
+            "Spell.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+        assert_eq!(
+            file.definitions[0].entries[0].comment_hints(),
+            vec![
+                CommentHint::EnumValues(vec![
+                    (1, "Global".to_string()),
+                    (2, "Category".to_string()),
+                ]),
+                CommentHint::Unit("ms".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,362 @@
+//! Compact, index based model for holding very many files in memory at once.
+//!
+//! Analyses that load the entire history of the definitions repository keep thousands of
+//! nearly identical [`DbdFile`]s alive, where every name, comment and type is its own allocation.
+//! [`CompactTables`] interns these once and [`CompactFile`] only stores `u32` ids into the tables,
+//! so identical names, comments and types across every file and commit are shared.
+//!
+//! Entries and layouts are stored in boxed slices instead of [`Vec`]s since they are never resized.
+//!
+//! ```rust
+//! use wowdbdefs_rs::compact::CompactTables;
+//! use wowdbdefs_rs::load_file_from_string;
+//!
+//! let file = load_file_from_string("COLUMNS\nint ID\n\nBUILD 1.12.1.5875\n$id$ID<32>\n\n", "Map.dbd")
+//!     .unwrap()
+//!     .into_proper()
+//!     .unwrap();
+//!
+//! let mut tables = CompactTables::new();
+//! let compact = tables.add(&file);
+//! assert_eq!(tables.resolve(compact.name), "Map.dbd");
+//! assert_eq!(compact.to_dbd_file(&tables), file);
+//! ```
+
+use crate::{DbdFile, Definition, Entry, Layout, Type, VersionSet};
+use std::collections::HashMap;
+use std::convert::TryInto;
+
+/// Id of a string in [`CompactTables`].
+#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    /// Index of the string in [`CompactTables`].
+    pub const fn index(&self) -> u32 {
+        self.0
+    }
+}
+
+/// Id of a [`Type`] in [`CompactTables`].
+#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct TypeId(u32);
+
+impl TypeId {
+    /// Index of the type in [`CompactTables`].
+    pub const fn index(&self) -> u32 {
+        self.0
+    }
+}
+
+/// Shared tables of strings and types used by [`CompactFile`]s.
+///
+/// Every [`CompactFile`] must be resolved with the tables it was added to.
+#[derive(Debug, Clone, Default)]
+pub struct CompactTables {
+    strings: Vec<Box<str>>,
+    string_ids: HashMap<Box<str>, Symbol>,
+    types: Vec<Type>,
+    type_ids: HashMap<Type, TypeId>,
+}
+
+impl CompactTables {
+    /// Creates empty tables.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the id of `s`, adding it if it is not already in the tables.
+    ///
+    /// # Panics
+    ///
+    /// Panics if more than [`u32::MAX`] strings are added.
+    pub fn intern(&mut self, s: &str) -> Symbol {
+        if let Some(symbol) = self.string_ids.get(s) {
+            return *symbol;
+        }
+
+        let symbol = Symbol(
+            self.strings
+                .len()
+                .try_into()
+                .expect("more than u32::MAX strings"),
+        );
+        self.strings.push(s.into());
+        self.string_ids.insert(s.into(), symbol);
+
+        symbol
+    }
+
+    /// Returns the id of `ty`, adding it if it is not already in the tables.
+    ///
+    /// # Panics
+    ///
+    /// Panics if more than [`u32::MAX`] types are added.
+    pub fn intern_type(&mut self, ty: &Type) -> TypeId {
+        if let Some(id) = self.type_ids.get(ty) {
+            return *id;
+        }
+
+        let id = TypeId(
+            self.types
+                .len()
+                .try_into()
+                .expect("more than u32::MAX types"),
+        );
+        self.types.push(ty.clone());
+        self.type_ids.insert(ty.clone(), id);
+
+        id
+    }
+
+    /// String of `symbol`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `symbol` is not from these tables.
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        &self.strings[symbol.0 as usize]
+    }
+
+    /// Type of `id`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is not from these tables.
+    pub fn resolve_type(&self, id: TypeId) -> &Type {
+        &self.types[id.0 as usize]
+    }
+
+    /// Amount of distinct strings in the tables.
+    pub fn strings_len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// Amount of distinct types in the tables.
+    pub fn types_len(&self) -> usize {
+        self.types.len()
+    }
+
+    /// Converts `file` into a [`CompactFile`] that uses these tables.
+    pub fn add(&mut self, file: &DbdFile) -> CompactFile {
+        CompactFile {
+            name: self.intern(&file.name),
+            definitions: file
+                .definitions
+                .iter()
+                .map(|a| self.add_definition(a))
+                .collect(),
+        }
+    }
+
+    fn add_definition(&mut self, definition: &Definition) -> CompactDefinition {
+        CompactDefinition {
+            versions: definition.versions.clone(),
+            layouts: definition.layouts.iter().cloned().collect(),
+            entries: definition
+                .entries
+                .iter()
+                .map(|a| self.add_entry(a))
+                .collect(),
+            filtered: definition.is_filtered(),
+        }
+    }
+
+    fn add_entry(&mut self, entry: &Entry) -> CompactEntry {
+        let mut flags = 0;
+        for (set, flag) in [
+            (entry.verified, CompactEntry::VERIFIED),
+            (entry.primary_key, CompactEntry::PRIMARY_KEY),
+            (entry.inline, CompactEntry::INLINE),
+            (entry.relation, CompactEntry::RELATION),
+        ] {
+            if set {
+                flags |= flag;
+            }
+        }
+
+        CompactEntry {
+            name: self.intern(&entry.name),
+            ty: self.intern_type(&entry.ty),
+            comment: entry.comment.as_deref().map(|a| self.intern(a)),
+            column_comment: entry.column_comment.as_deref().map(|a| self.intern(a)),
+            flags,
+        }
+    }
+}
+
+/// Compact version of a [`DbdFile`].
+///
+/// Created from [`CompactTables::add`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct CompactFile {
+    /// Name of the `dbd` file, including `.dbd`.
+    pub name: Symbol,
+    /// Definitions of the file.
+    pub definitions: Box<[CompactDefinition]>,
+}
+
+impl CompactFile {
+    /// Converts back into a [`DbdFile`].
+    ///
+    /// [`Entry::enumeration`] and [`Entry::type_override`] are not kept by the compact model
+    /// and are always [`None`].
+    pub fn to_dbd_file(&self, tables: &CompactTables) -> DbdFile {
+        DbdFile {
+            name: tables.resolve(self.name).to_string(),
+            definitions: self
+                .definitions
+                .iter()
+                .map(|a| a.to_definition(tables))
+                .collect(),
+        }
+    }
+}
+
+/// Compact version of a [`Definition`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct CompactDefinition {
+    /// Valid versions.
+    pub versions: VersionSet,
+    /// Specific valid layouts, sorted.
+    pub layouts: Box<[Layout]>,
+    /// Entries in the definition.
+    pub entries: Box<[CompactEntry]>,
+    filtered: bool,
+}
+
+impl CompactDefinition {
+    /// Same as [`Definition::is_filtered`].
+    pub const fn is_filtered(&self) -> bool {
+        self.filtered
+    }
+
+    /// Converts back into a [`Definition`].
+    pub fn to_definition(&self, tables: &CompactTables) -> Definition {
+        Definition {
+            versions: self.versions.clone(),
+            layouts: self.layouts.iter().cloned().collect(),
+            entries: self.entries.iter().map(|a| a.to_entry(tables)).collect(),
+            filtered: self.filtered,
+        }
+    }
+}
+
+/// Compact version of an [`Entry`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct CompactEntry {
+    /// Name of the column.
+    pub name: Symbol,
+    /// Type of the column.
+    pub ty: TypeId,
+    /// Comment on the entry itself.
+    pub comment: Option<Symbol>,
+    /// Comment on the column definition under `COLUMNS`.
+    pub column_comment: Option<Symbol>,
+    flags: u8,
+}
+
+impl CompactEntry {
+    const VERIFIED: u8 = 1 << 0;
+    const PRIMARY_KEY: u8 = 1 << 1;
+    const INLINE: u8 = 1 << 2;
+    const RELATION: u8 = 1 << 3;
+
+    /// Same as [`Entry::verified`].
+    pub const fn verified(&self) -> bool {
+        self.flags & Self::VERIFIED != 0
+    }
+
+    /// Same as [`Entry::primary_key`].
+    pub const fn primary_key(&self) -> bool {
+        self.flags & Self::PRIMARY_KEY != 0
+    }
+
+    /// Same as [`Entry::inline`].
+    pub const fn inline(&self) -> bool {
+        self.flags & Self::INLINE != 0
+    }
+
+    /// Same as [`Entry::relation`].
+    pub const fn relation(&self) -> bool {
+        self.flags & Self::RELATION != 0
+    }
+
+    /// Converts back into an [`Entry`].
+    pub fn to_entry(&self, tables: &CompactTables) -> Entry {
+        Entry {
+            name: tables.resolve(self.name).to_string(),
+            ty: tables.resolve_type(self.ty).clone(),
+            comment: self.comment.map(|a| tables.resolve(a).to_string()),
+            column_comment: self.column_comment.map(|a| tables.resolve(a).to_string()),
+            verified: self.verified(),
+            primary_key: self.primary_key(),
+            inline: self.inline(),
+            relation: self.relation(),
+            enumeration: None,
+            type_override: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compact::CompactTables;
+    use crate::error::CodegenError;
+    use crate::{load_file_from_string, DbdFile, EntryFilter, Version};
+
+    fn file(contents: &str) -> DbdFile {
+        load_file_from_string(contents, "Map.dbd")
+            .unwrap()
+            .into_proper()
+            .unwrap()
+    }
+
+    #[test]
+    fn shared_tables() {
+        let old = file("COLUMNS\nint ID\nint Flags // Unknown\nlocstring Name_lang\n\nBUILD 1.12.1.5875\n$id$ID<32>\nFlags<u32>\nName_lang\n\n");
+        let new = file("COLUMNS\nint ID\nint Flags // Unknown\nlocstring Name_lang\nint Unk0?\n\nBUILD 1.12.1.5875\n$id$ID<32>\nFlags<u32>\nName_lang\n\nBUILD 3.3.5.12340\n$id$ID<32>\nFlags<u32>\nName_lang\nUnk0<16>\n\n");
+
+        let mut tables = CompactTables::new();
+        let compact_old = tables.add(&old);
+        let compact_new = tables.add(&new);
+
+        assert_eq!(compact_old.to_dbd_file(&tables), old);
+        assert_eq!(compact_new.to_dbd_file(&tables), new);
+
+        // Map.dbd, ID, Flags, Unknown, Name_lang and Unk0.
+        assert_eq!(tables.strings_len(), 6);
+        // i32, u32, locstring and i16.
+        assert_eq!(tables.types_len(), 4);
+        assert_eq!(compact_old.definitions[0], compact_new.definitions[0]);
+
+        let id = &compact_new.definitions[1].entries[0];
+        assert!(id.primary_key());
+        assert!(id.verified());
+        assert!(!compact_new.definitions[1].entries[3].verified());
+    }
+
+    #[test]
+    fn filtered_definition() {
+        let mut filtered = file(
+            "COLUMNS\nint ID\nint Flags\n\nBUILD 1.12.1.5875\n$noninline,id$ID<32>\nFlags<u32>\n\n",
+        );
+        filtered.filter_entries(&EntryFilter {
+            exclude_noninline: true,
+            ..EntryFilter::new()
+        });
+
+        let mut tables = CompactTables::new();
+        let compact = tables.add(&filtered);
+        assert!(compact.definitions[0].is_filtered());
+
+        let expanded = compact.to_dbd_file(&tables);
+        assert!(expanded.definitions[0].is_filtered());
+        assert_eq!(
+            crate::codegen::wow_dbc::generate(&expanded, &Version::new(1, 12, 1, 5875)),
+            Err(CodegenError::FilteredDefinition(Version::new(
+                1, 12, 1, 5875
+            )))
+        );
+    }
+}
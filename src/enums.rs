@@ -0,0 +1,271 @@
+//! Sidecar files with named values for integer columns.
+//!
+//! The `.dbd` format has no way of describing the meaning of integer values,
+//! so enums and flags can be declared in a separate file and attached to the entries
+//! with [`DbdFile::attach_enums`] or [`DbdCollection::attach_enums`].
+//! Codegen backends use the attached values to generate proper enum and flag types.
+//!
+//! The format is line based like `.dbd` files.
+//! Every block starts with either `ENUM` or `FLAGS`, the name of the type and a comma separated list of columns,
+//! and is followed by one value per line.
+//! Blocks are separated by empty lines and comments start with `//`.
+//!
+//! ```text
+//! ENUM InstanceType Map::InstanceType
+//! 0 None
+//! 1 Party
+//! 2 Raid // Also used for some scenarios
+//!
+//! FLAGS MapFlags Map::Flags, Map::Flags2
+//! 0x1 Unk0
+//! 0x2 Instanceable
+//! ```
+
+use crate::error::EnumsError;
+use crate::{DbdCollection, DbdFile};
+use std::fs::read_to_string;
+use std::path::Path;
+
+/// Whether the values are exclusive or can be combined.
+#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum EnumKind {
+    /// Only a single value is valid at a time.
+    Enum,
+    /// Values are bits that can be combined.
+    Flags,
+}
+
+/// Reference to a column in a specific table.
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct ColumnReference {
+    /// Name of the table, without `.dbd`.
+    pub table: String,
+    /// Name of the column.
+    pub column: String,
+}
+
+impl ColumnReference {
+    /// Constructor for column reference.
+    pub fn new(table: impl Into<String>, column: impl Into<String>) -> Self {
+        Self {
+            table: table.into(),
+            column: column.into(),
+        }
+    }
+}
+
+/// Single named value.
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct EnumValue {
+    /// Name of the value.
+    pub name: String,
+    /// Integer value.
+    pub value: i64,
+    /// Comment placed after the value with `//`.
+    pub comment: Option<String>,
+}
+
+/// Enum or flags declared in a sidecar file.
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct EnumDefinition {
+    /// Name of the type.
+    pub name: String,
+    /// Enum or flags.
+    pub kind: EnumKind,
+    /// Columns the values apply to.
+    pub columns: Vec<ColumnReference>,
+    /// Named values.
+    pub values: Vec<EnumValue>,
+}
+
+/// Parse the contents of a sidecar enum file.
+///
+/// # Errors
+///
+/// Errors if a block header or value line is malformed.
+pub fn parse_enums(contents: &str) -> Result<Vec<EnumDefinition>, EnumsError> {
+    let mut enums: Vec<EnumDefinition> = Vec::new();
+    let mut in_block = false;
+
+    for (line_count, line) in contents.lines().enumerate() {
+        let (line, comment) = if let Some((line, comment)) = line.split_once("//") {
+            (line.trim(), Some(comment.trim().to_string()))
+        } else {
+            (line.trim(), None)
+        };
+
+        if line.is_empty() {
+            if comment.is_none() {
+                in_block = false;
+            }
+            continue;
+        }
+
+        let kind = if line.starts_with("ENUM ") {
+            Some(EnumKind::Enum)
+        } else if line.starts_with("FLAGS ") {
+            Some(EnumKind::Flags)
+        } else {
+            None
+        };
+
+        if let Some(kind) = kind {
+            let err = || EnumsError::InvalidHeader {
+                line: line_count + 1,
+                contents: line.to_string(),
+            };
+
+            let (_, rest) = line.split_once(' ').ok_or_else(err)?;
+            let (name, columns) = rest.trim().split_once(' ').ok_or_else(err)?;
+
+            let mut references = Vec::new();
+            for column in columns.split(',') {
+                let (table, column) = column.trim().split_once("::").ok_or_else(err)?;
+                references.push(ColumnReference {
+                    table: table.to_string(),
+                    column: column.to_string(),
+                });
+            }
+
+            enums.push(EnumDefinition {
+                name: name.to_string(),
+                kind,
+                columns: references,
+                values: vec![],
+            });
+            in_block = true;
+
+            continue;
+        }
+
+        let err = || EnumsError::InvalidValue {
+            line: line_count + 1,
+            contents: line.to_string(),
+        };
+
+        let definition = match enums.last_mut() {
+            Some(definition) if in_block => definition,
+            _ => return Err(err()),
+        };
+
+        let (value, name) = line.split_once(' ').ok_or_else(err)?;
+        let value = if let Some(hex) = value.strip_prefix("0x") {
+            u64::from_str_radix(hex, 16).map(|a| a as i64).ok()
+        } else {
+            value.parse::<i64>().ok()
+        }
+        .ok_or_else(err)?;
+
+        definition.values.push(EnumValue {
+            name: name.trim().to_string(),
+            value,
+            comment,
+        });
+    }
+
+    Ok(enums)
+}
+
+/// Load and parse a sidecar enum file.
+///
+/// # Errors
+///
+/// The function has two error types:
+///
+/// * [`std::io::Error`], for errors in reading the file.
+/// * [`EnumsError`], for errors in parsing the file.
+///
+pub fn load_enums(path: &Path) -> std::io::Result<Result<Vec<EnumDefinition>, EnumsError>> {
+    let contents = read_to_string(path)?;
+
+    Ok(parse_enums(&contents))
+}
+
+impl DbdFile {
+    /// Attach the enums that reference columns of this table to the matching entries.
+    pub fn attach_enums(&mut self, enums: &[EnumDefinition]) {
+        let table = self.table_name().to_string();
+
+        for definition in &mut self.definitions {
+            for entry in &mut definition.entries {
+                let e = enums.iter().find(|e| {
+                    e.columns
+                        .iter()
+                        .any(|c| c.table == table && c.column == entry.name)
+                });
+
+                if let Some(e) = e {
+                    entry.enumeration = Some(e.clone());
+                }
+            }
+        }
+    }
+}
+
+impl DbdCollection {
+    /// Attach the enums to the matching entries of all files.
+    pub fn attach_enums(&mut self, enums: &[EnumDefinition]) {
+        for file in self.files.values_mut() {
+            file.attach_enums(enums);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::enums::{parse_enums, EnumKind};
+    use crate::error::EnumsError;
+    use crate::load_file_from_string;
+
+    const ENUMS: &str = "// Map enums
+ENUM InstanceType Map::InstanceType
+0 None
+1 Party // Dungeons
+-1 Invalid
+
+FLAGS MapFlags Map::Flags, AreaTable::Flags
+0x1 Unk0
+0x80000000 Last
+";
+
+    #[test]
+    fn parse_and_attach() {
+        let enums = parse_enums(ENUMS).unwrap();
+        assert_eq!(enums.len(), 2);
+        assert_eq!(enums[0].kind, EnumKind::Enum);
+        assert_eq!(enums[0].values[1].comment.as_deref(), Some("Dungeons"));
+        assert_eq!(enums[0].values[2].value, -1);
+        assert_eq!(enums[1].kind, EnumKind::Flags);
+        assert_eq!(enums[1].columns[1].table, "AreaTable");
+        assert_eq!(enums[1].values[1].value, 0x8000_0000);
+
+        let mut file = load_file_from_string(
+            "COLUMNS\nint ID\nint InstanceType\nint Flags\n\nBUILD 1.12.1.5875\n$id$ID<32>\nInstanceType<32>\nFlags<32>\n",
+            "Map.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+        file.attach_enums(&enums);
+
+        let entries = &file.definitions[0].entries;
+        assert!(entries[0].enumeration.is_none());
+        assert_eq!(
+            entries[1].enumeration.as_ref().unwrap().name,
+            "InstanceType"
+        );
+        assert_eq!(entries[2].enumeration.as_ref().unwrap().name, "MapFlags");
+
+        assert_eq!(
+            parse_enums("1 None\n"),
+            Err(EnumsError::InvalidValue {
+                line: 1,
+                contents: "1 None".to_string()
+            })
+        );
+        assert!(matches!(
+            parse_enums("\nENUM Broken\n"),
+            Err(EnumsError::InvalidHeader { line: 2, .. })
+        ));
+    }
+}
@@ -1,5 +1,7 @@
 //! Error types for the crate.
 //!
+use crate::enums::ColumnReference;
+use crate::{ForeignKey, Type, Version};
 use std::fmt::{Display, Formatter};
 
 /// Main error for parsing the files.
@@ -85,7 +87,47 @@ pub enum DbdErrorReason {
     InvalidBuild(String),
 
     /// The type name is not valid.
-    InvalidType(String),
+    InvalidType {
+        /// Type name found.
+        ty: String,
+        /// Closest valid type name, if any is close enough.
+        suggestion: Option<String>,
+    },
+
+    /// The file exceeds a limit of [`ParseLimits`](crate::ParseLimits).
+    LimitExceeded {
+        /// Limit that was exceeded.
+        limit: Limit,
+        /// Maximum allowed by the limit.
+        max: usize,
+    },
+}
+
+/// Limit of [`ParseLimits`](crate::ParseLimits) for [`DbdErrorReason::LimitExceeded`].
+#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum Limit {
+    /// [`ParseLimits::max_file_size`](crate::ParseLimits::max_file_size).
+    FileSize,
+    /// [`ParseLimits::max_line_length`](crate::ParseLimits::max_line_length).
+    LineLength,
+    /// [`ParseLimits::max_columns`](crate::ParseLimits::max_columns).
+    Columns,
+    /// [`ParseLimits::max_definitions`](crate::ParseLimits::max_definitions).
+    Definitions,
+    /// [`ParseLimits::max_entries`](crate::ParseLimits::max_entries).
+    Entries,
+}
+
+impl Display for Limit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Limit::FileSize => "file size in bytes",
+            Limit::LineLength => "line length in bytes",
+            Limit::Columns => "amount of columns",
+            Limit::Definitions => "amount of definitions",
+            Limit::Entries => "amount of entries",
+        })
+    }
 }
 
 impl Display for DbdErrorReason {
@@ -116,17 +158,78 @@ impl Display for DbdErrorReason {
             DbdErrorReason::InvalidArraySizeNumber(s) => {
                 return f.write_fmt(format_args!("invalid array size: '{}'", s));
             }
-            DbdErrorReason::InvalidType(s) => {
-                return f.write_fmt(format_args!("invalid type name: '{}'", s));
+            DbdErrorReason::InvalidType { ty, suggestion } => {
+                f.write_fmt(format_args!("invalid type name: '{}'", ty))?;
+                return write_suggestion(f, suggestion);
             }
             DbdErrorReason::InvalidBuild(s) => {
                 return f.write_fmt(format_args!("invalid build format: '{}'", s));
             }
+            DbdErrorReason::LimitExceeded { limit, max } => {
+                return f.write_fmt(format_args!("{} is above the limit of {}", limit, max));
+            }
         };
         f.write_str(s)
     }
 }
 
+fn write_suggestion(f: &mut Formatter<'_>, suggestion: &Option<String>) -> std::fmt::Result {
+    if let Some(suggestion) = suggestion {
+        write!(f, ", did you mean '{}'?", suggestion)
+    } else {
+        Ok(())
+    }
+}
+
+/// Finds the candidate with the smallest edit distance to `name`, ignoring case.
+///
+/// Candidates further away than a third of the length of `name`, with a minimum of 1, are not suggested.
+/// Ties are broken by the order of `candidates`.
+pub(crate) fn closest_match<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<String> {
+    let name = name.to_lowercase();
+    let max_distance = (name.chars().count() / 3).max(1);
+
+    let mut best: Option<(usize, &str)> = None;
+    for candidate in candidates {
+        let distance = edit_distance(&name, &candidate.to_lowercase());
+
+        if distance <= max_distance && best.map_or(true, |(d, _)| distance < d) {
+            best = Some((distance, candidate));
+        }
+    }
+
+    best.map(|(_, a)| a.to_string())
+}
+
+/// Edit distance between `a` and `b` in characters, counting swapped adjacent characters as a single edit.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    let mut rows = vec![(0..=b.len()).collect::<Vec<usize>>()];
+    for i in 1..=a.len() {
+        let mut row = vec![i; b.len() + 1];
+
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            row[j] = (rows[i - 1][j] + 1)
+                .min(row[j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                row[j] = row[j].min(rows[i - 2][j - 2] + 1);
+            }
+        }
+
+        rows.push(row);
+    }
+
+    rows[a.len()][b.len()]
+}
+
 /// Errors for converting from raw types to proper types.
 #[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub enum ConversionError {
@@ -136,7 +239,12 @@ pub enum ConversionError {
     NoIntegerWidth,
 
     /// The corresponding column was not found for an entry.
-    ColumnNotFound(String),
+    ColumnNotFound {
+        /// Name of the entry.
+        name: String,
+        /// Closest column name under `COLUMNS`, if any is close enough.
+        suggestion: Option<String>,
+    },
 
     /// A `locstring` is attempted used as a foreign key.
     LocStringAsForeignKey,
@@ -144,6 +252,10 @@ pub enum ConversionError {
     StringAsForeignKey,
     /// A `float` is attempted used as a foreign key.
     FloatAsForeignKey,
+
+    /// A column has different types or foreign keys in different definitions
+    /// and can not be written under `COLUMNS`.
+    ConflictingColumn(String),
 }
 
 impl Display for ConversionError {
@@ -153,7 +265,10 @@ impl Display for ConversionError {
                 write!(f, "invalid integer size '{}'", i)
             }
             ConversionError::NoIntegerWidth => write!(f, "no integer width for integer"),
-            ConversionError::ColumnNotFound(s) => write!(f, "column not found '{}'", s),
+            ConversionError::ColumnNotFound { name, suggestion } => {
+                write!(f, "column not found '{}'", name)?;
+                write_suggestion(f, suggestion)
+            }
             ConversionError::LocStringAsForeignKey => {
                 write!(f, "LocString type is set as foreign key")
             }
@@ -163,8 +278,604 @@ impl Display for ConversionError {
             ConversionError::FloatAsForeignKey => {
                 write!(f, "Float type is set as foreign key")
             }
+            ConversionError::ConflictingColumn(s) => {
+                write!(
+                    f,
+                    "column '{}' has conflicting types between definitions",
+                    s
+                )
+            }
         }
     }
 }
 
 impl std::error::Error for ConversionError {}
+
+/// [`ConversionError`] for a specific entry.
+///
+/// Returned from [`RawDbdFile::into_proper_with_errors`](crate::RawDbdFile::into_proper_with_errors)
+/// and [`RawDefinition::to_definition_with_errors`](crate::RawDefinition::to_definition_with_errors).
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct EntryConversionError {
+    /// Name of the entry.
+    pub entry: String,
+    /// Conversion error.
+    pub error: ConversionError,
+}
+
+impl Display for EntryConversionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "entry '{}': {}", self.entry, self.error)
+    }
+}
+
+impl std::error::Error for EntryConversionError {}
+
+/// Errors for generating code from definitions.
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum CodegenError {
+    /// The file does not have a definition for the requested version.
+    NoDefinitionForVersion(Version),
+    /// The target does not support the requested version.
+    UnsupportedVersion(Version),
+    /// Entries were removed from the definition for the requested version with
+    /// [`DbdFile::filter_entries`](crate::DbdFile::filter_entries),
+    /// so it does not describe the record layout the target requires.
+    FilteredDefinition(Version),
+    /// The entry has a type that the target can not represent.
+    UnsupportedType {
+        /// Name of the entry.
+        entry: String,
+        /// Type of the entry.
+        ty: Type,
+    },
+    /// The entry does not have an id in the [`FieldIds`](crate::codegen::field_ids::FieldIds) used for generation.
+    MissingFieldId(String),
+}
+
+impl Display for CodegenError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CodegenError::NoDefinitionForVersion(v) => {
+                write!(f, "no definition for version '{}'", v)
+            }
+            CodegenError::UnsupportedVersion(v) => {
+                write!(f, "version '{}' is not supported by the target", v)
+            }
+            CodegenError::FilteredDefinition(v) => {
+                write!(f, "definition for version '{}' has filtered entries", v)
+            }
+            CodegenError::UnsupportedType { entry, ty } => {
+                write!(f, "entry '{}' has unsupported type '{:?}'", entry, ty)
+            }
+            CodegenError::MissingFieldId(entry) => {
+                write!(f, "entry '{}' does not have a field id", entry)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CodegenError {}
+
+/// Errors for loading a directory of `.dbd` files.
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum LoadError {
+    /// A file could not be parsed.
+    Parse {
+        /// Name of the file including `.dbd`.
+        file: String,
+        /// Parse error.
+        error: ParseError,
+    },
+    /// A file could not be converted with [`RawDbdFile::into_proper`](crate::RawDbdFile::into_proper).
+    Conversion {
+        /// Name of the file including `.dbd`.
+        file: String,
+        /// Conversion error.
+        error: ConversionError,
+    },
+    /// An entry of a file could not be converted with
+    /// [`RawDbdFile::into_proper_with_errors`](crate::RawDbdFile::into_proper_with_errors).
+    EntryConversion {
+        /// Name of the file including `.dbd`.
+        file: String,
+        /// Conversion error.
+        error: EntryConversionError,
+    },
+    /// `manifest.json` could not be parsed.
+    Manifest(ManifestError),
+    /// A table of a bundle could not be found.
+    Bundle(BundleError),
+    /// Loading was cancelled with a [`CancelToken`](crate::CancelToken).
+    Cancelled,
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::Parse { file, error } => write!(f, "{}: {}", file, error),
+            LoadError::Conversion { file, error } => write!(f, "{}: {}", file, error),
+            LoadError::EntryConversion { file, error } => write!(f, "{}: {}", file, error),
+            LoadError::Manifest(error) => write!(f, "manifest.json: {}", error),
+            LoadError::Bundle(error) => write!(f, "bundle: {}", error),
+            LoadError::Cancelled => f.write_str("loading was cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+/// Errors for reading a `.dbc` or `.db2` header with [`inspect_header`](crate::header::inspect_header).
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum HeaderError {
+    /// The bytes end before the header does.
+    TooShort,
+    /// The first four bytes are not a known format.
+    UnknownMagic([u8; 4]),
+}
+
+impl Display for HeaderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HeaderError::TooShort => write!(f, "file is too short for the header"),
+            HeaderError::UnknownMagic(magic) => {
+                write!(f, "unknown magic '{}'", String::from_utf8_lossy(magic))
+            }
+        }
+    }
+}
+
+impl std::error::Error for HeaderError {}
+
+/// Error for parsing `manifest.json` with [`parse_manifest`](crate::manifest::parse_manifest).
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct ManifestError {
+    /// Amount of bytes into the file the error starts on.
+    pub offset: usize,
+    /// Reason for error.
+    pub reason: ManifestErrorReason,
+}
+
+impl Display for ManifestError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Offset {}: {}", self.offset, self.reason)
+    }
+}
+
+impl std::error::Error for ManifestError {}
+
+/// Specific reason parsing `manifest.json` failed.
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum ManifestErrorReason {
+    /// A different character was expected.
+    UnexpectedCharacter {
+        /// Character expected.
+        expected: char,
+        /// Character found.
+        found: char,
+    },
+    /// The file ended before the array was closed.
+    UnexpectedEnd,
+    /// Characters were found after the array was closed.
+    TrailingCharacters(char),
+    /// A value is not a string, a non-negative integer or `null`.
+    InvalidValue,
+    /// An object has no `tableName`, or it is empty.
+    MissingTableName,
+}
+
+impl Display for ManifestErrorReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ManifestErrorReason::UnexpectedCharacter { expected, found } => {
+                write!(f, "expected '{}', found '{}'", expected, found)
+            }
+            ManifestErrorReason::UnexpectedEnd => write!(f, "unexpected end of file"),
+            ManifestErrorReason::TrailingCharacters(c) => {
+                write!(f, "unexpected '{}' after end of array", c)
+            }
+            ManifestErrorReason::InvalidValue => write!(f, "invalid value"),
+            ManifestErrorReason::MissingTableName => write!(f, "missing 'tableName'"),
+        }
+    }
+}
+
+/// Errors for applying a [`Transform`](crate::transform::Transform).
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum TransformError {
+    /// The table does not exist in the collection.
+    TableNotFound(String),
+    /// The column does not exist in any definition of the table.
+    ColumnNotFound {
+        /// Name of the table.
+        table: String,
+        /// Name of the column.
+        column: String,
+    },
+    /// A line could not be parsed as an [`Operation`](crate::session::Operation).
+    InvalidOperation(String),
+}
+
+impl Display for TransformError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransformError::TableNotFound(s) => write!(f, "table not found '{}'", s),
+            TransformError::ColumnNotFound { table, column } => {
+                write!(f, "column not found '{}::{}'", table, column)
+            }
+            TransformError::InvalidOperation(s) => write!(f, "invalid operation '{}'", s),
+        }
+    }
+}
+
+impl std::error::Error for TransformError {}
+
+/// Errors for parsing and executing an [`ExportPlan`](crate::ExportPlan).
+#[cfg(feature = "toml")]
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum ExportPlanError {
+    /// A line of the plan is not valid or has an unknown key.
+    InvalidLine {
+        /// Line number, starting at 1.
+        line: usize,
+        /// Contents of the line.
+        contents: String,
+    },
+    /// A required key is missing from the plan.
+    MissingKey(&'static str),
+    /// The input directory could not be loaded.
+    Load(LoadError),
+    /// A table of the plan does not exist in the collection.
+    TableNotFound(String),
+    /// A rename or drop of the plan could not be applied.
+    Transform(TransformError),
+    /// The format with the given name requires a build, but the plan does not have one.
+    BuildRequired(&'static str),
+}
+
+#[cfg(feature = "toml")]
+impl Display for ExportPlanError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportPlanError::InvalidLine { line, contents } => {
+                write!(f, "line {}: invalid export plan '{}'", line, contents)
+            }
+            ExportPlanError::MissingKey(s) => write!(f, "export plan is missing '{}'", s),
+            ExportPlanError::Load(e) => write!(f, "could not load input: {}", e),
+            ExportPlanError::TableNotFound(s) => write!(f, "table not found '{}'", s),
+            ExportPlanError::Transform(e) => write!(f, "{}", e),
+            ExportPlanError::BuildRequired(s) => write!(f, "format '{}' requires a build", s),
+        }
+    }
+}
+
+#[cfg(feature = "toml")]
+impl std::error::Error for ExportPlanError {}
+
+/// Errors for committing an [`EditSession`](crate::session::EditSession).
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum EditError {
+    /// A changed file could not be converted with [`DbdFile::into_raw`](crate::DbdFile::into_raw).
+    Conversion {
+        /// Name of the file including `.dbd`.
+        file: String,
+        /// Conversion error.
+        error: ConversionError,
+    },
+    /// A foreign key points to a column that does not exist in a table of the collection,
+    /// and did not do so before the edits.
+    DanglingForeignKey {
+        /// Column with the foreign key.
+        column: ColumnReference,
+        /// Column the foreign key points to.
+        key: ForeignKey,
+    },
+}
+
+impl Display for EditError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EditError::Conversion { file, error } => write!(f, "{}: {}", file, error),
+            EditError::DanglingForeignKey { column, key } => write!(
+                f,
+                "'{}::{}' points to '{}::{}' which does not exist",
+                column.table, column.column, key.database, key.column
+            ),
+        }
+    }
+}
+
+impl std::error::Error for EditError {}
+
+/// Errors for parsing a field id file with [`FieldIds::parse`](crate::codegen::field_ids::FieldIds::parse).
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum FieldIdsError {
+    /// A line is not a table, a column and a positive id.
+    InvalidLine {
+        /// Line number, starting at 1.
+        line: usize,
+        /// Contents of the line.
+        contents: String,
+    },
+    /// Two columns of the same table have the same id.
+    DuplicateId {
+        /// Second column with the id.
+        column: ColumnReference,
+        /// Id used by both columns.
+        id: u32,
+    },
+    /// A table does not use an id that is lower than its highest id.
+    MissingId {
+        /// Name of the table.
+        table: String,
+        /// Lowest id that is not used.
+        id: u32,
+    },
+}
+
+impl Display for FieldIdsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FieldIdsError::InvalidLine { line, contents } => {
+                write!(f, "line {}: invalid field id '{}'", line, contents)
+            }
+            FieldIdsError::DuplicateId { column, id } => write!(
+                f,
+                "'{}::{}' uses id {} which is already used",
+                column.table, column.column, id
+            ),
+            FieldIdsError::MissingId { table, id } => {
+                write!(f, "'{}' does not use id {}", table, id)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FieldIdsError {}
+
+/// Errors for verifying a bundle with [`BundleReader::verify`](crate::BundleReader::verify).
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum BundleVerificationError {
+    /// The bundle was written without checksums.
+    NoChecksums,
+    /// The hash of the entire bundle does not match the `HASH` line.
+    ContentHash {
+        /// Hash in the bundle.
+        expected: u64,
+        /// Hash of the contents.
+        actual: u64,
+    },
+    /// The checksum of a table does not match its `INDEX` line.
+    TableChecksum {
+        /// Name of the table.
+        table: String,
+        /// Checksum in the index.
+        expected: u64,
+        /// Checksum of the table contents.
+        actual: u64,
+    },
+    /// A detached signature is not valid for the bundle.
+    InvalidSignature,
+}
+
+impl Display for BundleVerificationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BundleVerificationError::NoChecksums => f.write_str("bundle has no checksums"),
+            BundleVerificationError::ContentHash { expected, actual } => write!(
+                f,
+                "bundle hash is {:016x} but contents hash to {:016x}",
+                expected, actual
+            ),
+            BundleVerificationError::TableChecksum {
+                table,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "checksum of table '{}' is {:016x} but contents hash to {:016x}",
+                table, expected, actual
+            ),
+            BundleVerificationError::InvalidSignature => {
+                f.write_str("signature is not valid for the bundle")
+            }
+        }
+    }
+}
+
+impl std::error::Error for BundleVerificationError {}
+
+/// Errors for parsing a sidecar enum file with [`parse_enums`](crate::enums::parse_enums).
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum EnumsError {
+    /// The enum or flags header is not `ENUM`/`FLAGS` followed by a name and a list of `Table::Column`.
+    InvalidHeader {
+        /// Line number, starting at 1.
+        line: usize,
+        /// Contents of the line.
+        contents: String,
+    },
+    /// The enum value is not a valid integer followed by a name, or is outside of a block.
+    InvalidValue {
+        /// Line number, starting at 1.
+        line: usize,
+        /// Contents of the line.
+        contents: String,
+    },
+}
+
+impl Display for EnumsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EnumsError::InvalidHeader { line, contents } => {
+                write!(f, "line {}: invalid enum header '{}'", line, contents)
+            }
+            EnumsError::InvalidValue { line, contents } => {
+                write!(f, "line {}: invalid enum value '{}'", line, contents)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EnumsError {}
+
+/// Errors for parsing type overrides with [`TypeOverrides::parse`](crate::overrides::TypeOverrides::parse).
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum TypeOverridesError {
+    /// A line is not a `Table::Column` followed by a valid override.
+    InvalidLine {
+        /// Line number, starting at 1.
+        line: usize,
+        /// Contents of the line, without the comment.
+        contents: String,
+    },
+}
+
+impl Display for TypeOverridesError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TypeOverridesError::InvalidLine { line, contents } => {
+                write!(f, "line {}: invalid type override '{}'", line, contents)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TypeOverridesError {}
+
+/// Errors for parsing a [`LintConfig`](crate::lint::LintConfig) with `LintConfig::from_toml`.
+#[cfg(feature = "toml")]
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum LintConfigError {
+    /// A line is not a valid section or key and value, or the severity does not exist.
+    InvalidLine {
+        /// Line number, starting at 1.
+        line: usize,
+        /// Contents of the line.
+        contents: String,
+    },
+    /// A rule is neither a built in rule nor one of the custom rules.
+    UnknownRule {
+        /// Line number, starting at 1.
+        line: usize,
+        /// Name of the rule.
+        rule: String,
+        /// Closest rule name, if any is close enough.
+        suggestion: Option<String>,
+    },
+}
+
+#[cfg(feature = "toml")]
+impl Display for LintConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LintConfigError::InvalidLine { line, contents } => {
+                write!(
+                    f,
+                    "line {}: invalid lint configuration '{}'",
+                    line, contents
+                )
+            }
+            LintConfigError::UnknownRule {
+                line,
+                rule,
+                suggestion,
+            } => {
+                write!(f, "line {}: unknown lint rule '{}'", line, rule)?;
+                write_suggestion(f, suggestion)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "toml")]
+impl std::error::Error for LintConfigError {}
+
+/// Errors for reading a bundle with [`BundleReader`](crate::BundleReader).
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum BundleError {
+    /// The bundle does not start with a valid `BUNDLE` line, or a `COMMIT`, `HASH` or `INDEX` line is not valid.
+    InvalidHeader {
+        /// Line number, starting at 1.
+        line: usize,
+        /// Contents of the line.
+        contents: String,
+    },
+    /// The `INDEX` line of a table does not point to the `TABLE` line of the table.
+    InvalidIndex {
+        /// Name of the table.
+        table: String,
+        /// Line number the index points to, starting at 1.
+        line: usize,
+    },
+}
+
+impl Display for BundleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BundleError::InvalidHeader { line, contents } => {
+                write!(f, "line {}: invalid bundle header '{}'", line, contents)
+            }
+            BundleError::InvalidIndex { table, line } => write!(
+                f,
+                "index of '{}' points to line {} which is not its 'TABLE' line",
+                table, line
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BundleError {}
+
+/// Errors for parsing a table group configuration with [`TableGroups::parse`](crate::groups::TableGroups::parse).
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum TableGroupsError {
+    /// A line is not a group name followed by a table name or prefix.
+    InvalidLine {
+        /// Line number, starting at 1.
+        line: usize,
+        /// Contents of the line, without the comment.
+        contents: String,
+    },
+}
+
+impl Display for TableGroupsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TableGroupsError::InvalidLine { line, contents } => {
+                write!(f, "line {}: invalid table group '{}'", line, contents)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TableGroupsError {}
+
+/// Errors for parsing an [`ArtifactManifest`](crate::artifacts::ArtifactManifest).
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum ArtifactManifestError {
+    /// A line is not a valid `VERSION`, `COMMIT` or `FILE` line.
+    InvalidLine {
+        /// Line number, starting at 1.
+        line: usize,
+        /// Contents of the line.
+        contents: String,
+    },
+    /// The manifest does not have a `VERSION` line.
+    MissingVersion,
+}
+
+impl Display for ArtifactManifestError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArtifactManifestError::InvalidLine { line, contents } => {
+                write!(f, "line {}: invalid artifact manifest '{}'", line, contents)
+            }
+            ArtifactManifestError::MissingVersion => {
+                write!(f, "artifact manifest is missing 'VERSION'")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ArtifactManifestError {}
@@ -0,0 +1,194 @@
+use crate::enums::ColumnReference;
+use crate::export::{builds, csv_field, export_type};
+use crate::writer::Writer;
+use crate::{DbdCollection, ScalarType, VersionSet};
+use std::collections::BTreeMap;
+
+/// Column with the same type and tags across a set of versions.
+///
+/// Created from [`DbdCollection::export_feature_table`].
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct FeatureRow {
+    /// Table and column.
+    pub column: ColumnReference,
+    /// Versions of all definitions where the column has these features.
+    pub versions: VersionSet,
+    /// Type of the column, or of the elements if it is an array.
+    pub ty: ScalarType,
+    /// Size of the array, if the column is an array.
+    pub array_size: Option<usize>,
+    /// Column has the `$id$` tag.
+    pub primary_key: bool,
+    /// Column does not have the `$noninline$` tag.
+    pub inline: bool,
+    /// Column has the `$relation$` tag.
+    pub relation: bool,
+    /// Column is verified.
+    pub verified: bool,
+    /// Column the foreign key points to, if any.
+    pub foreign_key: Option<ColumnReference>,
+}
+
+impl FeatureRow {
+    fn tags(&self) -> Vec<&'static str> {
+        let mut tags = Vec::new();
+        if self.primary_key {
+            tags.push("id");
+        }
+        if !self.inline {
+            tags.push("noninline");
+        }
+        if self.relation {
+            tags.push("relation");
+        }
+        tags
+    }
+}
+
+type FeatureKey = (
+    ColumnReference,
+    ScalarType,
+    Option<usize>,
+    [bool; 4],
+    Option<ColumnReference>,
+);
+
+impl DbdCollection {
+    /// Finds the features of every column in every table as a flat dataset, sorted by column and versions.
+    ///
+    /// Definitions where a column has the same features are combined into a single row,
+    /// so a column gets a new row every time its type, tags, verification or foreign key changes.
+    pub fn export_feature_table(&self) -> Vec<FeatureRow> {
+        let mut rows: BTreeMap<FeatureKey, VersionSet> = BTreeMap::new();
+
+        for (table, file) in &self.files {
+            for definition in &file.definitions {
+                for entry in &definition.entries {
+                    let ty = export_type(entry);
+                    let versions = rows
+                        .entry((
+                            ColumnReference::new(table.as_str(), entry.name.as_str()),
+                            entry.ty.scalar(),
+                            ty.array_size,
+                            [
+                                entry.primary_key,
+                                entry.inline,
+                                entry.relation,
+                                entry.verified,
+                            ],
+                            ty.foreign_key.map(|a| {
+                                ColumnReference::new(a.database.as_str(), a.column.as_str())
+                            }),
+                        ))
+                        .or_default();
+                    *versions = versions.union(&definition.versions);
+                }
+            }
+        }
+
+        let mut rows: Vec<FeatureRow> = rows
+            .into_iter()
+            .map(
+                |(
+                    (
+                        column,
+                        ty,
+                        array_size,
+                        [primary_key, inline, relation, verified],
+                        foreign_key,
+                    ),
+                    versions,
+                )| FeatureRow {
+                    column,
+                    versions,
+                    ty,
+                    array_size,
+                    primary_key,
+                    inline,
+                    relation,
+                    verified,
+                    foreign_key,
+                },
+            )
+            .collect();
+        rows.sort_by(|a, b| (&a.column, &a.versions).cmp(&(&b.column, &b.versions)));
+
+        rows
+    }
+
+    /// Write [`DbdCollection::export_feature_table`] as CSV with a header row.
+    ///
+    /// The columns are `table`, `column`, `builds`, `type`, `width`, `array_size`, `tags`,
+    /// `verified`, `foreign_table` and `foreign_column`.
+    /// `builds` is a `;` separated list of builds and build ranges in the same format as `BUILD` lines.
+    /// `type` is one of `int`, `uint`, `float`, `string` or `locstring` and `width` is the size in bits of integers.
+    /// `tags` is a `;` separated list of `id`, `noninline` and `relation`.
+    /// Empty fields mean that the value does not apply.
+    pub fn feature_table_to_csv(&self) -> String {
+        let mut s = Writer::new();
+        s.wln(
+            "table,column,builds,type,width,array_size,tags,verified,foreign_table,foreign_column",
+        );
+
+        for row in self.export_feature_table() {
+            let ty = match row.ty {
+                ScalarType::Float => "float",
+                ScalarType::LocString => "locstring",
+                ScalarType::String => "string",
+                ty if ty.is_unsigned() => "uint",
+                _ => "int",
+            };
+            let (foreign_table, foreign_column) = row
+                .foreign_key
+                .as_ref()
+                .map_or((String::new(), String::new()), |a| {
+                    (csv_field(&a.table), csv_field(&a.column))
+                });
+
+            s.wln(format!(
+                "{},{},{},{},{},{},{},{},{},{}",
+                csv_field(&row.column.table),
+                csv_field(&row.column.column),
+                builds(&row.versions).join(";"),
+                ty,
+                row.ty
+                    .integer_width()
+                    .map_or_else(String::new, |a| a.to_string()),
+                row.array_size.map_or_else(String::new, |a| a.to_string()),
+                row.tags().join(";"),
+                row.verified,
+                foreign_table,
+                foreign_column,
+            ));
+        }
+
+        s.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{load_file_from_string, DbdCollection};
+
+    #[test]
+    fn feature_table_csv() {
+        let area = load_file_from_string(
+            "COLUMNS\nint ID\nint<Map::ID> MapID\nfloat Pos?\n\nBUILD 1.12.1.5875\n$id$ID<32>\nMapID<32>\nPos[3]\n\nBUILD 3.0.1.8303-3.3.5.12340\n$noninline,id$ID<32>\nMapID<u16>\nPos[3]\n",
+            "AreaTable.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+
+        assert_eq!(
+            DbdCollection::from_files(vec![area]).feature_table_to_csv(),
+            "table,column,builds,type,width,array_size,tags,verified,foreign_table,foreign_column
+AreaTable,ID,1.12.1.5875,int,32,,id,true,,
+AreaTable,ID,3.0.1.8303-3.3.5.12340,int,32,,id;noninline,true,,
+AreaTable,MapID,1.12.1.5875,int,32,,,true,Map,ID
+AreaTable,MapID,3.0.1.8303-3.3.5.12340,uint,16,,,true,Map,ID
+AreaTable,Pos,1.12.1.5875;3.0.1.8303-3.3.5.12340,float,,3,,false,,
+"
+        );
+    }
+}
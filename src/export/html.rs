@@ -0,0 +1,283 @@
+use crate::export::{builds, export_type};
+use crate::groups::TableGroups;
+use crate::writer::Writer;
+use crate::{DbdCollection, DbdFile, Definition, VersionSet};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const STYLE: &str = "body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #ccc; padding: 0.2em 0.6em; text-align: left; }
+tr:target { background: #ffa; }
+.unverified { color: #888; }";
+
+/// Selecting a build in the `<select>` scrolls to its definition.
+const SCRIPT: &str = "document.getElementById('build').addEventListener('change', \
+function (e) { location.hash = e.target.value; });";
+
+impl DbdCollection {
+    /// Generates a static HTML site for browsing the collection, keyed by file name.
+    ///
+    /// Same as [`DbdCollection::to_html_site_with_groups`] with [`TableGroups::builtin`].
+    pub fn to_html_site(&self) -> BTreeMap<String, String> {
+        self.to_html_site_with_groups(&TableGroups::builtin())
+    }
+
+    /// Generates a static HTML site for browsing the collection, keyed by file name.
+    ///
+    /// The site consists of an `index.html` listing every table under a heading for its group in `groups`,
+    /// and a `<table name>.html` page for every table with a build selector and a section for every definition.
+    /// Foreign keys link to the row of the column on the page of the other table,
+    /// and tables not in the collection are not linked.
+    pub fn to_html_site_with_groups(&self, groups: &TableGroups) -> BTreeMap<String, String> {
+        let mut site = BTreeMap::new();
+
+        site.insert("index.html".to_string(), self.html_index(groups));
+        for (table, file) in &self.files {
+            site.insert(format!("{}.html", table), self.html_table(file));
+        }
+
+        site
+    }
+
+    /// Writes [`DbdCollection::to_html_site`] to `dir`, creating it if it does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the directory or files can not be written.
+    pub fn write_html_site(&self, dir: &Path) -> std::io::Result<()> {
+        std::fs::create_dir_all(dir)?;
+
+        for (name, contents) in self.to_html_site() {
+            std::fs::write(dir.join(name), contents)?;
+        }
+
+        Ok(())
+    }
+
+    fn html_index(&self, groups: &TableGroups) -> String {
+        let mut s = Writer::new();
+        header(&mut s, "Tables");
+
+        s.wln("<h1>Tables</h1>");
+
+        let tables = self.table_groups(groups);
+        s.wln("<nav>");
+        for group in tables.keys() {
+            s.wln(format!(
+                "<a href=\"#{group}\">{group}</a>",
+                group = escape(group)
+            ));
+        }
+        s.wln("</nav>");
+
+        for (group, tables) in &tables {
+            s.wln(format!(
+                "<h2 id=\"{group}\">{group}</h2>",
+                group = escape(group)
+            ));
+            s.wln("<ul>");
+            for table in tables {
+                let versions = self.files[*table]
+                    .definitions
+                    .iter()
+                    .fold(VersionSet::new(), |acc, a| acc.union(&a.versions));
+
+                s.wln(format!(
+                    "<li><a href=\"{table}.html\">{table}</a> {}</li>",
+                    versions_string(&versions),
+                    table = escape(table),
+                ));
+            }
+            s.wln("</ul>");
+        }
+
+        footer(&mut s);
+        s.inner
+    }
+
+    fn html_table(&self, file: &DbdFile) -> String {
+        let table = file.table_name();
+
+        let mut s = Writer::new();
+        header(&mut s, table);
+
+        s.wln("<p><a href=\"index.html\">Tables</a></p>");
+        s.wln(format!("<h1>{}</h1>", escape(table)));
+
+        s.wln("<label for=\"build\">Build</label>");
+        s.wln("<select id=\"build\">");
+        for (i, definition) in file.definitions.iter().enumerate() {
+            s.wln(format!(
+                "<option value=\"definition-{}\">{}</option>",
+                i,
+                versions_string(&definition.versions)
+            ));
+        }
+        s.wln("</select>");
+
+        for (i, definition) in file.definitions.iter().enumerate() {
+            self.html_definition(&mut s, file, i, definition);
+        }
+
+        s.wln(format!("<script>{}</script>", SCRIPT));
+        footer(&mut s);
+        s.inner
+    }
+
+    fn html_definition(&self, s: &mut Writer, file: &DbdFile, i: usize, definition: &Definition) {
+        s.newline();
+        s.wln(format!("<section id=\"definition-{}\">", i));
+        s.wln(format!(
+            "<h2>{}</h2>",
+            versions_string(&definition.versions)
+        ));
+
+        if !definition.layouts.is_empty() {
+            let layouts: Vec<String> = definition
+                .layouts
+                .iter()
+                .map(|a| format!("{:08X}", a.inner))
+                .collect();
+            s.wln(format!("<p>Layouts: {}</p>", layouts.join(", ")));
+        }
+
+        s.wln("<table>");
+        s.wln("<tr><th>Name</th><th>Type</th><th>Foreign key</th><th>Comment</th></tr>");
+        for entry in &definition.entries {
+            let ty = export_type(entry);
+
+            let mut name = escape(&entry.name);
+            if entry.primary_key {
+                name = format!("<b>{}</b>", name);
+            }
+
+            let mut ty_name = ty.name.to_string();
+            if let Some(size) = ty.array_size {
+                ty_name = format!("{}[{}]", ty_name, size);
+            }
+
+            let foreign_key = match ty.foreign_key {
+                Some(key) if self.get(&key.database).is_some() => format!(
+                    "<a href=\"{table}.html#{column}\">{table}::{column}</a>",
+                    table = escape(&key.database),
+                    column = escape(&key.column),
+                ),
+                Some(key) => escape(&format!("{}::{}", key.database, key.column)),
+                None => String::new(),
+            };
+
+            let comment = entry
+                .comment
+                .as_ref()
+                .or(entry.column_comment.as_ref())
+                .map_or_else(String::new, |a| escape(a));
+
+            let class = if entry.verified {
+                ""
+            } else {
+                " class=\"unverified\""
+            };
+
+            // Only the first definition with the column gets the id so links to the column are unique.
+            let id = if first_with_column(file, &entry.name) == Some(i) {
+                format!(" id=\"{}\"", escape(&entry.name))
+            } else {
+                String::new()
+            };
+
+            s.wln(format!(
+                "<tr{}{}><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                id, class, name, ty_name, foreign_key, comment
+            ));
+        }
+        s.wln("</table>");
+        s.wln("</section>");
+    }
+}
+
+/// Index of the first definition of `file` with `column`.
+fn first_with_column(file: &DbdFile, column: &str) -> Option<usize> {
+    file.definitions
+        .iter()
+        .position(|d| d.entries.iter().any(|e| e.name == column))
+}
+
+fn header(s: &mut Writer, title: &str) {
+    s.wln("<!DOCTYPE html>");
+    s.wln("<html>");
+    s.wln("<head>");
+    s.wln("<meta charset=\"utf-8\">");
+    s.wln(format!("<title>{}</title>", escape(title)));
+    s.wln(format!("<style>\n{}\n</style>", STYLE));
+    s.wln("</head>");
+    s.wln("<body>");
+}
+
+fn footer(s: &mut Writer) {
+    s.wln("</body>");
+    s.wln("</html>");
+}
+
+fn versions_string(versions: &VersionSet) -> String {
+    builds(versions).join(", ")
+}
+
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{load_file_from_string, DbdCollection, DbdFile};
+
+    fn file(name: &str, contents: &str) -> DbdFile {
+        load_file_from_string(contents, name)
+            .unwrap()
+            .into_proper()
+            .unwrap()
+    }
+
+    #[test]
+    fn html_site() {
+        let collection = DbdCollection::from_files(vec![
+            file(
+                "AreaTable.dbd",
+                "COLUMNS\nint ID\nint<Map::ID> MapID\nint<Missing::ID> Other // <b>\n\nLAYOUT 43E6E8A6\nBUILD 3.3.5.12340\n$id$ID<32>\nMapID<32>\nOther<32>\n",
+            ),
+            file("Map.dbd", "COLUMNS\nint ID\n\nBUILD 1.12.1.5875-3.3.5.12340\n$id$ID<32>\n"),
+        ]);
+
+        let site = collection.to_html_site();
+        assert_eq!(
+            site.keys().collect::<Vec<_>>(),
+            vec!["AreaTable.html", "Map.html", "index.html"]
+        );
+
+        let index = &site["index.html"];
+        assert!(index.contains("<li><a href=\"Map.html\">Map</a> 1.12.1.5875-3.3.5.12340</li>"));
+        assert!(index.contains("<h2 id=\"Maps\">Maps</h2>"));
+
+        let area = &site["AreaTable.html"];
+        assert!(area.contains("<option value=\"definition-0\">3.3.5.12340</option>"));
+        assert!(area.contains("<p>Layouts: 43E6E8A6</p>"));
+        assert!(area.contains("<a href=\"Map.html#ID\">Map::ID</a>"));
+        assert!(area.contains("<td>Missing::ID</td><td>&lt;b&gt;</td>"));
+
+        let map = &site["Map.html"];
+        assert!(map.contains("<tr id=\"ID\"><td><b>ID</b></td><td>int32</td>"));
+    }
+}
@@ -0,0 +1,120 @@
+use crate::export::{export_type, quoted};
+use crate::writer::Writer;
+use crate::{DbdFile, Version};
+
+impl DbdFile {
+    /// Write the definition valid for `version` as a JSON document with the resolved fields.
+    ///
+    /// The document follows the shape of the community `dbd` to JSON scripts:
+    ///
+    /// ```json
+    /// {
+    ///   "name": "Map",
+    ///   "build": "3.3.5.12340",
+    ///   "layouts": ["43E6E8A6"],
+    ///   "fields": [
+    ///     {
+    ///       "name": "ID",
+    ///       "type": "int",
+    ///       "size": 32,
+    ///       "arrayLength": 0,
+    ///       "isID": true,
+    ///       "isInline": true,
+    ///       "isRelation": false,
+    ///       "isVerified": true,
+    ///       "foreignKey": null,
+    ///       "comment": null
+    ///     }
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// `type` is one of `int`, `uint`, `float`, `string` or `locstring`.
+    /// `size` is the size in bits of integers and floats, and `0` for strings.
+    ///
+    /// Returns [`None`] if there is no definition for `version`.
+    pub fn to_build_json(&self, version: &Version) -> Option<String> {
+        let definition = self.specific_version(version)?;
+
+        let mut s = Writer::new();
+        s.wln("{");
+        s.wln(format!("  \"name\": {},", quoted(self.table_name())));
+        s.wln(format!("  \"build\": {},", quoted(&version.to_string())));
+
+        let layouts: Vec<String> = definition
+            .layouts
+            .iter()
+            .map(|a| format!("\"{:08X}\"", a.inner))
+            .collect();
+        s.wln(format!("  \"layouts\": [{}],", layouts.join(", ")));
+
+        s.wln("  \"fields\": [");
+        for (i, entry) in definition.entries.iter().enumerate() {
+            let ty = export_type(entry);
+            let (name, size) = ty.sized_name();
+            let foreign_key = if let Some(key) = ty.foreign_key {
+                format!(
+                    "{{ \"table\": {}, \"column\": {} }}",
+                    quoted(&key.database),
+                    quoted(&key.column)
+                )
+            } else {
+                "null".to_string()
+            };
+            let comment = entry
+                .comment
+                .as_ref()
+                .or(entry.column_comment.as_ref())
+                .map_or_else(|| "null".to_string(), |a| quoted(a));
+
+            s.wln("    {");
+            s.wln(format!("      \"name\": {},", quoted(&entry.name)));
+            s.wln(format!("      \"type\": \"{}\",", name));
+            s.wln(format!("      \"size\": {},", size));
+            s.wln(format!(
+                "      \"arrayLength\": {},",
+                ty.array_size.unwrap_or(0)
+            ));
+            s.wln(format!("      \"isID\": {},", entry.primary_key));
+            s.wln(format!("      \"isInline\": {},", entry.inline));
+            s.wln(format!("      \"isRelation\": {},", entry.relation));
+            s.wln(format!("      \"isVerified\": {},", entry.verified));
+            s.wln(format!("      \"foreignKey\": {},", foreign_key));
+            s.wln(format!("      \"comment\": {}", comment));
+
+            if i + 1 == definition.entries.len() {
+                s.wln("    }");
+            } else {
+                s.wln("    },");
+            }
+        }
+        s.wln("  ]");
+        s.wln("}");
+
+        Some(s.inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{load_file_from_string, Version};
+
+    #[test]
+    fn build_json() {
+        let file = load_file_from_string(
+            "COLUMNS\nint ID\nint<Map::ID> ParentMapID\nlocstring Name_lang\n\nLAYOUT 0B14E4AA\nBUILD 7.3.5.25600\n$noninline,id$ID<32>\nName_lang\n$relation$ParentMapID<u16>\n",
+            "Map.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+
+        let s = file.to_build_json(&Version::new(7, 3, 5, 25600)).unwrap();
+        assert!(s.starts_with("{\n  \"name\": \"Map\",\n  \"build\": \"7.3.5.25600\",\n  \"layouts\": [\"0B14E4AA\"],\n"));
+        assert!(s.contains("      \"type\": \"uint\",\n      \"size\": 16,\n"));
+        assert!(s.contains("      \"foreignKey\": { \"table\": \"Map\", \"column\": \"ID\" },\n"));
+        assert!(s.ends_with("    }\n  ]\n}\n"));
+
+        assert!(file.to_build_json(&Version::new(1, 12, 1, 5875)).is_none());
+    }
+}
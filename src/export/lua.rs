@@ -0,0 +1,172 @@
+use crate::export::export_type;
+use crate::writer::Writer;
+use crate::{DbdCollection, DbdFile, Definition, Version};
+
+impl DbdFile {
+    /// Write the definition valid for `version` as a Lua chunk returning a table with the resolved fields.
+    ///
+    /// The table has the same contents as [`DbdFile::to_build_json`],
+    /// with a single line for every field:
+    ///
+    /// ```lua
+    /// return {
+    ///   name = "Map",
+    ///   build = "3.3.5.12340",
+    ///   layouts = { "43E6E8A6" },
+    ///   fields = {
+    ///     { name = "ID", type = "int", size = 32, arrayLength = 0, isID = true, isInline = true, isRelation = false, isVerified = true },
+    ///     { name = "ParentMapID", type = "int", size = 32, arrayLength = 0, isID = false, isInline = true, isRelation = false, isVerified = true, foreignKey = { table = "Map", column = "ID" } },
+    ///   },
+    /// }
+    /// ```
+    ///
+    /// `foreignKey` and `comment` are left out if the field does not have them.
+    ///
+    /// Returns [`None`] if there is no definition for `version`.
+    pub fn to_build_lua(&self, version: &Version) -> Option<String> {
+        let definition = self.specific_version(version)?;
+
+        let mut s = Writer::new();
+        s.wln("return {");
+        s.wln(format!("  name = {},", lua_quoted(self.table_name())));
+        write_definition(&mut s, definition, version, "  ");
+        s.wln("}");
+
+        Some(s.inner)
+    }
+}
+
+impl DbdCollection {
+    /// Write the definitions of every table valid for `version` as a Lua chunk returning a table keyed by table name.
+    ///
+    /// Every table has the same contents as [`DbdFile::to_build_lua`] without `name`.
+    /// Tables without a definition for `version` are left out.
+    pub fn to_build_lua(&self, version: &Version) -> String {
+        let mut s = Writer::new();
+        s.wln("return {");
+
+        for (name, file) in &self.files {
+            if let Some(definition) = file.specific_version(version) {
+                s.wln(format!("  [{}] = {{", lua_quoted(name)));
+                write_definition(&mut s, definition, version, "    ");
+                s.wln("  },");
+            }
+        }
+
+        s.wln("}");
+
+        s.inner
+    }
+}
+
+fn write_definition(s: &mut Writer, definition: &Definition, version: &Version, indent: &str) {
+    s.wln(format!(
+        "{}build = {},",
+        indent,
+        lua_quoted(&version.to_string())
+    ));
+
+    let layouts: Vec<String> = definition
+        .layouts
+        .iter()
+        .map(|a| format!("\"{:08X}\"", a.inner))
+        .collect();
+    if layouts.is_empty() {
+        s.wln(format!("{}layouts = {{}},", indent));
+    } else {
+        s.wln(format!("{}layouts = {{ {} }},", indent, layouts.join(", ")));
+    }
+
+    s.wln(format!("{}fields = {{", indent));
+    for entry in &definition.entries {
+        let ty = export_type(entry);
+        let (name, size) = ty.sized_name();
+
+        let mut field = vec![
+            format!("name = {}", lua_quoted(&entry.name)),
+            format!("type = \"{}\"", name),
+            format!("size = {}", size),
+            format!("arrayLength = {}", ty.array_size.unwrap_or(0)),
+            format!("isID = {}", entry.primary_key),
+            format!("isInline = {}", entry.inline),
+            format!("isRelation = {}", entry.relation),
+            format!("isVerified = {}", entry.verified),
+        ];
+        if let Some(key) = ty.foreign_key {
+            field.push(format!(
+                "foreignKey = {{ table = {}, column = {} }}",
+                lua_quoted(&key.database),
+                lua_quoted(&key.column)
+            ));
+        }
+        if let Some(comment) = entry.comment.as_ref().or(entry.column_comment.as_ref()) {
+            field.push(format!("comment = {}", lua_quoted(comment)));
+        }
+
+        s.wln(format!("{}  {{ {} }},", indent, field.join(", ")));
+    }
+    s.wln(format!("{}}},", indent));
+}
+
+/// Escapes `s` as a double quoted Lua string.
+fn lua_quoted(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_ascii_control() => out.push_str(&format!("\\{:03}", c as u32)),
+            c => out.push(c),
+        }
+    }
+
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{load_file_from_string, DbdCollection, Version};
+
+    #[test]
+    fn build_lua() {
+        let file = load_file_from_string(
+            "COLUMNS\nint ID\nint<Map::ID> ParentMapID\nlocstring Name_lang // \"Display\" name\n\nLAYOUT 0B14E4AA\nBUILD 7.3.5.25600\n$noninline,id$ID<32>\nName_lang\n$relation$ParentMapID<u16>\n",
+            "Map.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+        let version = Version::new(7, 3, 5, 25600);
+
+        assert_eq!(
+            file.to_build_lua(&version).unwrap(),
+            r#"return {
+  name = "Map",
+  build = "7.3.5.25600",
+  layouts = { "0B14E4AA" },
+  fields = {
+    { name = "ID", type = "int", size = 32, arrayLength = 0, isID = true, isInline = false, isRelation = false, isVerified = true },
+    { name = "Name_lang", type = "locstring", size = 0, arrayLength = 0, isID = false, isInline = true, isRelation = false, isVerified = true, comment = "\"Display\" name" },
+    { name = "ParentMapID", type = "uint", size = 16, arrayLength = 0, isID = false, isInline = true, isRelation = true, isVerified = true, foreignKey = { table = "Map", column = "ID" } },
+  },
+}
+"#
+        );
+        assert!(file.to_build_lua(&Version::new(1, 12, 1, 5875)).is_none());
+
+        let collection = DbdCollection::from_files(vec![file]);
+        let s = collection.to_build_lua(&version);
+        assert!(s.starts_with("return {\n  [\"Map\"] = {\n    build = \"7.3.5.25600\",\n"));
+        assert!(s.ends_with("    },\n  },\n}\n"));
+        assert_eq!(
+            collection.to_build_lua(&Version::new(1, 12, 1, 5875)),
+            "return {\n}\n"
+        );
+    }
+}
@@ -0,0 +1,107 @@
+mod features;
+mod html;
+mod json;
+mod lua;
+mod relations;
+#[cfg(feature = "toml")]
+mod toml;
+#[cfg(feature = "yaml")]
+mod yaml;
+
+use crate::{Entry, ScalarType, VersionSet};
+
+pub use features::FeatureRow;
+pub use relations::Relation;
+
+/// Escapes `s` as a double quoted string that is valid in JSON, TOML and YAML.
+pub(crate) fn quoted(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04X}", c as u32)),
+            c => out.push(c),
+        }
+    }
+
+    out.push('"');
+    out
+}
+
+/// Type of `entry` split into the scalar type name, array size and foreign key.
+pub(crate) struct ExportType<'a> {
+    pub name: &'static str,
+    pub array_size: Option<usize>,
+    pub foreign_key: Option<&'a crate::ForeignKey>,
+}
+
+impl ExportType<'_> {
+    /// Type as `int`, `uint`, `float`, `string` or `locstring`
+    /// and the size in bits of integers and floats, `0` for strings.
+    pub fn sized_name(&self) -> (&'static str, usize) {
+        match self.name {
+            "int8" => ("int", 8),
+            "int16" => ("int", 16),
+            "int32" => ("int", 32),
+            "int64" => ("int", 64),
+            "uint8" => ("uint", 8),
+            "uint16" => ("uint", 16),
+            "uint32" => ("uint", 32),
+            "uint64" => ("uint", 64),
+            "float" => ("float", 32),
+            name => (name, 0),
+        }
+    }
+}
+
+pub(crate) fn export_type(entry: &Entry) -> ExportType<'_> {
+    let name = match entry.ty.scalar() {
+        ScalarType::Int8 => "int8",
+        ScalarType::Int16 => "int16",
+        ScalarType::Int32 => "int32",
+        ScalarType::Int64 => "int64",
+        ScalarType::UInt8 => "uint8",
+        ScalarType::UInt16 => "uint16",
+        ScalarType::UInt32 => "uint32",
+        ScalarType::UInt64 => "uint64",
+        ScalarType::Float => "float",
+        ScalarType::LocString => "locstring",
+        ScalarType::String => "string",
+    };
+
+    ExportType {
+        name,
+        array_size: entry.ty.array_width(),
+        foreign_key: entry.ty.foreign_key(),
+    }
+}
+
+/// Builds and build ranges of `versions` in the same format as `BUILD` lines.
+pub(crate) fn builds(versions: &VersionSet) -> Vec<String> {
+    versions
+        .intervals()
+        .iter()
+        .map(|a| {
+            if a.from == a.to {
+                a.from.to_string()
+            } else {
+                format!("{}-{}", a.from, a.to)
+            }
+        })
+        .collect()
+}
+
+/// Quotes `s` for a CSV field if needed.
+pub(crate) fn csv_field(s: &str) -> String {
+    if s.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
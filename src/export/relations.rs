@@ -0,0 +1,190 @@
+use crate::enums::ColumnReference;
+use crate::export::{builds, csv_field, quoted};
+use crate::writer::Writer;
+use crate::{DbdCollection, VersionSet};
+use std::collections::BTreeMap;
+
+/// Foreign key from one column to another.
+///
+/// Created from [`DbdCollection::export_relations`].
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct Relation {
+    /// Column with the foreign key.
+    pub source: ColumnReference,
+    /// Column the foreign key points to.
+    pub target: ColumnReference,
+    /// Versions of all definitions where the source column has the foreign key.
+    pub versions: VersionSet,
+}
+
+impl DbdCollection {
+    /// Finds every foreign key in every table, sorted by source and target column.
+    ///
+    /// Foreign keys to tables or columns that do not exist are included.
+    pub fn export_relations(&self) -> Vec<Relation> {
+        let mut relations: BTreeMap<(ColumnReference, ColumnReference), VersionSet> =
+            BTreeMap::new();
+
+        for (table, file) in &self.files {
+            for definition in &file.definitions {
+                for entry in &definition.entries {
+                    if let Some(key) = entry.ty.foreign_key() {
+                        let versions = relations
+                            .entry((
+                                ColumnReference::new(table.as_str(), entry.name.as_str()),
+                                ColumnReference::new(key.database.as_str(), key.column.as_str()),
+                            ))
+                            .or_default();
+                        *versions = versions.union(&definition.versions);
+                    }
+                }
+            }
+        }
+
+        relations
+            .into_iter()
+            .map(|((source, target), versions)| Relation {
+                source,
+                target,
+                versions,
+            })
+            .collect()
+    }
+
+    /// Write [`DbdCollection::export_relations`] as CSV with a header row.
+    ///
+    /// The columns are `source_table`, `source_column`, `target_table`, `target_column` and `builds`.
+    /// `builds` is a `;` separated list of builds and build ranges in the same format as `BUILD` lines.
+    pub fn relations_to_csv(&self) -> String {
+        let mut s = Writer::new();
+        s.wln("source_table,source_column,target_table,target_column,builds");
+
+        for relation in self.export_relations() {
+            s.wln(format!(
+                "{},{},{},{},{}",
+                csv_field(&relation.source.table),
+                csv_field(&relation.source.column),
+                csv_field(&relation.target.table),
+                csv_field(&relation.target.column),
+                builds(&relation.versions).join(";"),
+            ));
+        }
+
+        s.inner
+    }
+
+    /// Write [`DbdCollection::export_relations`] as a JSON array.
+    ///
+    /// ```json
+    /// [
+    ///   {
+    ///     "sourceTable": "AreaTable",
+    ///     "sourceColumn": "MapID",
+    ///     "targetTable": "Map",
+    ///     "targetColumn": "ID",
+    ///     "builds": ["1.12.1.5875", "3.0.1.8303-3.3.5.12340"]
+    ///   }
+    /// ]
+    /// ```
+    pub fn relations_to_json(&self) -> String {
+        let relations = self.export_relations();
+
+        let mut s = Writer::new();
+        s.wln("[");
+
+        for (i, relation) in relations.iter().enumerate() {
+            let builds: Vec<String> = builds(&relation.versions)
+                .iter()
+                .map(|a| quoted(a))
+                .collect();
+
+            s.wln("  {");
+            s.wln(format!(
+                "    \"sourceTable\": {},",
+                quoted(&relation.source.table)
+            ));
+            s.wln(format!(
+                "    \"sourceColumn\": {},",
+                quoted(&relation.source.column)
+            ));
+            s.wln(format!(
+                "    \"targetTable\": {},",
+                quoted(&relation.target.table)
+            ));
+            s.wln(format!(
+                "    \"targetColumn\": {},",
+                quoted(&relation.target.column)
+            ));
+            s.wln(format!("    \"builds\": [{}]", builds.join(", ")));
+
+            if i + 1 == relations.len() {
+                s.wln("  }");
+            } else {
+                s.wln("  },");
+            }
+        }
+
+        s.wln("]");
+
+        s.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{load_file_from_string, DbdCollection};
+
+    fn collection() -> DbdCollection {
+        let map = load_file_from_string(
+            "COLUMNS\nint ID\nint<Map::ID> ParentMapID\n\nBUILD 3.3.5.12340\n$id$ID<32>\nParentMapID<32>\n",
+            "Map.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+        let area = load_file_from_string(
+            "COLUMNS\nint ID\nint<Map::ID> MapID\n\nBUILD 1.12.1.5875\n$id$ID<32>\nMapID<32>\n\nBUILD 3.0.1.8303-3.3.5.12340\n$id$ID<32>\nMapID<32>\n",
+            "AreaTable.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+
+        DbdCollection::from_files(vec![map, area])
+    }
+
+    #[test]
+    fn relations_csv() {
+        assert_eq!(
+            collection().relations_to_csv(),
+            "source_table,source_column,target_table,target_column,builds
+AreaTable,MapID,Map,ID,1.12.1.5875;3.0.1.8303-3.3.5.12340
+Map,ParentMapID,Map,ID,3.3.5.12340
+"
+        );
+    }
+
+    #[test]
+    fn relations_json() {
+        assert_eq!(
+            collection().relations_to_json(),
+            r#"[
+  {
+    "sourceTable": "AreaTable",
+    "sourceColumn": "MapID",
+    "targetTable": "Map",
+    "targetColumn": "ID",
+    "builds": ["1.12.1.5875", "3.0.1.8303-3.3.5.12340"]
+  },
+  {
+    "sourceTable": "Map",
+    "sourceColumn": "ParentMapID",
+    "targetTable": "Map",
+    "targetColumn": "ID",
+    "builds": ["3.3.5.12340"]
+  }
+]
+"#
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use crate::export::{export_type, quoted};
+use crate::writer::Writer;
+use crate::DbdFile;
+
+impl DbdFile {
+    /// Write the file as a TOML document.
+    ///
+    /// Layouts are written as hex strings in the same way as in the `.dbd` format.
+    pub fn to_toml(&self) -> String {
+        let mut s = Writer::new();
+
+        s.wln(format!("name = {}", quoted(&self.name)));
+
+        for definition in &self.definitions {
+            s.newline();
+            s.wln("[[definitions]]");
+
+            let versions: Vec<String> = definition
+                .versions
+                .versions()
+                .map(|a| quoted(&a.to_string()))
+                .collect();
+            s.wln(format!("versions = [{}]", versions.join(", ")));
+
+            let version_ranges: Vec<String> = definition
+                .versions
+                .ranges()
+                .map(|a| {
+                    format!(
+                        "{{ from = {}, to = {} }}",
+                        quoted(&a.from.to_string()),
+                        quoted(&a.to.to_string())
+                    )
+                })
+                .collect();
+            s.wln(format!("version_ranges = [{}]", version_ranges.join(", ")));
+
+            let layouts: Vec<String> = definition
+                .layouts
+                .iter()
+                .map(|a| format!("\"{:08X}\"", a.inner))
+                .collect();
+            s.wln(format!("layouts = [{}]", layouts.join(", ")));
+
+            for entry in &definition.entries {
+                let ty = export_type(entry);
+
+                s.newline();
+                s.wln("[[definitions.entries]]");
+                s.wln(format!("name = {}", quoted(&entry.name)));
+                s.wln(format!("type = \"{}\"", ty.name));
+                if let Some(array_size) = ty.array_size {
+                    s.wln(format!("array_size = {}", array_size));
+                }
+                if let Some(key) = ty.foreign_key {
+                    s.wln(format!(
+                        "foreign_key = {{ table = {}, column = {} }}",
+                        quoted(&key.database),
+                        quoted(&key.column)
+                    ));
+                }
+                if let Some(comment) = &entry.comment {
+                    s.wln(format!("comment = {}", quoted(comment)));
+                }
+                if let Some(comment) = &entry.column_comment {
+                    s.wln(format!("column_comment = {}", quoted(comment)));
+                }
+                s.wln(format!("verified = {}", entry.verified));
+                s.wln(format!("primary_key = {}", entry.primary_key));
+                s.wln(format!("inline = {}", entry.inline));
+                s.wln(format!("relation = {}", entry.relation));
+            }
+        }
+
+        s.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::load_file_from_string;
+
+    #[test]
+    fn toml() {
+        let file = load_file_from_string(
+            "COLUMNS\nint ID\nstring Directory // World\\Map\\\n\nBUILD 1.12.1.5875\nBUILD 1.0.0.3980-1.12.0.5595\n$id$ID<32>\nDirectory\n",
+            "Map.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+
+        let s = file.to_toml();
+        assert!(s.contains("versions = [\"1.12.1.5875\"]\n"));
+        assert!(s.contains("version_ranges = [{ from = \"1.0.0.3980\", to = \"1.12.0.5595\" }]\n"));
+        assert!(s.contains("[[definitions.entries]]\nname = \"Directory\"\ntype = \"string\"\ncolumn_comment = \"World\\\\Map\\\\\"\n"));
+    }
+}
@@ -0,0 +1,152 @@
+use crate::export::{export_type, quoted};
+use crate::writer::Writer;
+use crate::DbdFile;
+
+impl DbdFile {
+    /// Write the file as a YAML document.
+    ///
+    /// Layouts are written as hex strings in the same way as in the `.dbd` format.
+    pub fn to_yaml(&self) -> String {
+        let mut s = Writer::new();
+
+        s.wln(format!("name: {}", quoted(&self.name)));
+        s.wln("definitions:");
+
+        for definition in &self.definitions {
+            let versions: Vec<_> = definition.versions.versions().collect();
+            if versions.is_empty() {
+                s.wln("  - versions: []");
+            } else {
+                s.wln("  - versions:");
+            }
+            for v in versions {
+                s.wln(format!("      - {}", quoted(&v.to_string())));
+            }
+
+            let ranges: Vec<_> = definition.versions.ranges().collect();
+            list_key(&mut s, "version_ranges", ranges.is_empty());
+            for v in ranges {
+                s.wln(format!("      - from: {}", quoted(&v.from.to_string())));
+                s.wln(format!("        to: {}", quoted(&v.to.to_string())));
+            }
+
+            list_key(&mut s, "layouts", definition.layouts.is_empty());
+            for l in &definition.layouts {
+                s.wln(format!("      - \"{:08X}\"", l.inner));
+            }
+
+            list_key(&mut s, "entries", definition.entries.is_empty());
+            for entry in &definition.entries {
+                let ty = export_type(entry);
+
+                s.wln(format!("      - name: {}", quoted(&entry.name)));
+                s.wln(format!("        type: {}", ty.name));
+                if let Some(array_size) = ty.array_size {
+                    s.wln(format!("        array_size: {}", array_size));
+                }
+                if let Some(key) = ty.foreign_key {
+                    s.wln("        foreign_key:");
+                    s.wln(format!("          table: {}", quoted(&key.database)));
+                    s.wln(format!("          column: {}", quoted(&key.column)));
+                }
+                if let Some(comment) = &entry.comment {
+                    s.wln(format!("        comment: {}", quoted(comment)));
+                }
+                if let Some(comment) = &entry.column_comment {
+                    s.wln(format!("        column_comment: {}", quoted(comment)));
+                }
+                s.wln(format!("        verified: {}", entry.verified));
+                s.wln(format!("        primary_key: {}", entry.primary_key));
+                s.wln(format!("        inline: {}", entry.inline));
+                s.wln(format!("        relation: {}", entry.relation));
+            }
+        }
+
+        s.inner
+    }
+}
+
+/// Writes the key of a list in a definition, with `[]` if the list is empty
+/// since a key without a value is `null` in YAML.
+fn list_key(s: &mut Writer, key: &str, empty: bool) {
+    if empty {
+        s.wln(format!("    {}: []", key));
+    } else {
+        s.wln(format!("    {}:", key));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::string_to_version;
+    use crate::{load_file_from_string, VersionRange, VersionSet};
+
+    /// Reads the versions of every definition back from the output of `to_yaml`.
+    fn read_versions(yaml: &str) -> Vec<VersionSet> {
+        let version = |s: &str| string_to_version(s.trim_matches('"'), 0, 0).unwrap();
+
+        yaml.split("  - versions:")
+            .skip(1)
+            .map(|definition| {
+                let (versions, rest) = definition.split_once("    version_ranges:").unwrap();
+                let (ranges, _) = rest.split_once("    layouts:").unwrap();
+
+                let mut set = VersionSet::new();
+                for v in versions.lines().filter_map(|a| a.trim().strip_prefix("- ")) {
+                    set.insert(version(v));
+                }
+
+                let mut from = None;
+                for line in ranges.lines().map(str::trim) {
+                    if let Some(v) = line.strip_prefix("- from: ") {
+                        from = Some(version(v));
+                    } else if let Some(v) = line.strip_prefix("to: ") {
+                        set.insert_range(VersionRange::new(from.take().unwrap(), version(v)));
+                    }
+                }
+
+                set
+            })
+            .collect()
+    }
+
+    #[test]
+    fn yaml() {
+        let file = load_file_from_string(
+            "COLUMNS\nint ID\nint<Map::ID> MapID // \"parent\"\n\nLAYOUT 0B14E4AA\nBUILD 7.3.5.25600\n$id$ID<32>\nMapID<u16>[2]\n",
+            "Map.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+
+        let s = file.to_yaml();
+        assert!(s.contains("      - \"0B14E4AA\"\n"));
+        assert!(s.contains("        type: uint16\n        array_size: 2\n        foreign_key:\n          table: \"Map\"\n          column: \"ID\"\n"));
+        assert!(s.contains("        column_comment: \"\\\"parent\\\"\"\n"));
+    }
+
+    #[test]
+    fn yaml_empty_lists() {
+        let file = load_file_from_string(
+            "COLUMNS\nint ID\n\nBUILD 1.12.1.5875-1.12.3.6141\n$id$ID<32>\n\nLAYOUT 0B14E4AA\nBUILD 3.3.5.12340\nBUILD 3.3.5.12341-3.3.5.12342\n$id$ID<32>\n",
+            "Map.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+
+        let s = file.to_yaml();
+        assert!(s.contains("  - versions: []\n    version_ranges:\n"));
+        assert!(s.contains("    layouts: []\n"));
+        assert!(!s.contains("    version_ranges: []\n"));
+
+        assert_eq!(
+            read_versions(&s),
+            file.definitions
+                .iter()
+                .map(|a| a.versions.clone())
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,265 @@
+use crate::{DbdCollection, DbdFile, Entry, RawDbdFile, RawEntry};
+
+/// Selects which entries are kept by [`DbdFile::filter_entries`] and [`RawDbdFile::filter_entries`].
+///
+/// Filter a file before passing it to the exports or [`write_to_file`](crate::write_to_file)
+/// in order to only include the relevant columns.
+/// The [`codegen`](crate::codegen) backends whose output must match the record layout exactly
+/// return [`CodegenError::FilteredDefinition`](crate::error::CodegenError::FilteredDefinition) for filtered definitions.
+/// The default keeps every entry.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub struct EntryFilter {
+    /// Remove entries that are not stored inline with the row, marked with `$noninline$`.
+    pub exclude_noninline: bool,
+    /// Remove entries that are relations, marked with `$relation$`.
+    pub exclude_relation: bool,
+    /// Remove entries whose column has not been verified, marked with `?`.
+    pub only_verified: bool,
+    /// Remove entries that are unknown placeholders according to [`UnknownFieldRules::new`].
+    pub exclude_unknown: bool,
+}
+
+impl EntryFilter {
+    /// Creates a filter that keeps every entry.
+    pub const fn new() -> Self {
+        Self {
+            exclude_noninline: false,
+            exclude_relation: false,
+            only_verified: false,
+            exclude_unknown: false,
+        }
+    }
+
+    /// Returns true if `entry` is kept.
+    pub fn includes(&self, entry: &Entry) -> bool {
+        self.includes_parts(&entry.name, entry.inline, entry.relation, entry.verified)
+    }
+
+    fn includes_parts(&self, name: &str, inline: bool, relation: bool, verified: bool) -> bool {
+        !(self.exclude_noninline && !inline
+            || self.exclude_relation && relation
+            || self.only_verified && !verified
+            || self.exclude_unknown && UnknownFieldRules::new().is_unknown_parts(name, verified))
+    }
+}
+
+/// Rules for classifying columns as unknown placeholders,
+/// like `Unk0`, `Field_9_0_1_034` or columns that have not been verified.
+///
+/// Used by [`Entry::is_unknown_field`] and [`EntryFilter::exclude_unknown`] with the rules from
+/// [`UnknownFieldRules::new`], and can be used directly with custom rules.
+///
+/// ```rust
+/// use wowdbdefs_rs::UnknownFieldRules;
+///
+/// let rules = UnknownFieldRules {
+///     prefixes: &["Unk", "Padding"],
+///     unverified: false,
+/// };
+/// assert!(rules.is_unknown_name("Padding_2"));
+/// assert!(!rules.is_unknown_name("Field_9_0_1_034"));
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct UnknownFieldRules<'a> {
+    /// Prefixes of placeholder names.
+    ///
+    /// A name matches a prefix if the rest of the name is empty or only digits and `_`,
+    /// so `Unk` matches `Unk`, `Unk0` and `Unk_1` but not `Unkempt`.
+    pub prefixes: &'a [&'a str],
+    /// Classify columns that have not been verified, marked with `?`, as unknown regardless of their name.
+    pub unverified: bool,
+}
+
+impl<'a> UnknownFieldRules<'a> {
+    /// Prefixes used by [`UnknownFieldRules::new`].
+    pub const DEFAULT_PREFIXES: &'static [&'static str] = &["Unk", "Unknown", "Field"];
+
+    /// Creates the rules used by [`Entry::is_unknown_field`],
+    /// with [`UnknownFieldRules::DEFAULT_PREFIXES`] and unverified columns.
+    pub const fn new() -> Self {
+        Self {
+            prefixes: Self::DEFAULT_PREFIXES,
+            unverified: true,
+        }
+    }
+
+    /// Returns true if `name` matches one of [`UnknownFieldRules::prefixes`].
+    pub fn is_unknown_name(&self, name: &str) -> bool {
+        self.prefixes.iter().any(|prefix| {
+            name.strip_prefix(prefix).map_or(false, |rest| {
+                rest.chars().all(|c| c.is_ascii_digit() || c == '_')
+            })
+        })
+    }
+
+    /// Returns true if `entry` is an unknown placeholder.
+    pub fn is_unknown(&self, entry: &Entry) -> bool {
+        self.is_unknown_parts(&entry.name, entry.verified)
+    }
+
+    fn is_unknown_parts(&self, name: &str, verified: bool) -> bool {
+        self.unverified && !verified || self.is_unknown_name(name)
+    }
+}
+
+impl Default for UnknownFieldRules<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Entry {
+    /// Returns true if the column is an unknown placeholder according to [`UnknownFieldRules::new`],
+    /// either because of its name or because it has not been verified.
+    pub fn is_unknown_field(&self) -> bool {
+        UnknownFieldRules::new().is_unknown(self)
+    }
+}
+
+impl DbdFile {
+    /// Removes all entries not included by `filter` from every definition.
+    ///
+    /// Definitions where entries were removed are marked as [`Definition::is_filtered`](crate::Definition::is_filtered).
+    pub fn filter_entries(&mut self, filter: &EntryFilter) {
+        for definition in &mut self.definitions {
+            let len = definition.entries.len();
+            definition.entries.retain(|a| filter.includes(a));
+            definition.filtered |= definition.entries.len() != len;
+        }
+    }
+}
+
+impl RawDbdFile {
+    /// Removes all entries not included by `filter` from every definition.
+    ///
+    /// Entries without a column are considered unverified.
+    /// Columns that are no longer used by any definition are removed.
+    pub fn filter_entries(&mut self, filter: &EntryFilter) {
+        let columns = &self.columns;
+        let includes = |a: &RawEntry| {
+            let verified = columns.get(&a.name).map_or(false, |c| c.verified);
+            filter.includes_parts(&a.name, a.inline, a.relation, verified)
+        };
+
+        for definition in &mut self.definitions {
+            definition.entries.retain(|a| includes(a));
+        }
+
+        let definitions = &self.definitions;
+        self.columns.retain(|name, _| {
+            definitions
+                .iter()
+                .any(|d| d.entries.iter().any(|e| &e.name == name))
+        });
+    }
+}
+
+impl DbdCollection {
+    /// Removes all entries not included by `filter` from every file.
+    pub fn filter_entries(&mut self, filter: &EntryFilter) {
+        for file in self.files.values_mut() {
+            file.filter_entries(filter);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::CodegenError;
+    use crate::{load_file_from_string, EntryFilter, UnknownFieldRules, Version};
+
+    const CONTENTS: &str = "COLUMNS
+int ID
+int<Map::ID> MapID
+int Unknown?
+
+BUILD 9.0.1.36216
+$noninline,id$ID<32>
+$relation$MapID<32>
+Unknown<32>
+";
+
+    #[test]
+    fn filter_entries() {
+        let raw = load_file_from_string(CONTENTS, "Area.dbd").unwrap();
+        let mut file = raw.clone().into_proper().unwrap();
+
+        file.filter_entries(&EntryFilter::new());
+        assert_eq!(file.definitions[0].entries.len(), 3);
+        assert!(!file.definitions[0].is_filtered());
+
+        file.filter_entries(&EntryFilter {
+            exclude_noninline: true,
+            ..EntryFilter::new()
+        });
+        assert_eq!(file.definitions[0].entries.len(), 2);
+        assert!(file.definitions[0].is_filtered());
+
+        let mut unfiltered = file.definitions[0].clone();
+        unfiltered.filtered = false;
+        assert_eq!(unfiltered, file.definitions[0]);
+        assert_eq!(
+            crate::codegen::wow_dbc::generate(&file, &Version::new(9, 0, 1, 36216)),
+            Err(CodegenError::FilteredDefinition(Version::new(
+                9, 0, 1, 36216
+            )))
+        );
+
+        file.filter_entries(&EntryFilter {
+            only_verified: true,
+            ..EntryFilter::new()
+        });
+        assert_eq!(file.definitions[0].entries[0].name, "MapID");
+
+        let mut raw = raw;
+        raw.filter_entries(&EntryFilter {
+            exclude_relation: true,
+            only_verified: true,
+            ..EntryFilter::new()
+        });
+        assert_eq!(raw.definitions[0].entries.len(), 1);
+        assert_eq!(raw.columns.len(), 1);
+        assert!(raw.columns.contains_key("ID"));
+    }
+
+    #[test]
+    fn unknown_fields() {
+        let rules = UnknownFieldRules::new();
+        for name in [
+            "Unk",
+            "Unk0",
+            "Unk_1",
+            "Unknown2",
+            "Field_9_0_1_034",
+            "Field12",
+        ] {
+            assert!(rules.is_unknown_name(name), "{}", name);
+        }
+        for name in ["Unkempt", "FieldOfView", "ID", "MapID"] {
+            assert!(!rules.is_unknown_name(name), "{}", name);
+        }
+
+        let mut file = load_file_from_string(
+            "COLUMNS\nint ID\nint Flags?\nint Unk0\n\nBUILD 1.12.1.5875\n$id$ID<32>\nFlags<32>\nUnk0<32>\n",
+            "Map.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+        let entries = &file.definitions[0].entries;
+        assert!(!entries[0].is_unknown_field());
+        assert!(entries[1].is_unknown_field());
+        assert!(entries[2].is_unknown_field());
+        assert!(!UnknownFieldRules {
+            unverified: false,
+            ..rules
+        }
+        .is_unknown(&entries[1]));
+
+        file.filter_entries(&EntryFilter {
+            exclude_unknown: true,
+            ..EntryFilter::new()
+        });
+        assert_eq!(file.definitions[0].entries.len(), 1);
+    }
+}
@@ -0,0 +1,201 @@
+//! Grouping of tables into domains like `Spells`, `Maps` and `Items`.
+//!
+//! Groups are used by [`DbdCollection::to_html_site_with_groups`] to split the index into sections,
+//! and can be queried through [`DbdCollection::table_groups`].
+//!
+//! The configuration format has a group name followed by a table name per line,
+//! with comments starting with `//`.
+//! Table names ending in `*` match every table starting with the name:
+//!
+//! ```text
+//! Spells Spell*
+//! Spells SkillLineAbility
+//! Maps AreaTable // Exact matches take precedence over prefixes
+//! ```
+//!
+//! Exact matches take precedence over prefixes, and longer prefixes take precedence over shorter ones.
+//! Tables that do not match anything are placed in [`TableGroups::OTHER`].
+
+use crate::error::TableGroupsError;
+use crate::DbdCollection;
+use std::collections::BTreeMap;
+
+/// Built in prefixes used by [`TableGroups::builtin`].
+const BUILTIN: &[(&str, &str)] = &[
+    ("Achievements", "Achievement*"),
+    ("Achievements", "Criteria*"),
+    ("Characters", "Char*"),
+    ("Characters", "Chr*"),
+    ("Creatures", "Creature*"),
+    ("Factions", "Faction*"),
+    ("Garrisons", "Garr*"),
+    ("Items", "Item*"),
+    ("Maps", "Area*"),
+    ("Maps", "Dungeon*"),
+    ("Maps", "Light*"),
+    ("Maps", "Map*"),
+    ("Maps", "World*"),
+    ("Maps", "Zone*"),
+    ("Quests", "Quest*"),
+    ("Skills", "Skill*"),
+    ("Sounds", "Sound*"),
+    ("Spells", "Spell*"),
+    ("Talents", "Talent*"),
+    ("UI", "UI*"),
+    ("UI", "Ui*"),
+    ("Vehicles", "Vehicle*"),
+];
+
+/// Rules for placing tables into named groups.
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct TableGroups {
+    /// Group names keyed by exact table name.
+    pub tables: BTreeMap<String, String>,
+    /// Group names keyed by table name prefix.
+    pub prefixes: BTreeMap<String, String>,
+}
+
+impl TableGroups {
+    /// Group of tables that do not match any rule.
+    pub const OTHER: &'static str = "Other";
+
+    /// Creates an empty set of rules that places every table in [`TableGroups::OTHER`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates rules based on common table name prefixes, like `Spell*` for `Spells`.
+    pub fn builtin() -> Self {
+        let mut s = Self::new();
+
+        for (group, pattern) in BUILTIN {
+            s.insert(*group, pattern);
+        }
+
+        s
+    }
+
+    /// Parse the configuration format described in the [module documentation](crate::groups).
+    ///
+    /// # Errors
+    ///
+    /// Errors if a line is not a group name followed by a table name or prefix.
+    pub fn parse(contents: &str) -> Result<Self, TableGroupsError> {
+        let mut s = Self::new();
+
+        for (line_count, line) in contents.lines().enumerate() {
+            let line = line.split_once("//").map_or(line, |a| a.0).trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let mut words = line.split_whitespace();
+            match (words.next(), words.next(), words.next()) {
+                (Some(group), Some(pattern), None) if pattern != "*" => {
+                    s.insert(group, pattern);
+                }
+                _ => {
+                    return Err(TableGroupsError::InvalidLine {
+                        line: line_count + 1,
+                        contents: line.to_string(),
+                    });
+                }
+            }
+        }
+
+        Ok(s)
+    }
+
+    /// Adds a rule for a table name, or a prefix if `pattern` ends in `*`.
+    ///
+    /// Replaces any previous rule for the same `pattern`.
+    pub fn insert(&mut self, group: impl Into<String>, pattern: &str) -> Option<String> {
+        match pattern.strip_suffix('*') {
+            Some(prefix) => self.prefixes.insert(prefix.to_string(), group.into()),
+            None => self.tables.insert(pattern.to_string(), group.into()),
+        }
+    }
+
+    /// Adds all rules of `other`, replacing rules for the same patterns.
+    ///
+    /// Used for applying user configuration on top of [`TableGroups::builtin`].
+    pub fn extend(&mut self, other: Self) {
+        self.tables.extend(other.tables);
+        self.prefixes.extend(other.prefixes);
+    }
+
+    /// Finds the group of `table`.
+    pub fn group(&self, table: &str) -> &str {
+        if let Some(group) = self.tables.get(table) {
+            return group;
+        }
+
+        self.prefixes
+            .iter()
+            .filter(|(prefix, _)| table.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map_or(Self::OTHER, |(_, group)| group)
+    }
+}
+
+impl DbdCollection {
+    /// Table names of the collection keyed by their group in `groups`.
+    pub fn table_groups<'a>(&'a self, groups: &'a TableGroups) -> BTreeMap<&'a str, Vec<&'a str>> {
+        let mut out: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+
+        for table in self.files.keys() {
+            out.entry(groups.group(table))
+                .or_default()
+                .push(table.as_str());
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::TableGroupsError;
+    use crate::groups::TableGroups;
+    use crate::{load_file_from_string, DbdCollection};
+
+    #[test]
+    fn groups() {
+        let mut groups = TableGroups::builtin();
+        groups.extend(
+            TableGroups::parse("Spells SkillLineAbility\n// Comment\nMaps AreaTable // Exact\nMounts SpellMount*\n")
+                .unwrap(),
+        );
+
+        assert_eq!(groups.group("Spell"), "Spells");
+        assert_eq!(groups.group("SpellMountEffect"), "Mounts");
+        assert_eq!(groups.group("SkillLineAbility"), "Spells");
+        assert_eq!(groups.group("SkillLine"), "Skills");
+        assert_eq!(groups.group("Movie"), TableGroups::OTHER);
+
+        assert_eq!(
+            TableGroups::parse("// Groups\nSpells\n"),
+            Err(TableGroupsError::InvalidLine {
+                line: 2,
+                contents: "Spells".to_string()
+            })
+        );
+
+        let collection =
+            DbdCollection::from_files(["Spell", "Movie", "ItemSet", "Item"].iter().map(|name| {
+                load_file_from_string("COLUMNS\nint ID\n", format!("{}.dbd", name))
+                    .unwrap()
+                    .into_proper()
+                    .unwrap()
+            }));
+        let tables = collection.table_groups(&groups);
+        assert_eq!(
+            tables.into_iter().collect::<Vec<_>>(),
+            vec![
+                ("Items", vec!["Item", "ItemSet"]),
+                (TableGroups::OTHER, vec!["Movie"]),
+                ("Spells", vec!["Spell"]),
+            ]
+        );
+    }
+}
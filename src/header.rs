@@ -0,0 +1,444 @@
+//! Inspection of `.dbc` and `.db2` headers without decoding any records.
+//!
+//! [`inspect_header`] reads the counts and hashes from the start of a file,
+//! and [`DbdCollection::match_header`] finds the definitions that can describe it,
+//! so unknown files can be identified before committing to a full decode.
+//! [`DbdCollection::detect_client_build`] combines the headers of many files to find the client build they come from.
+
+use crate::error::HeaderError;
+use crate::{DbdCollection, Definition, Layout, Version};
+use std::collections::BTreeSet;
+use std::convert::TryInto;
+use std::io::Read;
+use std::path::Path;
+
+/// Largest header of any format, [`DbFormat::Wdc5`].
+const MAX_HEADER_SIZE: u64 = 4 + 4 + 128 + 6 * 4;
+
+/// Format of a `.dbc` or `.db2` file, from the magic at the start of the file.
+#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum DbFormat {
+    /// `WDBC`, used up to and including Wrath of the Lich King.
+    Wdbc,
+    /// `WDB2`, used from Cataclysm.
+    Wdb2,
+    /// `WDB3`.
+    Wdb3,
+    /// `WDB4`.
+    Wdb4,
+    /// `WDB5`, the first format with a layout hash.
+    Wdb5,
+    /// `WDB6`.
+    Wdb6,
+    /// `WDC1`.
+    Wdc1,
+    /// `WDC2` and `1SLC`.
+    Wdc2,
+    /// `WDC3`.
+    Wdc3,
+    /// `WDC4`.
+    Wdc4,
+    /// `WDC5`.
+    Wdc5,
+}
+
+impl DbFormat {
+    /// Finds the format for the first four bytes of a file.
+    pub const fn from_magic(magic: [u8; 4]) -> Option<Self> {
+        Some(match &magic {
+            b"WDBC" => Self::Wdbc,
+            b"WDB2" => Self::Wdb2,
+            b"WDB3" => Self::Wdb3,
+            b"WDB4" => Self::Wdb4,
+            b"WDB5" => Self::Wdb5,
+            b"WDB6" => Self::Wdb6,
+            b"WDC1" => Self::Wdc1,
+            b"WDC2" | b"1SLC" => Self::Wdc2,
+            b"WDC3" => Self::Wdc3,
+            b"WDC4" => Self::Wdc4,
+            b"WDC5" => Self::Wdc5,
+            _ => return None,
+        })
+    }
+
+    /// Returns true if the header contains a layout hash.
+    pub const fn has_layout_hash(&self) -> bool {
+        !matches!(self, Self::Wdbc | Self::Wdb2 | Self::Wdb3 | Self::Wdb4)
+    }
+
+    /// Offset of the record count, after the magic and any format specific fields before it.
+    const fn counts_offset(&self) -> usize {
+        match self {
+            // Version number and 128 byte schema string.
+            Self::Wdc5 => 4 + 4 + 128,
+            _ => 4,
+        }
+    }
+}
+
+/// Counts and hashes from the header of a `.dbc` or `.db2` file.
+///
+/// Created from [`inspect_header`].
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct DbHeader {
+    /// Format of the file.
+    pub format: DbFormat,
+    /// Amount of records.
+    pub record_count: u32,
+    /// Amount of fields in a record.
+    ///
+    /// For [`DbFormat::Wdbc`] this is the amount of 32 bit values and not the amount of columns.
+    pub field_count: u32,
+    /// Size of a record in bytes.
+    pub record_size: u32,
+    /// Hash of the table name. [`None`] for [`DbFormat::Wdbc`].
+    pub table_hash: Option<u32>,
+    /// Layout hash. [`None`] for formats without one, see [`DbFormat::has_layout_hash`].
+    pub layout_hash: Option<Layout>,
+    /// Build number of the client. Only set for [`DbFormat::Wdb2`], [`DbFormat::Wdb3`] and [`DbFormat::Wdb4`].
+    pub build: Option<u32>,
+}
+
+/// Reads the header at the start of `bytes`.
+///
+/// Only the header needs to be present, the rest of the file is never read.
+///
+/// # Errors
+///
+/// Errors if the magic is not a known format or if `bytes` is too short for the header.
+pub fn inspect_header(bytes: &[u8]) -> Result<DbHeader, HeaderError> {
+    let magic: [u8; 4] = bytes
+        .get(..4)
+        .ok_or(HeaderError::TooShort)?
+        .try_into()
+        .map_err(|_| HeaderError::TooShort)?;
+    let format = DbFormat::from_magic(magic).ok_or(HeaderError::UnknownMagic(magic))?;
+
+    let offset = format.counts_offset();
+    let read = |index: usize| -> Result<u32, HeaderError> {
+        let start = offset + index * 4;
+        let value = bytes
+            .get(start..start + 4)
+            .ok_or(HeaderError::TooShort)?
+            .try_into()
+            .map_err(|_| HeaderError::TooShort)?;
+
+        Ok(u32::from_le_bytes(value))
+    };
+
+    let (table_hash, layout_hash, build) = match format {
+        DbFormat::Wdbc => (None, None, None),
+        DbFormat::Wdb2 | DbFormat::Wdb3 | DbFormat::Wdb4 => (Some(read(4)?), None, Some(read(5)?)),
+        _ => (Some(read(4)?), Some(Layout::new(read(5)?)), None),
+    };
+
+    Ok(DbHeader {
+        format,
+        record_count: read(0)?,
+        field_count: read(1)?,
+        record_size: read(2)?,
+        table_hash,
+        layout_hash,
+        build,
+    })
+}
+
+impl DbdCollection {
+    /// Finds the definitions that can describe a file with `header`, sorted by table name.
+    ///
+    /// Headers with a layout hash match definitions with that layout.
+    /// Other headers with a table hash match every definition of the table with that hash in [`DbdCollection::manifest`].
+    /// [`DbFormat::Wdbc`] headers have neither and never match.
+    pub fn match_header(&self, header: &DbHeader) -> Vec<(&str, &Definition)> {
+        let mut matches = Vec::new();
+
+        for (table, file) in &self.files {
+            let table_hash_matches =
+                header.table_hash.is_some() && self.table_hash(table) == header.table_hash;
+
+            for definition in &file.definitions {
+                let is_match = match &header.layout_hash {
+                    Some(layout) => definition.layouts.contains(layout),
+                    None => table_hash_matches,
+                };
+
+                if is_match {
+                    matches.push((table.as_str(), definition));
+                }
+            }
+        }
+
+        matches
+    }
+
+    /// Ranks the builds the `.dbc` and `.db2` files in `dir` can come from, most likely first.
+    ///
+    /// Only the headers are read, see [`DbdCollection::detect_client_build_from_headers`] for how builds are ranked.
+    /// Files that do not end in `.dbc` or `.db2`, files with an invalid header and subdirectories are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the directory or files can not be read.
+    pub fn detect_client_build(&self, dir: &Path) -> std::io::Result<Vec<BuildCandidate>> {
+        let mut headers = Vec::new();
+
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            let path = entry.path();
+
+            let is_db = path.extension().map_or(false, |a| {
+                a.eq_ignore_ascii_case("dbc") || a.eq_ignore_ascii_case("db2")
+            });
+            if !entry.file_type()?.is_file() || !is_db {
+                continue;
+            }
+
+            let mut bytes = Vec::new();
+            std::fs::File::open(&path)?
+                .take(MAX_HEADER_SIZE)
+                .read_to_end(&mut bytes)?;
+
+            if let (Some(table), Ok(header)) = (path.file_stem(), inspect_header(&bytes)) {
+                headers.push((table.to_string_lossy().to_string(), header));
+            }
+        }
+
+        Ok(self.detect_client_build_from_headers(
+            headers
+                .iter()
+                .map(|(table, header)| (table.as_str(), header)),
+        ))
+    }
+
+    /// Ranks the builds that files with `headers`, keyed by table name, can come from.
+    ///
+    /// Every build mentioned in a definition of one of the tables is a candidate.
+    /// A table supports a candidate if it has a definition for the build that matches the header:
+    ///
+    /// * Headers with a layout hash must match a layout of the definition.
+    /// * Headers with a build number must match the build.
+    /// * [`DbFormat::Wdbc`] headers must match [`Definition::field_count`].
+    ///
+    /// Candidates are sorted by the amount of supporting tables, most first, then by version.
+    /// Candidates without any supporting tables are not included.
+    /// Table names are matched without regard to case, since extracted files are often lowercase.
+    pub fn detect_client_build_from_headers<'a>(
+        &self,
+        headers: impl IntoIterator<Item = (&'a str, &'a DbHeader)>,
+    ) -> Vec<BuildCandidate> {
+        let headers: Vec<_> = headers
+            .into_iter()
+            .filter_map(|(table, header)| {
+                let file = self
+                    .iter()
+                    .find(|a| a.table_name().eq_ignore_ascii_case(table))?;
+                Some((file, header))
+            })
+            .collect();
+
+        let mut versions = BTreeSet::new();
+        for (file, _) in &headers {
+            for definition in &file.definitions {
+                for interval in definition.versions.intervals() {
+                    versions.insert(interval.from);
+                    versions.insert(interval.to);
+                }
+            }
+        }
+
+        let mut candidates: Vec<BuildCandidate> = versions
+            .into_iter()
+            .map(|version| BuildCandidate {
+                version,
+                supporting_tables: headers
+                    .iter()
+                    .filter(|(file, header)| {
+                        file.definitions.iter().any(|definition| {
+                            definition.versions.contains(&version)
+                                && header_matches(header, definition, &version)
+                        })
+                    })
+                    .count(),
+            })
+            .filter(|a| a.supporting_tables > 0)
+            .collect();
+
+        candidates.sort_by(|a, b| {
+            b.supporting_tables
+                .cmp(&a.supporting_tables)
+                .then(a.version.cmp(&b.version))
+        });
+
+        candidates
+    }
+}
+
+fn header_matches(header: &DbHeader, definition: &Definition, version: &Version) -> bool {
+    if let Some(layout) = &header.layout_hash {
+        definition.layouts.contains(layout)
+    } else if let Some(build) = header.build {
+        u32::from(version.build) == build
+    } else {
+        definition.field_count(version) == header.field_count as usize
+    }
+}
+
+/// Build that loose client files can come from.
+///
+/// Created from [`DbdCollection::detect_client_build`].
+#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct BuildCandidate {
+    /// Version of the build.
+    pub version: Version,
+    /// Amount of tables with a definition for the build that matches the header of the file.
+    pub supporting_tables: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::HeaderError;
+    use crate::header::{inspect_header, BuildCandidate, DbFormat, DbHeader};
+    use crate::manifest::ManifestEntry;
+    use crate::{load_file_from_string, DbdCollection, Layout, Version};
+
+    fn header(magic: &[u8], values: &[u32]) -> Vec<u8> {
+        let mut bytes = magic.to_vec();
+        for value in values {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+
+        bytes
+    }
+
+    #[test]
+    fn inspect() {
+        assert_eq!(
+            inspect_header(&header(b"WDBC", &[10, 4, 16, 1])),
+            Ok(DbHeader {
+                format: DbFormat::Wdbc,
+                record_count: 10,
+                field_count: 4,
+                record_size: 16,
+                table_hash: None,
+                layout_hash: None,
+                build: None,
+            })
+        );
+        assert_eq!(
+            inspect_header(&header(b"WDB2", &[10, 4, 16, 1, 0xF568DF12, 15595])),
+            Ok(DbHeader {
+                format: DbFormat::Wdb2,
+                record_count: 10,
+                field_count: 4,
+                record_size: 16,
+                table_hash: Some(0xF568DF12),
+                layout_hash: None,
+                build: Some(15595),
+            })
+        );
+        assert_eq!(
+            inspect_header(&header(b"WDC3", &[10, 4, 16, 1, 0xF568DF12, 0x43E6E8A6])),
+            Ok(DbHeader {
+                format: DbFormat::Wdc3,
+                record_count: 10,
+                field_count: 4,
+                record_size: 16,
+                table_hash: Some(0xF568DF12),
+                layout_hash: Some(Layout::new(0x43E6E8A6)),
+                build: None,
+            })
+        );
+
+        let mut wdc5 = header(b"WDC5", &[5]);
+        wdc5.extend_from_slice(&[0; 128]);
+        wdc5.extend_from_slice(&header(b"", &[10, 4, 16, 1, 0xF568DF12, 0x43E6E8A6]));
+        assert_eq!(
+            inspect_header(&wdc5).unwrap().layout_hash,
+            Some(Layout::new(0x43E6E8A6))
+        );
+
+        assert_eq!(
+            inspect_header(b"WDBX\0\0\0\0"),
+            Err(HeaderError::UnknownMagic(*b"WDBX"))
+        );
+        assert_eq!(
+            inspect_header(&header(b"WDB5", &[10, 4, 16, 1])),
+            Err(HeaderError::TooShort)
+        );
+        assert_eq!(inspect_header(b"WD"), Err(HeaderError::TooShort));
+    }
+
+    #[test]
+    fn match_header() {
+        let map = load_file_from_string(
+            "COLUMNS\nint ID\n\nLAYOUT 43E6E8A6\nBUILD 9.0.1.36216\n$id$ID<32>\n\nBUILD 4.3.4.15595\n$id$ID<32>\n",
+            "Map.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+        let mut collection = DbdCollection::from_files(vec![map]);
+
+        let wdc3 =
+            inspect_header(&header(b"WDC3", &[10, 1, 4, 1, 0xF568DF12, 0x43E6E8A6])).unwrap();
+        let matches = collection.match_header(&wdc3);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].0, "Map");
+        assert!(matches[0].1.layouts.contains(&Layout::new(0x43E6E8A6)));
+
+        let wdb2 = inspect_header(&header(b"WDB2", &[10, 1, 4, 1, 0xF568DF12, 15595])).unwrap();
+        assert!(collection.match_header(&wdb2).is_empty());
+
+        collection.manifest.insert(
+            "Map".to_string(),
+            ManifestEntry {
+                table_name: "Map".to_string(),
+                table_hash: Some(0xF568DF12),
+                dbc_file_data_id: None,
+                db2_file_data_id: None,
+            },
+        );
+        assert_eq!(collection.match_header(&wdb2).len(), 2);
+    }
+
+    #[test]
+    fn detect_client_build() {
+        let map = load_file_from_string(
+            "COLUMNS\nint ID\nlocstring Name\n\nBUILD 1.12.1.5875\n$id$ID<32>\nName\n\nBUILD 3.3.5.12340\n$id$ID<32>\nName\n",
+            "Map.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+        let area = load_file_from_string(
+            "COLUMNS\nint ID\nint Flags\n\nBUILD 1.12.1.5875, 3.3.5.12340\n$id$ID<32>\nFlags<32>\n",
+            "AreaTable.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+        let collection = DbdCollection::from_files(vec![map, area]);
+
+        let map = inspect_header(&header(b"WDBC", &[10, 18, 72, 1])).unwrap();
+        let area = inspect_header(&header(b"WDBC", &[10, 2, 8, 1])).unwrap();
+        let unknown = inspect_header(&header(b"WDBC", &[10, 2, 8, 1])).unwrap();
+
+        assert_eq!(
+            collection.detect_client_build_from_headers(vec![
+                ("map", &map),
+                ("AreaTable", &area),
+                ("Unknown", &unknown),
+            ]),
+            vec![
+                BuildCandidate {
+                    version: Version::new(3, 3, 5, 12340),
+                    supporting_tables: 2,
+                },
+                BuildCandidate {
+                    version: Version::new(1, 12, 1, 5875),
+                    supporting_tables: 1,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,260 @@
+//! Token classification for syntax highlighting of `.dbd` files.
+//!
+//! [`tokenize`] classifies a single line following the grammar of [`parse_events`](crate::parse_events),
+//! so editors do not have to reimplement it.
+//! Unlike the parser it does not validate the line, so it can be used while the line is being edited.
+
+use std::ops::Range;
+
+const TYPE_NAMES: &[&str] = &["int", "float", "string", "locstring"];
+
+/// Kind of a token found by [`tokenize`].
+#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum TokenKind {
+    /// `COLUMNS`, `BUILD`, `LAYOUT` and `COMMENT`.
+    Keyword,
+    /// Type of a column, for example `int`.
+    Type,
+    /// Name of a column or entry.
+    Identifier,
+    /// Single annotation of an entry, for example `id` in `$id$`.
+    Tag,
+    /// Foreign key of a column, for example `Map::ID` in `int<Map::ID>`.
+    ForeignKey,
+    /// Builds, layouts, integer sizes and array sizes.
+    Number,
+    /// Comments starting with `//` and the text of `COMMENT` lines.
+    Comment,
+    /// `<`, `>`, `[`, `]`, `$`, `,`, `-` and the `?` of unverified columns.
+    Punctuation,
+}
+
+/// Classifies the tokens of a single line, without the line ending.
+///
+/// Ranges are byte offsets into `line`, sorted and not overlapping.
+/// Whitespace is not included in any token.
+///
+/// Lines starting with one of the column types followed by a space are column declarations,
+/// all other lines that do not start with a keyword are entries.
+pub fn tokenize(line: &str) -> Vec<(Range<usize>, TokenKind)> {
+    let mut t = Tokenizer {
+        line,
+        tokens: Vec::new(),
+    };
+
+    let (code, comment) = match line.find("//") {
+        Some(i) => (&line[..i], Some(i)),
+        None => (line, None),
+    };
+
+    let start = code.len() - code.trim_start().len();
+    let rest = &code[start..];
+
+    if let Some(keyword) = ["COLUMNS", "BUILD", "LAYOUT", "COMMENT"]
+        .iter()
+        .find(|a| rest.starts_with(*a))
+    {
+        t.push(start..start + keyword.len(), TokenKind::Keyword);
+        let start = start + keyword.len();
+
+        if *keyword == "COMMENT" {
+            t.trimmed(start..line.len(), TokenKind::Comment);
+            return t.tokens;
+        }
+
+        t.build_or_layout(start..code.len());
+    } else if is_column(rest) {
+        t.column(start..code.len());
+    } else {
+        t.entry(start..code.len());
+    }
+
+    if let Some(i) = comment {
+        t.trimmed(i..line.len(), TokenKind::Comment);
+    }
+
+    t.tokens
+}
+
+fn is_column(s: &str) -> bool {
+    let word = match s.split_once(' ') {
+        Some((word, _)) => word,
+        None => return false,
+    };
+    let ty = word.split('<').next().unwrap_or(word);
+
+    TYPE_NAMES.contains(&ty)
+}
+
+struct Tokenizer<'a> {
+    line: &'a str,
+    tokens: Vec<(Range<usize>, TokenKind)>,
+}
+
+impl Tokenizer<'_> {
+    fn push(&mut self, range: Range<usize>, kind: TokenKind) {
+        if !range.is_empty() {
+            self.tokens.push((range, kind));
+        }
+    }
+
+    /// Pushes `range` without leading and trailing whitespace.
+    fn trimmed(&mut self, range: Range<usize>, kind: TokenKind) {
+        let s = &self.line[range.clone()];
+        let start = range.start + (s.len() - s.trim_start().len());
+        let end = range.end - (s.len() - s.trim_end().len());
+
+        if start < end {
+            self.push(start..end, kind);
+        }
+    }
+
+    /// Splits `range` on `separators`, pushing separators as [`TokenKind::Punctuation`]
+    /// and everything between them as `kind`.
+    fn separated(&mut self, range: Range<usize>, separators: &[char], kind: TokenKind) {
+        let mut start = range.start;
+
+        for (i, c) in self.line[range.clone()].char_indices() {
+            let i = range.start + i;
+
+            if separators.contains(&c) {
+                self.trimmed(start..i, kind);
+                self.push(i..i + c.len_utf8(), TokenKind::Punctuation);
+                start = i + c.len_utf8();
+            }
+        }
+
+        self.trimmed(start..range.end, kind);
+    }
+
+    fn build_or_layout(&mut self, range: Range<usize>) {
+        self.separated(range, &[',', '-'], TokenKind::Number);
+    }
+
+    fn column(&mut self, range: Range<usize>) {
+        let s = &self.line[range.clone()];
+        let space = range.start + s.find(' ').unwrap_or(s.len());
+
+        let ty = &self.line[range.start..space];
+        match (ty.find('<'), ty.find('>')) {
+            (Some(open), Some(close)) if open < close => {
+                let open = range.start + open;
+                let close = range.start + close;
+
+                self.push(range.start..open, TokenKind::Type);
+                self.push(open..open + 1, TokenKind::Punctuation);
+                self.trimmed(open + 1..close, TokenKind::ForeignKey);
+                self.push(close..close + 1, TokenKind::Punctuation);
+            }
+            _ => self.push(range.start..space, TokenKind::Type),
+        }
+
+        let name = &self.line[space..range.end];
+        match name.trim_end().strip_suffix('?') {
+            Some(_) => {
+                let question = space + name.trim_end().len() - 1;
+                self.trimmed(space..question, TokenKind::Identifier);
+                self.push(question..question + 1, TokenKind::Punctuation);
+            }
+            None => self.trimmed(space..range.end, TokenKind::Identifier),
+        }
+    }
+
+    fn entry(&mut self, range: Range<usize>) {
+        let mut start = range.start;
+
+        if self.line[start..range.end].starts_with('$') {
+            self.push(start..start + 1, TokenKind::Punctuation);
+
+            match self.line[start + 1..range.end].find('$') {
+                Some(close) => {
+                    let close = start + 1 + close;
+                    self.separated(start + 1..close, &[','], TokenKind::Tag);
+                    self.push(close..close + 1, TokenKind::Punctuation);
+                    start = close + 1;
+                }
+                None => {
+                    self.separated(start + 1..range.end, &[','], TokenKind::Tag);
+                    return;
+                }
+            }
+        }
+
+        let s = &self.line[start..range.end];
+        let name_end = start + s.find(['<', '[']).unwrap_or(s.len());
+        self.trimmed(start..name_end, TokenKind::Identifier);
+
+        self.separated(
+            name_end..range.end,
+            &['<', '>', '[', ']'],
+            TokenKind::Number,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::highlight::{tokenize, TokenKind};
+
+    fn kinds(line: &str) -> Vec<(&str, TokenKind)> {
+        tokenize(line)
+            .into_iter()
+            .map(|(range, kind)| (&line[range], kind))
+            .collect()
+    }
+
+    #[test]
+    fn tokens() {
+        use TokenKind::*;
+
+        assert_eq!(
+            kinds("int<Map::ID> ParentMapID? // Parent"),
+            vec![
+                ("int", Type),
+                ("<", Punctuation),
+                ("Map::ID", ForeignKey),
+                (">", Punctuation),
+                ("ParentMapID", Identifier),
+                ("?", Punctuation),
+                ("// Parent", Comment),
+            ]
+        );
+        assert_eq!(
+            kinds("$noninline,id$ID<u32>[2]"),
+            vec![
+                ("$", Punctuation),
+                ("noninline", Tag),
+                (",", Punctuation),
+                ("id", Tag),
+                ("$", Punctuation),
+                ("ID", Identifier),
+                ("<", Punctuation),
+                ("u32", Number),
+                (">", Punctuation),
+                ("[", Punctuation),
+                ("2", Number),
+                ("]", Punctuation),
+            ]
+        );
+        assert_eq!(
+            kinds("BUILD 1.12.1.5875, 3.0.1.8303-3.3.5.12340"),
+            vec![
+                ("BUILD", Keyword),
+                ("1.12.1.5875", Number),
+                (",", Punctuation),
+                ("3.0.1.8303", Number),
+                ("-", Punctuation),
+                ("3.3.5.12340", Number),
+            ]
+        );
+        assert_eq!(
+            kinds("COMMENT Some text"),
+            vec![("COMMENT", Keyword), ("Some text", Comment)]
+        );
+        assert_eq!(
+            kinds("string Directory"),
+            vec![("string", Type), ("Directory", Identifier)]
+        );
+        assert_eq!(kinds("Directory"), vec![("Directory", Identifier)]);
+    }
+}
@@ -0,0 +1,204 @@
+use crate::error::EntryConversionError;
+use crate::lint::{Span, Spans};
+use crate::{DbdFile, ParseError, RawDbdFile};
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+
+/// Something in a `.dbd` file that is not kept by [`RawDbdFile::into_proper_with_ignored`].
+///
+/// Writing the converted [`DbdFile`] again does not include any of these.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Ignored {
+    /// Location in the file, if the contents are known.
+    pub span: Option<Span>,
+    /// What was ignored.
+    pub kind: IgnoredKind,
+}
+
+/// What was ignored, see [`Ignored`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum IgnoredKind {
+    /// Annotation of an entry that is not `id`, `noninline` or `relation`.
+    UnknownTag {
+        /// Index into [`DbdFile::definitions`].
+        definition: usize,
+        /// Name of the entry.
+        entry: String,
+        /// Text of the annotation.
+        tag: String,
+    },
+    /// `COMMENT` line of a definition.
+    DefinitionComment {
+        /// Index into [`DbdFile::definitions`].
+        definition: usize,
+        /// Text of the comment.
+        comment: String,
+    },
+    /// Entry that could not be converted and was left out of its definition.
+    Entry {
+        /// Index into [`DbdFile::definitions`].
+        definition: usize,
+        /// Conversion error of the entry.
+        error: EntryConversionError,
+    },
+    /// Column under `COLUMNS` that is not used by any definition.
+    UnusedColumn(String),
+}
+
+impl Display for Ignored {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(span) = &self.span {
+            write!(f, "line {}: ", span.line + 1)?;
+        }
+
+        match &self.kind {
+            IgnoredKind::UnknownTag { entry, tag, .. } => {
+                write!(f, "unknown annotation '{}' on entry '{}'", tag, entry)
+            }
+            IgnoredKind::DefinitionComment { comment, .. } => {
+                write!(f, "definition comment '{}'", comment)
+            }
+            IgnoredKind::Entry { error, .. } => write!(f, "{}", error),
+            IgnoredKind::UnusedColumn(column) => write!(f, "unused column '{}'", column),
+        }
+    }
+}
+
+impl RawDbdFile {
+    /// Same as [`RawDbdFile::into_proper_with_errors`] but also returns everything else
+    /// that is not kept by the converted file, so lossy loads can be audited.
+    ///
+    /// Pass the [`Spans`] of the contents the file was parsed from in order to get the location of everything.
+    pub fn into_proper_with_ignored(self, spans: Option<&Spans>) -> (DbdFile, Vec<Ignored>) {
+        let mut ignored = Vec::new();
+        let mut push = |span: Option<Span>, kind: IgnoredKind| ignored.push(Ignored { span, kind });
+
+        let mut used = BTreeSet::new();
+        let mut comments = 0;
+        let mut definitions = Vec::with_capacity(self.definitions.len());
+
+        for (i, definition) in self.definitions.iter().enumerate() {
+            for comment in &definition.comments {
+                push(
+                    spans.and_then(|a| a.comments().get(comments).copied()),
+                    IgnoredKind::DefinitionComment {
+                        definition: i,
+                        comment: comment.clone(),
+                    },
+                );
+                comments += 1;
+            }
+
+            for entry in &definition.entries {
+                used.insert(entry.name.as_str());
+
+                for tag in &entry.unknown_tags {
+                    push(
+                        spans.and_then(|a| a.entry(i, &entry.name)),
+                        IgnoredKind::UnknownTag {
+                            definition: i,
+                            entry: entry.name.clone(),
+                            tag: tag.clone(),
+                        },
+                    );
+                }
+            }
+
+            let (definition, errors) = definition.to_definition_with_errors(&self.columns);
+            for error in errors {
+                push(
+                    spans.and_then(|a| a.entry(i, &error.entry)),
+                    IgnoredKind::Entry {
+                        definition: i,
+                        error,
+                    },
+                );
+            }
+            definitions.push(definition);
+        }
+
+        let mut unused: Vec<&str> = self
+            .columns
+            .keys()
+            .map(String::as_str)
+            .filter(|a| !used.contains(a))
+            .collect();
+        unused.sort_unstable();
+        for column in unused {
+            push(
+                spans.and_then(|a| a.column(column)),
+                IgnoredKind::UnusedColumn(column.to_string()),
+            );
+        }
+
+        (
+            DbdFile {
+                name: self.name,
+                definitions,
+            },
+            ignored,
+        )
+    }
+}
+
+/// Load DBD file from string and convert it while recording everything that is not kept.
+///
+/// See [`RawDbdFile::into_proper_with_ignored`].
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] in case parsing fails.
+pub fn load_file_from_string_with_ignored(
+    contents: &str,
+    name: impl Into<String>,
+) -> Result<(DbdFile, Vec<Ignored>), ParseError> {
+    let raw = crate::load_file_from_string(contents, name)?;
+    let spans = Spans::new(contents)?;
+
+    Ok(raw.into_proper_with_ignored(Some(&spans)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{load_file_from_string_with_ignored, IgnoredKind};
+
+    #[test]
+    fn ignored() {
+        let (file, ignored) = load_file_from_string_with_ignored(
+            "COLUMNS\nint ID\nint Unused // Old\n\nBUILD 1.12.1.5875\nCOMMENT Only on test realms\n$id,future$ID<32>\nMissing<32>\n",
+            "Map.dbd",
+        )
+        .unwrap();
+        assert_eq!(file.definitions[0].entries.len(), 1);
+
+        let ignored: Vec<_> = ignored
+            .iter()
+            .map(|a| (a.span.map(|a| a.line), a.to_string()))
+            .collect();
+        assert_eq!(
+            ignored,
+            vec![
+                (
+                    Some(5),
+                    "line 6: definition comment 'Only on test realms'".to_string()
+                ),
+                (
+                    Some(6),
+                    "line 7: unknown annotation 'future' on entry 'ID'".to_string()
+                ),
+                (
+                    Some(7),
+                    "line 8: entry 'Missing': column not found 'Missing'".to_string()
+                ),
+                (Some(2), "line 3: unused column 'Unused'".to_string()),
+            ]
+        );
+        assert!(matches!(
+            load_file_from_string_with_ignored("COLUMNS\nint ID\n", "Map.dbd")
+                .unwrap()
+                .1[0]
+                .kind,
+            IgnoredKind::UnusedColumn(_)
+        ));
+    }
+}
@@ -40,6 +40,12 @@
 //! # }
 //! ```
 //!
+//! The most commonly used items can be imported with the [`prelude`]:
+//!
+//! ```rust
+//! use wowdbdefs_rs::prelude::*;
+//! ```
+//!
 //! # Usage
 //!
 //! Add the following to your `Cargo.toml`:
@@ -49,6 +55,12 @@
 //! wowdbdefs-rs = "0.1.0"
 //! ```
 //!
+//! # Features
+//!
+//! * `builds`, enables `Builds` with constants for the builds of well known client releases.
+//! * `yaml`, enables `DbdFile::to_yaml`.
+//! * `toml`, enables `DbdFile::to_toml`, `LintConfig::from_toml` and `ExportPlan`.
+//!
 //! # MSRV
 //!
 //! The MSRV for this crate is `1.58.1`.
@@ -66,17 +78,71 @@
     clippy::missing_panics_doc
 )]
 
-use crate::error::ParseError;
+use crate::error::{LoadError, ParseError};
 use crate::parser::parse_file;
 use std::fs::read_to_string;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 pub use types::*;
 
+pub mod artifacts;
+#[cfg(feature = "builds")]
+mod builds;
+mod bundle;
+mod cache;
+mod cancel;
+pub mod changelog;
+pub mod codegen;
+mod collection;
+pub mod comment_hints;
+pub mod compact;
+pub mod enums;
 pub mod error;
+mod export;
+mod filter;
+pub mod groups;
+pub mod header;
+pub mod highlight;
+mod ignored;
+mod limits;
+pub mod lint;
+pub mod manifest;
+pub mod mapping;
+pub mod merge;
+mod overlay;
+pub mod overrides;
 mod parser;
+#[cfg(feature = "toml")]
+mod plan;
+pub mod prelude;
+mod progress;
+pub mod propose;
+pub mod provenance;
+pub mod report;
+pub mod session;
+mod subset;
+pub mod symbols;
+pub mod transform;
 mod types;
+mod version_set;
+mod visitor;
 mod write_to_file;
 
+#[cfg(feature = "builds")]
+pub use builds::Builds;
+pub use bundle::{load_bundle_from_string, BundleOptions, BundleReader, SignatureVerifier};
+pub use cache::DefinitionCache;
+pub use cancel::CancelToken;
+pub use collection::DbdCollection;
+pub use export::{FeatureRow, Relation};
+pub use filter::{EntryFilter, UnknownFieldRules};
+pub use ignored::{load_file_from_string_with_ignored, Ignored, IgnoredKind};
+pub use limits::{load_file_from_string_with_limits, ParseLimits};
+pub use parser::{Event, Events};
+#[cfg(feature = "toml")]
+pub use plan::{ExportFormat, ExportPlan};
+pub use progress::Progress;
+pub use version_set::VersionSet;
+pub use visitor::Visitor;
 pub use write_to_file::*;
 
 mod writer;
@@ -122,9 +188,267 @@ pub fn load_file_from_string(
     parse_file(contents, name.into())
 }
 
+/// Parse the `.dbd` contents line by line as [`Event`]s without building a [`RawDbdFile`].
+///
+/// This uses the same grammar as [`load_file_from_string`],
+/// which is built on top of the events.
+/// Useful for formatters, linters and syntax highlighters that process the file as a stream.
+///
+/// Parsing continues after an error, so every line with an error can be found.
+pub fn parse_events(contents: &str) -> Events<'_> {
+    Events::new(contents)
+}
+
+/// Load all `.dbd` files in a directory into a [`DbdCollection`].
+///
+/// Files that do not end in `.dbd` and subdirectories are ignored.
+///
+/// # Errors
+///
+/// The function has two error types:
+///
+/// * [`std::io::Error`], for errors in reading the directory or files.
+/// * [`LoadError`], for errors in parsing or converting a `.dbd` file.
+///
+pub fn load_directory(path: &Path) -> std::io::Result<Result<DbdCollection, LoadError>> {
+    load_directory_with_cancel(path, &CancelToken::new())
+}
+
+/// Same as [`load_directory`] but stops with [`LoadError::Cancelled`] when `cancel` is cancelled.
+///
+/// The token is checked before reading and before converting every file.
+///
+/// # Errors
+///
+/// The function has two error types:
+///
+/// * [`std::io::Error`], for errors in reading the directory or files.
+/// * [`LoadError`], for errors in parsing or converting a `.dbd` file, or if loading was cancelled.
+///
+pub fn load_directory_with_cancel(
+    path: &Path,
+    cancel: &CancelToken,
+) -> std::io::Result<Result<DbdCollection, LoadError>> {
+    load_directory_with_progress(path, cancel, |_| {})
+}
+
+/// Same as [`load_directory_with_cancel`] but calls `progress` after every file has been read and parsed.
+///
+/// # Errors
+///
+/// The function has two error types:
+///
+/// * [`std::io::Error`], for errors in reading the directory or files.
+/// * [`LoadError`], for errors in parsing or converting a `.dbd` file, or if loading was cancelled.
+///
+pub fn load_directory_with_progress(
+    path: &Path,
+    cancel: &CancelToken,
+    mut progress: impl FnMut(Progress<'_>),
+) -> std::io::Result<Result<DbdCollection, LoadError>> {
+    let files = match load_raw_directory(path, cancel, &mut progress)? {
+        Ok(files) => files,
+        Err(e) => return Ok(Err(e)),
+    };
+
+    Ok(into_collection(files, cancel))
+}
+
+/// Load a checkout of the [`WoWDBDefs`](https://github.com/wowdev/WoWDBDefs) repository into a [`DbdCollection`].
+///
+/// The `.dbd` files are loaded from the `definitions` directory like in [`load_directory`],
+/// and `manifest.json` is parsed into [`DbdCollection::manifest`] if it exists.
+///
+/// # Errors
+///
+/// The function has two error types:
+///
+/// * [`std::io::Error`], for errors in reading the directory or files.
+/// * [`LoadError`], for errors in parsing or converting a `.dbd` file, or in parsing `manifest.json`.
+///
+pub fn load_repository(path: &Path) -> std::io::Result<Result<DbdCollection, LoadError>> {
+    let mut collection = match load_directory(&path.join("definitions"))? {
+        Ok(collection) => collection,
+        Err(e) => return Ok(Err(e)),
+    };
+
+    let manifest = path.join("manifest.json");
+    if manifest.is_file() {
+        match manifest::parse_manifest(&read_to_string(manifest)?) {
+            Ok(manifest) => collection.manifest = manifest,
+            Err(e) => return Ok(Err(LoadError::Manifest(e))),
+        }
+    }
+
+    Ok(Ok(collection))
+}
+
+/// Load all `.dbd` files in a directory and layer the partial `.dbd` files in `overlay` on top.
+///
+/// Overlay files are applied with [`RawDbdFile::apply_overlay`] to the file with the same name.
+/// Overlay files without a matching file are added as new tables.
+///
+/// This allows documenting local columns without modifying the upstream definitions.
+///
+/// # Errors
+///
+/// The function has two error types:
+///
+/// * [`std::io::Error`], for errors in reading the directories or files.
+/// * [`LoadError`], for errors in parsing a `.dbd` file, or in converting the file after applying the overlay.
+///
+pub fn load_directory_with_overlay(
+    path: &Path,
+    overlay: &Path,
+) -> std::io::Result<Result<DbdCollection, LoadError>> {
+    let cancel = CancelToken::new();
+    let mut files = match load_raw_directory(path, &cancel, &mut |_| {})? {
+        Ok(files) => files,
+        Err(e) => return Ok(Err(e)),
+    };
+    let overlays = match load_raw_directory(overlay, &cancel, &mut |_| {})? {
+        Ok(files) => files,
+        Err(e) => return Ok(Err(e)),
+    };
+
+    for overlay in overlays {
+        if let Some(file) = files.iter_mut().find(|a| a.name == overlay.name) {
+            file.apply_overlay(overlay);
+        } else {
+            files.push(overlay);
+        }
+    }
+
+    Ok(into_collection(files, &cancel))
+}
+
+/// Load all `.dbd` files in a directory into a [`DbdCollection`] without stopping at the first error.
+///
+/// Files that can not be parsed are left out of the collection
+/// and entries that can not be converted are left out of their definition,
+/// see [`RawDbdFile::into_proper_with_errors`].
+/// All errors are returned together with the collection so every problem can be reported in one run.
+///
+/// # Errors
+///
+/// Errors if the directory or any file could not be read.
+pub fn load_directory_with_errors(path: &Path) -> std::io::Result<(DbdCollection, Vec<LoadError>)> {
+    let mut collection = DbdCollection::new();
+    let mut errors = Vec::new();
+
+    for (file, result) in read_raw_directory(path)? {
+        match result {
+            Ok(raw) => {
+                let (proper, e) = raw.into_proper_with_errors();
+                errors.extend(e.into_iter().map(|error| LoadError::EntryConversion {
+                    file: file.clone(),
+                    error,
+                }));
+                collection.insert(proper);
+            }
+            Err(error) => errors.push(LoadError::Parse { file, error }),
+        }
+    }
+
+    Ok((collection, errors))
+}
+
+fn load_raw_directory(
+    path: &Path,
+    cancel: &CancelToken,
+    progress: &mut dyn FnMut(Progress<'_>),
+) -> std::io::Result<Result<Vec<RawDbdFile>, LoadError>> {
+    let paths = dbd_paths(path)?;
+    let mut files = Vec::with_capacity(paths.len());
+
+    for (i, (file, path)) in paths.iter().enumerate() {
+        if cancel.is_cancelled() {
+            return Ok(Err(LoadError::Cancelled));
+        }
+
+        match load_file(path)? {
+            Ok(raw) => files.push(raw),
+            Err(error) => {
+                return Ok(Err(LoadError::Parse {
+                    file: file.clone(),
+                    error,
+                }))
+            }
+        }
+
+        progress(Progress {
+            done: i + 1,
+            total: paths.len(),
+            name: file,
+        });
+    }
+
+    Ok(Ok(files))
+}
+
+fn read_raw_directory(
+    path: &Path,
+) -> std::io::Result<Vec<(String, Result<RawDbdFile, ParseError>)>> {
+    let mut files = Vec::new();
+
+    for (file, path) in dbd_paths(path)? {
+        files.push((file, load_file(&path)?));
+    }
+
+    Ok(files)
+}
+
+fn dbd_paths(path: &Path) -> std::io::Result<Vec<(String, PathBuf)>> {
+    let mut paths = Vec::new();
+
+    for entry in std::fs::read_dir(path)? {
+        let entry = entry?;
+        let path = entry.path();
+
+        if !entry.file_type()?.is_file() || path.extension().map_or(true, |a| a != "dbd") {
+            continue;
+        }
+
+        paths.push((entry.file_name().to_string_lossy().to_string(), path));
+    }
+
+    Ok(paths)
+}
+
+fn into_collection(
+    files: Vec<RawDbdFile>,
+    cancel: &CancelToken,
+) -> Result<DbdCollection, LoadError> {
+    let mut collection = DbdCollection::new();
+
+    for file in files {
+        if cancel.is_cancelled() {
+            return Err(LoadError::Cancelled);
+        }
+
+        let name = file.name.clone();
+        match file.into_proper() {
+            Ok(file) => {
+                collection.insert(file);
+            }
+            Err(error) => return Err(LoadError::Conversion { file: name, error }),
+        }
+    }
+
+    Ok(collection)
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{load_file, load_file_from_string, write_to_file, RawDbdFile, Version};
+    use crate::error::{ConversionError, DbdErrorReason, EntryConversionError, LoadError};
+    use crate::{
+        load_directory, load_directory_with_cancel, load_directory_with_errors,
+        load_directory_with_progress, load_file, load_file_from_string, load_repository,
+        parse_events, write_to_file, write_to_file_with_options, CancelToken, DbdCollection,
+        EntryStorage, Event, ForeignKey, Layout, RawDbdFile, ScalarType, Type, Version,
+        WriteOptions,
+    };
+    use std::path::Path;
 
     const MAP_CONTENTS: &str = include_str!(concat!(
         env!("CARGO_MANIFEST_DIR"),
@@ -137,6 +461,215 @@ mod tests {
         println!("{}", write_to_file(&f));
     }
 
+    #[test]
+    fn into_raw_round_trip() {
+        let f = load_file_from_string(MAP_CONTENTS, "Map.dbd")
+            .unwrap()
+            .into_proper()
+            .unwrap();
+
+        let raw = f.clone().into_raw().unwrap();
+        assert_eq!(raw.clone().into_proper().unwrap(), f);
+
+        let options = WriteOptions {
+            collapse_builds: false,
+            ..WriteOptions::new()
+        };
+        let written =
+            load_file_from_string(&write_to_file_with_options(&raw, &options), "Map.dbd").unwrap();
+        assert_eq!(written.into_proper().unwrap(), f);
+    }
+
+    #[test]
+    fn field_count() {
+        let f = load_file_from_string(MAP_CONTENTS, "Map.dbd")
+            .unwrap()
+            .into_proper()
+            .unwrap();
+
+        let vanilla = Version::new(1, 12, 1, 5875);
+        let definition = f.specific_version(&vanilla).unwrap();
+        assert_eq!(definition.field_count(&vanilla), 15);
+
+        let wrath = Version::new(3, 3, 5, 12340);
+        let definition = f.specific_version(&wrath).unwrap();
+        assert_eq!(definition.entries[1].expanded_field_count(&wrath), 1);
+        assert_eq!(definition.entries[5].expanded_field_count(&wrath), 17);
+    }
+
+    #[test]
+    fn entry_storage() {
+        let f = load_file_from_string(
+            "COLUMNS\nint ID\nint ParentID\nint Flags\n\nBUILD 8.0.1.26231\n$noninline,id$ID<32>\nFlags<32>\n$noninline,relation$ParentID<32>\n",
+            "Map.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+        let definition = &f.definitions[0];
+
+        assert_eq!(definition.entries[0].storage(), EntryStorage::IdList);
+        assert_eq!(definition.entries[1].storage(), EntryStorage::Record);
+        assert_eq!(
+            definition.entries[2].storage(),
+            EntryStorage::RelationshipMap
+        );
+        assert_eq!(definition.parent_relation(), Some(&definition.entries[2]));
+    }
+
+    #[test]
+    fn latest_layout() {
+        let map = load_file_from_string(
+            "COLUMNS\nint ID\n\nLAYOUT FFFFFFFF\nBUILD 9.0.1.36216\n$id$ID<32>\n\nLAYOUT 00000002\nBUILD 9.1.0.39185-10.0.0.46366\n$id$ID<32>\n\nLAYOUT 00000001\nBUILD 8.0.1.26231\n$id$ID<32>\n\nBUILD 1.12.1.5875\n$id$ID<32>\n",
+            "Map.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+        let spell = load_file_from_string(
+            "COLUMNS\nint ID\n\nBUILD 1.12.1.5875\n$id$ID<32>\n",
+            "Spell.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+        assert_eq!(map.latest_layout(), Some(&Layout::new(2)));
+
+        let collection = DbdCollection::from_files(vec![map, spell]);
+        let layouts = collection.latest_layouts();
+        assert_eq!(layouts.len(), 1);
+        assert_eq!(layouts["Map"], &Layout::new(2));
+    }
+
+    #[test]
+    fn definitions_between() {
+        let map = load_file_from_string(
+            "COLUMNS\nint ID\nint Flags\n\nBUILD 3.3.5.12340\n$id$ID<32>\nFlags<32>\n\nBUILD 1.12.1.5875\n$id$ID<32>\n\nBUILD 2.0.0.5610-2.4.3.8606\n$id$ID<32>\nFlags<16>\n",
+            "Map.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+
+        let definitions =
+            map.definitions_between(&Version::new(2, 4, 0, 8089), &Version::new(3, 3, 5, 12340));
+        assert_eq!(definitions, vec![&map.definitions[2], &map.definitions[0]]);
+
+        let all = map.definitions_between(&Version::new(0, 0, 0, 0), &Version::new(9, 0, 0, 0));
+        assert_eq!(
+            all,
+            vec![
+                &map.definitions[1],
+                &map.definitions[2],
+                &map.definitions[0]
+            ]
+        );
+        assert!(map
+            .definitions_between(&Version::new(4, 0, 0, 0), &Version::new(5, 0, 0, 0))
+            .is_empty());
+    }
+
+    #[test]
+    fn structure_fingerprint() {
+        let load = |contents: &str, name: &str| {
+            load_file_from_string(contents, name)
+                .unwrap()
+                .into_proper()
+                .unwrap()
+                .definitions
+                .remove(0)
+        };
+
+        let map = load(
+            "COLUMNS\nint ID\nint<Area::ID> AreaID // Comment\nfloat Scale\n\nBUILD 1.12.1.5875\n$noninline,id$ID<32>\nAreaID<32>[2]\nScale\n",
+            "Map.dbd",
+        );
+        let spell = load(
+            "COLUMNS\nint Other\nfloat Radius\n\nBUILD 3.3.5.12340\nOther<32>[2]\nRadius\n",
+            "Spell.dbd",
+        );
+        let unsigned = load(
+            "COLUMNS\nint Other\nfloat Radius\n\nBUILD 3.3.5.12340\nOther<u32>[2]\nRadius\n",
+            "Spell.dbd",
+        );
+        let reordered = load(
+            "COLUMNS\nint Other\nfloat Radius\n\nBUILD 3.3.5.12340\nRadius\nOther<32>[2]\n",
+            "Spell.dbd",
+        );
+
+        assert_eq!(map.structure_fingerprint(), spell.structure_fingerprint());
+        assert_ne!(
+            spell.structure_fingerprint(),
+            unsigned.structure_fingerprint()
+        );
+        assert_ne!(
+            spell.structure_fingerprint(),
+            reordered.structure_fingerprint()
+        );
+    }
+
+    #[test]
+    fn events() {
+        let events: Vec<_> = parse_events(
+            "COLUMNS\nint ID\n\nLAYOUT 0A0B0C0D\nBUILD 1.12.1.5875\nCOMMENT Vanilla\n$id$ID<32>\n\nBUILD 3.3.5.12340\nID<q>\n",
+        )
+        .collect();
+
+        assert_eq!(events.len(), 10);
+        assert_eq!(events[0], Ok((0, Event::Columns)));
+        assert!(matches!(events[1], Ok((1, Event::Column(_)))));
+        assert_eq!(events[2], Ok((3, Event::DefinitionStart)));
+        assert_eq!(
+            events[3],
+            Ok((3, Event::Layout(vec![Layout::new(0x0A0B0C0D)])))
+        );
+        assert_eq!(
+            events[4],
+            Ok((
+                4,
+                Event::Build {
+                    versions: vec![Version::new(1, 12, 1, 5875)],
+                    version_ranges: vec![],
+                }
+            ))
+        );
+        assert_eq!(events[5], Ok((5, Event::Comment("Vanilla".to_string()))));
+        assert!(matches!(events[6], Ok((6, Event::Entry(_)))));
+        assert_eq!(events[7], Ok((8, Event::DefinitionStart)));
+        assert!(matches!(events[8], Ok((8, Event::Build { .. }))));
+        assert_eq!(events[9].as_ref().unwrap_err().line, 9);
+    }
+
+    #[test]
+    fn normalized_type() {
+        let key = ForeignKey::new("Map".to_string(), "ID".to_string());
+        let ty = Type::ForeignKey {
+            ty: Box::new(Type::Array {
+                ty: Box::new(Type::Array {
+                    ty: Box::new(Type::UInt16),
+                    width: 2,
+                }),
+                width: 3,
+            }),
+            key: key.clone(),
+        };
+
+        assert_eq!(ty.scalar(), ScalarType::UInt16);
+        assert_eq!(ty.array_width(), Some(6));
+        assert_eq!(ty.foreign_key(), Some(&key));
+        assert_eq!(
+            ty.normalized(),
+            Type::Array {
+                ty: Box::new(Type::ForeignKey {
+                    ty: Box::new(Type::UInt16),
+                    key,
+                }),
+                width: 6,
+            }
+        );
+        assert_eq!(Type::Float.normalized(), Type::Float);
+    }
+
     #[test]
     fn find_version() {
         let f = load_file_from_string(MAP_CONTENTS, "Map.dbd").unwrap();
@@ -176,6 +709,182 @@ MapName_lang
         }
     }
 
+    #[test]
+    fn load_definitions_directory() {
+        let collection = load_directory(Path::new("./WoWDBDefs/definitions/"))
+            .unwrap()
+            .unwrap();
+        assert!(collection.get("Map").is_some());
+    }
+
+    #[test]
+    fn cancel_loading() {
+        let cancel = CancelToken::new();
+        cancel.cancel();
+
+        assert_eq!(
+            load_directory_with_cancel(Path::new("./WoWDBDefs/definitions/"), &cancel).unwrap(),
+            Err(LoadError::Cancelled)
+        );
+
+        let collection = DbdCollection::from_files(vec![load_file_from_string(
+            "COLUMNS\nint<Map::ID> ID\n\nBUILD 1.12.1.5875\nID<32>\n",
+            "Map.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap()]);
+        assert!(!collection.lints().is_empty());
+        assert!(collection.lints_with_cancel(&cancel).is_none());
+    }
+
+    #[test]
+    fn load_progress() {
+        let mut done = Vec::new();
+        let collection = load_directory_with_progress(
+            Path::new("./WoWDBDefs/definitions/"),
+            &CancelToken::new(),
+            |a| done.push((a.done, a.total)),
+        )
+        .unwrap()
+        .unwrap();
+
+        let total = collection.len();
+        assert_eq!(done, (1..=total).map(|a| (a, total)).collect::<Vec<_>>());
+
+        let mut tables = Vec::new();
+        collection
+            .lints_with_progress(&CancelToken::new(), |a| tables.push(a.name.to_string()))
+            .unwrap();
+        assert_eq!(tables, collection.files.keys().cloned().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn load_definitions_repository() {
+        let collection = load_repository(Path::new("./WoWDBDefs/")).unwrap().unwrap();
+        assert!(collection.get("Map").is_some());
+        assert!(collection.manifest.is_empty());
+    }
+
+    #[test]
+    fn conversion_with_errors() {
+        let raw = load_file_from_string(
+            "COLUMNS\nint ID\nfloat Scale\n\nBUILD 1.0.0.1\n$id$ID<32>\nMissing\nFlags<32>\nScale\nID\n",
+            "Map.dbd",
+        )
+        .unwrap();
+        assert_eq!(
+            raw.clone().into_proper(),
+            Err(ConversionError::ColumnNotFound {
+                name: "Missing".to_string(),
+                suggestion: None,
+            })
+        );
+
+        let (file, errors) = raw.into_proper_with_errors();
+        assert_eq!(file.definitions[0].entries.len(), 2);
+        assert_eq!(
+            errors,
+            vec![
+                EntryConversionError {
+                    entry: "Missing".to_string(),
+                    error: ConversionError::ColumnNotFound {
+                        name: "Missing".to_string(),
+                        suggestion: None,
+                    },
+                },
+                EntryConversionError {
+                    entry: "Flags".to_string(),
+                    error: ConversionError::ColumnNotFound {
+                        name: "Flags".to_string(),
+                        suggestion: None,
+                    },
+                },
+                EntryConversionError {
+                    entry: "ID".to_string(),
+                    error: ConversionError::NoIntegerWidth,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn error_suggestions() {
+        let err = load_file_from_string(
+            "COLUMNS\nint ID\nint Flags\n\nBUILD 1.0.0.1\n$id$ID<32>\nFLags<32>\n",
+            "Map.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ConversionError::ColumnNotFound {
+                name: "FLags".to_string(),
+                suggestion: Some("Flags".to_string()),
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "column not found 'FLags', did you mean 'Flags'?"
+        );
+
+        let err = load_file_from_string("COLUMNS\nitn ID\n", "Map.dbd").unwrap_err();
+        assert_eq!(
+            err.reason,
+            DbdErrorReason::InvalidType {
+                ty: "itn".to_string(),
+                suggestion: Some("int".to_string()),
+            }
+        );
+
+        let err = load_file_from_string("COLUMNS\nbool ID\n", "Map.dbd").unwrap_err();
+        assert_eq!(
+            err.reason,
+            DbdErrorReason::InvalidType {
+                ty: "bool".to_string(),
+                suggestion: None,
+            }
+        );
+    }
+
+    #[test]
+    fn load_definitions_directory_with_errors() {
+        let (collection, errors) =
+            load_directory_with_errors(Path::new("./WoWDBDefs/definitions/")).unwrap();
+        assert!(collection.get("Map").is_some());
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn semantic_equality() {
+        let a = load_file_from_string(
+            "COLUMNS\nint ID // Primary\nint Flags\n\nBUILD 1.0.0.1-1.0.0.5\nBUILD 1.1.0.1-1.1.0.5\n$id$ID<32> // Comment\nFlags<32>\n",
+            "Map.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+        let b = load_file_from_string(
+            "COLUMNS\nint ID\nint Flags?\n\nBUILD 1.1.0.1-1.1.0.5\nBUILD 1.0.0.1-1.0.0.5\n$id$ID<32>\nFlags<32>\n",
+            "Map.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+        let c = load_file_from_string(
+            "COLUMNS\nint ID\nint Flags\n\nBUILD 1.0.0.1-1.0.0.5\nBUILD 1.1.0.1-1.1.0.5\n$id$ID<32>\nFlags<u32>\n",
+            "Map.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+
+        assert_ne!(a.definitions[0], b.definitions[0]);
+        assert!(a.definitions[0].semantically_eq(&b.definitions[0]));
+        assert!(!a.definitions[0].semantically_eq(&c.definitions[0]));
+    }
+
     #[test]
     fn parse_one() {
         load_file_from_string(MAP_CONTENTS, "Contents.dbd").unwrap();
@@ -0,0 +1,134 @@
+use crate::error::{DbdErrorReason, Limit};
+use crate::parser::parse_file_with_limits;
+use crate::{ParseError, RawDbdFile};
+
+/// Limits for parsing untrusted `.dbd` files with [`load_file_from_string_with_limits`].
+///
+/// Files that exceed a limit are rejected with [`DbdErrorReason::LimitExceeded`]
+/// as soon as the limit is reached, before the rest of the file is parsed.
+/// `None` means no limit.
+/// The default has no limits.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub struct ParseLimits {
+    /// Maximum size of the file in bytes.
+    pub max_file_size: Option<usize>,
+    /// Maximum length of a single line in bytes.
+    pub max_line_length: Option<usize>,
+    /// Maximum amount of columns under `COLUMNS`.
+    pub max_columns: Option<usize>,
+    /// Maximum amount of definitions.
+    pub max_definitions: Option<usize>,
+    /// Maximum amount of entries in all definitions combined.
+    pub max_entries: Option<usize>,
+}
+
+impl ParseLimits {
+    /// Creates limits that allow everything.
+    pub const fn new() -> Self {
+        Self {
+            max_file_size: None,
+            max_line_length: None,
+            max_columns: None,
+            max_definitions: None,
+            max_entries: None,
+        }
+    }
+
+    /// Creates limits well above the largest files in the definitions repository.
+    pub const fn untrusted() -> Self {
+        Self {
+            max_file_size: Some(4 * 1024 * 1024),
+            max_line_length: Some(4 * 1024),
+            max_columns: Some(1024),
+            max_definitions: Some(1024),
+            max_entries: Some(64 * 1024),
+        }
+    }
+
+    pub(crate) const fn check(
+        limit: Option<usize>,
+        kind: Limit,
+        amount: usize,
+        line: usize,
+    ) -> Result<(), ParseError> {
+        match limit {
+            Some(max) if amount > max => Err(ParseError::new(
+                0,
+                line,
+                DbdErrorReason::LimitExceeded { limit: kind, max },
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Load DBD file from string while rejecting files that exceed `limits`.
+///
+/// Same as [`load_file_from_string`](crate::load_file_from_string) but for files from untrusted sources,
+/// for example files uploaded by users of a service.
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] in case parsing fails or a limit is exceeded.
+pub fn load_file_from_string_with_limits(
+    contents: &str,
+    name: impl Into<String>,
+    limits: &ParseLimits,
+) -> Result<RawDbdFile, ParseError> {
+    parse_file_with_limits(contents, name.into(), limits)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::{DbdErrorReason, Limit};
+    use crate::{load_file_from_string, load_file_from_string_with_limits, ParseLimits};
+
+    const MAP: &str = "COLUMNS\nint ID\nint Flags\n\nBUILD 1.12.1.5875\n$id$ID<32>\nFlags<32>\n\nBUILD 3.3.5.12340\n$id$ID<32>\n";
+
+    fn exceeded(limits: ParseLimits) -> (Limit, usize) {
+        let error = load_file_from_string_with_limits(MAP, "Map.dbd", &limits).unwrap_err();
+        match error.reason {
+            DbdErrorReason::LimitExceeded { limit, .. } => (limit, error.line),
+            reason => panic!("unexpected error {}", reason),
+        }
+    }
+
+    #[test]
+    fn limits() {
+        assert_eq!(
+            load_file_from_string_with_limits(MAP, "Map.dbd", &ParseLimits::untrusted()),
+            load_file_from_string(MAP, "Map.dbd")
+        );
+
+        let limits = |f: fn(&mut ParseLimits)| {
+            let mut limits = ParseLimits::new();
+            f(&mut limits);
+            exceeded(limits)
+        };
+        assert_eq!(limits(|a| a.max_file_size = Some(16)), (Limit::FileSize, 0));
+        assert_eq!(
+            limits(|a| a.max_line_length = Some(8)),
+            (Limit::LineLength, 2)
+        );
+        assert_eq!(limits(|a| a.max_columns = Some(1)), (Limit::Columns, 2));
+        assert_eq!(
+            limits(|a| a.max_definitions = Some(1)),
+            (Limit::Definitions, 8)
+        );
+        assert_eq!(limits(|a| a.max_entries = Some(2)), (Limit::Entries, 9));
+    }
+
+    #[test]
+    fn closing_bracket_before_opening() {
+        for entry in ["ID]x[", "ID<32>]x["] {
+            let contents = format!("COLUMNS\nint ID\n\nBUILD 1.12.1.5875\n{}\n", entry);
+            let error =
+                load_file_from_string_with_limits(&contents, "Map.dbd", &ParseLimits::untrusted())
+                    .unwrap_err();
+            assert_eq!(
+                error.reason,
+                DbdErrorReason::NoClosingArraySizeSquareBracket
+            );
+        }
+    }
+}
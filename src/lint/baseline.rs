@@ -0,0 +1,128 @@
+use crate::lint::{Lint, Severity};
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+
+/// Known lints that are not reported again, so strict lints can be introduced on existing definitions.
+///
+/// The file format has a single lint per line, as the [`LintRule`](crate::lint::LintRule) followed by the lint message.
+/// Empty lines and lines starting with `#` are ignored:
+///
+/// ```text
+/// # Existing findings
+/// mixed_signedness 'Map::Flags' is both signed and unsigned
+/// ```
+///
+/// Spans are not included, so moving a column does not make its lints new.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
+pub struct Baseline {
+    /// Lints in the baseline, in the file format.
+    pub lints: BTreeSet<String>,
+}
+
+impl Baseline {
+    /// Creates an empty baseline.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a baseline with every lint in `lints`.
+    pub fn from_lints<'a>(lints: impl IntoIterator<Item = &'a Lint>) -> Self {
+        Self {
+            lints: lints.into_iter().map(key).collect(),
+        }
+    }
+
+    /// Parses the file format described in [`Baseline`].
+    pub fn parse(contents: &str) -> Self {
+        Self {
+            lints: contents
+                .lines()
+                .map(str::trim)
+                .filter(|a| !a.is_empty() && !a.starts_with('#'))
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+
+    /// Reads and parses a baseline file.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the file can not be read.
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        Ok(Self::parse(&std::fs::read_to_string(path)?))
+    }
+
+    /// Writes the baseline to a file, sorted.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the file can not be written.
+    pub fn write(&self, path: &Path) -> std::io::Result<()> {
+        std::fs::write(path, self.to_string())
+    }
+
+    /// Returns true if `lint` is in the baseline.
+    pub fn contains(&self, lint: &Lint) -> bool {
+        self.lints.contains(&key(lint))
+    }
+
+    /// Removes the lints that are in the baseline.
+    pub fn new_lints(&self, lints: Vec<(Lint, Severity)>) -> Vec<(Lint, Severity)> {
+        lints
+            .into_iter()
+            .filter(|(lint, _)| !self.contains(lint))
+            .collect()
+    }
+}
+
+impl Display for Baseline {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for lint in &self.lints {
+            writeln!(f, "{}", lint)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn key(lint: &Lint) -> String {
+    format!("{} {}", lint.kind.rule(), lint)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::enums::ColumnReference;
+    use crate::lint::{Baseline, Lint, LintKind, Severity};
+
+    fn lint(column: &str) -> Lint {
+        Lint {
+            column: ColumnReference::new("Map", column),
+            span: None,
+            fix: None,
+            kind: LintKind::SelfReference,
+        }
+    }
+
+    #[test]
+    fn baseline() {
+        let baseline = Baseline::from_lints(&[lint("ParentID")]);
+        assert_eq!(
+            baseline.to_string(),
+            "self_reference 'Map::ParentID' is a foreign key to itself\n"
+        );
+        assert_eq!(
+            Baseline::parse(&format!("# Comment\n\n{}", baseline)),
+            baseline
+        );
+
+        assert_eq!(
+            baseline.new_lints(vec![
+                (lint("ParentID"), Severity::Error),
+                (lint("OtherID"), Severity::Warning),
+            ]),
+            vec![(lint("OtherID"), Severity::Warning)]
+        );
+    }
+}
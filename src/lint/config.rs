@@ -0,0 +1,467 @@
+use crate::lint::{Lint, LintKind};
+use crate::DbdCollection;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+/// Rule that produces a [`LintKind`], used to configure lints with a [`LintConfig`].
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum LintRule {
+    /// [`LintKind::MixedSignedness`].
+    MixedSignedness,
+    /// [`LintKind::ShrinkingIntegerWidth`].
+    ShrinkingIntegerWidth,
+    /// [`LintKind::UnknownStorage`].
+    UnknownStorage,
+    /// [`LintKind::MultipleRelations`].
+    MultipleRelations,
+    /// [`LintKind::SelfReference`].
+    SelfReference,
+    /// [`LintKind::ForeignKeyTypeMismatch`].
+    ForeignKeyTypeMismatch,
+    /// [`LintKind::LayoutFormat`].
+    LayoutFormat,
+    /// [`LintKind::UnsortedBuilds`].
+    UnsortedBuilds,
+    /// [`LintKind::MissingIntegerWidth`].
+    MissingIntegerWidth,
+    /// [`LintKind::Custom`] from the [`Rule`](crate::lint::Rule) with this name.
+    Custom(String),
+}
+
+impl LintRule {
+    /// Every built in rule.
+    pub const ALL: [LintRule; 9] = [
+        Self::MixedSignedness,
+        Self::ShrinkingIntegerWidth,
+        Self::UnknownStorage,
+        Self::MultipleRelations,
+        Self::SelfReference,
+        Self::ForeignKeyTypeMismatch,
+        Self::LayoutFormat,
+        Self::UnsortedBuilds,
+        Self::MissingIntegerWidth,
+    ];
+
+    /// Name of the rule in `snake_case`, for example `mixed_signedness`.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::MixedSignedness => "mixed_signedness",
+            Self::ShrinkingIntegerWidth => "shrinking_integer_width",
+            Self::UnknownStorage => "unknown_storage",
+            Self::MultipleRelations => "multiple_relations",
+            Self::SelfReference => "self_reference",
+            Self::ForeignKeyTypeMismatch => "foreign_key_type_mismatch",
+            Self::LayoutFormat => "layout_format",
+            Self::UnsortedBuilds => "unsorted_builds",
+            Self::MissingIntegerWidth => "missing_integer_width",
+            Self::Custom(name) => name,
+        }
+    }
+
+    /// Finds the built in rule with [`LintRule::name`].
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().find(|a| a.name() == name).cloned()
+    }
+
+    /// Finds the built in rule with [`LintRule::name`],
+    /// or a [`LintRule::Custom`] if `name` is one of the [`Rule::name`](crate::lint::Rule::name)s in `custom_rules`.
+    pub fn from_name_or_custom(name: &str, custom_rules: &[&str]) -> Option<Self> {
+        if let Some(rule) = Self::from_name(name) {
+            return Some(rule);
+        }
+
+        if custom_rules.contains(&name) {
+            Some(Self::Custom(name.to_string()))
+        } else {
+            None
+        }
+    }
+}
+
+impl Display for LintRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl LintKind {
+    /// Rule that produces the lint.
+    pub fn rule(&self) -> LintRule {
+        match self {
+            LintKind::MixedSignedness { .. } => LintRule::MixedSignedness,
+            LintKind::ShrinkingIntegerWidth { .. } => LintRule::ShrinkingIntegerWidth,
+            LintKind::UnknownStorage { .. } => LintRule::UnknownStorage,
+            LintKind::MultipleRelations { .. } => LintRule::MultipleRelations,
+            LintKind::SelfReference => LintRule::SelfReference,
+            LintKind::ForeignKeyTypeMismatch { .. } => LintRule::ForeignKeyTypeMismatch,
+            LintKind::LayoutFormat => LintRule::LayoutFormat,
+            LintKind::UnsortedBuilds => LintRule::UnsortedBuilds,
+            LintKind::MissingIntegerWidth => LintRule::MissingIntegerWidth,
+            LintKind::Custom { rule, .. } => LintRule::Custom(rule.clone()),
+        }
+    }
+}
+
+/// How a lint is reported.
+#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum Severity {
+    /// The lint is not reported.
+    Allow,
+    /// The lint is reported but should not fail a build.
+    Warning,
+    /// The lint is reported and should fail a build.
+    Error,
+}
+
+impl Severity {
+    /// Finds the severity for `allow`, `warning`, `warn` or `error`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "allow" => Self::Allow,
+            "warning" | "warn" => Self::Warning,
+            "error" => Self::Error,
+            _ => return None,
+        })
+    }
+}
+
+/// Suppression of lints for a table or column, from [`LintConfig::suppressions`].
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct Suppression {
+    /// Name of the table, without `.dbd`.
+    pub table: String,
+    /// Name of the column, or [`None`] for every column in the table.
+    pub column: Option<String>,
+    /// Rule to suppress, or [`None`] for every rule.
+    pub rule: Option<LintRule>,
+}
+
+impl Suppression {
+    /// Returns true if `lint` is suppressed.
+    pub fn suppresses(&self, lint: &Lint) -> bool {
+        self.table == lint.column.table
+            && self
+                .column
+                .as_ref()
+                .map_or(true, |a| a == &lint.column.column)
+            && self.rule.as_ref().map_or(true, |a| a == &lint.kind.rule())
+    }
+}
+
+/// Configuration of which lints are reported and how.
+///
+/// Allows adopting the lints incrementally by disabling rules or suppressing known findings.
+/// Rules without a configured severity are [`Severity::Warning`].
+///
+/// With the `toml` feature the configuration can be loaded with `LintConfig::from_toml`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
+pub struct LintConfig {
+    /// Severity of rules.
+    pub severities: BTreeMap<LintRule, Severity>,
+    /// Lints that are never reported, regardless of severity.
+    pub suppressions: Vec<Suppression>,
+}
+
+impl LintConfig {
+    /// Creates a configuration that reports every lint as [`Severity::Warning`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Severity of `lint`, [`Severity::Allow`] if it is suppressed.
+    pub fn severity(&self, lint: &Lint) -> Severity {
+        if self.suppressions.iter().any(|a| a.suppresses(lint)) {
+            return Severity::Allow;
+        }
+
+        self.severities
+            .get(&lint.kind.rule())
+            .copied()
+            .unwrap_or(Severity::Warning)
+    }
+
+    /// Pairs every lint with its [`LintConfig::severity`], leaving out lints that are [`Severity::Allow`].
+    pub fn apply(&self, lints: Vec<Lint>) -> Vec<(Lint, Severity)> {
+        lints
+            .into_iter()
+            .map(|lint| {
+                let severity = self.severity(&lint);
+                (lint, severity)
+            })
+            .filter(|(_, severity)| *severity != Severity::Allow)
+            .collect()
+    }
+}
+
+#[cfg(feature = "toml")]
+impl LintConfig {
+    /// Parses a configuration in TOML.
+    ///
+    /// ```toml
+    /// [rules]
+    /// unknown_storage = "allow"
+    /// foreign_key_type_mismatch = "error"
+    ///
+    /// [suppress]
+    /// "Map::Flags" = ["mixed_signedness"]
+    /// "Spell" = ["all"]
+    /// ```
+    ///
+    /// `[rules]` sets the severity of rules, which is either `allow`, `warning` or `error`.
+    /// `[suppress]` suppresses rules for a `Table::Column` or every column of a `Table`, with `all` for every rule.
+    /// Only built in rules can be configured, use [`LintConfig::from_toml_with_custom_rules`] for custom rules.
+    ///
+    /// Only the subset of TOML needed for this is supported: sections, bare and quoted keys,
+    /// strings and single line arrays of strings.
+    ///
+    /// # Errors
+    ///
+    /// Errors if a line is not valid, or if a rule or severity does not exist.
+    pub fn from_toml(contents: &str) -> Result<Self, crate::error::LintConfigError> {
+        Self::from_toml_with_custom_rules(contents, &[])
+    }
+
+    /// Same as [`LintConfig::from_toml`], but rules can also be one of the
+    /// [`Rule::name`](crate::lint::Rule::name)s in `custom_rules`.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`LintConfig::from_toml`].
+    pub fn from_toml_with_custom_rules(
+        contents: &str,
+        custom_rules: &[&str],
+    ) -> Result<Self, crate::error::LintConfigError> {
+        use crate::error::{closest_match, LintConfigError};
+
+        let mut s = Self::new();
+        let mut section = "";
+
+        for (line_count, line) in contents.lines().enumerate() {
+            let err = || LintConfigError::InvalidLine {
+                line: line_count + 1,
+                contents: line.to_string(),
+            };
+            let rule = |name: &str| {
+                LintRule::from_name_or_custom(name, custom_rules).ok_or_else(|| {
+                    LintConfigError::UnknownRule {
+                        line: line_count + 1,
+                        rule: name.to_string(),
+                        suggestion: closest_match(
+                            name,
+                            LintRule::ALL
+                                .iter()
+                                .map(LintRule::name)
+                                .chain(custom_rules.iter().copied()),
+                        ),
+                    }
+                })
+            };
+
+            let line = toml_without_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|a| a.strip_suffix(']')) {
+                section = match name.trim() {
+                    "rules" => "rules",
+                    "suppress" => "suppress",
+                    _ => return Err(err()),
+                };
+                continue;
+            }
+
+            let (key, value) = line.split_once('=').ok_or_else(err)?;
+            let key = toml_key(key.trim()).ok_or_else(err)?;
+            let value = value.trim();
+
+            match section {
+                "rules" => {
+                    let rule = rule(&key)?;
+                    let severity = toml_string(value)
+                        .and_then(|a| Severity::from_name(&a))
+                        .ok_or_else(err)?;
+
+                    s.severities.insert(rule, severity);
+                }
+                "suppress" => {
+                    let (table, column) = match key.split_once("::") {
+                        Some((table, column)) => (table.to_string(), Some(column.to_string())),
+                        None => (key.clone(), None),
+                    };
+
+                    for name in toml_array(value).ok_or_else(err)? {
+                        let rule = match name.as_str() {
+                            "all" => None,
+                            name => Some(rule(name)?),
+                        };
+
+                        s.suppressions.push(Suppression {
+                            table: table.clone(),
+                            column: column.clone(),
+                            rule,
+                        });
+                    }
+                }
+                _ => return Err(err()),
+            }
+        }
+
+        Ok(s)
+    }
+}
+
+#[cfg(feature = "toml")]
+pub(crate) fn toml_without_comment(line: &str) -> &str {
+    let mut in_string = false;
+
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+
+    line
+}
+
+#[cfg(feature = "toml")]
+pub(crate) fn toml_key(key: &str) -> Option<String> {
+    if key.starts_with('"') {
+        return toml_string(key);
+    }
+
+    let is_bare = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+
+    if is_bare {
+        Some(key.to_string())
+    } else {
+        None
+    }
+}
+
+#[cfg(feature = "toml")]
+pub(crate) fn toml_string(value: &str) -> Option<String> {
+    let inner = value.strip_prefix('"')?.strip_suffix('"')?;
+
+    if inner.contains(['"', '\\']) {
+        return None;
+    }
+
+    Some(inner.to_string())
+}
+
+#[cfg(feature = "toml")]
+pub(crate) fn toml_array(value: &str) -> Option<Vec<String>> {
+    let inner = value.strip_prefix('[')?.strip_suffix(']')?.trim();
+    let inner = inner.strip_suffix(',').unwrap_or(inner);
+
+    if inner.trim().is_empty() {
+        return Some(Vec::new());
+    }
+
+    inner.split(',').map(|a| toml_string(a.trim())).collect()
+}
+
+impl DbdCollection {
+    /// Runs [`DbdCollection::lints`] and applies `config` with [`LintConfig::apply`].
+    pub fn lints_with_config(&self, config: &LintConfig) -> Vec<(Lint, Severity)> {
+        config.apply(self.lints())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::enums::ColumnReference;
+    use crate::lint::{Lint, LintConfig, LintKind, LintRule, Severity, Suppression};
+
+    fn lint(table: &str, column: &str) -> Lint {
+        Lint {
+            column: ColumnReference::new(table, column),
+            span: None,
+            fix: None,
+            kind: LintKind::SelfReference,
+        }
+    }
+
+    #[test]
+    fn config() {
+        let mut config = LintConfig::new();
+        assert_eq!(config.severity(&lint("Map", "ID")), Severity::Warning);
+
+        config
+            .severities
+            .insert(LintRule::SelfReference, Severity::Error);
+        config.suppressions.push(Suppression {
+            table: "Map".to_string(),
+            column: Some("ParentID".to_string()),
+            rule: Some(LintRule::SelfReference),
+        });
+        config.suppressions.push(Suppression {
+            table: "Spell".to_string(),
+            column: None,
+            rule: None,
+        });
+
+        assert_eq!(
+            config.apply(vec![
+                lint("Map", "ID"),
+                lint("Map", "ParentID"),
+                lint("Spell", "ID"),
+            ]),
+            vec![(lint("Map", "ID"), Severity::Error)]
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn config_from_toml() {
+        use crate::error::LintConfigError;
+
+        let config = LintConfig::from_toml(
+            "# Lints\n[rules]\nunknown_storage = \"allow\"\nself_reference = \"error\" # Always wrong\n\n[suppress]\n\"Map::ParentID\" = [\"self_reference\"]\nSpell = [\"all\"]\n",
+        )
+        .unwrap();
+
+        assert_eq!(config.severities.len(), 2);
+        assert_eq!(
+            config.severities[&LintRule::UnknownStorage],
+            Severity::Allow
+        );
+        assert_eq!(config.severity(&lint("Map", "ID")), Severity::Error);
+        assert_eq!(config.severity(&lint("Map", "ParentID")), Severity::Allow);
+        assert_eq!(config.severity(&lint("Spell", "ID")), Severity::Allow);
+
+        assert!(matches!(
+            LintConfig::from_toml("[rules]\nmixed_signedness = \"fatal\"\n"),
+            Err(LintConfigError::InvalidLine { line: 2, .. })
+        ));
+        assert!(LintConfig::from_toml("[unknown]\n").is_err());
+        assert!(LintConfig::from_toml("[suppress]\nMap = [\"Not a rule\"]\n").is_err());
+
+        assert_eq!(
+            LintConfig::from_toml("[rules]\nunknown_storag = \"allow\"\n"),
+            Err(LintConfigError::UnknownRule {
+                line: 2,
+                rule: "unknown_storag".to_string(),
+                suggestion: Some("unknown_storage".to_string()),
+            })
+        );
+        assert!(LintConfig::from_toml("[suppress]\nMap = [\"self_referenc\"]\n").is_err());
+        assert!(LintConfig::from_toml("[rules]\nrequire_comments = \"error\"\n").is_err());
+
+        let config = LintConfig::from_toml_with_custom_rules(
+            "[rules]\nrequire_comments = \"error\"\n",
+            &["require_comments"],
+        )
+        .unwrap();
+        assert_eq!(
+            config.severities[&LintRule::Custom("require_comments".to_string())],
+            Severity::Error
+        );
+    }
+}
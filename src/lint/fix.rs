@@ -0,0 +1,179 @@
+use crate::enums::ColumnReference;
+use crate::error::ParseError;
+use crate::lint::{Lint, LintKind, Span};
+use crate::types::table_name;
+use crate::{parse_events, Event, Layout, RawType, Version};
+use std::collections::BTreeMap;
+
+/// Machine applicable change to the contents of a `.dbd` file that fixes a [`Lint`].
+///
+/// Apply with [`apply_fixes`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Fix {
+    /// Text to replace, from [`Span::start`] to [`Span::end`].
+    ///
+    /// The span is empty if the fix only inserts text.
+    pub span: Span,
+    /// Text that replaces the span.
+    pub replacement: String,
+}
+
+/// Replaces the span of every fix in `source` with its replacement.
+///
+/// Fixes are applied in order of their start, and fixes that overlap an earlier fix are skipped
+/// so the result is always valid.
+/// Run the lints again to find the skipped fixes.
+pub fn apply_fixes(source: &str, fixes: &[Fix]) -> String {
+    let mut fixes: Vec<&Fix> = fixes.iter().collect();
+    fixes.sort_by_key(|a| (a.span.start, a.span.end));
+
+    let mut s = String::with_capacity(source.len());
+    let mut offset = 0;
+
+    for fix in fixes {
+        if fix.span.start < offset || fix.span.end > source.len() {
+            continue;
+        }
+
+        s.push_str(&source[offset..fix.span.start]);
+        s.push_str(&fix.replacement);
+        offset = fix.span.end;
+    }
+
+    s.push_str(&source[offset..]);
+    s
+}
+
+/// Finds lints in the text of a `.dbd` file that can not be found after parsing, all with a [`Lint::fix`]:
+///
+/// * [`LintKind::LayoutFormat`] for layouts not written as 8 uppercase hexadecimal digits.
+/// * [`LintKind::UnsortedBuilds`] for lists of builds that are not in ascending order.
+/// * [`LintKind::MissingIntegerWidth`] for integer entries without an integer width, fixed by using 32 bits.
+///
+/// `name` is the file name with or without `.dbd`.
+/// Lints for lines instead of columns have an empty [`ColumnReference::column`].
+///
+/// # Errors
+///
+/// Errors if `contents` can not be parsed.
+pub fn source_lints(contents: &str, name: &str) -> Result<Vec<Lint>, ParseError> {
+    let table = table_name(name);
+    let spans = super::rule::line_spans(contents);
+
+    let mut integer_columns = BTreeMap::new();
+    let mut lints = Vec::new();
+
+    for event in parse_events(contents) {
+        let (line, event) = event?;
+        let span = spans[line];
+        let text = contents[span.start..span.end].trim_end();
+
+        let lint = |column: &str, kind: LintKind, replacement: String, span: Span| Lint {
+            column: ColumnReference::new(table, column),
+            span: Some(span),
+            fix: Some(Fix { span, replacement }),
+            kind,
+        };
+
+        match event {
+            Event::Column(column) => {
+                integer_columns.insert(column.name, column.ty == RawType::Int);
+            }
+            Event::Layout(layouts) => {
+                let replacement = layout_line(&layouts);
+                if replacement != text {
+                    lints.push(lint("", LintKind::LayoutFormat, replacement, span));
+                }
+            }
+            Event::Build { mut versions, .. } => {
+                if !versions.windows(2).all(|a| a[0] <= a[1]) {
+                    versions.sort();
+                    lints.push(lint(
+                        "",
+                        LintKind::UnsortedBuilds,
+                        build_line(&versions),
+                        span,
+                    ));
+                }
+            }
+            Event::Entry(entry) => {
+                let is_integer = integer_columns.get(&entry.name).copied().unwrap_or(false);
+                if !is_integer || entry.integer_width.is_some() {
+                    continue;
+                }
+
+                let name_start = match text.strip_prefix('$') {
+                    Some(tags) => tags.find('$').map_or(0, |i| i + 2),
+                    None => 0,
+                };
+                let name_end = text[name_start..]
+                    .find(|c: char| c == '[' || c == '/' || c.is_whitespace())
+                    .map_or(text.len(), |i| name_start + i);
+
+                let start = span.start + name_end;
+                let insertion = Span {
+                    line: span.line,
+                    column: span.column + name_end,
+                    start,
+                    end: start,
+                };
+
+                lints.push(lint(
+                    &entry.name,
+                    LintKind::MissingIntegerWidth,
+                    "<32>".to_string(),
+                    insertion,
+                ));
+            }
+            Event::Columns | Event::DefinitionStart | Event::Comment(_) => {}
+        }
+    }
+
+    Ok(lints)
+}
+
+fn layout_line(layouts: &[Layout]) -> String {
+    let layouts: Vec<String> = layouts.iter().map(|a| format!("{:08X}", a.inner)).collect();
+    format!("LAYOUT {}", layouts.join(", "))
+}
+
+fn build_line(versions: &[Version]) -> String {
+    let versions: Vec<String> = versions.iter().map(|a| a.to_string()).collect();
+    format!("BUILD {}", versions.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lint::{apply_fixes, source_lints, LintKind};
+
+    #[test]
+    fn fixes() {
+        const CONTENTS: &str = "COLUMNS\nint ID\nint Flags\nfloat Scale\n\nLAYOUT 43e6e8a6, 1234\nBUILD 3.3.5.12340, 1.12.1.5875\n$id$ID<32>\nFlags[2] // Comment\nScale\n";
+
+        let lints = source_lints(CONTENTS, "Map.dbd").unwrap();
+        assert_eq!(
+            lints.iter().map(|a| a.kind.clone()).collect::<Vec<_>>(),
+            vec![
+                LintKind::LayoutFormat,
+                LintKind::UnsortedBuilds,
+                LintKind::MissingIntegerWidth,
+            ]
+        );
+        assert_eq!(lints[2].column.column, "Flags");
+        assert_eq!(lints[2].to_string(), "'Map::Flags' has no integer width");
+
+        let fixes: Vec<_> = lints.into_iter().filter_map(|a| a.fix).collect();
+        let fixed = apply_fixes(CONTENTS, &fixes);
+        assert_eq!(
+            fixed,
+            "COLUMNS\nint ID\nint Flags\nfloat Scale\n\nLAYOUT 43E6E8A6, 00001234\nBUILD 1.12.1.5875, 3.3.5.12340\n$id$ID<32>\nFlags<32>[2] // Comment\nScale\n"
+        );
+        assert!(source_lints(&fixed, "Map").unwrap().is_empty());
+
+        // Overlapping fixes are skipped.
+        assert_eq!(
+            apply_fixes(CONTENTS, &[fixes[0].clone(), fixes[0].clone()]),
+            apply_fixes(CONTENTS, &fixes[..1])
+        );
+    }
+}
@@ -0,0 +1,565 @@
+//! Lints for likely documentation mistakes in definitions.
+//!
+//! These are not errors in the `.dbd` format, but often mean that a column is documented
+//! in a way that silently corrupts values when decoding.
+//!
+//! Use a [`LintConfig`] to change the severity of rules or suppress known findings,
+//! and a [`Linter`] to run custom [`Rule`]s together with the built in lints.
+//! A [`Baseline`] records existing lints so only new ones are reported.
+//! Some lints can be fixed automatically with [`apply_fixes`].
+//! Results can be written with [`lints_to_sarif`] for code scanning tools or [`lints_to_json`].
+
+mod baseline;
+mod config;
+mod fix;
+mod output;
+mod rule;
+
+use crate::enums::ColumnReference;
+use crate::{
+    CancelToken, DbdCollection, DbdFile, EntryStorage, Progress, ScalarType, Version, VersionSet,
+};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+
+pub use baseline::Baseline;
+#[cfg(feature = "toml")]
+pub(crate) use config::{toml_array, toml_key, toml_string, toml_without_comment};
+pub use config::{LintConfig, LintRule, Severity, Suppression};
+pub use fix::{apply_fixes, source_lints, Fix};
+pub use output::{lints_to_json, lints_to_sarif};
+pub use rule::{Linter, Rule, RuleContext, Span, Spans};
+
+/// Likely documentation mistake in a single column or line.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Lint {
+    /// Column the lint is for.
+    pub column: ColumnReference,
+    /// Location in the `.dbd` file, if known.
+    ///
+    /// Set by [`Linter::run`] for files with [`Spans`].
+    pub span: Option<Span>,
+    /// Change to the `.dbd` file that fixes the lint, if it can be fixed automatically.
+    pub fix: Option<Fix>,
+    /// What is wrong with the column.
+    pub kind: LintKind,
+}
+
+/// Kind of [`Lint`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum LintKind {
+    /// The column is a signed integer in some definitions and an unsigned integer in others.
+    MixedSignedness {
+        /// Versions of the definitions where the column is signed.
+        signed: VersionSet,
+        /// Versions of the definitions where the column is unsigned.
+        unsigned: VersionSet,
+    },
+    /// The integer width of the column is smaller than in an earlier definition.
+    ShrinkingIntegerWidth {
+        /// Width in the earlier definition.
+        from: u8,
+        /// Width in the later definition.
+        to: u8,
+        /// First version of the later definition.
+        version: Version,
+    },
+    /// The column is `$noninline$` without being `$id$` or `$relation$`,
+    /// so it is not known where the value is stored.
+    ///
+    /// See [`EntryStorage::Unknown`].
+    UnknownStorage {
+        /// Versions of the definitions where the column is affected.
+        versions: VersionSet,
+    },
+    /// The column is `$relation$` in a definition that already has an earlier `$relation$` column.
+    ///
+    /// A record can only have a single parent, see [`Definition::parent_relation`](crate::Definition::parent_relation).
+    MultipleRelations {
+        /// Versions of the definitions where the column is affected.
+        versions: VersionSet,
+    },
+    /// The column is a foreign key to itself.
+    SelfReference,
+    /// The column is a foreign key with an integer type that can not hold every value
+    /// of the column it points to, either because it is smaller or has different signedness.
+    ///
+    /// Only versions where both tables have a definition are checked.
+    ForeignKeyTypeMismatch {
+        /// Column the foreign key points to.
+        target: ColumnReference,
+        /// Type of the foreign key column.
+        ty: ScalarType,
+        /// Type of the column the foreign key points to.
+        target_ty: ScalarType,
+        /// Versions where both columns have these types.
+        versions: VersionSet,
+    },
+    /// A `LAYOUT` line has layouts that are not written as 8 uppercase hexadecimal digits
+    /// separated by `, `.
+    ///
+    /// Found by [`source_lints`].
+    LayoutFormat,
+    /// A `BUILD` line has a list of builds that are not in ascending order.
+    ///
+    /// Found by [`source_lints`].
+    UnsortedBuilds,
+    /// The column is an integer, but the entry has no integer width.
+    ///
+    /// This is a parsing error in [`RawDbdFile::into_proper`](crate::RawDbdFile::into_proper),
+    /// and is fixed by using 32 bits.
+    /// Found by [`source_lints`].
+    MissingIntegerWidth,
+    /// Lint from a custom [`Rule`].
+    Custom {
+        /// [`Rule::name`] of the rule.
+        rule: String,
+        /// Description of what is wrong.
+        message: String,
+    },
+}
+
+impl Display for Lint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let column = if self.column.column.is_empty() {
+            self.column.table.clone()
+        } else {
+            format!("{}::{}", self.column.table, self.column.column)
+        };
+
+        match &self.kind {
+            LintKind::MixedSignedness { .. } => {
+                write!(f, "'{}' is both signed and unsigned", column)
+            }
+            LintKind::ShrinkingIntegerWidth { from, to, version } => {
+                write!(
+                    f,
+                    "'{}' shrinks from {} to {} bits in '{}'",
+                    column, from, to, version
+                )
+            }
+            LintKind::UnknownStorage { .. } => {
+                write!(
+                    f,
+                    "'{}' is noninline without being an id or relation",
+                    column
+                )
+            }
+            LintKind::MultipleRelations { .. } => {
+                write!(
+                    f,
+                    "'{}' is a relation in addition to another column",
+                    column
+                )
+            }
+            LintKind::SelfReference => write!(f, "'{}' is a foreign key to itself", column),
+            LintKind::ForeignKeyTypeMismatch {
+                target,
+                ty,
+                target_ty,
+                ..
+            } => {
+                write!(
+                    f,
+                    "'{}' is '{:?}' but points to '{}::{}' which is '{:?}'",
+                    column, ty, target.table, target.column, target_ty
+                )
+            }
+            LintKind::LayoutFormat => {
+                write!(f, "'{}' has a layout that is not uppercase hex", column)
+            }
+            LintKind::UnsortedBuilds => {
+                write!(f, "'{}' has builds that are not in ascending order", column)
+            }
+            LintKind::MissingIntegerWidth => write!(f, "'{}' has no integer width", column),
+            LintKind::Custom { message, .. } => write!(f, "'{}': {}", column, message),
+        }
+    }
+}
+
+impl DbdFile {
+    /// Finds integer columns that are signed in some definitions and unsigned in others,
+    /// or that have a smaller width than in an earlier definition.
+    ///
+    /// Also finds columns with [`EntryStorage::Unknown`]
+    /// and relations in definitions that already have a relation.
+    ///
+    /// Definitions are ordered by their first version.
+    /// Lints are sorted by column name.
+    pub fn lints(&self) -> Vec<Lint> {
+        #[derive(Default)]
+        struct Column {
+            signed: VersionSet,
+            unsigned: VersionSet,
+            widths: Vec<(Version, u8)>,
+            unknown_storage: VersionSet,
+            multiple_relations: VersionSet,
+        }
+
+        let mut columns: BTreeMap<&str, Column> = BTreeMap::new();
+
+        for definition in &self.definitions {
+            let mut has_relation = false;
+
+            for entry in &definition.entries {
+                let column = columns.entry(entry.name.as_str()).or_default();
+
+                if entry.storage() == EntryStorage::Unknown {
+                    column.unknown_storage = column.unknown_storage.union(&definition.versions);
+                }
+
+                if entry.relation {
+                    if has_relation {
+                        column.multiple_relations =
+                            column.multiple_relations.union(&definition.versions);
+                    }
+                    has_relation = true;
+                }
+
+                let ty = entry.ty.scalar();
+                let width = match ty.integer_width() {
+                    Some(width) => width,
+                    None => continue,
+                };
+
+                let versions = if ty.is_unsigned() {
+                    &mut column.unsigned
+                } else {
+                    &mut column.signed
+                };
+                *versions = versions.union(&definition.versions);
+
+                if let Some(first) = definition.versions.first() {
+                    column.widths.push((first, width));
+                }
+            }
+        }
+
+        let mut lints = Vec::new();
+
+        for (name, mut column) in columns {
+            let reference = || ColumnReference::new(self.table_name(), name);
+
+            if !column.signed.is_empty() && !column.unsigned.is_empty() {
+                lints.push(Lint {
+                    column: reference(),
+                    span: None,
+                    fix: None,
+                    kind: LintKind::MixedSignedness {
+                        signed: column.signed,
+                        unsigned: column.unsigned,
+                    },
+                });
+            }
+
+            column.widths.sort();
+            for pair in column.widths.windows(2) {
+                let ((_, from), (version, to)) = (pair[0], pair[1]);
+
+                if to < from {
+                    lints.push(Lint {
+                        column: reference(),
+                        span: None,
+                        fix: None,
+                        kind: LintKind::ShrinkingIntegerWidth { from, to, version },
+                    });
+                }
+            }
+
+            if !column.unknown_storage.is_empty() {
+                lints.push(Lint {
+                    column: reference(),
+                    span: None,
+                    fix: None,
+                    kind: LintKind::UnknownStorage {
+                        versions: column.unknown_storage,
+                    },
+                });
+            }
+
+            if !column.multiple_relations.is_empty() {
+                lints.push(Lint {
+                    column: reference(),
+                    span: None,
+                    fix: None,
+                    kind: LintKind::MultipleRelations {
+                        versions: column.multiple_relations,
+                    },
+                });
+            }
+        }
+
+        lints
+    }
+}
+
+impl DbdCollection {
+    /// Runs [`DbdFile::lints`] for every table,
+    /// and checks that foreign keys do not point to themselves
+    /// and have types that can hold every value of the column they point to.
+    ///
+    /// Lints are sorted by table name.
+    pub fn lints(&self) -> Vec<Lint> {
+        self.lints_with_cancel(&CancelToken::new())
+            .unwrap_or_default()
+    }
+
+    /// Same as [`DbdCollection::lints`] but returns [`None`] when `cancel` is cancelled.
+    ///
+    /// The token is checked before every table.
+    pub fn lints_with_cancel(&self, cancel: &CancelToken) -> Option<Vec<Lint>> {
+        self.lints_with_progress(cancel, |_| {})
+    }
+
+    /// Same as [`DbdCollection::lints_with_cancel`] but calls `progress` after every table.
+    pub fn lints_with_progress(
+        &self,
+        cancel: &CancelToken,
+        mut progress: impl FnMut(Progress<'_>),
+    ) -> Option<Vec<Lint>> {
+        let mut lints = Vec::new();
+
+        for (i, (table, file)) in self.files.iter().enumerate() {
+            if cancel.is_cancelled() {
+                return None;
+            }
+
+            lints.append(&mut file.lints());
+            lints.append(&mut self.foreign_key_lints(table, file));
+
+            progress(Progress {
+                done: i + 1,
+                total: self.files.len(),
+                name: table,
+            });
+        }
+
+        Some(lints)
+    }
+
+    fn foreign_key_lints(&self, table: &str, file: &DbdFile) -> Vec<Lint> {
+        let mut self_references = BTreeSet::new();
+        let mut mismatches: BTreeMap<
+            (ColumnReference, ColumnReference, ScalarType, ScalarType),
+            VersionSet,
+        > = BTreeMap::new();
+
+        for definition in &file.definitions {
+            for entry in &definition.entries {
+                let key = match entry.ty.foreign_key() {
+                    Some(key) => key,
+                    None => continue,
+                };
+
+                if key.database == table && key.column == entry.name {
+                    self_references.insert(entry.name.as_str());
+                    continue;
+                }
+
+                let ty = entry.ty.scalar();
+                let target_file = match self.get(&key.database) {
+                    Some(f) => f,
+                    None => continue,
+                };
+
+                for target_definition in &target_file.definitions {
+                    let versions = definition
+                        .versions
+                        .intersection(&target_definition.versions);
+                    if versions.is_empty() {
+                        continue;
+                    }
+
+                    let target_ty = match target_definition
+                        .entries
+                        .iter()
+                        .find(|a| a.name == key.column)
+                    {
+                        Some(e) => e.ty.scalar(),
+                        None => continue,
+                    };
+
+                    if can_hold(ty, target_ty) {
+                        continue;
+                    }
+
+                    let mismatch = mismatches
+                        .entry((
+                            ColumnReference::new(table, entry.name.as_str()),
+                            ColumnReference::new(key.database.as_str(), key.column.as_str()),
+                            ty,
+                            target_ty,
+                        ))
+                        .or_default();
+                    *mismatch = mismatch.union(&versions);
+                }
+            }
+        }
+
+        let mut lints: Vec<Lint> = self_references
+            .into_iter()
+            .map(|column| Lint {
+                column: ColumnReference::new(table, column),
+                span: None,
+                fix: None,
+                kind: LintKind::SelfReference,
+            })
+            .collect();
+
+        for ((column, target, ty, target_ty), versions) in mismatches {
+            lints.push(Lint {
+                column,
+                span: None,
+                fix: None,
+                kind: LintKind::ForeignKeyTypeMismatch {
+                    target,
+                    ty,
+                    target_ty,
+                    versions,
+                },
+            });
+        }
+
+        lints
+    }
+}
+
+/// Returns true if every value of `target` can be stored in `ty`.
+///
+/// Non integer types are never flagged since foreign keys are always integers.
+const fn can_hold(ty: ScalarType, target: ScalarType) -> bool {
+    match (ty.integer_width(), target.integer_width()) {
+        (Some(width), Some(target_width)) => {
+            width > target_width
+                || (width == target_width && ty.is_unsigned() == target.is_unsigned())
+        }
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::enums::ColumnReference;
+    use crate::lint::{Lint, LintKind};
+    use crate::{load_file_from_string, DbdCollection, ScalarType, Version, VersionSet};
+
+    #[test]
+    fn lints() {
+        let file = load_file_from_string(
+            "COLUMNS\nint ID\nint Flags\nint Count\nfloat Scale\n\nBUILD 1.12.1.5875\n$id$ID<32>\nFlags<u32>\nCount<32>\nScale\n\nBUILD 3.3.5.12340\n$id$ID<32>\nFlags<32>\nCount<16>\nScale\n",
+            "Map.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+
+        let lints = DbdCollection::from_files(vec![file]).lints();
+        assert_eq!(
+            lints,
+            vec![
+                Lint {
+                    column: ColumnReference::new("Map", "Count"),
+                    span: None,
+                    fix: None,
+                    kind: LintKind::ShrinkingIntegerWidth {
+                        from: 32,
+                        to: 16,
+                        version: Version::new(3, 3, 5, 12340),
+                    },
+                },
+                Lint {
+                    column: ColumnReference::new("Map", "Flags"),
+                    span: None,
+                    fix: None,
+                    kind: LintKind::MixedSignedness {
+                        signed: VersionSet::from(Version::new(3, 3, 5, 12340)),
+                        unsigned: VersionSet::from(Version::new(1, 12, 1, 5875)),
+                    },
+                },
+            ]
+        );
+        assert_eq!(
+            lints[0].to_string(),
+            "'Map::Count' shrinks from 32 to 16 bits in '3.3.5.12340'"
+        );
+    }
+
+    #[test]
+    fn relation_lints() {
+        let file = load_file_from_string(
+            "COLUMNS\nint ID\nint ParentID\nint OtherID\nint Unk\n\nBUILD 8.0.1.26231\n$noninline,id$ID<32>\n$noninline,relation$ParentID<32>\n$relation$OtherID<32>\n$noninline$Unk<32>\n",
+            "Map.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+
+        let versions = VersionSet::from(Version::new(8, 0, 1, 26231));
+        assert_eq!(
+            file.lints(),
+            vec![
+                Lint {
+                    column: ColumnReference::new("Map", "OtherID"),
+                    span: None,
+                    fix: None,
+                    kind: LintKind::MultipleRelations {
+                        versions: versions.clone(),
+                    },
+                },
+                Lint {
+                    column: ColumnReference::new("Map", "Unk"),
+                    span: None,
+                    fix: None,
+                    kind: LintKind::UnknownStorage { versions },
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn foreign_key_lints() {
+        let map = load_file_from_string(
+            "COLUMNS\nint ID\nint<Map::ID> ParentMapID\nint<Map::Loop> Loop\n\nBUILD 1.12.1.5875\nBUILD 3.3.5.12340\n$id$ID<u32>\nParentMapID<u32>\nLoop<32>\n",
+            "Map.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+        let area = load_file_from_string(
+            "COLUMNS\nint ID\nint<Map::ID> MapID\nint<Map::ID> ContinentID\n\nBUILD 1.12.1.5875\n$id$ID<32>\nMapID<u16>\nContinentID<u64>\n\nBUILD 3.3.5.12340\n$id$ID<32>\nMapID<u16>\nContinentID<u64>\n",
+            "AreaTable.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+
+        let lints = DbdCollection::from_files(vec![map, area]).lints();
+        assert_eq!(
+            lints[0].to_string(),
+            "'AreaTable::MapID' is 'UInt16' but points to 'Map::ID' which is 'UInt32'"
+        );
+        assert_eq!(
+            lints,
+            vec![
+                Lint {
+                    column: ColumnReference::new("AreaTable", "MapID"),
+                    span: None,
+                    fix: None,
+                    kind: LintKind::ForeignKeyTypeMismatch {
+                        target: ColumnReference::new("Map", "ID"),
+                        ty: ScalarType::UInt16,
+                        target_ty: ScalarType::UInt32,
+                        versions: VersionSet::from(Version::new(1, 12, 1, 5875))
+                            .union(&VersionSet::from(Version::new(3, 3, 5, 12340))),
+                    },
+                },
+                Lint {
+                    column: ColumnReference::new("Map", "Loop"),
+                    span: None,
+                    fix: None,
+                    kind: LintKind::SelfReference,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use crate::export::quoted;
+use crate::lint::{Lint, Severity};
+use crate::writer::Writer;
+use std::collections::BTreeSet;
+
+/// Writes the results of [`Linter::run`](crate::lint::Linter::run) as a [SARIF](https://sarifweb.azurewebsites.net/) 2.1.0 log,
+/// for example for GitHub code scanning.
+///
+/// `directory` is the path of the `.dbd` files relative to the root of the repository, for example `definitions`.
+/// Lines and columns start at 1 as required by SARIF, and are left out for lints without a [`Lint::span`].
+pub fn lints_to_sarif(lints: &[(Lint, Severity)], directory: &str) -> String {
+    let rules: BTreeSet<String> = lints
+        .iter()
+        .map(|(lint, _)| lint.kind.rule().to_string())
+        .collect();
+    let rules: Vec<String> = rules
+        .iter()
+        .map(|a| format!("{{ \"id\": {} }}", quoted(a)))
+        .collect();
+
+    let mut s = Writer::new();
+    s.wln("{");
+    s.wln("  \"$schema\": \"https://json.schemastore.org/sarif-2.1.0.json\",");
+    s.wln("  \"version\": \"2.1.0\",");
+    s.wln("  \"runs\": [");
+    s.wln("    {");
+    s.wln("      \"tool\": {");
+    s.wln("        \"driver\": {");
+    s.wln("          \"name\": \"wowdbdefs-rs\",");
+    s.wln("          \"informationUri\": \"https://github.com/gtker/wowdbdefs-rs\",");
+    s.wln(format!("          \"rules\": [{}]", rules.join(", ")));
+    s.wln("        }");
+    s.wln("      },");
+    s.wln("      \"results\": [");
+
+    for (i, (lint, severity)) in lints.iter().enumerate() {
+        let region = match &lint.span {
+            Some(span) => format!(
+                ", \"region\": {{ \"startLine\": {}, \"startColumn\": {} }}",
+                span.line + 1,
+                span.column + 1
+            ),
+            None => String::new(),
+        };
+
+        s.wln("        {");
+        s.wln(format!(
+            "          \"ruleId\": {},",
+            quoted(&lint.kind.rule().to_string())
+        ));
+        s.wln(format!(
+            "          \"level\": \"{}\",",
+            sarif_level(*severity)
+        ));
+        s.wln(format!(
+            "          \"message\": {{ \"text\": {} }},",
+            quoted(&lint.to_string())
+        ));
+        s.wln(format!(
+            "          \"locations\": [{{ \"physicalLocation\": {{ \"artifactLocation\": {{ \"uri\": {} }}{} }} }}]",
+            quoted(&file_path(lint, directory)),
+            region
+        ));
+        s.wln(if i + 1 == lints.len() {
+            "        }"
+        } else {
+            "        },"
+        });
+    }
+
+    s.wln("      ]");
+    s.wln("    }");
+    s.wln("  ]");
+    s.wln("}");
+
+    s.inner
+}
+
+/// Writes the results of [`Linter::run`](crate::lint::Linter::run) as a JSON array with a single object per line:
+///
+/// ```json
+/// [
+///   { "file": "definitions/Map.dbd", "line": 3, "column": 1, "rule": "mixed_signedness", "severity": "warning", "message": "'Map::Flags' is both signed and unsigned" }
+/// ]
+/// ```
+///
+/// `line` and `column` start at 1 like in [`lints_to_sarif`], and are `null` for lints without a [`Lint::span`].
+pub fn lints_to_json(lints: &[(Lint, Severity)], directory: &str) -> String {
+    let mut s = Writer::new();
+    s.wln("[");
+
+    for (i, (lint, severity)) in lints.iter().enumerate() {
+        let (line, column) = match &lint.span {
+            Some(span) => ((span.line + 1).to_string(), (span.column + 1).to_string()),
+            None => ("null".to_string(), "null".to_string()),
+        };
+
+        s.wln(format!(
+            "  {{ \"file\": {}, \"line\": {}, \"column\": {}, \"rule\": {}, \"severity\": \"{}\", \"message\": {} }}{}",
+            quoted(&file_path(lint, directory)),
+            line,
+            column,
+            quoted(&lint.kind.rule().to_string()),
+            severity_name(*severity),
+            quoted(&lint.to_string()),
+            if i + 1 == lints.len() { "" } else { "," }
+        ));
+    }
+
+    s.wln("]");
+    s.inner
+}
+
+fn file_path(lint: &Lint, directory: &str) -> String {
+    let directory = directory.trim_end_matches('/');
+
+    if directory.is_empty() {
+        format!("{}.dbd", lint.column.table)
+    } else {
+        format!("{}/{}.dbd", directory, lint.column.table)
+    }
+}
+
+const fn sarif_level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Allow => "none",
+        Severity::Warning => "warning",
+        Severity::Error => "error",
+    }
+}
+
+const fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Allow => "allow",
+        Severity::Warning => "warning",
+        Severity::Error => "error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::enums::ColumnReference;
+    use crate::lint::{lints_to_json, lints_to_sarif, Lint, LintKind, Severity, Span};
+
+    fn lints() -> Vec<(Lint, Severity)> {
+        vec![
+            (
+                Lint {
+                    column: ColumnReference::new("Map", "ParentID"),
+                    span: Some(Span {
+                        line: 2,
+                        column: 0,
+                        start: 15,
+                        end: 30,
+                    }),
+                    fix: None,
+                    kind: LintKind::SelfReference,
+                },
+                Severity::Error,
+            ),
+            (
+                Lint {
+                    column: ColumnReference::new("Spell", "ID"),
+                    span: None,
+                    fix: None,
+                    kind: LintKind::SelfReference,
+                },
+                Severity::Warning,
+            ),
+        ]
+    }
+
+    #[test]
+    fn sarif() {
+        let sarif = lints_to_sarif(&lints(), "definitions/");
+
+        assert!(sarif.contains("\"version\": \"2.1.0\""));
+        assert!(sarif.contains("\"rules\": [{ \"id\": \"self_reference\" }]"));
+        assert!(sarif.contains("\"level\": \"error\""));
+        assert!(sarif.contains(
+            "{ \"artifactLocation\": { \"uri\": \"definitions/Map.dbd\" }, \"region\": { \"startLine\": 3, \"startColumn\": 1 } }"
+        ));
+        assert!(sarif.contains("{ \"artifactLocation\": { \"uri\": \"definitions/Spell.dbd\" } }"));
+        assert!(sarif.contains("        }\n      ]"));
+    }
+
+    #[test]
+    fn json() {
+        assert_eq!(
+            lints_to_json(&lints(), ""),
+            "[
+  { \"file\": \"Map.dbd\", \"line\": 3, \"column\": 1, \"rule\": \"self_reference\", \"severity\": \"error\", \"message\": \"'Map::ParentID' is a foreign key to itself\" },
+  { \"file\": \"Spell.dbd\", \"line\": null, \"column\": null, \"rule\": \"self_reference\", \"severity\": \"warning\", \"message\": \"'Spell::ID' is a foreign key to itself\" }
+]
+"
+        );
+    }
+}
@@ -0,0 +1,363 @@
+use crate::enums::ColumnReference;
+use crate::error::ParseError;
+use crate::lint::{source_lints, Baseline, Lint, LintConfig, LintKind, Severity};
+use crate::{parse_events, DbdCollection, DbdFile, Event};
+use std::collections::BTreeMap;
+
+/// Location of a line in the contents of a `.dbd` file.
+#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct Span {
+    /// Line number, counted the same way as [`ParseError::line`].
+    pub line: usize,
+    /// Amount of bytes into the line of [`Span::start`].
+    pub column: usize,
+    /// Byte offset into the contents of the first character after leading whitespace.
+    pub start: usize,
+    /// Byte offset into the contents of the end of the line, without the line ending.
+    pub end: usize,
+}
+
+/// Locations of the columns, definitions and entries of a `.dbd` file.
+///
+/// Created from [`Spans::new`] with the contents of the file.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
+pub struct Spans {
+    columns: BTreeMap<String, Span>,
+    definitions: Vec<Span>,
+    entries: Vec<BTreeMap<String, Span>>,
+    comments: Vec<Span>,
+}
+
+impl Spans {
+    /// Finds the locations in `contents` with [`parse_events`].
+    ///
+    /// # Errors
+    ///
+    /// Errors if `contents` can not be parsed.
+    pub fn new(contents: &str) -> Result<Self, ParseError> {
+        let lines: Vec<Span> = line_spans(contents);
+        let mut s = Self::default();
+
+        for event in parse_events(contents) {
+            let (line, event) = event?;
+            let span = lines[line];
+
+            match event {
+                Event::Column(column) => {
+                    s.columns.insert(column.name, span);
+                }
+                Event::DefinitionStart => {
+                    s.definitions.push(span);
+                    s.entries.push(BTreeMap::new());
+                }
+                Event::Entry(entry) => {
+                    if let Some(entries) = s.entries.last_mut() {
+                        entries.insert(entry.name, span);
+                    }
+                }
+                Event::Comment(_) => s.comments.push(span),
+                Event::Columns | Event::Build { .. } | Event::Layout(_) => {}
+            }
+        }
+
+        Ok(s)
+    }
+
+    /// Finds the declaration of `column` under `COLUMNS`.
+    pub fn column(&self, column: &str) -> Option<Span> {
+        self.columns.get(column).copied()
+    }
+
+    /// Finds the first line of the definition with the index into [`DbdFile::definitions`].
+    pub fn definition(&self, definition: usize) -> Option<Span> {
+        self.definitions.get(definition).copied()
+    }
+
+    /// Finds the entry for `column` in the definition with the index into [`DbdFile::definitions`].
+    pub fn entry(&self, definition: usize, column: &str) -> Option<Span> {
+        self.entries.get(definition)?.get(column).copied()
+    }
+
+    /// Every `COMMENT` line, in the order of the file.
+    pub fn comments(&self) -> &[Span] {
+        &self.comments
+    }
+}
+
+pub(crate) fn line_spans(contents: &str) -> Vec<Span> {
+    let mut offset = 0;
+
+    contents
+        .split('\n')
+        .enumerate()
+        .map(|(line, text)| {
+            let trimmed = text.trim_end_matches('\r');
+            let column = trimmed.len() - trimmed.trim_start().len();
+            let span = Span {
+                line,
+                column,
+                start: offset + column,
+                end: offset + trimmed.len(),
+            };
+            offset += text.len() + 1;
+
+            span
+        })
+        .collect()
+}
+
+/// Everything a [`Rule`] can inspect.
+#[derive(Debug, Clone, Copy)]
+pub struct RuleContext<'a> {
+    /// Collection being linted.
+    pub collection: &'a DbdCollection,
+    /// File being checked.
+    pub file: &'a DbdFile,
+    /// Locations in the file, if the contents were added with [`Linter::add_source`].
+    pub spans: Option<&'a Spans>,
+}
+
+impl RuleContext<'_> {
+    /// Creates a [`LintKind::Custom`] lint for `column` of the file being checked,
+    /// at the declaration of the column if known.
+    pub fn lint(&self, rule: &dyn Rule, column: &str, message: impl Into<String>) -> Lint {
+        Lint {
+            column: ColumnReference::new(self.file.table_name(), column),
+            span: self.spans.and_then(|a| a.column(column)),
+            fix: None,
+            kind: LintKind::Custom {
+                rule: rule.name().to_string(),
+                message: message.into(),
+            },
+        }
+    }
+}
+
+/// User defined lint, run by a [`Linter`] for every file.
+///
+/// ```rust
+/// use wowdbdefs_rs::lint::{Lint, Rule, RuleContext};
+///
+/// struct CommentUnverified;
+///
+/// impl Rule for CommentUnverified {
+///     fn name(&self) -> &str {
+///         "comment_unverified"
+///     }
+///
+///     fn check(&self, context: &RuleContext<'_>) -> Vec<Lint> {
+///         let mut lints = Vec::new();
+///
+///         for definition in &context.file.definitions {
+///             for entry in &definition.entries {
+///                 if !entry.verified && entry.column_comment.is_none() {
+///                     lints.push(context.lint(self, &entry.name, "unverified column without a comment"));
+///                 }
+///             }
+///         }
+///
+///         lints
+///     }
+/// }
+/// ```
+pub trait Rule {
+    /// Name of the rule, used for [`LintRule::Custom`](crate::lint::LintRule::Custom).
+    ///
+    /// Should be `snake_case` so it can be configured with `LintConfig::from_toml_with_custom_rules`.
+    fn name(&self) -> &str;
+
+    /// Finds the lints in [`RuleContext::file`].
+    fn check(&self, context: &RuleContext<'_>) -> Vec<Lint>;
+}
+
+/// Runs the built in lints and registered [`Rule`]s, and applies a [`LintConfig`].
+#[derive(Default)]
+pub struct Linter {
+    /// Configuration applied to every lint.
+    pub config: LintConfig,
+    /// Lints that are not reported by [`Linter::run`].
+    pub baseline: Baseline,
+    rules: Vec<Box<dyn Rule>>,
+    spans: BTreeMap<String, Spans>,
+    source_lints: BTreeMap<String, Vec<Lint>>,
+}
+
+impl Linter {
+    /// Creates a linter with only the built in lints.
+    pub fn new(config: LintConfig) -> Self {
+        Self {
+            config,
+            baseline: Baseline::new(),
+            rules: Vec::new(),
+            spans: BTreeMap::new(),
+            source_lints: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a rule that is run for every file after the built in lints.
+    pub fn register(&mut self, rule: impl Rule + 'static) {
+        self.rules.push(Box::new(rule));
+    }
+
+    /// Adds the contents of the file for `table`, without `.dbd`,
+    /// so lints are given a [`Lint::span`] and rules have access to [`RuleContext::spans`].
+    ///
+    /// The lints from [`source_lints`] are also reported for the file,
+    /// even if it is not in the linted collection.
+    ///
+    /// # Errors
+    ///
+    /// Errors if `contents` can not be parsed.
+    pub fn add_source(
+        &mut self,
+        table: impl Into<String>,
+        contents: &str,
+    ) -> Result<(), ParseError> {
+        let table = table.into();
+
+        self.source_lints
+            .insert(table.clone(), source_lints(contents, &table)?);
+        self.spans.insert(table, Spans::new(contents)?);
+        Ok(())
+    }
+
+    /// Runs [`DbdCollection::lints`] followed by the registered rules for every file,
+    /// then applies [`Linter::config`] with [`LintConfig::apply`] and removes lints in [`Linter::baseline`].
+    ///
+    /// Use [`Linter::run_all`] to create a new baseline.
+    ///
+    /// Lints without a span get the declaration of their column, if the source of the table was added.
+    pub fn run(&self, collection: &DbdCollection) -> Vec<(Lint, Severity)> {
+        self.baseline.new_lints(self.run_all(collection))
+    }
+
+    /// Same as [`Linter::run`], but includes lints in [`Linter::baseline`].
+    pub fn run_all(&self, collection: &DbdCollection) -> Vec<(Lint, Severity)> {
+        let mut lints = collection.lints();
+
+        for (table, file) in &collection.files {
+            let context = RuleContext {
+                collection,
+                file,
+                spans: self.spans.get(table),
+            };
+
+            for rule in &self.rules {
+                lints.append(&mut rule.check(&context));
+            }
+        }
+
+        for source_lints in self.source_lints.values() {
+            lints.extend(source_lints.iter().cloned());
+        }
+
+        for lint in &mut lints {
+            if lint.span.is_none() {
+                lint.span = self
+                    .spans
+                    .get(&lint.column.table)
+                    .and_then(|a| a.column(&lint.column.column));
+            }
+        }
+
+        self.config.apply(lints)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lint::{
+        Baseline, Lint, LintConfig, LintKind, LintRule, Linter, Rule, RuleContext, Severity, Span,
+        Spans,
+    };
+    use crate::{load_file_from_string, DbdCollection};
+
+    const CONTENTS: &str = "COLUMNS\nint ID\nint Flags?\n\nBUILD 1.12.1.5875\n$id$ID<32>\nFlags<32>\n\nBUILD 3.3.5.12340\n$id$ID<32>\nFlags<u32>\n";
+
+    struct Unverified;
+
+    impl Rule for Unverified {
+        fn name(&self) -> &str {
+            "unverified"
+        }
+
+        fn check(&self, context: &RuleContext<'_>) -> Vec<Lint> {
+            context.file.definitions[0]
+                .entries
+                .iter()
+                .filter(|a| !a.verified)
+                .map(|a| context.lint(self, &a.name, "is not verified"))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn spans() {
+        let spans = Spans::new(CONTENTS).unwrap();
+
+        let flags = spans.column("Flags").unwrap();
+        assert_eq!(
+            flags,
+            Span {
+                line: 2,
+                column: 0,
+                start: 15,
+                end: 25,
+            }
+        );
+        assert_eq!(&CONTENTS[flags.start..flags.end], "int Flags?");
+
+        let definition = spans.definition(1).unwrap();
+        assert_eq!(
+            &CONTENTS[definition.start..definition.end],
+            "BUILD 3.3.5.12340"
+        );
+
+        let entry = spans.entry(1, "Flags").unwrap();
+        assert_eq!(entry.line, 10);
+        assert_eq!(&CONTENTS[entry.start..entry.end], "Flags<u32>");
+        assert_eq!(spans.entry(2, "Flags"), None);
+    }
+
+    #[test]
+    fn linter() {
+        let file = load_file_from_string(CONTENTS, "Map.dbd")
+            .unwrap()
+            .into_proper()
+            .unwrap();
+        let collection = DbdCollection::from_files(vec![file]);
+
+        let mut config = LintConfig::new();
+        config
+            .severities
+            .insert(LintRule::Custom("unverified".to_string()), Severity::Error);
+
+        let mut linter = Linter::new(config);
+        linter.register(Unverified);
+        linter.add_source("Map", CONTENTS).unwrap();
+
+        let lints = linter.run(&collection);
+        assert_eq!(lints.len(), 2);
+
+        let (lint, severity) = &lints[0];
+        assert_eq!(lint.kind.rule(), LintRule::MixedSignedness);
+        assert_eq!(lint.span.map(|a| a.line), Some(2));
+        assert_eq!(*severity, Severity::Warning);
+
+        let (lint, severity) = &lints[1];
+        assert_eq!(
+            lint.kind,
+            LintKind::Custom {
+                rule: "unverified".to_string(),
+                message: "is not verified".to_string(),
+            }
+        );
+        assert_eq!(lint.to_string(), "'Map::Flags': is not verified");
+        assert_eq!(lint.span.map(|a| a.line), Some(2));
+        assert_eq!(*severity, Severity::Error);
+
+        linter.baseline = Baseline::from_lints(lints.iter().take(1).map(|(lint, _)| lint));
+        assert_eq!(linter.run(&collection), lints[1..].to_vec());
+        assert_eq!(linter.run_all(&collection), lints);
+    }
+}
@@ -0,0 +1,303 @@
+//! Parsing of the `manifest.json` file in the root of the [`WoWDBDefs`](https://github.com/wowdev/WoWDBDefs) repository.
+//!
+//! The manifest lists every table together with the hash of the table name
+//! and the file data IDs of the `.dbc` and `.db2` files:
+//!
+//! ```json
+//! [
+//!     {
+//!         "tableName": "Map",
+//!         "tableHash": "F568DF12",
+//!         "dbcFileDataID": 1349477,
+//!         "db2FileDataID": 1349477
+//!     }
+//! ]
+//! ```
+//!
+//! Use [`load_repository`](crate::load_repository) to load it together with the definitions.
+
+use crate::error::{ManifestError, ManifestErrorReason};
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+
+/// Single table in `manifest.json`.
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct ManifestEntry {
+    /// Name of the table, without `.dbd`.
+    pub table_name: String,
+    /// Hash of the table name as used in `.db2` headers.
+    pub table_hash: Option<u32>,
+    /// File data ID of the `.dbc` file.
+    pub dbc_file_data_id: Option<u32>,
+    /// File data ID of the `.db2` file.
+    pub db2_file_data_id: Option<u32>,
+}
+
+/// Parses the contents of `manifest.json`, keyed by table name.
+///
+/// Unknown keys are ignored and `null`, missing or empty values become [`None`].
+///
+/// # Errors
+///
+/// Errors if `contents` is not an array of objects, or if an object has no `tableName`.
+pub fn parse_manifest(contents: &str) -> Result<BTreeMap<String, ManifestEntry>, ManifestError> {
+    let mut p = Parser {
+        contents,
+        offset: 0,
+    };
+    let mut entries = BTreeMap::new();
+
+    p.expect('[')?;
+    if p.consume(']') {
+        return p.end(entries);
+    }
+
+    loop {
+        let entry = p.entry()?;
+        entries.insert(entry.table_name.clone(), entry);
+
+        if p.consume(']') {
+            return p.end(entries);
+        }
+        p.expect(',')?;
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum Value {
+    String(String),
+    Number(u64),
+    Null,
+}
+
+struct Parser<'a> {
+    contents: &'a str,
+    offset: usize,
+}
+
+impl Parser<'_> {
+    const fn error(&self, reason: ManifestErrorReason) -> ManifestError {
+        ManifestError {
+            offset: self.offset,
+            reason,
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        let rest = &self.contents[self.offset..];
+        self.offset += rest.len() - rest.trim_start().len();
+
+        self.contents[self.offset..].chars().next()
+    }
+
+    fn consume(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.offset += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), ManifestError> {
+        if self.consume(c) {
+            return Ok(());
+        }
+
+        Err(match self.peek() {
+            Some(found) => {
+                self.error(ManifestErrorReason::UnexpectedCharacter { expected: c, found })
+            }
+            None => self.error(ManifestErrorReason::UnexpectedEnd),
+        })
+    }
+
+    fn end<T>(&mut self, value: T) -> Result<T, ManifestError> {
+        match self.peek() {
+            None => Ok(value),
+            Some(found) => Err(self.error(ManifestErrorReason::TrailingCharacters(found))),
+        }
+    }
+
+    fn entry(&mut self) -> Result<ManifestEntry, ManifestError> {
+        let start = self.offset;
+        let mut values = BTreeMap::new();
+
+        self.expect('{')?;
+        if !self.consume('}') {
+            loop {
+                let key = self.string()?;
+                self.expect(':')?;
+                values.insert(key, self.value()?);
+
+                if self.consume('}') {
+                    break;
+                }
+                self.expect(',')?;
+            }
+        }
+
+        let table_name = match values.remove("tableName") {
+            Some(Value::String(s)) if !s.is_empty() => s,
+            _ => {
+                return Err(ManifestError {
+                    offset: start,
+                    reason: ManifestErrorReason::MissingTableName,
+                })
+            }
+        };
+
+        let number = |value: Option<Value>| match value {
+            Some(Value::Number(v)) => u32::try_from(v).ok(),
+            _ => None,
+        };
+
+        Ok(ManifestEntry {
+            table_name,
+            table_hash: match values.remove("tableHash") {
+                Some(Value::String(s)) => u32::from_str_radix(&s, 16).ok(),
+                _ => None,
+            },
+            dbc_file_data_id: number(values.remove("dbcFileDataID")),
+            db2_file_data_id: number(values.remove("db2FileDataID")),
+        })
+    }
+
+    fn value(&mut self) -> Result<Value, ManifestError> {
+        match self.peek() {
+            Some('"') => Ok(Value::String(self.string()?)),
+            Some(c) if c.is_ascii_digit() => {
+                let rest = &self.contents[self.offset..];
+                let len = rest
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(rest.len());
+
+                let value = rest[..len]
+                    .parse()
+                    .map_err(|_| self.error(ManifestErrorReason::InvalidValue))?;
+                self.offset += len;
+
+                Ok(Value::Number(value))
+            }
+            Some(_) if self.contents[self.offset..].starts_with("null") => {
+                self.offset += "null".len();
+                Ok(Value::Null)
+            }
+            Some(_) => Err(self.error(ManifestErrorReason::InvalidValue)),
+            None => Err(self.error(ManifestErrorReason::UnexpectedEnd)),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, ManifestError> {
+        self.expect('"')?;
+
+        let mut s = String::new();
+        let mut chars = self.contents[self.offset..].char_indices();
+
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.offset += i + 1;
+                    return Ok(s);
+                }
+                '\\' => match chars.next() {
+                    Some((_, c @ ('"' | '\\' | '/'))) => s.push(c),
+                    Some((_, 'n')) => s.push('\n'),
+                    Some((_, 't')) => s.push('\t'),
+                    _ => {
+                        self.offset += i;
+                        return Err(self.error(ManifestErrorReason::InvalidValue));
+                    }
+                },
+                c => s.push(c),
+            }
+        }
+
+        self.offset = self.contents.len();
+        Err(self.error(ManifestErrorReason::UnexpectedEnd))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::{ManifestError, ManifestErrorReason};
+    use crate::manifest::{parse_manifest, ManifestEntry};
+    use crate::DbdCollection;
+
+    #[test]
+    fn manifest() {
+        let manifest = parse_manifest(
+            r#"[
+    {
+        "tableName": "Map",
+        "tableHash": "F568DF12",
+        "dbcFileDataID": 1349477,
+        "db2FileDataID": 1349477
+    },
+    {
+        "tableName": "AreaTable",
+        "tableHash": "",
+        "dbcFileDataID": null,
+        "extra": "ignored"
+    }
+]"#,
+        )
+        .unwrap();
+
+        assert_eq!(manifest.len(), 2);
+        assert_eq!(
+            manifest["Map"],
+            ManifestEntry {
+                table_name: "Map".to_string(),
+                table_hash: Some(0xF568DF12),
+                dbc_file_data_id: Some(1349477),
+                db2_file_data_id: Some(1349477),
+            }
+        );
+        assert_eq!(
+            manifest["AreaTable"],
+            ManifestEntry {
+                table_name: "AreaTable".to_string(),
+                table_hash: None,
+                dbc_file_data_id: None,
+                db2_file_data_id: None,
+            }
+        );
+
+        assert_eq!(parse_manifest(" [ ] ").unwrap().len(), 0);
+        assert_eq!(
+            parse_manifest("[{\"tableHash\": \"F568DF12\"}]"),
+            Err(ManifestError {
+                offset: 1,
+                reason: ManifestErrorReason::MissingTableName,
+            })
+        );
+        assert_eq!(
+            parse_manifest("[{\"tableName\": \"Map\"}"),
+            Err(ManifestError {
+                offset: 21,
+                reason: ManifestErrorReason::UnexpectedEnd,
+            })
+        );
+    }
+
+    #[test]
+    fn table_hash() {
+        let mut collection = DbdCollection::new();
+        collection.manifest = parse_manifest(
+            r#"[
+    { "tableName": "Map", "tableHash": "F568DF12" },
+    { "tableName": "AreaTable", "tableHash": "" }
+]"#,
+        )
+        .unwrap();
+
+        let hash = collection.table_hash("Map").unwrap();
+        assert_eq!(hash, 0xF568DF12);
+        assert_eq!(collection.table_name_from_hash(hash), Some("Map"));
+
+        assert_eq!(collection.table_hash("AreaTable"), None);
+        assert_eq!(collection.table_hash("Spell"), None);
+        assert_eq!(collection.table_name_from_hash(0), None);
+    }
+}
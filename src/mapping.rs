@@ -0,0 +1,341 @@
+//! Best effort correspondence between the columns of two definitions of the same table.
+//!
+//! Columns are renamed between builds as they are better understood,
+//! so [`map_columns`] also pairs columns whose names differ:
+//!
+//! 1. Pairs from [`ColumnOverrides`].
+//! 2. Columns with the same name.
+//! 3. Columns whose names only differ in case or underscores.
+//! 4. Columns with similar names and compatible types.
+//! 5. The only unpaired column on both sides between two paired columns, if the types are compatible.
+//!
+//! Types are compatible if both are integers, both are floats or both are strings,
+//! and the array widths are the same.
+
+use crate::error::closest_match;
+use crate::{Definition, Entry, ScalarType};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Pairs that [`map_columns_with_overrides`] uses instead of the heuristics.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
+pub struct ColumnOverrides {
+    /// Columns of the first definition paired with columns of the second definition.
+    pub pairs: BTreeMap<String, String>,
+    /// Columns of either definition that are never paired.
+    pub unmapped: BTreeSet<String>,
+}
+
+impl ColumnOverrides {
+    /// Creates overrides that leave everything to the heuristics.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Why two columns were paired by [`map_columns`].
+#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum MatchReason {
+    /// Paired by [`ColumnOverrides::pairs`].
+    Override,
+    /// Same name.
+    Name,
+    /// Names only differ in case or underscores.
+    NormalizedName,
+    /// Names are similar and the types are compatible.
+    SimilarName,
+    /// Only unpaired columns between the same two paired columns, and the types are compatible.
+    Position,
+}
+
+/// Column of the first definition paired with a column of the second definition.
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct ColumnPair {
+    /// Name in the first definition.
+    pub from: String,
+    /// Name in the second definition.
+    pub to: String,
+    /// Why the columns were paired.
+    pub reason: MatchReason,
+}
+
+/// Correspondence between the columns of two definitions.
+///
+/// Created from [`map_columns`] and [`map_columns_with_overrides`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
+pub struct ColumnMapping {
+    /// Paired columns, in the order of the first definition.
+    pub pairs: Vec<ColumnPair>,
+    /// Columns of the first definition without a pair, in order.
+    pub only_in_from: Vec<String>,
+    /// Columns of the second definition without a pair, in order.
+    pub only_in_to: Vec<String>,
+}
+
+impl ColumnMapping {
+    /// Finds the column of the second definition paired with `from`.
+    pub fn to_column(&self, from: &str) -> Option<&str> {
+        self.pairs
+            .iter()
+            .find(|a| a.from == from)
+            .map(|a| a.to.as_str())
+    }
+
+    /// Finds the column of the first definition paired with `to`.
+    pub fn from_column(&self, to: &str) -> Option<&str> {
+        self.pairs
+            .iter()
+            .find(|a| a.to == to)
+            .map(|a| a.from.as_str())
+    }
+}
+
+/// Pairs the columns of `from` with the columns of `to`, see the [module docs](self) for how.
+pub fn map_columns(from: &Definition, to: &Definition) -> ColumnMapping {
+    map_columns_with_overrides(from, to, &ColumnOverrides::new())
+}
+
+/// Same as [`map_columns`], but with `overrides` taking precedence over the heuristics.
+///
+/// Override pairs with a column that does not exist are ignored.
+pub fn map_columns_with_overrides(
+    from: &Definition,
+    to: &Definition,
+    overrides: &ColumnOverrides,
+) -> ColumnMapping {
+    let mut m = Mapper {
+        from: &from.entries,
+        to: &to.entries,
+        pairs: vec![None; from.entries.len()],
+        used: vec![false; to.entries.len()],
+    };
+
+    for (i, entry) in from.entries.iter().enumerate() {
+        if let Some(to) = overrides.pairs.get(&entry.name) {
+            if let Some(j) = to_index(&m, |b| &b.name == to) {
+                m.pair(i, j, MatchReason::Override);
+            }
+        }
+    }
+
+    for (i, entry) in from.entries.iter().enumerate() {
+        if overrides.unmapped.contains(&entry.name) && m.pairs[i].is_none() {
+            m.pairs[i] = Some((usize::MAX, MatchReason::Override));
+        }
+    }
+    for (j, entry) in to.entries.iter().enumerate() {
+        m.used[j] |= overrides.unmapped.contains(&entry.name);
+    }
+
+    m.pair_by(MatchReason::Name, |a, b| a.name == b.name);
+    m.pair_by(MatchReason::NormalizedName, |a, b| {
+        normalized(&a.name) == normalized(&b.name)
+    });
+
+    for i in 0..from.entries.len() {
+        if m.pairs[i].is_some() || !m.has_unused() {
+            continue;
+        }
+
+        let candidates: Vec<&str> =
+            m.to.iter()
+                .zip(&m.used)
+                .filter(|(b, used)| !**used && compatible(&from.entries[i], b))
+                .map(|(b, _)| b.name.as_str())
+                .collect();
+
+        if let Some(name) = closest_match(&from.entries[i].name, candidates) {
+            if let Some(j) = to_index(&m, |b| b.name == name) {
+                m.pair(i, j, MatchReason::SimilarName);
+            }
+        }
+    }
+
+    m.pair_by_position();
+
+    let mut mapping = ColumnMapping::default();
+    for (i, pair) in m.pairs.iter().enumerate() {
+        match pair {
+            Some((j, reason)) if *j != usize::MAX => mapping.pairs.push(ColumnPair {
+                from: from.entries[i].name.clone(),
+                to: to.entries[*j].name.clone(),
+                reason: *reason,
+            }),
+            _ => mapping.only_in_from.push(from.entries[i].name.clone()),
+        }
+    }
+    for entry in &to.entries {
+        if mapping.from_column(&entry.name).is_none() {
+            mapping.only_in_to.push(entry.name.clone());
+        }
+    }
+
+    mapping
+}
+
+fn to_index(m: &Mapper<'_>, f: impl Fn(&Entry) -> bool) -> Option<usize> {
+    m.to.iter()
+        .zip(&m.used)
+        .position(|(b, used)| !*used && f(b))
+}
+
+fn normalized(name: &str) -> String {
+    name.chars()
+        .filter(|c| *c != '_')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn compatible(a: &Entry, b: &Entry) -> bool {
+    let kind = |ty: ScalarType| match ty {
+        ScalarType::Float => 1,
+        ScalarType::String | ScalarType::LocString => 2,
+        _ => 0,
+    };
+
+    kind(a.ty.scalar()) == kind(b.ty.scalar()) && a.ty.array_width() == b.ty.array_width()
+}
+
+struct Mapper<'a> {
+    from: &'a [Entry],
+    to: &'a [Entry],
+    /// Index into `to` for every entry in `from`. [`usize::MAX`] for columns that are never paired.
+    pairs: Vec<Option<(usize, MatchReason)>>,
+    used: Vec<bool>,
+}
+
+impl Mapper<'_> {
+    fn pair(&mut self, i: usize, j: usize, reason: MatchReason) {
+        self.pairs[i] = Some((j, reason));
+        self.used[j] = true;
+    }
+
+    fn has_unused(&self) -> bool {
+        self.used.iter().any(|a| !a)
+    }
+
+    fn pair_by(&mut self, reason: MatchReason, f: impl Fn(&Entry, &Entry) -> bool) {
+        for i in 0..self.from.len() {
+            if self.pairs[i].is_some() {
+                continue;
+            }
+
+            let a = &self.from[i];
+            if let Some(j) = to_index(self, |b| f(a, b)) {
+                self.pair(i, j, reason);
+            }
+        }
+    }
+
+    /// Pairs the single unpaired column on both sides of every gap between paired columns.
+    fn pair_by_position(&mut self) {
+        let mut anchors: Vec<(usize, usize)> = self
+            .pairs
+            .iter()
+            .enumerate()
+            .filter_map(|(i, a)| match a {
+                Some((j, _)) if *j != usize::MAX => Some((i, *j)),
+                _ => None,
+            })
+            .collect();
+        anchors.push((self.from.len(), self.to.len()));
+
+        let (mut from_start, mut to_start) = (0, 0);
+        for (from_end, to_end) in anchors {
+            if to_end >= to_start {
+                let from_gap: Vec<usize> = (from_start..from_end)
+                    .filter(|i| self.pairs[*i].is_none())
+                    .collect();
+                let to_gap: Vec<usize> = (to_start..to_end).filter(|j| !self.used[*j]).collect();
+
+                if let ([i], [j]) = (from_gap.as_slice(), to_gap.as_slice()) {
+                    if compatible(&self.from[*i], &self.to[*j]) {
+                        self.pair(*i, *j, MatchReason::Position);
+                    }
+                }
+            }
+
+            from_start = from_end + 1;
+            to_start = to_start.max(to_end + 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mapping::{map_columns, map_columns_with_overrides, ColumnOverrides, MatchReason};
+    use crate::{load_file_from_string, DbdFile};
+
+    fn file() -> DbdFile {
+        load_file_from_string(
+            "COLUMNS
+int ID
+int Flags
+int Flag
+int Map_ID
+int MapID
+float Radius
+string Name
+float Scale
+int Unknown
+
+BUILD 1.12.1.5875
+$id$ID<32>
+Flags<32>
+Map_ID<32>
+Radius
+Name
+Unknown<32>
+
+BUILD 3.3.5.12340
+$id$ID<32>
+Flag<32>
+MapID<32>
+Scale
+Name
+",
+            "AreaTable.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap()
+    }
+
+    #[test]
+    fn mapping() {
+        let file = file();
+        let (from, to) = (&file.definitions[0], &file.definitions[1]);
+        let mapping = map_columns(from, to);
+
+        let pairs: Vec<(&str, &str, MatchReason)> = mapping
+            .pairs
+            .iter()
+            .map(|a| (a.from.as_str(), a.to.as_str(), a.reason))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("ID", "ID", MatchReason::Name),
+                ("Flags", "Flag", MatchReason::SimilarName),
+                ("Map_ID", "MapID", MatchReason::NormalizedName),
+                ("Radius", "Scale", MatchReason::Position),
+                ("Name", "Name", MatchReason::Name),
+            ]
+        );
+        assert_eq!(mapping.only_in_from, vec!["Unknown"]);
+        assert!(mapping.only_in_to.is_empty());
+        assert_eq!(mapping.to_column("Radius"), Some("Scale"));
+        assert_eq!(mapping.from_column("Scale"), Some("Radius"));
+
+        let mut overrides = ColumnOverrides::new();
+        overrides
+            .pairs
+            .insert("Unknown".to_string(), "Flag".to_string());
+        overrides.unmapped.insert("Radius".to_string());
+        let mapping = map_columns_with_overrides(from, to, &overrides);
+
+        assert_eq!(mapping.to_column("Unknown"), Some("Flag"));
+        assert_eq!(mapping.to_column("Radius"), None);
+        assert_eq!(mapping.only_in_from, vec!["Flags", "Radius"]);
+        assert_eq!(mapping.only_in_to, vec!["Scale"]);
+    }
+}
@@ -0,0 +1,349 @@
+//! Three-way merging of `.dbd` files.
+//!
+//! [`merge3`] merges the columns and definitions of two changed versions of a file
+//! instead of their text, so changes to different definitions or entries never conflict
+//! and the result is always a valid file.
+//!
+//! Definitions are identified by their versions and version ranges,
+//! so changing the builds of a definition is a removal of the old definition and an addition of a new one.
+//! Definitions changed on both sides are merged entry by entry if at most one side
+//! added, removed or reordered entries.
+
+use crate::{RawColumn, RawDbdFile, RawDefinition, RawEntry, Version, VersionRange};
+use std::collections::{BTreeSet, HashMap};
+
+/// Result of [`merge3`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Merge {
+    /// Merged file, with the version from `ours` for every conflict.
+    pub file: RawDbdFile,
+    /// Changes that could not be merged, in the order they were found.
+    pub conflicts: Vec<MergeConflict>,
+}
+
+impl Merge {
+    /// Returns true if the merge has no conflicts.
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// Change made differently on both sides of a [`merge3`].
+///
+/// [`None`] means that the side removed the item.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum MergeConflict {
+    /// Column under `COLUMNS`.
+    Column {
+        /// Name of the column.
+        name: String,
+        /// Column in `ours`.
+        ours: Option<RawColumn>,
+        /// Column in `theirs`.
+        theirs: Option<RawColumn>,
+    },
+    /// Whole definition, if entries were added, removed or reordered on both sides
+    /// or the definition was changed on one side and removed on the other.
+    Definition {
+        /// Index into the definitions of [`Merge::file`], or [`None`] if the definition is not in the result.
+        definition: Option<usize>,
+        /// Definition in `ours`.
+        ours: Option<RawDefinition>,
+        /// Definition in `theirs`.
+        theirs: Option<RawDefinition>,
+    },
+    /// Single entry of a definition.
+    Entry {
+        /// Index into the definitions of [`Merge::file`].
+        definition: usize,
+        /// Entry in `ours`.
+        ours: RawEntry,
+        /// Entry in `theirs`.
+        theirs: RawEntry,
+    },
+    /// `LAYOUT` lines or `COMMENT` lines of a definition.
+    Metadata {
+        /// Index into the definitions of [`Merge::file`].
+        definition: usize,
+    },
+}
+
+type DefinitionKey = (BTreeSet<Version>, Vec<VersionRange>);
+
+fn key(definition: &RawDefinition) -> DefinitionKey {
+    (
+        definition.versions.clone(),
+        definition.version_ranges.clone(),
+    )
+}
+
+/// Merges the changes from `base` to `ours` and from `base` to `theirs`.
+///
+/// The name of the merged file is the name of `ours`.
+pub fn merge3(base: &RawDbdFile, ours: &RawDbdFile, theirs: &RawDbdFile) -> Merge {
+    let mut conflicts = Vec::new();
+
+    let mut columns = HashMap::new();
+    let names: BTreeSet<&String> = base
+        .columns
+        .keys()
+        .chain(ours.columns.keys())
+        .chain(theirs.columns.keys())
+        .collect();
+    for name in names {
+        let (b, o, t) = (
+            base.columns.get(name),
+            ours.columns.get(name),
+            theirs.columns.get(name),
+        );
+
+        let column = match merge_value(b, o, t) {
+            Some(column) => column,
+            None => {
+                conflicts.push(MergeConflict::Column {
+                    name: name.clone(),
+                    ours: o.cloned(),
+                    theirs: t.cloned(),
+                });
+                o.or(t)
+            }
+        };
+
+        if let Some(column) = column {
+            columns.insert(name.clone(), column.clone());
+        }
+    }
+
+    let find = |file: &RawDbdFile, k: &DefinitionKey| -> Option<RawDefinition> {
+        file.definitions.iter().find(|a| &key(a) == k).cloned()
+    };
+
+    let mut definitions: Vec<(DefinitionKey, RawDefinition)> = Vec::new();
+    let mut definition_conflicts = Vec::new();
+
+    let mut push = |definitions: &mut Vec<(DefinitionKey, RawDefinition)>,
+                    position: usize,
+                    k: DefinitionKey,
+                    b: Option<RawDefinition>,
+                    o: Option<RawDefinition>,
+                    t: Option<RawDefinition>| {
+        match merge_definition(b.as_ref(), o.as_ref(), t.as_ref()) {
+            Ok((Some(definition), entry_conflicts)) => {
+                definitions.insert(position, (k.clone(), definition));
+                definition_conflicts.push((k, None, entry_conflicts));
+            }
+            Ok((None, _)) => {}
+            Err(()) => {
+                if let Some(definition) = o.clone().or_else(|| t.clone()) {
+                    definitions.insert(position, (k.clone(), definition));
+                }
+                definition_conflicts.push((k, Some((o, t)), Vec::new()));
+            }
+        }
+    };
+
+    for definition in &ours.definitions {
+        let k = key(definition);
+        let position = definitions.len();
+        push(
+            &mut definitions,
+            position,
+            k.clone(),
+            find(base, &k),
+            Some(definition.clone()),
+            find(theirs, &k),
+        );
+    }
+
+    let mut previous: Option<DefinitionKey> = None;
+    for definition in &theirs.definitions {
+        let k = key(definition);
+
+        if find(ours, &k).is_none() {
+            let position = previous
+                .as_ref()
+                .and_then(|p| definitions.iter().position(|(a, _)| a == p))
+                .map_or(0, |a| a + 1);
+            push(
+                &mut definitions,
+                position,
+                k.clone(),
+                find(base, &k),
+                None,
+                Some(definition.clone()),
+            );
+        }
+
+        previous = Some(k);
+    }
+
+    let index = |k: &DefinitionKey| definitions.iter().position(|(a, _)| a == k);
+    for (k, whole, entry_conflicts) in definition_conflicts {
+        let definition = index(&k);
+
+        if let Some((ours, theirs)) = whole {
+            conflicts.push(MergeConflict::Definition {
+                definition,
+                ours,
+                theirs,
+            });
+        }
+
+        if let Some(definition) = definition {
+            for conflict in entry_conflicts {
+                conflicts.push(match conflict {
+                    DefinitionConflict::Entry(ours, theirs) => MergeConflict::Entry {
+                        definition,
+                        ours,
+                        theirs,
+                    },
+                    DefinitionConflict::Metadata => MergeConflict::Metadata { definition },
+                });
+            }
+        }
+    }
+
+    Merge {
+        file: RawDbdFile {
+            name: ours.name.clone(),
+            columns,
+            definitions: definitions.into_iter().map(|(_, a)| a).collect(),
+        },
+        conflicts,
+    }
+}
+
+enum DefinitionConflict {
+    Entry(RawEntry, RawEntry),
+    Metadata,
+}
+
+/// Three-way merge of a single value, [`None`] if both sides changed it differently.
+fn merge_value<T: PartialEq>(base: T, ours: T, theirs: T) -> Option<T> {
+    if ours == theirs || theirs == base {
+        Some(ours)
+    } else if ours == base {
+        Some(theirs)
+    } else {
+        None
+    }
+}
+
+fn merge_definition(
+    base: Option<&RawDefinition>,
+    ours: Option<&RawDefinition>,
+    theirs: Option<&RawDefinition>,
+) -> Result<(Option<RawDefinition>, Vec<DefinitionConflict>), ()> {
+    if let Some(definition) = merge_value(base, ours, theirs) {
+        return Ok((definition.cloned(), Vec::new()));
+    }
+
+    // Both sides changed the definition, or one removed it while the other changed it.
+    let (base, ours, theirs) = match (base, ours, theirs) {
+        (Some(b), Some(o), Some(t)) => (b, o, t),
+        _ => return Err(()),
+    };
+
+    let names = |a: &RawDefinition| a.entries.iter().map(|e| e.name.clone()).collect::<Vec<_>>();
+    let structure = match merge_value(names(base), names(ours), names(theirs)) {
+        Some(_) if names(ours) != names(base) => ours,
+        Some(_) => theirs,
+        None => return Err(()),
+    };
+
+    let mut conflicts = Vec::new();
+
+    let find = |definition: &RawDefinition, name: &str| -> Option<RawEntry> {
+        definition.entries.iter().find(|a| a.name == name).cloned()
+    };
+
+    let mut entries = Vec::with_capacity(structure.entries.len());
+    for entry in &structure.entries {
+        let (b, o, t) = (
+            find(base, &entry.name),
+            find(ours, &entry.name),
+            find(theirs, &entry.name),
+        );
+
+        let merged = match (b, o, t) {
+            (Some(b), Some(o), Some(t)) => merge_value(&b, &o, &t).cloned().unwrap_or_else(|| {
+                conflicts.push(DefinitionConflict::Entry(o.clone(), t.clone()));
+                o.clone()
+            }),
+            _ => entry.clone(),
+        };
+        entries.push(merged);
+    }
+
+    let layouts = merge_value(&base.layouts, &ours.layouts, &theirs.layouts);
+    let comments = merge_value(&base.comments, &ours.comments, &theirs.comments);
+    if layouts.is_none() || comments.is_none() {
+        conflicts.push(DefinitionConflict::Metadata);
+    }
+
+    let mut definition = ours.clone();
+    definition.entries = entries;
+    definition.layouts = layouts.unwrap_or(&ours.layouts).clone();
+    definition.comments = comments.unwrap_or(&ours.comments).clone();
+
+    Ok((Some(definition), conflicts))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::merge::{merge3, MergeConflict};
+    use crate::{load_file_from_string, write_to_file, RawDbdFile};
+
+    fn file(contents: &str) -> RawDbdFile {
+        load_file_from_string(contents, "Map.dbd").unwrap()
+    }
+
+    const BASE: &str = "COLUMNS\nint ID\nint Unk0\n\nBUILD 1.12.1.5875\n$id$ID<32>\nUnk0<32>\n\nBUILD 3.3.5.12340\n$id$ID<32>\nUnk0<32>\n\n";
+
+    #[test]
+    fn clean_merge() {
+        let ours = file("COLUMNS\nint ID\nint Unk0\n\nBUILD 1.12.1.5875\n$id$ID<32>\nUnk0<u32>\n\nBUILD 3.3.5.12340\n$id$ID<32>\nUnk0<32>\n\n");
+        let theirs = file("COLUMNS\nint ID\nint Unk0\nint Flags\n\nBUILD 1.12.1.5875\n$id$ID<32>\nUnk0<32>\n\nBUILD 2.4.3.8606\n$id$ID<32>\n\nBUILD 3.3.5.12340\n$id$ID<32>\nUnk0<32>\nFlags<32>\n\n");
+
+        let merge = merge3(&file(BASE), &ours, &theirs);
+        assert!(merge.is_clean());
+        assert_eq!(
+            write_to_file(&merge.file),
+            "COLUMNS\nint Flags\nint ID\nint Unk0\n\nBUILD 1.12.1.5875\n$id$ID<32>\nUnk0<u32>\n\nBUILD 2.4.3.8606\n$id$ID<32>\n\nBUILD 3.3.5.12340\n$id$ID<32>\nUnk0<32>\nFlags<32>\n\n"
+        );
+    }
+
+    #[test]
+    fn conflicts() {
+        let ours = file("COLUMNS\nint ID\nint Unk0 // Ours\n\nBUILD 1.12.1.5875\n$id$ID<32>\nUnk0<u32>\n\nBUILD 3.3.5.12340\n$id$ID<32>\n\n");
+        let theirs = file("COLUMNS\nint ID\nint Unk0 // Theirs\n\nBUILD 1.12.1.5875\n$id$ID<32>\nUnk0<16>\n\nBUILD 3.3.5.12340\nUnk0<32>\n$id$ID<32>\n\n");
+
+        let merge = merge3(&file(BASE), &ours, &theirs);
+        assert_eq!(merge.conflicts.len(), 3);
+
+        assert!(
+            matches!(&merge.conflicts[0], MergeConflict::Column { name, .. } if name == "Unk0")
+        );
+        match &merge.conflicts[1] {
+            MergeConflict::Entry {
+                definition,
+                ours,
+                theirs,
+            } => {
+                assert_eq!(*definition, 0);
+                assert!(ours.unsigned);
+                assert_eq!(theirs.integer_width, Some(16));
+            }
+            c => panic!("{:?}", c),
+        }
+        assert!(matches!(
+            &merge.conflicts[2],
+            MergeConflict::Definition {
+                definition: Some(1),
+                ..
+            }
+        ));
+
+        assert_eq!(merge.file.definitions[1], ours.definitions[1]);
+    }
+}
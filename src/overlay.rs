@@ -0,0 +1,72 @@
+use crate::RawDbdFile;
+
+impl RawDbdFile {
+    /// Layers the partial file `overlay` on top of `self`.
+    ///
+    /// * Columns in `overlay` are added, replacing columns with the same name.
+    /// * Definitions in `overlay` that are valid for exactly the same versions and version ranges
+    ///   as a definition in `self` replace that definition.
+    /// * Other definitions in `overlay` are placed before the existing definitions so that
+    ///   [`RawDbdFile::specific_version`] prefers them.
+    ///
+    /// This allows documenting local columns without modifying the upstream files.
+    pub fn apply_overlay(&mut self, overlay: RawDbdFile) {
+        for (name, column) in overlay.columns {
+            self.columns.insert(name, column);
+        }
+
+        let mut new_definitions = Vec::new();
+
+        for definition in overlay.definitions {
+            let existing = self.definitions.iter_mut().find(|a| {
+                a.versions == definition.versions && a.version_ranges == definition.version_ranges
+            });
+
+            if let Some(existing) = existing {
+                *existing = definition;
+            } else {
+                new_definitions.push(definition);
+            }
+        }
+
+        if !new_definitions.is_empty() {
+            new_definitions.append(&mut self.definitions);
+            self.definitions = new_definitions;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{load_file_from_string, Version};
+
+    #[test]
+    fn overlay() {
+        let mut file = load_file_from_string(
+            "COLUMNS\nint ID\nint Flags\n\nBUILD 1.12.1.5875\n$id$ID<32>\nFlags<32>\n\nBUILD 3.3.5.12340\n$id$ID<32>\n",
+            "Map.dbd",
+        )
+        .unwrap();
+        let overlay = load_file_from_string(
+            "COLUMNS\nint CustomFlags // Server specific\n\nBUILD 1.12.1.5875\n$id$ID<32>\nFlags<32>\nCustomFlags<32>\n\nBUILD 3.3.5.12340-3.3.5.12341\n$id$ID<32>\nCustomFlags<32>\n",
+            "Map.dbd",
+        )
+        .unwrap();
+
+        file.apply_overlay(overlay);
+        assert!(file.columns.contains_key("CustomFlags"));
+        assert_eq!(file.definitions.len(), 3);
+
+        let vanilla = file
+            .specific_version(&Version::new(1, 12, 1, 5875))
+            .unwrap();
+        assert_eq!(vanilla.entries.len(), 3);
+
+        let wrath = file
+            .specific_version(&Version::new(3, 3, 5, 12340))
+            .unwrap();
+        assert_eq!(wrath.entries[1].name, "CustomFlags");
+
+        file.into_proper().unwrap();
+    }
+}
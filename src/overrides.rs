@@ -0,0 +1,197 @@
+//! User supplied type information that can not be expressed in `.dbd` files.
+//!
+//! Overrides are applied to the entries during conversion with [`RawDbdFile::into_proper_with_overrides`]
+//! or afterwards with [`DbdFile::apply_type_overrides`], and are used by the codegen backends.
+//!
+//! The configuration format has a single override per line, with comments starting with `//`:
+//!
+//! ```text
+//! Map::Directory path
+//! Map::IsInMap bool
+//! Spell::SchoolMask bitmask SpellSchool
+//! Spell::Effect custom SpellEffect // Must implement From<i32>
+//! ```
+
+use crate::enums::ColumnReference;
+use crate::error::{ConversionError, TypeOverridesError};
+use crate::{DbdCollection, DbdFile, RawDbdFile};
+use std::collections::BTreeMap;
+
+/// Specific domain type of a column.
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum TypeOverride {
+    /// Integer that is only ever `0` or `1`.
+    Bool,
+    /// String that contains a file path.
+    Path,
+    /// Integer where every bit corresponds to a value of the named enum.
+    Bitmask(String),
+    /// User defined type with the given name.
+    ///
+    /// Generated code expects it to be convertible from and into the underlying integer type.
+    Custom(String),
+}
+
+/// Collection of [`TypeOverride`]s for specific columns.
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct TypeOverrides {
+    /// Overrides keyed by the column they apply to.
+    pub overrides: BTreeMap<ColumnReference, TypeOverride>,
+}
+
+impl TypeOverrides {
+    /// Creates an empty set of overrides.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse the configuration format described in the [module documentation](crate::overrides).
+    ///
+    /// # Errors
+    ///
+    /// Errors if a line is not a `Table::Column` followed by a valid override.
+    pub fn parse(contents: &str) -> Result<Self, TypeOverridesError> {
+        let mut s = Self::new();
+
+        for (line_count, line) in contents.lines().enumerate() {
+            let line = line.split_once("//").map_or(line, |a| a.0).trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let err = || TypeOverridesError::InvalidLine {
+                line: line_count + 1,
+                contents: line.to_string(),
+            };
+
+            let mut words = line.split_whitespace();
+            let (table, column) = words
+                .next()
+                .and_then(|a| a.split_once("::"))
+                .ok_or_else(err)?;
+
+            let ty = match (words.next(), words.next()) {
+                (Some("bool"), None) => TypeOverride::Bool,
+                (Some("path"), None) => TypeOverride::Path,
+                (Some("bitmask"), Some(name)) => TypeOverride::Bitmask(name.to_string()),
+                (Some("custom"), Some(name)) => TypeOverride::Custom(name.to_string()),
+                _ => return Err(err()),
+            };
+
+            if words.next().is_some() {
+                return Err(err());
+            }
+
+            s.insert(table, column, ty);
+        }
+
+        Ok(s)
+    }
+
+    /// Adds an override, replacing any previous override for the column.
+    pub fn insert(
+        &mut self,
+        table: impl Into<String>,
+        column: impl Into<String>,
+        ty: TypeOverride,
+    ) -> Option<TypeOverride> {
+        self.overrides.insert(
+            ColumnReference {
+                table: table.into(),
+                column: column.into(),
+            },
+            ty,
+        )
+    }
+
+    /// Finds the override for the column, if any.
+    pub fn get(&self, table: &str, column: &str) -> Option<&TypeOverride> {
+        self.overrides.get(&ColumnReference {
+            table: table.to_string(),
+            column: column.to_string(),
+        })
+    }
+}
+
+impl RawDbdFile {
+    /// Same as [`RawDbdFile::into_proper`], but with the matching `overrides` set on the entries.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the file does not uphold the invariants described in [`ConversionError`].
+    pub fn into_proper_with_overrides(
+        self,
+        overrides: &TypeOverrides,
+    ) -> Result<DbdFile, ConversionError> {
+        let mut file = self.into_proper()?;
+        file.apply_type_overrides(overrides);
+
+        Ok(file)
+    }
+}
+
+impl DbdFile {
+    /// Set the matching `overrides` on the entries.
+    pub fn apply_type_overrides(&mut self, overrides: &TypeOverrides) {
+        let table = self.table_name().to_string();
+
+        for definition in &mut self.definitions {
+            for entry in &mut definition.entries {
+                if let Some(ty) = overrides.get(&table, &entry.name) {
+                    entry.type_override = Some(ty.clone());
+                }
+            }
+        }
+    }
+}
+
+impl DbdCollection {
+    /// Set the matching `overrides` on the entries of all files.
+    pub fn apply_type_overrides(&mut self, overrides: &TypeOverrides) {
+        for file in self.files.values_mut() {
+            file.apply_type_overrides(overrides);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::TypeOverridesError;
+    use crate::load_file_from_string;
+    use crate::overrides::{TypeOverride, TypeOverrides};
+
+    #[test]
+    fn parse_and_apply() {
+        let overrides = TypeOverrides::parse(
+            "// Overrides\nMap::Directory path\nMap::IsInMap bool // 0 or 1\nSpell::SchoolMask bitmask SpellSchool\n",
+        )
+        .unwrap();
+        assert_eq!(
+            overrides.get("Spell", "SchoolMask"),
+            Some(&TypeOverride::Bitmask("SpellSchool".to_string()))
+        );
+
+        let file = load_file_from_string(
+            "COLUMNS\nint ID\nstring Directory\nint IsInMap\n\nBUILD 1.12.1.5875\n$id$ID<32>\nDirectory\nIsInMap<32>\n",
+            "Map.dbd",
+        )
+        .unwrap()
+        .into_proper_with_overrides(&overrides)
+        .unwrap();
+
+        let entries = &file.definitions[0].entries;
+        assert_eq!(entries[0].type_override, None);
+        assert_eq!(entries[1].type_override, Some(TypeOverride::Path));
+        assert_eq!(entries[2].type_override, Some(TypeOverride::Bool));
+
+        assert_eq!(
+            TypeOverrides::parse("// Overrides\nMap::Directory // Missing type"),
+            Err(TypeOverridesError::InvalidLine {
+                line: 2,
+                contents: "Map::Directory".to_string()
+            })
+        );
+        assert!(TypeOverrides::parse("Map::Directory custom").is_err());
+        assert!(TypeOverrides::parse("Directory path").is_err());
+    }
+}
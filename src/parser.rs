@@ -1,116 +1,261 @@
-use crate::error::DbdErrorReason;
+use crate::error::{closest_match, DbdErrorReason, Limit};
 use crate::{
-    ForeignKey, Layout, ParseError, RawColumn, RawDbdFile, RawDefinition, RawEntry, RawType,
-    Version, VersionRange,
+    ForeignKey, Layout, ParseError, ParseLimits, RawColumn, RawDbdFile, RawDefinition, RawEntry,
+    RawType, Version, VersionRange,
 };
 use std::collections::BTreeSet;
 
+/// Single line of a `.dbd` file.
+///
+/// Created from [`parse_events`](crate::parse_events).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Event {
+    /// `COLUMNS` line, starting the section with column declarations.
+    Columns,
+    /// Start of a new definition.
+    ///
+    /// Emitted for the same line as the first [`Event::Build`] or [`Event::Layout`] of the definition.
+    DefinitionStart,
+    /// Column declared under `COLUMNS`.
+    Column(RawColumn),
+    /// `BUILD` line with either a list of builds or a single build range.
+    Build {
+        /// Specific builds.
+        versions: Vec<Version>,
+        /// Build ranges.
+        version_ranges: Vec<VersionRange>,
+    },
+    /// `LAYOUT` line.
+    Layout(Vec<Layout>),
+    /// Entry of the current definition.
+    Entry(RawEntry),
+    /// Text of a `COMMENT` line.
+    Comment(String),
+}
+
+#[derive(Debug, Clone, Copy)]
 enum Mode {
     Column,
     Build,
     Entry,
 }
 
-pub(crate) fn parse_file(contents: &str, name: String) -> Result<RawDbdFile, ParseError> {
-    let mut file = RawDbdFile::empty(name);
-    let mut mode = Mode::Column;
+/// Iterator over the [`Event`]s of a `.dbd` file.
+///
+/// Created from [`parse_events`](crate::parse_events).
+/// Items are the line number, counted the same way as [`ParseError::line`], and the event of the line.
+/// Empty lines do not produce events.
+#[derive(Debug, Clone)]
+pub struct Events<'a> {
+    lines: std::iter::Enumerate<std::str::Lines<'a>>,
+    mode: Mode,
+    pending: Option<(usize, Event)>,
+}
 
-    let mut layouts = BTreeSet::new();
-    let mut versions = BTreeSet::new();
-    let mut version_ranges = Vec::new();
-    let mut entries = Vec::new();
+impl<'a> Events<'a> {
+    pub(crate) fn new(contents: &'a str) -> Self {
+        Self {
+            lines: contents.lines().enumerate(),
+            mode: Mode::Column,
+            pending: None,
+        }
+    }
 
-    for (line_count, line) in contents.lines().enumerate() {
-        if line.trim().is_empty() {
-            continue;
+    fn parse_line(&mut self, line: &str, line_count: usize) -> Result<Event, ParseError> {
+        if line.starts_with("COLUMNS") {
+            self.mode = Mode::Column;
+            return Ok(Event::Columns);
+        } else if let Some(comment) = line.strip_prefix("COMMENT") {
+            return Ok(Event::Comment(comment.trim().to_string()));
+        } else if line.starts_with("BUILD") || line.starts_with("LAYOUT") {
+            let event = parse_build_or_layout(line, line_count)?;
+
+            return Ok(match self.mode {
+                Mode::Build => event,
+                Mode::Column | Mode::Entry => {
+                    self.mode = Mode::Build;
+                    self.pending = Some((line_count, event));
+                    Event::DefinitionStart
+                }
+            });
         }
 
-        let should_continue = parse_builds(
-            &mut file,
-            &mut mode,
-            &mut layouts,
-            &mut versions,
-            &mut version_ranges,
-            &mut entries,
-            line,
-            line_count,
-        )?;
+        match self.mode {
+            Mode::Column => Ok(Event::Column(parse_column(line, line_count)?)),
+            Mode::Entry | Mode::Build => {
+                self.mode = Mode::Entry;
+                Ok(Event::Entry(parse_entry(line, line_count)?))
+            }
+        }
+    }
+}
+
+impl Iterator for Events<'_> {
+    type Item = Result<(usize, Event), ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(pending) = self.pending.take() {
+            return Some(Ok(pending));
+        }
+
+        loop {
+            let (line_count, line) = self.lines.next()?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            return Some(self.parse_line(line, line_count).map(|a| (line_count, a)));
+        }
+    }
+}
+
+pub(crate) fn parse_file(contents: &str, name: String) -> Result<RawDbdFile, ParseError> {
+    parse_file_with_limits(contents, name, &ParseLimits::new())
+}
+
+pub(crate) fn parse_file_with_limits(
+    contents: &str,
+    name: String,
+    limits: &ParseLimits,
+) -> Result<RawDbdFile, ParseError> {
+    ParseLimits::check(limits.max_file_size, Limit::FileSize, contents.len(), 0)?;
+    if limits.max_line_length.is_some() {
+        for (line_count, line) in contents.lines().enumerate() {
+            ParseLimits::check(
+                limits.max_line_length,
+                Limit::LineLength,
+                line.len(),
+                line_count,
+            )?;
+        }
+    }
 
-        if should_continue {
-            continue;
+    let mut columns = 0;
+    let mut definitions = 0;
+    let mut entries = 0;
+
+    let mut file = RawDbdFile::empty(name);
+    let mut definition: Option<RawDefinition> = None;
+    let mut comments = Vec::new();
+
+    fn finish(
+        file: &mut RawDbdFile,
+        definition: Option<RawDefinition>,
+        comments: &mut Vec<String>,
+    ) {
+        if let Some(mut definition) = definition {
+            normalize_versions(&mut definition.versions, &definition.version_ranges);
+            definition.comments = std::mem::take(comments);
+            file.add_database(definition);
         }
+    }
+
+    for event in Events::new(contents) {
+        let (line_count, event) = event?;
+
+        match event {
+            Event::Columns => {}
+            Event::DefinitionStart => {
+                definitions += 1;
+                ParseLimits::check(
+                    limits.max_definitions,
+                    Limit::Definitions,
+                    definitions,
+                    line_count,
+                )?;
+
+                finish(&mut file, definition.take(), &mut comments);
+                definition = Some(RawDefinition::default());
+            }
+            Event::Column(column) => {
+                columns += 1;
+                ParseLimits::check(limits.max_columns, Limit::Columns, columns, line_count)?;
 
-        match mode {
-            Mode::Column => {
-                let (ty_name, split) = match line.split_once(' ') {
-                    None => {
-                        return Err(ParseError::new(
-                            0,
-                            line_count,
-                            DbdErrorReason::NoSpaceInColumn,
-                        ));
-                    }
-                    Some(i) => i,
-                };
-                let (name, comment) = split_comment(split);
-
-                let (ty, foreign_key) = if let Some(i) = ty_name.find('<') {
-                    if let Some(j) = ty_name[i..].find('>') {
-                        let ty = ty_to_type(&ty_name[..i], line_count, i)?;
-
-                        let foreign_key = &ty_name[i + 1..j + i];
-                        let foreign_key =
-                            if let Some((database, column)) = foreign_key.split_once("::") {
-                                ForeignKey::new(database.to_string(), column.to_string())
-                            } else {
-                                return Err(ParseError::new(
-                                    i,
-                                    line_count,
-                                    DbdErrorReason::NoDoubleColonInForeignKey,
-                                ));
-                            };
-
-                        (ty, Some(foreign_key))
-                    } else {
-                        return Err(ParseError::new(
-                            i,
-                            line_count,
-                            DbdErrorReason::NoClosingForeignKeyAngleBracket,
-                        ));
-                    }
-                } else {
-                    (ty_to_type(ty_name, line_count, ty_name.len() + 1)?, None)
-                };
-
-                let (name, verified) = if name.ends_with('?') {
-                    (name.replace('?', ""), false)
-                } else {
-                    (name.to_string(), true)
-                };
-
-                let column = RawColumn::new(name, ty, foreign_key, verified, comment);
                 file.add_column(column);
             }
-            Mode::Entry | Mode::Build => {
-                parse_entry(&mut mode, &mut entries, line, line_count)?;
+            Event::Build {
+                versions,
+                version_ranges,
+            } => {
+                let definition = definition.get_or_insert_with(RawDefinition::default);
+                definition.versions.extend(versions);
+                definition.version_ranges.extend(version_ranges);
+            }
+            Event::Layout(layouts) => {
+                let definition = definition.get_or_insert_with(RawDefinition::default);
+                definition.layouts.extend(layouts);
+            }
+            Event::Entry(entry) => {
+                entries += 1;
+                ParseLimits::check(limits.max_entries, Limit::Entries, entries, line_count)?;
+
+                definition
+                    .get_or_insert_with(RawDefinition::default)
+                    .entries
+                    .push(entry);
             }
+            Event::Comment(comment) => comments.push(comment),
         }
     }
 
+    finish(&mut file, definition, &mut comments);
+
     Ok(file)
 }
 
-fn parse_entry(
-    mode: &mut Mode,
-    entries: &mut Vec<RawEntry>,
-    line: &str,
-    line_count: usize,
-) -> Result<(), ParseError> {
-    *mode = Mode::Entry;
+fn parse_column(line: &str, line_count: usize) -> Result<RawColumn, ParseError> {
+    let (ty_name, split) = match line.split_once(' ') {
+        None => {
+            return Err(ParseError::new(
+                0,
+                line_count,
+                DbdErrorReason::NoSpaceInColumn,
+            ));
+        }
+        Some(i) => i,
+    };
+    let (name, comment) = split_comment(split);
+
+    let (ty, foreign_key) = if let Some(i) = ty_name.find('<') {
+        if let Some(j) = ty_name[i..].find('>') {
+            let ty = ty_to_type(&ty_name[..i], line_count, i)?;
+
+            let foreign_key = &ty_name[i + 1..j + i];
+            let foreign_key = if let Some((database, column)) = foreign_key.split_once("::") {
+                ForeignKey::new(database.to_string(), column.to_string())
+            } else {
+                return Err(ParseError::new(
+                    i,
+                    line_count,
+                    DbdErrorReason::NoDoubleColonInForeignKey,
+                ));
+            };
+
+            (ty, Some(foreign_key))
+        } else {
+            return Err(ParseError::new(
+                i,
+                line_count,
+                DbdErrorReason::NoClosingForeignKeyAngleBracket,
+            ));
+        }
+    } else {
+        (ty_to_type(ty_name, line_count, ty_name.len() + 1)?, None)
+    };
 
+    let (name, verified) = if name.ends_with('?') {
+        (name.replace('?', ""), false)
+    } else {
+        (name.to_string(), true)
+    };
+
+    Ok(RawColumn::new(name, ty, foreign_key, verified, comment))
+}
+
+fn parse_entry(line: &str, line_count: usize) -> Result<RawEntry, ParseError> {
     let (name, comment) = split_comment(line);
 
-    let (primary_key, inline, relation, name) = if name.starts_with('$') {
+    let (primary_key, inline, relation, unknown_tags, name) = if name.starts_with('$') {
         const OFFSET: usize = 1;
 
         let j = match name[OFFSET..].find('$') {
@@ -127,6 +272,7 @@ fn parse_entry(
         let mut primary_key = false;
         let mut inline = true;
         let mut relation = false;
+        let mut unknown_tags = Vec::new();
 
         for s in annotations {
             match s {
@@ -139,14 +285,16 @@ fn parse_entry(
                 "relation" => {
                     relation = true;
                 }
-                _ => {}
+                s => {
+                    unknown_tags.push(s.to_string());
+                }
             }
         }
         let name = &name[j + OFFSET + 1..];
 
-        (primary_key, inline, relation, name)
+        (primary_key, inline, relation, unknown_tags, name)
     } else {
-        (false, true, false, name)
+        (false, true, false, Vec::new(), name)
     };
 
     let (integer_width, array_size, unsigned, name) = if let Some(i) = name.find('<') {
@@ -186,7 +334,7 @@ fn parse_entry(
         let name = &name[i + j..];
 
         let array_size = if let Some(i) = name.find('[') {
-            let j = match name.find(']') {
+            let j = match name[i..].find(']').map(|o| i + o) {
                 None => {
                     return Err(ParseError::new(
                         i,
@@ -214,7 +362,7 @@ fn parse_entry(
         (Some(integer_width), array_size, unsigned, identifier)
     } else {
         let (array_size, name) = if let Some(i) = name.find('[') {
-            let j = match name.find(']') {
+            let j = match name[i..].find(']').map(|o| i + o) {
                 None => {
                     return Err(ParseError::new(
                         i,
@@ -244,7 +392,7 @@ fn parse_entry(
         (None, array_size, false, name)
     };
 
-    entries.push(RawEntry::new(
+    let mut entry = RawEntry::new(
         name.to_string(),
         comment,
         integer_width,
@@ -253,9 +401,10 @@ fn parse_entry(
         primary_key,
         inline,
         relation,
-    ));
+    );
+    entry.unknown_tags = unknown_tags;
 
-    Ok(())
+    Ok(entry)
 }
 
 fn normalize_versions(versions: &mut BTreeSet<Version>, version_ranges: &[VersionRange]) {
@@ -279,122 +428,86 @@ fn version_normalization() {
     let mut versions = BTreeSet::new();
     versions.insert(Version::new(3, 1, 6, 0));
 
-    let mut version_ranges = vec![VersionRange::new(
+    let version_ranges = vec![VersionRange::new(
         Version::new(3, 0, 0, 0),
         Version::new(3, 2, 5, 0),
     )];
 
-    normalize_versions(&mut versions, &mut version_ranges);
+    normalize_versions(&mut versions, &version_ranges);
 
     assert!(versions.is_empty());
 }
 
-#[allow(clippy::too_many_arguments)]
-fn parse_builds(
-    file: &mut RawDbdFile,
-    mode: &mut Mode,
-    layouts: &mut BTreeSet<Layout>,
-    versions: &mut BTreeSet<Version>,
-    version_ranges: &mut Vec<VersionRange>,
-    entries: &mut Vec<RawEntry>,
-    line: &str,
-    line_count: usize,
-) -> Result<bool, ParseError> {
-    if line.starts_with("COLUMNS") {
-        *mode = Mode::Column;
-        return Ok(true);
-    } else if line.starts_with("COMMENT") {
-        return Ok(true);
-    } else if line.starts_with("BUILD") || line.starts_with("LAYOUT") {
-        match mode {
-            Mode::Column | Mode::Build => {
-                *mode = Mode::Build;
-            }
-            Mode::Entry => {
-                normalize_versions(versions, version_ranges);
+fn parse_build_or_layout(line: &str, line_count: usize) -> Result<Event, ParseError> {
+    if let Some(b) = line.strip_prefix("BUILD ") {
+        const BUILD_OFFSET: usize = 6;
 
-                let database = RawDefinition::new(
-                    versions.clone(),
-                    version_ranges.clone(),
-                    layouts.clone(),
-                    entries.clone(),
-                );
+        let mut versions = Vec::new();
+        let mut version_ranges = Vec::new();
 
-                file.add_database(database);
+        if b.contains(',') {
+            let mut offset = BUILD_OFFSET;
+            for v in b.split(',') {
+                let column = offset + v.len() - v.trim_start().len();
+                versions.push(string_to_version(v.trim(), line_count, column)?);
 
-                versions.clear();
-                version_ranges.clear();
-                layouts.clear();
-                entries.clear();
-
-                *mode = Mode::Build;
+                offset += v.len() + 1;
             }
-        }
+        } else if let Some(i) = b.find('-') {
+            let from = string_to_version(&b[..i], line_count, BUILD_OFFSET)?;
+            let to = string_to_version(&b[i + 1..], line_count, BUILD_OFFSET + i + 1)?;
 
-        if let Some(b) = line.strip_prefix("BUILD ") {
-            const BUILD_OFFSET: usize = 6;
-            if let Some((first, mut b)) = b.split_once(',') {
-                versions.insert(string_to_version(first.trim(), line_count, BUILD_OFFSET)?);
-
-                while let Some(i) = b.find(',') {
-                    versions.insert(string_to_version(b[..i].trim(), line_count, i)?);
+            version_ranges.push(VersionRange::new(from, to));
+        } else {
+            versions.push(string_to_version(b, line_count, BUILD_OFFSET)?);
+        }
 
-                    b = &b[i + 1..];
+        Ok(Event::Build {
+            versions,
+            version_ranges,
+        })
+    } else if let Some(b) = line.strip_prefix("LAYOUT ") {
+        const LAYOUT_SPACE_OFFSET: usize = 7;
+
+        let mut layouts = Vec::new();
+        let mut offset = LAYOUT_SPACE_OFFSET;
+        for l in b.split(',') {
+            let value = match u32::from_str_radix(l.trim(), 16) {
+                Ok(i) => i,
+                Err(_) => {
+                    return Err(ParseError::new(
+                        offset + l.len() - l.trim_start().len(),
+                        line_count,
+                        DbdErrorReason::InvalidLayout(l.trim().to_string()),
+                    ));
                 }
-            } else if b.contains('-') {
-                let i = b.find('-').unwrap();
-
-                let from = string_to_version(&b[..i], line_count, BUILD_OFFSET)?;
-                let to = string_to_version(&b[i + 1..], line_count, BUILD_OFFSET + i + 1)?;
+            };
+            offset += l.len() + 1;
 
-                version_ranges.push(VersionRange::new(from, to));
-            } else {
-                versions.insert(string_to_version(b, line_count, BUILD_OFFSET)?);
-            }
-        } else if let Some(mut b) = line.strip_prefix("LAYOUT ") {
-            if b.contains(',') {
-                while let Some(i) = b.find(',') {
-                    let value = match u32::from_str_radix(&b[..i], 16) {
-                        Ok(i) => i,
-                        Err(_) => {
-                            return Err(ParseError::new(
-                                i,
-                                line_count,
-                                DbdErrorReason::InvalidLayout(b[..i].to_string()),
-                            ));
-                        }
-                    };
-                    b = &b[..i];
-
-                    layouts.insert(Layout::new(value));
-                }
-            } else {
-                const LAYOUT_SPACE_OFFSET: usize = 7;
-
-                let value = match u32::from_str_radix(b, 16) {
-                    Ok(i) => i,
-                    Err(_) => {
-                        return Err(ParseError::new(
-                            LAYOUT_SPACE_OFFSET,
-                            line_count,
-                            DbdErrorReason::InvalidLayout(b.to_string()),
-                        ));
-                    }
-                };
-
-                layouts.insert(Layout::new(value));
-            }
-        } else {
-            unreachable!()
-        };
+            layouts.push(Layout::new(value));
+        }
 
-        return Ok(true);
+        Ok(Event::Layout(layouts))
+    } else if line.starts_with("BUILD") {
+        Err(ParseError::new(
+            0,
+            line_count,
+            DbdErrorReason::InvalidBuild(line.to_string()),
+        ))
+    } else {
+        Err(ParseError::new(
+            0,
+            line_count,
+            DbdErrorReason::InvalidLayout(line.to_string()),
+        ))
     }
-
-    Ok(false)
 }
 
-fn string_to_version(s: &str, line_count: usize, column: usize) -> Result<Version, ParseError> {
+pub(crate) fn string_to_version(
+    s: &str,
+    line_count: usize,
+    column: usize,
+) -> Result<Version, ParseError> {
     let err = || -> Result<Version, ParseError> {
         Err(ParseError::new(
             column,
@@ -466,8 +579,96 @@ fn ty_to_type(ty: &str, line_count: usize, column: usize) -> Result<RawType, Par
             return Err(ParseError::new(
                 column,
                 line_count,
-                DbdErrorReason::InvalidType(v.to_string()),
+                DbdErrorReason::InvalidType {
+                    ty: v.to_string(),
+                    suggestion: closest_match(v, ["int", "float", "locstring", "string"]),
+                },
             ));
         }
     })
 }
+
+#[test]
+fn last_definition_is_kept() {
+    let file = parse_file(
+        "COLUMNS\nint ID\n\nBUILD 1.12.1.5875\n$id$ID<32>\n",
+        "Map.dbd".to_string(),
+    )
+    .unwrap();
+
+    assert_eq!(file.definitions.len(), 1);
+}
+
+#[test]
+fn last_of_several_definitions_is_kept() {
+    let file = parse_file(
+        "COLUMNS\nint ID\nint Flags\n\nBUILD 1.12.1.5875\n$id$ID<32>\n\nLAYOUT 0B14E4AA\nBUILD 3.3.5.12340\n$id$ID<32>\nFlags<32>",
+        "Map.dbd".to_string(),
+    )
+    .unwrap();
+
+    assert_eq!(file.definitions.len(), 2);
+
+    let last = &file.definitions[1];
+    assert!(last.versions.contains(&Version::new(3, 3, 5, 12340)));
+    assert!(last.layouts.contains(&Layout::new(0x0B14E4AA)));
+    assert_eq!(
+        last.entries
+            .iter()
+            .map(|a| a.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["ID", "Flags"]
+    );
+}
+
+#[test]
+fn empty_last_definition_is_kept() {
+    let file = parse_file(
+        "COLUMNS\nint ID\n\nBUILD 1.12.1.5875\n$id$ID<32>\n\nBUILD 3.3.5.12340\n",
+        "Map.dbd".to_string(),
+    )
+    .unwrap();
+
+    assert_eq!(file.definitions.len(), 2);
+
+    let last = &file.definitions[1];
+    assert!(last.versions.contains(&Version::new(3, 3, 5, 12340)));
+    assert!(last.entries.is_empty());
+}
+
+#[test]
+fn build_and_layout_lists() {
+    let file = parse_file(
+        "COLUMNS\nint ID\n\nLAYOUT 4F4F1B22, 0A0B0C0D\nBUILD 1.12.1.5875, 1.12.2.6005, 1.12.3.6141\n$id$ID<32>\n",
+        "Map.dbd".to_string(),
+    )
+    .unwrap();
+
+    let definition = &file.definitions[0];
+    assert_eq!(definition.versions.len(), 3);
+    assert!(definition.layouts.contains(&Layout::new(0x0A0B0C0D)));
+    assert!(definition.layouts.contains(&Layout::new(0x4F4F1B22)));
+}
+
+#[test]
+fn build_and_layout_list_errors() {
+    let err = parse_file(
+        "COLUMNS\nint ID\n\nBUILD 1.12.1.5875, 1.12.2\n$id$ID<32>\n",
+        "Map.dbd".to_string(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ParseError::new(19, 3, DbdErrorReason::InvalidBuild("1.12.2".to_string()))
+    );
+
+    let err = parse_file(
+        "COLUMNS\nint ID\n\nLAYOUT 4F4F1B22,0A0B0C0D, XYZ\n",
+        "Map.dbd".to_string(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ParseError::new(26, 3, DbdErrorReason::InvalidLayout("XYZ".to_string()))
+    );
+}
@@ -0,0 +1,407 @@
+use crate::artifacts::ArtifactManifest;
+use crate::error::ExportPlanError;
+use crate::lint::{toml_array, toml_key, toml_string, toml_without_comment};
+use crate::parser::string_to_version;
+use crate::transform::{DropColumn, RenameColumn, Transform};
+use crate::{load_directory, DbdCollection, Version, VersionSet};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// File name of the [`ArtifactManifest`] written by [`ExportPlan::run`].
+const ARTIFACTS: &str = "artifacts.txt";
+
+/// Output format of an [`ExportPlan`].
+#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum ExportFormat {
+    /// `<table>.json` from [`DbdFile::to_build_json`](crate::DbdFile::to_build_json) for every table.
+    ///
+    /// Requires a build.
+    Json,
+    /// `definitions.lua` from [`DbdCollection::to_build_lua`].
+    ///
+    /// Requires a build.
+    Lua,
+    /// `<table>.toml` from [`DbdFile::to_toml`](crate::DbdFile::to_toml) for every table.
+    Toml,
+    /// `<table>.yaml` from [`DbdFile::to_yaml`](crate::DbdFile::to_yaml) for every table.
+    #[cfg(feature = "yaml")]
+    Yaml,
+    /// The site from [`DbdCollection::to_html_site`].
+    Html,
+    /// `relations.csv` from [`DbdCollection::relations_to_csv`].
+    RelationsCsv,
+    /// `relations.json` from [`DbdCollection::relations_to_json`].
+    RelationsJson,
+}
+
+impl ExportFormat {
+    /// Finds the format from its name in an [`ExportPlan`], like `json` or `relations_csv`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "json" => Self::Json,
+            "lua" => Self::Lua,
+            "toml" => Self::Toml,
+            #[cfg(feature = "yaml")]
+            "yaml" => Self::Yaml,
+            "html" => Self::Html,
+            "relations_csv" => Self::RelationsCsv,
+            "relations_json" => Self::RelationsJson,
+            _ => return None,
+        })
+    }
+
+    /// Name of the format in an [`ExportPlan`].
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Lua => "lua",
+            Self::Toml => "toml",
+            #[cfg(feature = "yaml")]
+            Self::Yaml => "yaml",
+            Self::Html => "html",
+            Self::RelationsCsv => "relations_csv",
+            Self::RelationsJson => "relations_json",
+        }
+    }
+
+    /// Whether the format describes a single build and needs [`ExportPlan::build`].
+    pub const fn requires_build(&self) -> bool {
+        matches!(self, Self::Json | Self::Lua)
+    }
+}
+
+/// Recurring export described in TOML and executed with [`ExportPlan::run`].
+///
+/// ```toml
+/// input = "WoWDBDefs/definitions"
+/// build = "3.3.5.12340"
+/// tables = ["Spell", "SpellIcon"]
+/// format = "json"
+/// destination = "out/wrath"
+/// drop = ["Spell::Unk0"]
+///
+/// [rename]
+/// "Spell::Field_3_3_5_12340_001" = "Category"
+/// ```
+///
+/// `input`, `format` and `destination` are required.
+/// Without `build` every definition is exported, and without `tables` every table is exported.
+/// Columns are renamed with [`RenameColumn`] and then dropped with [`DropColumn`] before anything else.
+/// Paths are relative to the current directory.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ExportPlan {
+    /// Directory of `.dbd` files loaded with [`load_directory`](crate::load_directory).
+    pub input: PathBuf,
+    /// Build to limit the definitions to.
+    pub build: Option<Version>,
+    /// Tables to export, every table if empty.
+    pub tables: Vec<String>,
+    /// Output format.
+    pub format: ExportFormat,
+    /// Columns to rename, applied in order.
+    pub renames: Vec<RenameColumn>,
+    /// Columns to drop, applied in order after [`ExportPlan::renames`].
+    pub drops: Vec<DropColumn>,
+    /// Directory to write the output files to.
+    pub destination: PathBuf,
+}
+
+impl ExportPlan {
+    /// Parses a plan in TOML.
+    ///
+    /// Only the subset of TOML needed for the format described in [`ExportPlan`] is supported.
+    ///
+    /// # Errors
+    ///
+    /// Errors if a line is not valid, if a key is unknown or if a required key is missing.
+    pub fn from_toml(contents: &str) -> Result<Self, ExportPlanError> {
+        let mut input = None;
+        let mut build = None;
+        let mut tables = Vec::new();
+        let mut format = None;
+        let mut renames = Vec::new();
+        let mut drops = Vec::new();
+        let mut destination = None;
+
+        let mut section = "";
+
+        for (i, line) in contents.lines().enumerate() {
+            let err = || ExportPlanError::InvalidLine {
+                line: i + 1,
+                contents: line.to_string(),
+            };
+
+            let line = toml_without_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|a| a.strip_suffix(']')) {
+                section = match name.trim() {
+                    "rename" => "rename",
+                    _ => return Err(err()),
+                };
+                continue;
+            }
+
+            let (key, value) = line.split_once('=').ok_or_else(err)?;
+            let key = toml_key(key.trim()).ok_or_else(err)?;
+            let value = value.trim();
+
+            match (section, key.as_str()) {
+                ("", "input") => input = Some(PathBuf::from(toml_string(value).ok_or_else(err)?)),
+                ("", "build") => {
+                    let value = toml_string(value).ok_or_else(err)?;
+                    build = Some(string_to_version(&value, i, 0).map_err(|_| err())?);
+                }
+                ("", "tables") => tables = toml_array(value).ok_or_else(err)?,
+                ("", "format") => {
+                    format = Some(
+                        toml_string(value)
+                            .and_then(|a| ExportFormat::from_name(&a))
+                            .ok_or_else(err)?,
+                    );
+                }
+                ("", "destination") => {
+                    destination = Some(PathBuf::from(toml_string(value).ok_or_else(err)?));
+                }
+                ("", "drop") => {
+                    for column in toml_array(value).ok_or_else(err)? {
+                        let (table, column) = column.split_once("::").ok_or_else(err)?;
+                        drops.push(DropColumn::new(table, column));
+                    }
+                }
+                ("rename", _) => {
+                    let (table, from) = key.split_once("::").ok_or_else(err)?;
+                    let to = toml_string(value).ok_or_else(err)?;
+                    renames.push(RenameColumn::new(table, from, to));
+                }
+                _ => return Err(err()),
+            }
+        }
+
+        Ok(Self {
+            input: input.ok_or(ExportPlanError::MissingKey("input"))?,
+            build,
+            tables,
+            format: format.ok_or(ExportPlanError::MissingKey("format"))?,
+            renames,
+            drops,
+            destination: destination.ok_or(ExportPlanError::MissingKey("destination"))?,
+        })
+    }
+
+    /// Creates the output files of the plan for `collection`, keyed by file name.
+    ///
+    /// [`ExportPlan::input`] and [`ExportPlan::destination`] are not used.
+    ///
+    /// # Errors
+    ///
+    /// Errors if a transform or table is not valid for the collection,
+    /// or if the format requires a build and the plan does not have one.
+    pub fn outputs(
+        &self,
+        collection: &DbdCollection,
+    ) -> Result<BTreeMap<String, String>, ExportPlanError> {
+        if self.format.requires_build() && self.build.is_none() {
+            return Err(ExportPlanError::BuildRequired(self.format.name()));
+        }
+
+        let mut collection = collection.clone();
+        for rename in &self.renames {
+            rename
+                .apply(&mut collection)
+                .map_err(ExportPlanError::Transform)?;
+        }
+        for column in &self.drops {
+            column
+                .apply(&mut collection)
+                .map_err(ExportPlanError::Transform)?;
+        }
+
+        if !self.tables.is_empty() {
+            let mut files = Vec::with_capacity(self.tables.len());
+            for table in &self.tables {
+                let file = collection
+                    .get(table)
+                    .ok_or_else(|| ExportPlanError::TableNotFound(table.clone()))?;
+                files.push(file.clone());
+            }
+
+            collection = DbdCollection::from_files(files);
+        }
+
+        if let Some(build) = &self.build {
+            collection = collection.subset(&VersionSet::from(*build));
+        }
+
+        let per_table = |extension: &str, f: &dyn Fn(&crate::DbdFile) -> Option<String>| {
+            collection
+                .iter()
+                .filter_map(|file| {
+                    f(file).map(|a| (format!("{}.{}", file.table_name(), extension), a))
+                })
+                .collect()
+        };
+
+        Ok(match self.format {
+            ExportFormat::Json => per_table("json", &|file| {
+                self.build.and_then(|build| file.to_build_json(&build))
+            }),
+            ExportFormat::Lua => {
+                let mut out = BTreeMap::new();
+                if let Some(build) = &self.build {
+                    out.insert(
+                        "definitions.lua".to_string(),
+                        collection.to_build_lua(build),
+                    );
+                }
+                out
+            }
+            ExportFormat::Toml => per_table("toml", &|file| Some(file.to_toml())),
+            #[cfg(feature = "yaml")]
+            ExportFormat::Yaml => per_table("yaml", &|file| Some(file.to_yaml())),
+            ExportFormat::Html => collection.to_html_site(),
+            ExportFormat::RelationsCsv => {
+                let mut out = BTreeMap::new();
+                out.insert("relations.csv".to_string(), collection.relations_to_csv());
+                out
+            }
+            ExportFormat::RelationsJson => {
+                let mut out = BTreeMap::new();
+                out.insert("relations.json".to_string(), collection.relations_to_json());
+                out
+            }
+        })
+    }
+
+    /// Loads [`ExportPlan::input`] and writes [`ExportPlan::outputs`] to [`ExportPlan::destination`],
+    /// creating it if it does not exist.
+    ///
+    /// An [`ArtifactManifest`] of the outputs is written to `artifacts.txt`,
+    /// and nothing is written if the outputs are the same as in the existing manifest and the files are not stale.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the input can not be read or the output can not be written.
+    /// The inner result errors if the input can not be loaded or [`ExportPlan::outputs`] fails.
+    pub fn run(&self) -> std::io::Result<Result<(), ExportPlanError>> {
+        let collection = match load_directory(&self.input)? {
+            Ok(collection) => collection,
+            Err(e) => return Ok(Err(ExportPlanError::Load(e))),
+        };
+
+        let outputs = match self.outputs(&collection) {
+            Ok(outputs) => outputs,
+            Err(e) => return Ok(Err(e)),
+        };
+
+        let manifest = ArtifactManifest::from_outputs(None, &outputs);
+        let manifest_path = self.destination.join(ARTIFACTS);
+        if let Ok(Ok(previous)) = ArtifactManifest::load(&manifest_path) {
+            if previous.compare(&manifest).is_unchanged()
+                && manifest.stale_files(&self.destination)?.is_empty()
+            {
+                return Ok(Ok(()));
+            }
+        }
+
+        std::fs::create_dir_all(&self.destination)?;
+        for (name, contents) in outputs {
+            std::fs::write(self.destination.join(name), contents)?;
+        }
+        std::fs::write(manifest_path, manifest.to_manifest_string())?;
+
+        Ok(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::{ExportPlanError, TransformError};
+    use crate::plan::{ExportFormat, ExportPlan};
+    use crate::transform::RenameColumn;
+    use crate::{load_file_from_string, DbdCollection, Version};
+    use std::path::PathBuf;
+
+    const PLAN: &str = r#"input = "WoWDBDefs/definitions" # Comment
+build = "3.3.5.12340"
+tables = ["Map"]
+format = "json"
+destination = "out"
+drop = ["Map::Unk0"]
+
+[rename]
+"Map::Field_1" = "Expansion"
+"#;
+
+    #[test]
+    fn export_plan() {
+        let plan = ExportPlan::from_toml(PLAN).unwrap();
+        assert_eq!(plan.input, PathBuf::from("WoWDBDefs/definitions"));
+        assert_eq!(plan.build, Some(Version::new(3, 3, 5, 12340)));
+        assert_eq!(plan.format, ExportFormat::Json);
+        assert_eq!(
+            plan.renames,
+            vec![RenameColumn::new("Map", "Field_1", "Expansion")]
+        );
+
+        let file = |name: &str, contents: &str| {
+            load_file_from_string(contents, name)
+                .unwrap()
+                .into_proper()
+                .unwrap()
+        };
+        let collection = DbdCollection::from_files(vec![
+            file(
+                "Map.dbd",
+                "COLUMNS\nint ID\nint Field_1\nint Unk0\n\nBUILD 3.3.5.12340\n$id$ID<32>\nField_1<8>\nUnk0<32>\n",
+            ),
+            file("Spell.dbd", "COLUMNS\nint ID\n\nBUILD 3.3.5.12340\n$id$ID<32>\n"),
+        ]);
+
+        let outputs = plan.outputs(&collection).unwrap();
+        assert_eq!(outputs.keys().collect::<Vec<_>>(), vec!["Map.json"]);
+        assert!(outputs["Map.json"].contains("\"Expansion\""));
+        assert!(!outputs["Map.json"].contains("\"Unk0\""));
+
+        let mut plan = plan;
+        plan.tables = vec!["Missing".to_string()];
+        assert_eq!(
+            plan.outputs(&collection),
+            Err(ExportPlanError::TableNotFound("Missing".to_string()))
+        );
+
+        plan.tables.clear();
+        plan.drops[0].column = "Unk1".to_string();
+        assert_eq!(
+            plan.outputs(&collection),
+            Err(ExportPlanError::Transform(TransformError::ColumnNotFound {
+                table: "Map".to_string(),
+                column: "Unk1".to_string(),
+            }))
+        );
+
+        plan.build = None;
+        assert_eq!(
+            plan.outputs(&collection),
+            Err(ExportPlanError::BuildRequired("json"))
+        );
+
+        assert_eq!(
+            ExportPlan::from_toml("input = \"a\"\nformat = \"xml\"\n"),
+            Err(ExportPlanError::InvalidLine {
+                line: 2,
+                contents: "format = \"xml\"".to_string()
+            })
+        );
+        assert!(matches!(
+            ExportPlan::from_toml("build = \"3.3.5\"\n"),
+            Err(ExportPlanError::InvalidLine { line: 1, .. })
+        ));
+        assert_eq!(
+            ExportPlan::from_toml("input = \"a\"\nformat = \"toml\"\n"),
+            Err(ExportPlanError::MissingKey("destination"))
+        );
+    }
+}
@@ -0,0 +1,12 @@
+//! Re-exports of the items most users need.
+//!
+//! ```rust
+//! use wowdbdefs_rs::prelude::*;
+//! ```
+
+pub use crate::error::{ConversionError, LoadError, ParseError};
+pub use crate::{
+    load_directory, load_file, load_file_from_string, write_to_file, DbdCollection, DbdFile,
+    Definition, Entry, ForeignKey, Layout, RawDbdFile, ScalarType, Type, Version, VersionRange,
+    VersionSet,
+};
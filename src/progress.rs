@@ -0,0 +1,25 @@
+/// Progress of a bulk operation over many files or tables.
+///
+/// Passed to the callback of for example [`load_directory_with_progress`](crate::load_directory_with_progress)
+/// and [`DbdCollection::lints_with_progress`](crate::DbdCollection::lints_with_progress)
+/// after every file or table.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Progress<'a> {
+    /// Amount of files or tables that are done, including this one.
+    pub done: usize,
+    /// Total amount of files or tables.
+    pub total: usize,
+    /// Name of the file or table that was just done.
+    pub name: &'a str,
+}
+
+impl Progress<'_> {
+    /// Fraction of the operation that is done, from `0.0` to `1.0`.
+    pub fn fraction(&self) -> f64 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.done as f64 / self.total as f64
+        }
+    }
+}
@@ -0,0 +1,497 @@
+//! Skeleton definitions for tables without a definition for a build.
+//!
+//! [`propose_definition`] guesses the columns of a table from its [`DbHeader`] and a sample of records,
+//! as a starting point for contributing a definition.
+//! Every column is unverified and named after its index, for example `Field_03`.
+//!
+//! Fields are guessed from their values in the sampled records:
+//!
+//! * Values that are all offsets to the start of a string in the string block are strings.
+//! * Values that are all reasonably sized floating point numbers are floats.
+//! * Other values are integers, unsigned if any value is too large to be a small negative number.
+//! * The first field is the `$id$` if it is an increasing integer.
+//!
+//! [`refine_definition`] then checks every value of the columns to find [`Suggestion`]s
+//! for better types and likely foreign keys, each with a confidence score.
+
+use crate::header::{DbFormat, DbHeader};
+use crate::{ForeignKey, RawColumn, RawDbdFile, RawDefinition, RawEntry, RawType};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Smallest value of a signed integer that is not guessed to be unsigned.
+const SMALLEST_NEGATIVE: i64 = -0x10000;
+
+/// Smallest fraction of values that must match for a float or foreign key suggestion.
+const MATCHING_FRACTION: f32 = 0.9;
+
+/// Proposes a definition for a table with `header` from a sample of its records.
+///
+/// `records` are the raw bytes of the sampled records, and `strings` is the string block of the file.
+/// Records shorter than [`DbHeader::record_size`] are ignored.
+///
+/// For [`DbFormat::Wdbc`] every field is 32 bits.
+/// Other formats have the size of the record divided between the fields if possible,
+/// and 32 bit fields otherwise.
+///
+/// The returned file has no name and a single definition with no builds,
+/// and the layout of the header if any.
+pub fn propose_definition(header: &DbHeader, records: &[&[u8]], strings: &[u8]) -> RawDbdFile {
+    let record_size = header.record_size as usize;
+    let records: Vec<&[u8]> = records
+        .iter()
+        .filter(|a| a.len() >= record_size)
+        .copied()
+        .collect();
+
+    let widths = field_widths(header);
+    let digits = widths.len().saturating_sub(1).to_string().len();
+
+    let mut file = RawDbdFile::empty(String::new());
+    let mut definition = RawDefinition::new(
+        BTreeSet::new(),
+        Vec::new(),
+        header.layout_hash.iter().cloned().collect(),
+        Vec::new(),
+    );
+
+    let mut offset = 0;
+    for (i, width) in widths.into_iter().enumerate() {
+        let values = column_values(&records, offset, width);
+        offset += width;
+
+        let guess = guess_field(&values, width, strings);
+        let primary_key = i == 0 && guess == Guess::Int && is_increasing(&values);
+
+        let name = if primary_key {
+            "ID".to_string()
+        } else {
+            format!("Field_{:0digits$}", i, digits = digits)
+        };
+
+        let (ty, integer_width, unsigned) = match guess {
+            Guess::Int => (
+                RawType::Int,
+                Some((width * 8) as u8),
+                is_unsigned(&values, width),
+            ),
+            Guess::Float => (RawType::Float, None, false),
+            Guess::String => (RawType::String, None, false),
+        };
+
+        file.columns.insert(
+            name.clone(),
+            RawColumn::new(name.clone(), ty, None, false, None),
+        );
+        definition.entries.push(RawEntry::new(
+            name,
+            None,
+            integer_width,
+            None,
+            unsigned,
+            primary_key,
+            true,
+            false,
+        ));
+    }
+
+    file.definitions.push(definition);
+    file
+}
+
+/// Improvement to a proposed column found by [`refine_definition`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suggestion {
+    /// Name of the column.
+    pub column: String,
+    /// What to change.
+    pub kind: SuggestionKind,
+    /// How likely the suggestion is to be correct, from 0 to 1.
+    ///
+    /// Suggestions based on few distinct values have a lower confidence.
+    pub confidence: f32,
+}
+
+/// Kind of [`Suggestion`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum SuggestionKind {
+    /// The integer column has float bit patterns in almost all non zero values.
+    Float,
+    /// Every value of the integer column fits in a smaller integer.
+    ///
+    /// The width of a [`DbFormat::Wdbc`] field is always 32 bits,
+    /// but the smaller width can indicate that the field is split into multiple columns.
+    IntegerWidth {
+        /// Smallest width that fits every value.
+        width: u8,
+        /// Signedness of the values.
+        unsigned: bool,
+    },
+    /// Almost all non zero values of the integer column are IDs of another table.
+    ForeignKey(ForeignKey),
+}
+
+impl Suggestion {
+    /// Changes the column in `file`, [`SuggestionKind::IntegerWidth`] changes every entry of the column.
+    pub fn apply(&self, file: &mut RawDbdFile) {
+        let column = match file.columns.get_mut(&self.column) {
+            Some(c) => c,
+            None => return,
+        };
+
+        match &self.kind {
+            SuggestionKind::Float => column.ty = RawType::Float,
+            SuggestionKind::ForeignKey(key) => column.foreign_key = Some(key.clone()),
+            SuggestionKind::IntegerWidth { .. } => {}
+        }
+
+        for definition in &mut file.definitions {
+            for entry in &mut definition.entries {
+                if entry.name != self.column {
+                    continue;
+                }
+
+                match &self.kind {
+                    SuggestionKind::Float => {
+                        entry.integer_width = None;
+                        entry.unsigned = false;
+                    }
+                    SuggestionKind::IntegerWidth { width, unsigned } => {
+                        entry.integer_width = Some(*width);
+                        entry.unsigned = *unsigned;
+                    }
+                    SuggestionKind::ForeignKey(_) => {}
+                }
+            }
+        }
+    }
+}
+
+/// Checks every value of the integer columns of a [`propose_definition`] `file`
+/// to suggest floats, smaller integer widths and foreign keys.
+///
+/// `records` should be every record of the file, with fields at the offsets of the entries of the first definition.
+/// `known_ids` has the IDs of other tables, keyed by table name,
+/// and non zero values are compared to them to find foreign keys to the `ID` column.
+/// If several tables match, the one with the fewest IDs is suggested.
+///
+/// Suggestions are sorted by column and are not applied, use [`Suggestion::apply`].
+pub fn refine_definition(
+    file: &RawDbdFile,
+    records: &[&[u8]],
+    known_ids: &BTreeMap<String, BTreeSet<u64>>,
+) -> Vec<Suggestion> {
+    let definition = match file.definitions.first() {
+        Some(d) => d,
+        None => return Vec::new(),
+    };
+
+    let mut suggestions = Vec::new();
+    let mut offset = 0;
+
+    for entry in &definition.entries {
+        let width = entry.integer_width.map_or(4, |a| usize::from(a / 8));
+        let values = column_values(records, offset, width);
+        offset += width;
+
+        let is_integer = file
+            .find_column(entry)
+            .map_or(false, |a| a.ty == RawType::Int);
+        if !is_integer || entry.primary_key {
+            continue;
+        }
+
+        let non_zero: Vec<u64> = values.iter().copied().filter(|a| *a != 0).collect();
+        let distinct: BTreeSet<u64> = non_zero.iter().copied().collect();
+        if distinct.is_empty() {
+            continue;
+        }
+        // One distinct value is as likely to be a coincidence as not.
+        let certainty = distinct.len() as f32 / (distinct.len() as f32 + 1.0);
+
+        let mut suggest = |kind: SuggestionKind, fraction: f32| {
+            suggestions.push(Suggestion {
+                column: entry.name.clone(),
+                kind,
+                confidence: fraction * certainty,
+            });
+        };
+
+        let floats = fraction(&non_zero, is_float);
+        if width == 4 && floats >= MATCHING_FRACTION {
+            suggest(SuggestionKind::Float, floats);
+            continue;
+        }
+
+        let unsigned = is_unsigned(&values, width);
+        let fits = |bits: u32| {
+            values.iter().all(|value| {
+                let value = sign_extend(*value, width);
+                if unsigned {
+                    value >= 0 && value < 1 << bits
+                } else {
+                    value >= -(1 << (bits - 1)) && value < 1 << (bits - 1)
+                }
+            })
+        };
+        if let Some(bits) = [8, 16, 32].iter().copied().find(|a| fits(*a)) {
+            if bits < width as u32 * 8 {
+                suggest(
+                    SuggestionKind::IntegerWidth {
+                        width: bits as u8,
+                        unsigned,
+                    },
+                    1.0,
+                );
+            }
+        }
+
+        let foreign_key = known_ids
+            .iter()
+            .map(|(table, ids)| (table, ids, fraction(&non_zero, |a| ids.contains(&a))))
+            .filter(|(_, _, matching)| *matching >= MATCHING_FRACTION)
+            .min_by(|a, b| {
+                b.2.partial_cmp(&a.2)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .then(a.1.len().cmp(&b.1.len()))
+            });
+        if let Some((table, _, matching)) = foreign_key {
+            suggest(
+                SuggestionKind::ForeignKey(ForeignKey::new(table.clone(), "ID".to_string())),
+                matching,
+            );
+        }
+    }
+
+    suggestions
+}
+
+fn fraction(values: &[u64], f: impl Fn(u64) -> bool) -> f32 {
+    if values.is_empty() {
+        return 0.0;
+    }
+
+    values.iter().filter(|a| f(**a)).count() as f32 / values.len() as f32
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Guess {
+    Int,
+    Float,
+    String,
+}
+
+fn field_widths(header: &DbHeader) -> Vec<usize> {
+    let record_size = header.record_size as usize;
+    let field_count = header.field_count as usize;
+
+    if header.format != DbFormat::Wdbc && field_count != 0 && record_size % field_count == 0 {
+        let width = record_size / field_count;
+        if matches!(width, 1 | 2 | 4 | 8) {
+            return vec![width; field_count];
+        }
+    }
+
+    vec![4; record_size / 4]
+}
+
+fn column_values(records: &[&[u8]], offset: usize, width: usize) -> Vec<u64> {
+    records
+        .iter()
+        .filter_map(|record| record.get(offset..offset + width))
+        .map(read_value)
+        .collect()
+}
+
+fn read_value(bytes: &[u8]) -> u64 {
+    let mut value = [0_u8; 8];
+    value[..bytes.len()].copy_from_slice(bytes);
+    u64::from_le_bytes(value)
+}
+
+fn guess_field(values: &[u64], width: usize, strings: &[u8]) -> Guess {
+    if width != 4 || values.iter().all(|a| *a == 0) {
+        return Guess::Int;
+    }
+
+    let is_string = |value: u64| {
+        let value = value as usize;
+        value == 0 || (value < strings.len() && strings[value - 1] == 0)
+    };
+    let has_text = values
+        .iter()
+        .any(|a| strings.get(*a as usize).map_or(false, |a| *a != 0));
+    if has_text && values.iter().all(|a| is_string(*a)) {
+        return Guess::String;
+    }
+
+    if values.iter().all(|a| *a == 0 || is_float(*a)) {
+        return Guess::Float;
+    }
+
+    Guess::Int
+}
+
+/// Returns true if `value` is a normal float that is not too small or large to be a value in a table.
+fn is_float(value: u64) -> bool {
+    let value = f32::from_bits(value as u32);
+    value.is_normal() && (1e-4..1e6).contains(&value.abs())
+}
+
+fn is_increasing(values: &[u64]) -> bool {
+    !values.is_empty() && values[0] != 0 && values.windows(2).all(|a| a[0] < a[1])
+}
+
+fn is_unsigned(values: &[u64], width: usize) -> bool {
+    values
+        .iter()
+        .any(|value| sign_extend(*value, width) < SMALLEST_NEGATIVE)
+}
+
+const fn sign_extend(value: u64, width: usize) -> i64 {
+    let bits = width as u32 * 8;
+    ((value << (64 - bits)) as i64) >> (64 - bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::header::{DbFormat, DbHeader};
+    use crate::propose::{propose_definition, refine_definition, SuggestionKind};
+    use crate::{ForeignKey, Layout, RawEntry, RawType};
+    use std::collections::{BTreeMap, BTreeSet};
+
+    fn record(values: &[u32]) -> Vec<u8> {
+        values.iter().flat_map(|a| a.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn propose() {
+        let header = DbHeader {
+            format: DbFormat::Wdbc,
+            record_count: 2,
+            field_count: 5,
+            record_size: 20,
+            table_hash: None,
+            layout_hash: None,
+            build: None,
+        };
+        let records = [
+            record(&[1, 1, 1.5_f32.to_bits(), -1_i32 as u32, 0]),
+            record(&[2, 5, 2.25_f32.to_bits(), 3, 0xF000_0000]),
+            record(&[3, 0]),
+        ];
+        let records: Vec<&[u8]> = records.iter().map(|a| a.as_slice()).collect();
+
+        let file = propose_definition(&header, &records, b"\0abc\0def\0");
+
+        let entries = &file.definitions[0].entries;
+        assert_eq!(
+            entries[0],
+            RawEntry::new(
+                "ID".to_string(),
+                None,
+                Some(32),
+                None,
+                false,
+                true,
+                true,
+                false
+            )
+        );
+        assert_eq!(file.find_column(&entries[1]).unwrap().ty, RawType::String);
+        assert_eq!(file.find_column(&entries[2]).unwrap().ty, RawType::Float);
+        assert_eq!(
+            entries[3],
+            RawEntry::new(
+                "Field_3".to_string(),
+                None,
+                Some(32),
+                None,
+                false,
+                false,
+                true,
+                false
+            )
+        );
+        assert!(entries[4].unsigned);
+        assert!(!file.find_column(&entries[4]).unwrap().verified);
+
+        let header = DbHeader {
+            format: DbFormat::Wdc3,
+            field_count: 2,
+            record_size: 4,
+            layout_hash: Some(Layout::new(0x43E6E8A6)),
+            ..header
+        };
+        let file = propose_definition(&header, &[&[1, 0, 0xFF, 0xFF]], &[]);
+        assert_eq!(
+            file.definitions[0].layouts.iter().collect::<Vec<_>>(),
+            vec![&Layout::new(0x43E6E8A6)]
+        );
+        assert_eq!(file.definitions[0].entries[1].integer_width, Some(16));
+        assert!(!file.definitions[0].entries[1].unsigned);
+    }
+
+    #[test]
+    fn refine() {
+        let header = DbHeader {
+            format: DbFormat::Wdbc,
+            record_count: 3,
+            field_count: 3,
+            record_size: 12,
+            table_hash: None,
+            layout_hash: None,
+            build: None,
+        };
+        let records = [
+            record(&[1, 0, 0]),
+            record(&[2, 1.5_f32.to_bits(), 5]),
+            record(&[3, 2.25_f32.to_bits(), 7]),
+        ];
+        let records: Vec<&[u8]> = records.iter().map(|a| a.as_slice()).collect();
+
+        // Only the first record is sampled, so the floats are not seen.
+        let mut file = propose_definition(&header, &records[..1], &[]);
+        assert_eq!(file.columns["Field_1"].ty, RawType::Int);
+
+        let mut known_ids = BTreeMap::new();
+        known_ids.insert("Map".to_string(), (0..10).collect::<BTreeSet<u64>>());
+        known_ids.insert("Spell".to_string(), (0..1000).collect::<BTreeSet<u64>>());
+
+        let suggestions = refine_definition(&file, &records, &known_ids);
+        assert_eq!(
+            suggestions
+                .iter()
+                .map(|a| (a.column.as_str(), a.kind.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Field_1", SuggestionKind::Float),
+                (
+                    "Field_2",
+                    SuggestionKind::IntegerWidth {
+                        width: 8,
+                        unsigned: false
+                    }
+                ),
+                (
+                    "Field_2",
+                    SuggestionKind::ForeignKey(ForeignKey::new(
+                        "Map".to_string(),
+                        "ID".to_string()
+                    ))
+                ),
+            ]
+        );
+        assert!((suggestions[2].confidence - 2.0 / 3.0).abs() < f32::EPSILON);
+
+        for suggestion in &suggestions {
+            suggestion.apply(&mut file);
+        }
+        assert_eq!(file.columns["Field_1"].ty, RawType::Float);
+        assert_eq!(file.definitions[0].entries[1].integer_width, None);
+        assert_eq!(file.definitions[0].entries[2].integer_width, Some(8));
+        assert_eq!(
+            file.columns["Field_2"].foreign_key,
+            Some(ForeignKey::new("Map".to_string(), "ID".to_string()))
+        );
+    }
+}
@@ -0,0 +1,245 @@
+//! Where the information about an entry comes from.
+//!
+//! Provenance is written as tags in the comment of an entry, after any other text:
+//!
+//! ```text
+//! Flags<32> // Unknown bits @source(inferred) @note(only set for raids)
+//! ```
+//!
+//! Since the tags are part of the comment they are kept by [`write_to_file`](crate::write_to_file).
+//! Use [`RequireProvenance`] with a [`Linter`](crate::lint::Linter) to require provenance for unverified columns.
+
+use crate::lint::{Lint, Rule, RuleContext};
+use crate::{Entry, RawEntry};
+use std::fmt::{Display, Formatter};
+
+const SOURCE_TAG: &str = "@source(";
+const NOTE_TAG: &str = "@note(";
+
+/// How the information about an entry was found.
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum Source {
+    /// Verified from the client binary, written as `binary`.
+    Binary,
+    /// Inferred from the data, written as `inferred`.
+    Inferred,
+    /// Any other source.
+    Other(String),
+}
+
+impl Source {
+    /// Parses the text inside `@source()`.
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "binary" => Self::Binary,
+            "inferred" => Self::Inferred,
+            name => Self::Other(name.to_string()),
+        }
+    }
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::Binary => f.write_str("binary"),
+            Source::Inferred => f.write_str("inferred"),
+            Source::Other(s) => f.write_str(s),
+        }
+    }
+}
+
+/// Provenance tags of an entry comment.
+///
+/// Created from [`Provenance::parse`], [`RawEntry::provenance`] and [`Entry::provenance`].
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Default)]
+pub struct Provenance {
+    /// Text of the `@source()` tag.
+    pub source: Option<Source>,
+    /// Text of the `@note()` tag, for example the contributor or how the entry was checked.
+    pub note: Option<String>,
+}
+
+impl Provenance {
+    /// Finds the tags in `comment`, or [`None`] if there are no tags.
+    pub fn parse(comment: &str) -> Option<Self> {
+        let source = tag(comment, SOURCE_TAG).map(|a| Source::from_name(a.trim()));
+        let note = tag(comment, NOTE_TAG).map(|a| a.trim().to_string());
+
+        if source.is_none() && note.is_none() {
+            None
+        } else {
+            Some(Self { source, note })
+        }
+    }
+
+    /// Returns `comment` without the tags.
+    pub fn strip(comment: &str) -> String {
+        let mut comment = comment.to_string();
+
+        for t in [SOURCE_TAG, NOTE_TAG] {
+            while let Some(start) = comment.find(t) {
+                let end = comment[start..]
+                    .find(')')
+                    .map_or(comment.len(), |a| start + a + 1);
+                comment.replace_range(start..end, "");
+            }
+        }
+
+        comment.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+}
+
+impl Display for Provenance {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut tags = Vec::new();
+        if let Some(source) = &self.source {
+            tags.push(format!("{}{})", SOURCE_TAG, source));
+        }
+        if let Some(note) = &self.note {
+            tags.push(format!("{}{})", NOTE_TAG, note));
+        }
+
+        f.write_str(&tags.join(" "))
+    }
+}
+
+fn tag<'a>(comment: &'a str, tag: &str) -> Option<&'a str> {
+    let start = comment.find(tag)? + tag.len();
+    let end = comment[start..].find(')')?;
+
+    Some(&comment[start..start + end])
+}
+
+impl RawEntry {
+    /// Provenance tags in [`RawEntry::comment`].
+    pub fn provenance(&self) -> Option<Provenance> {
+        Provenance::parse(self.comment.as_ref()?)
+    }
+
+    /// Replaces the provenance tags in [`RawEntry::comment`], removing them if `provenance` is [`None`].
+    ///
+    /// Text in the comment other than the tags is kept.
+    pub fn set_provenance(&mut self, provenance: Option<&Provenance>) {
+        let text = self
+            .comment
+            .as_ref()
+            .map_or_else(String::new, |a| Provenance::strip(a));
+        let tags = provenance.map_or_else(String::new, |a| a.to_string());
+
+        let comment = format!("{} {}", text, tags);
+        let comment = comment.trim();
+
+        self.comment = if comment.is_empty() {
+            None
+        } else {
+            Some(comment.to_string())
+        };
+    }
+}
+
+impl Entry {
+    /// Provenance tags in [`Entry::comment`].
+    pub fn provenance(&self) -> Option<Provenance> {
+        Provenance::parse(self.comment.as_ref()?)
+    }
+}
+
+/// [`Rule`] that requires entries of unverified columns to have a [`Provenance`] with a source.
+///
+/// Every column is only reported once, at the first entry without a source.
+///
+/// ```rust
+/// use wowdbdefs_rs::lint::{LintConfig, Linter};
+/// use wowdbdefs_rs::provenance::RequireProvenance;
+///
+/// let mut linter = Linter::new(LintConfig::new());
+/// linter.register(RequireProvenance);
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub struct RequireProvenance;
+
+impl Rule for RequireProvenance {
+    fn name(&self) -> &str {
+        "require_provenance"
+    }
+
+    fn check(&self, context: &RuleContext<'_>) -> Vec<Lint> {
+        let mut lints: Vec<Lint> = Vec::new();
+
+        for (i, definition) in context.file.definitions.iter().enumerate() {
+            for entry in &definition.entries {
+                let has_source = entry.provenance().map_or(false, |a| a.source.is_some());
+                if entry.verified
+                    || has_source
+                    || lints.iter().any(|a| a.column.column == entry.name)
+                {
+                    continue;
+                }
+
+                let mut lint =
+                    context.lint(self, &entry.name, "unverified column without a source");
+                if let Some(span) = context.spans.and_then(|a| a.entry(i, &entry.name)) {
+                    lint.span = Some(span);
+                }
+                lints.push(lint);
+            }
+        }
+
+        lints
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lint::{LintConfig, Linter};
+    use crate::provenance::{Provenance, RequireProvenance, Source};
+    use crate::{load_file_from_string, write_to_file, DbdCollection};
+
+    const CONTENTS: &str = "COLUMNS\nint Flags?\nint ID\nint Other?\n\nBUILD 1.12.1.5875\n$id$ID<32>\nFlags<32> // Unknown bits @source(inferred) @note(only set for raids)\nOther<32>\n\n";
+
+    #[test]
+    fn provenance() {
+        let mut raw = load_file_from_string(CONTENTS, "Map.dbd").unwrap();
+        assert_eq!(write_to_file(&raw), CONTENTS);
+
+        let flags = &mut raw.definitions[0].entries[1];
+        let provenance = Provenance {
+            source: Some(Source::Inferred),
+            note: Some("only set for raids".to_string()),
+        };
+        assert_eq!(flags.provenance(), Some(provenance));
+        assert_eq!(
+            Provenance::strip(flags.comment.as_ref().unwrap()),
+            "Unknown bits"
+        );
+
+        let provenance = Provenance {
+            source: Some(Source::Binary),
+            note: None,
+        };
+        flags.set_provenance(Some(&provenance));
+        assert_eq!(
+            flags.comment.as_deref(),
+            Some("Unknown bits @source(binary)")
+        );
+        flags.set_provenance(None);
+        assert_eq!(flags.comment.as_deref(), Some("Unknown bits"));
+
+        let collection =
+            DbdCollection::from_files(vec![load_file_from_string(CONTENTS, "Map.dbd")
+                .unwrap()
+                .into_proper()
+                .unwrap()]);
+        let mut linter = Linter::new(LintConfig::new());
+        linter.register(RequireProvenance);
+        linter.add_source("Map", CONTENTS).unwrap();
+
+        let lints = linter.run(&collection);
+        assert_eq!(lints.len(), 1);
+        assert_eq!(
+            lints[0].0.to_string(),
+            "'Map::Other': unverified column without a source"
+        );
+        assert_eq!(lints[0].0.span.map(|a| a.line), Some(8));
+    }
+}
@@ -0,0 +1,416 @@
+//! Reports about how tables and columns are documented.
+//!
+//! Useful for deciding whether a column can be safely renamed or removed
+//! with the [`transform`](crate::transform) module.
+
+use crate::enums::ColumnReference;
+use crate::{DbdCollection, DbdFile, Version, VersionSet};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Where a column is used.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
+pub struct ColumnUsage {
+    /// Indices into [`DbdFile::definitions`] of the definitions that have the column.
+    pub definitions: Vec<usize>,
+    /// Versions of all definitions that have the column.
+    pub versions: VersionSet,
+    /// Columns with foreign keys that point to the column, sorted.
+    ///
+    /// Only set by [`DbdCollection::column_usage`].
+    pub incoming_foreign_keys: Vec<ColumnReference>,
+}
+
+impl DbdFile {
+    /// Finds the definitions and versions every column is used in, keyed by column name.
+    pub fn column_usage(&self) -> BTreeMap<String, ColumnUsage> {
+        let mut usage: BTreeMap<String, ColumnUsage> = BTreeMap::new();
+
+        for (i, definition) in self.definitions.iter().enumerate() {
+            for entry in &definition.entries {
+                let column = usage.entry(entry.name.clone()).or_default();
+
+                if column.definitions.last() != Some(&i) {
+                    column.definitions.push(i);
+                    column.versions = column.versions.union(&definition.versions);
+                }
+            }
+        }
+
+        usage
+    }
+
+    /// Earliest version of any definition with `column`, or [`None`] if no definition has it.
+    pub fn introduced_in(&self, column: &str) -> Option<Version> {
+        self.column_versions(column).first()
+    }
+
+    /// Earliest version without `column` that is newer than every version with it,
+    /// or [`None`] if the column is still in the newest definition or is not in any definition.
+    ///
+    /// The latest version with the column is the [`VersionSet::last`] of [`ColumnUsage::versions`].
+    pub fn removed_in(&self, column: &str) -> Option<Version> {
+        let last = self.column_versions(column).last()?;
+
+        self.definitions
+            .iter()
+            .filter(|a| !a.entries.iter().any(|e| e.name == column))
+            .flat_map(|a| a.versions.intervals().into_iter().map(|r| r.from))
+            .filter(|a| *a > last)
+            .min()
+    }
+
+    fn column_versions(&self, column: &str) -> VersionSet {
+        self.definitions
+            .iter()
+            .filter(|a| a.entries.iter().any(|e| e.name == column))
+            .fold(VersionSet::new(), |acc, a| acc.union(&a.versions))
+    }
+}
+
+impl DbdCollection {
+    /// Finds the definitions, versions and incoming foreign keys of every column in every table.
+    ///
+    /// Foreign keys to columns that do not exist are not included.
+    pub fn column_usage(&self) -> BTreeMap<ColumnReference, ColumnUsage> {
+        let mut usage = BTreeMap::new();
+
+        for (table, file) in &self.files {
+            for (column, u) in file.column_usage() {
+                usage.insert(ColumnReference::new(table.as_str(), column), u);
+            }
+        }
+
+        let mut incoming: BTreeMap<ColumnReference, BTreeSet<ColumnReference>> = BTreeMap::new();
+        for (table, file) in &self.files {
+            for definition in &file.definitions {
+                for entry in &definition.entries {
+                    if let Some(key) = entry.ty.foreign_key() {
+                        incoming
+                            .entry(ColumnReference::new(
+                                key.database.as_str(),
+                                key.column.as_str(),
+                            ))
+                            .or_default()
+                            .insert(ColumnReference::new(table.as_str(), entry.name.as_str()));
+                    }
+                }
+            }
+        }
+
+        for (target, references) in incoming {
+            if let Some(u) = usage.get_mut(&target) {
+                u.incoming_foreign_keys = references.into_iter().collect();
+            }
+        }
+
+        usage
+    }
+}
+
+/// Whether a [`ConceptEvent`] is the first or last appearance of a column.
+#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum ConceptEventKind {
+    /// The column appears for the first time, see [`DbdFile::introduced_in`].
+    Introduced,
+    /// The column is no longer in the table, see [`DbdFile::removed_in`].
+    Removed,
+}
+
+/// Appearance or disappearance of a column, created from [`DbdCollection::concept_timeline`].
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct ConceptEvent {
+    /// Version the event happens in.
+    pub version: Version,
+    /// What happens to the column.
+    pub kind: ConceptEventKind,
+    /// The column.
+    pub column: ColumnReference,
+}
+
+impl DbdCollection {
+    /// Finds when every column with a name matching `pattern` is introduced and removed across all tables,
+    /// sorted by version.
+    ///
+    /// `pattern` is a subset of regular expressions, matched anywhere in the name unless anchored:
+    ///
+    /// * `.` matches any character.
+    /// * `*`, `+` and `?` repeat the previous character zero or more times, one or more times, or zero or one times.
+    /// * `^` and `$` anchor the pattern to the start and end of the name.
+    /// * `\` matches the next character literally.
+    ///
+    /// For example `SpellVisual` and `.*SpellVisual.*` both match `SpellVisualID`.
+    pub fn concept_timeline(&self, pattern: &str) -> Vec<ConceptEvent> {
+        let pattern: Vec<char> = pattern.chars().collect();
+        let mut events = Vec::new();
+
+        for (table, file) in &self.files {
+            for column in file.column_usage().keys() {
+                if !pattern_matches(&pattern, column) {
+                    continue;
+                }
+
+                let mut event = |version, kind| {
+                    events.push(ConceptEvent {
+                        version,
+                        kind,
+                        column: ColumnReference::new(table.as_str(), column.as_str()),
+                    });
+                };
+
+                if let Some(version) = file.introduced_in(column) {
+                    event(version, ConceptEventKind::Introduced);
+                }
+                if let Some(version) = file.removed_in(column) {
+                    event(version, ConceptEventKind::Removed);
+                }
+            }
+        }
+
+        events.sort();
+        events
+    }
+}
+
+fn pattern_matches(pattern: &[char], text: &str) -> bool {
+    let text: Vec<char> = text.chars().collect();
+
+    if let Some(('^', pattern)) = pattern.split_first() {
+        return match_here(pattern, &text);
+    }
+
+    (0..=text.len()).any(|i| match_here(pattern, &text[i..]))
+}
+
+fn match_here(pattern: &[char], text: &[char]) -> bool {
+    let (atom, rest) = match pattern {
+        [] => return true,
+        ['$'] => return text.is_empty(),
+        ['\\', c, rest @ ..] => (Some(*c), rest),
+        ['.', rest @ ..] => (None, rest),
+        [c, rest @ ..] => (Some(*c), rest),
+    };
+    let matches_atom = |c: &char| atom.map_or(true, |a| a == *c);
+
+    let (min, max, rest) = match rest {
+        ['*', rest @ ..] => (0, text.len(), rest),
+        ['+', rest @ ..] => (1, text.len(), rest),
+        ['?', rest @ ..] => (0, 1, rest),
+        rest => (1, 1, rest),
+    };
+
+    let available = text.iter().take_while(|c| matches_atom(c)).count().min(max);
+    if available < min {
+        return false;
+    }
+
+    (min..=available)
+        .rev()
+        .any(|i| match_here(rest, &text[i..]))
+}
+
+/// How well the tables of a collection are documented for a single version.
+///
+/// Created from [`DbdCollection::coverage_report`].
+/// All table names are without `.dbd` and sorted.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct CoverageReport {
+    /// Version the report is for.
+    pub version: Version,
+    /// Tables with a definition that explicitly lists the version.
+    pub specific: Vec<String>,
+    /// Tables where the version is only covered by a version range.
+    pub range: Vec<String>,
+    /// Tables without a definition for the version.
+    pub missing: Vec<String>,
+}
+
+impl DbdCollection {
+    /// Finds the tables that have no definition for `version`,
+    /// and the tables that only have a definition through a version range.
+    pub fn coverage_report(&self, version: &Version) -> CoverageReport {
+        let mut report = CoverageReport {
+            version: *version,
+            specific: Vec::new(),
+            range: Vec::new(),
+            missing: Vec::new(),
+        };
+
+        for (table, file) in &self.files {
+            let mut covered = false;
+            let mut specific = false;
+
+            for definition in &file.definitions {
+                covered |= definition.versions.contains(version);
+                specific |= definition.versions.versions().any(|a| a == *version);
+            }
+
+            let list = if specific {
+                &mut report.specific
+            } else if covered {
+                &mut report.range
+            } else {
+                &mut report.missing
+            };
+            list.push(table.clone());
+        }
+
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::enums::ColumnReference;
+    use crate::report::{pattern_matches, ConceptEventKind};
+    use crate::{load_file_from_string, DbdCollection, Version, VersionRange, VersionSet};
+
+    #[test]
+    fn column_usage() {
+        let map = load_file_from_string(
+            "COLUMNS\nint ID\nint Unk0\n\nBUILD 1.12.1.5875\n$id$ID<32>\nUnk0<32>\n\nBUILD 3.0.1.8303-3.3.5.12340\n$id$ID<32>\n",
+            "Map.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+        let area = load_file_from_string(
+            "COLUMNS\nint ID\nint<Map::ID> MapID\nint<Map::Missing> Other\n\nBUILD 1.12.1.5875\n$id$ID<32>\nMapID<32>\nOther<32>\n",
+            "AreaTable.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+
+        let usage = map.column_usage();
+        assert_eq!(usage["ID"].definitions, vec![0, 1]);
+        assert_eq!(usage["Unk0"].definitions, vec![0]);
+        assert_eq!(
+            usage["ID"].versions,
+            VersionSet::from(Version::new(1, 12, 1, 5875)).union(&VersionSet::from(
+                VersionRange::new(Version::new(3, 0, 1, 8303), Version::new(3, 3, 5, 12340))
+            ))
+        );
+
+        let usage = DbdCollection::from_files(vec![map, area]).column_usage();
+        assert_eq!(
+            usage[&ColumnReference::new("Map", "ID")].incoming_foreign_keys,
+            vec![ColumnReference::new("AreaTable", "MapID")]
+        );
+        assert!(usage[&ColumnReference::new("Map", "Unk0")]
+            .incoming_foreign_keys
+            .is_empty());
+        assert!(!usage.contains_key(&ColumnReference::new("Map", "Missing")));
+    }
+
+    #[test]
+    fn introduced_and_removed() {
+        let map = load_file_from_string(
+            "COLUMNS\nint ID\nint Unk0\nint Flags\n\nBUILD 3.0.1.8303-3.3.5.12340\n$id$ID<32>\nFlags<32>\n\nBUILD 1.12.1.5875\n$id$ID<32>\nUnk0<32>\n\nBUILD 2.4.3.8606\n$id$ID<32>\nUnk0<32>\nFlags<32>\n",
+            "Map.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+
+        assert_eq!(map.introduced_in("ID"), Some(Version::new(1, 12, 1, 5875)));
+        assert_eq!(map.removed_in("ID"), None);
+
+        assert_eq!(
+            map.introduced_in("Flags"),
+            Some(Version::new(2, 4, 3, 8606))
+        );
+        assert_eq!(map.removed_in("Flags"), None);
+
+        assert_eq!(
+            map.introduced_in("Unk0"),
+            Some(Version::new(1, 12, 1, 5875))
+        );
+        assert_eq!(map.removed_in("Unk0"), Some(Version::new(3, 0, 1, 8303)));
+
+        assert_eq!(map.introduced_in("Missing"), None);
+        assert_eq!(map.removed_in("Missing"), None);
+    }
+
+    #[test]
+    fn patterns() {
+        let matches =
+            |pattern: &str, text: &str| pattern_matches(&pattern.chars().collect::<Vec<_>>(), text);
+
+        assert!(matches(".*SpellVisual.*", "SpellVisualID"));
+        assert!(matches("SpellVisual", "CastSpellVisualID"));
+        assert!(!matches("^SpellVisual", "CastSpellVisualID"));
+        assert!(matches("^Spell.*ID$", "SpellVisualID"));
+        assert!(!matches("ID$", "IDs"));
+        assert!(matches("^Unk_?\\.$", "Unk_."));
+        assert!(!matches("^Unk_?\\.$", "Unk_0"));
+        assert!(matches("^a+b?c*$", "aaac"));
+        assert!(!matches("^a+b?c*$", "bc"));
+    }
+
+    #[test]
+    fn concept_timeline() {
+        let spell = load_file_from_string(
+            "COLUMNS\nint ID\nint SpellVisualID\n\nBUILD 1.12.1.5875\n$id$ID<32>\nSpellVisualID<32>\n\nBUILD 7.0.1.20740\n$id$ID<32>\n",
+            "Spell.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+        let misc = load_file_from_string(
+            "COLUMNS\nint ID\nint SpellVisualID\n\nBUILD 7.0.1.20740\n$id$ID<32>\nSpellVisualID<32>\n",
+            "SpellMisc.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+
+        let timeline = DbdCollection::from_files(vec![spell, misc]).concept_timeline("SpellVisual");
+        assert_eq!(
+            timeline
+                .iter()
+                .map(|a| (a.version, a.kind, a.column.table.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    Version::new(1, 12, 1, 5875),
+                    ConceptEventKind::Introduced,
+                    "Spell"
+                ),
+                (
+                    Version::new(7, 0, 1, 20740),
+                    ConceptEventKind::Introduced,
+                    "SpellMisc"
+                ),
+                (
+                    Version::new(7, 0, 1, 20740),
+                    ConceptEventKind::Removed,
+                    "Spell"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn coverage_report() {
+        let files = vec![
+            ("Map.dbd", "BUILD 3.3.5.12340\n"),
+            ("AreaTable.dbd", "BUILD 3.0.1.8303-3.3.5.12340\n"),
+            ("Spell.dbd", "BUILD 1.12.1.5875\n"),
+            ("Item.dbd", "BUILD 3.3.5.12339, 3.3.5.12340\n"),
+            ("Talent.dbd", "BUILD 3.3.5.12340, 3.3.5.12341\n"),
+        ]
+        .into_iter()
+        .map(|(name, build)| {
+            load_file_from_string(&format!("COLUMNS\nint ID\n\n{}$id$ID<32>\n", build), name)
+                .unwrap()
+                .into_proper()
+                .unwrap()
+        });
+
+        let report =
+            DbdCollection::from_files(files).coverage_report(&Version::new(3, 3, 5, 12340));
+        assert_eq!(report.specific, vec!["Item", "Map", "Talent"]);
+        assert_eq!(report.range, vec!["AreaTable"]);
+        assert_eq!(report.missing, vec!["Spell"]);
+    }
+}
@@ -0,0 +1,656 @@
+//! Batched edits across several files of a [`DbdCollection`].
+//!
+//! An [`EditSession`] applies [`Operation`]s to a copy of the collection,
+//! validates the result as a whole and only then writes the changed files.
+//! Operations can be undone and redone, and the pending operations can be saved with
+//! [`EditSession::operations_to_string`] and loaded again with [`EditSession::apply_operations`].
+//!
+//! ```rust
+//! # use wowdbdefs_rs::DbdCollection;
+//! # use wowdbdefs_rs::session::EditSession;
+//! # fn t(collection: DbdCollection) -> Result<(), Box<dyn std::error::Error>> {
+//! let mut session = EditSession::new(collection);
+//! session.rename_column("Map", "Unk0", "Expansion")?;
+//!
+//! let collection = session.commit("definitions".as_ref())??;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::enums::ColumnReference;
+use crate::error::{EditError, TransformError};
+use crate::transform::{DropColumn, RenameColumn, RetargetForeignKey, Transform};
+use crate::{write_to_file, DbdCollection, ForeignKey};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+/// Single edit in an [`EditSession`].
+///
+/// Operations are written as a single line with the kind followed by the arguments:
+///
+/// ```text
+/// rename Map Unk0 Expansion
+/// drop Map Unk1
+/// retarget AreaTable Other Spell::ID
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum Operation {
+    /// [`RenameColumn`].
+    Rename(RenameColumn),
+    /// [`DropColumn`].
+    Drop(DropColumn),
+    /// [`RetargetForeignKey`].
+    Retarget(RetargetForeignKey),
+}
+
+impl Operation {
+    /// Parses a single line in the format described in [`Operation`].
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`TransformError::InvalidOperation`] if the line is not a known operation
+    /// with the correct amount of arguments.
+    pub fn parse(line: &str) -> Result<Self, TransformError> {
+        let invalid = || TransformError::InvalidOperation(line.to_string());
+        let words: Vec<&str> = line.split_whitespace().collect();
+
+        Ok(match words.as_slice() {
+            ["rename", table, from, to] => Self::Rename(RenameColumn::new(*table, *from, *to)),
+            ["drop", table, column] => Self::Drop(DropColumn::new(*table, *column)),
+            ["retarget", table, column, key] => {
+                let (database, key_column) = key.split_once("::").ok_or_else(invalid)?;
+                Self::Retarget(RetargetForeignKey::new(
+                    *table,
+                    *column,
+                    ForeignKey::new(database.to_string(), key_column.to_string()),
+                ))
+            }
+            _ => return Err(invalid()),
+        })
+    }
+}
+
+impl Transform for Operation {
+    fn apply(&self, collection: &mut DbdCollection) -> Result<(), TransformError> {
+        match self {
+            Operation::Rename(t) => t.apply(collection),
+            Operation::Drop(t) => t.apply(collection),
+            Operation::Retarget(t) => t.apply(collection),
+        }
+    }
+}
+
+impl Display for Operation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operation::Rename(t) => write!(f, "rename {} {} {}", t.table, t.from, t.to),
+            Operation::Drop(t) => write!(f, "drop {} {}", t.table, t.column),
+            Operation::Retarget(t) => write!(
+                f,
+                "retarget {} {} {}::{}",
+                t.table, t.column, t.key.database, t.key.column
+            ),
+        }
+    }
+}
+
+impl From<RenameColumn> for Operation {
+    fn from(t: RenameColumn) -> Self {
+        Self::Rename(t)
+    }
+}
+
+impl From<DropColumn> for Operation {
+    fn from(t: DropColumn) -> Self {
+        Self::Drop(t)
+    }
+}
+
+impl From<RetargetForeignKey> for Operation {
+    fn from(t: RetargetForeignKey) -> Self {
+        Self::Retarget(t)
+    }
+}
+
+/// Edits of a [`DbdCollection`] that are written together.
+///
+/// Created from [`EditSession::new`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct EditSession {
+    original: DbdCollection,
+    collection: DbdCollection,
+    operations: Vec<Operation>,
+    undone: Vec<Operation>,
+}
+
+impl EditSession {
+    /// Starts a session for `collection`. Nothing is changed until [`EditSession::commit`].
+    pub fn new(collection: DbdCollection) -> Self {
+        Self {
+            original: collection.clone(),
+            collection,
+            operations: Vec::new(),
+            undone: Vec::new(),
+        }
+    }
+
+    /// Collection with every edit applied.
+    pub const fn collection(&self) -> &DbdCollection {
+        &self.collection
+    }
+
+    /// Applies `operation` to the collection of the session and adds it to the pending operations.
+    ///
+    /// Operations that were undone can no longer be redone.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the operation fails, in which case the collection and operations are unchanged.
+    pub fn apply(&mut self, operation: impl Into<Operation>) -> Result<(), TransformError> {
+        let operation = operation.into();
+
+        let mut collection = self.collection.clone();
+        operation.apply(&mut collection)?;
+
+        self.collection = collection;
+        self.operations.push(operation);
+        self.undone.clear();
+
+        Ok(())
+    }
+
+    /// Applies every operation in `operations`, in the format of [`EditSession::operations_to_string`].
+    ///
+    /// # Errors
+    ///
+    /// Errors if a line can not be parsed with [`Operation::parse`] or if an operation fails.
+    /// Nothing is applied if a line can not be parsed,
+    /// and the operations before a failing operation are kept.
+    pub fn apply_operations(&mut self, operations: &str) -> Result<(), TransformError> {
+        let operations = operations
+            .lines()
+            .map(str::trim)
+            .filter(|a| !a.is_empty())
+            .map(Operation::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for operation in operations {
+            self.apply(operation)?;
+        }
+
+        Ok(())
+    }
+
+    /// Operations applied since the session was started, in order, without undone operations.
+    pub fn operations(&self) -> &[Operation] {
+        &self.operations
+    }
+
+    /// Writes [`EditSession::operations`] with a single operation per line.
+    pub fn operations_to_string(&self) -> String {
+        self.operations.iter().map(|a| format!("{}\n", a)).collect()
+    }
+
+    /// Undoes the latest operation by applying the operations before it to the original collection again.
+    ///
+    /// Returns the undone operation, or [`None`] if there are no operations.
+    pub fn undo(&mut self) -> Option<&Operation> {
+        let operation = self.operations.pop()?;
+
+        let mut collection = self.original.clone();
+        for operation in &self.operations {
+            // Every operation succeeded when it was applied to the same collection.
+            let _ = operation.apply(&mut collection);
+        }
+        self.collection = collection;
+
+        self.undone.push(operation);
+        self.undone.last()
+    }
+
+    /// Applies the latest undone operation again.
+    ///
+    /// Returns the redone operation, or [`None`] if there are no undone operations.
+    pub fn redo(&mut self) -> Option<&Operation> {
+        let operation = self.undone.pop()?;
+
+        // The operation succeeded when it was applied to the same collection.
+        let _ = operation.apply(&mut self.collection);
+
+        self.operations.push(operation);
+        self.operations.last()
+    }
+
+    /// Applies a [`RenameColumn`], which also updates foreign keys in other tables.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the table or column does not exist.
+    pub fn rename_column(
+        &mut self,
+        table: &str,
+        from: &str,
+        to: &str,
+    ) -> Result<(), TransformError> {
+        self.apply(RenameColumn::new(table, from, to))
+    }
+
+    /// Applies a [`RetargetForeignKey`].
+    ///
+    /// # Errors
+    ///
+    /// Errors if the table or column does not exist.
+    pub fn retarget_foreign_key(
+        &mut self,
+        table: &str,
+        column: &str,
+        key: ForeignKey,
+    ) -> Result<(), TransformError> {
+        self.apply(RetargetForeignKey::new(table, column, key))
+    }
+
+    /// Tables that are different from the collection the session was started with, sorted.
+    pub fn changed_tables(&self) -> Vec<&str> {
+        self.collection
+            .files
+            .iter()
+            .filter(|(table, file)| self.original.get(table) != Some(*file))
+            .map(|(table, _)| table.as_str())
+            .collect()
+    }
+
+    /// Checks the collection of the session and returns the contents of every changed file,
+    /// keyed by file name.
+    ///
+    /// Changed files that exist in `dir` are patched with [`EditSession::patch`],
+    /// so only the lines of the edited columns change.
+    /// Changed files that do not exist in `dir` are written with [`write_to_file`].
+    ///
+    /// # Errors
+    ///
+    /// Errors if an existing file can not be read.
+    /// The inner result errors if a changed file that does not exist in `dir` can not be converted for writing,
+    /// or if a foreign key now points to a column that does not exist in its table.
+    /// Foreign keys to tables that are not in the collection are not checked.
+    pub fn validate(
+        &self,
+        dir: &Path,
+    ) -> std::io::Result<Result<BTreeMap<String, String>, EditError>> {
+        let dangling = dangling_foreign_keys(&self.original);
+        if let Some((column, key)) = dangling_foreign_keys(&self.collection)
+            .into_iter()
+            .find(|a| !dangling.contains(a))
+        {
+            return Ok(Err(EditError::DanglingForeignKey { column, key }));
+        }
+
+        let mut files = BTreeMap::new();
+        for table in self.changed_tables() {
+            let name = format!("{}.dbd", table);
+
+            let contents = match std::fs::read_to_string(dir.join(&name)) {
+                Ok(original) => self.patch(table, &original),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    let file = self.collection.files[table].clone();
+                    match file.into_raw() {
+                        Ok(raw) => write_to_file(&raw),
+                        Err(error) => return Ok(Err(EditError::Conversion { file: name, error })),
+                    }
+                }
+                Err(e) => return Err(e),
+            };
+
+            files.insert(name, contents);
+        }
+
+        Ok(Ok(files))
+    }
+
+    /// Applies [`EditSession::operations`] to the `.dbd` `contents` of `table`.
+    ///
+    /// Only the `COLUMNS` and entry lines of the edited columns are changed,
+    /// everything else including comments, unknown tags, unused columns and the order of lines is kept.
+    pub fn patch(&self, table: &str, contents: &str) -> String {
+        let mut contents = contents.to_string();
+
+        for operation in &self.operations {
+            contents = patch_contents(&contents, table, operation);
+        }
+
+        contents
+    }
+
+    /// Validates the session with [`EditSession::validate`] and writes the changed files to `dir`.
+    ///
+    /// Every changed file is first written to a temporary file in `dir`,
+    /// and the temporary files are only renamed to replace the `.dbd` files after all of them have been written.
+    /// Nothing is written if validation fails.
+    ///
+    /// Returns the collection with every edit applied.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the files can not be read or written. The temporary files are removed on every error.
+    /// Renaming is not atomic across files, so if a rename fails
+    /// the files renamed before it have already been replaced and the rest are unchanged.
+    /// The inner error is the same as for [`EditSession::validate`].
+    pub fn commit(self, dir: &Path) -> std::io::Result<Result<DbdCollection, EditError>> {
+        let files = match self.validate(dir)? {
+            Ok(files) => files,
+            Err(e) => return Ok(Err(e)),
+        };
+
+        let mut written: Vec<(PathBuf, PathBuf)> = Vec::with_capacity(files.len());
+        for (name, contents) in &files {
+            let temporary = dir.join(format!(".{}.tmp", name));
+
+            if let Err(e) = std::fs::write(&temporary, contents) {
+                remove_temporary(&written);
+                let _ = std::fs::remove_file(&temporary);
+
+                return Err(e);
+            }
+
+            written.push((temporary, dir.join(name)));
+        }
+
+        for (i, (temporary, path)) in written.iter().enumerate() {
+            if let Err(e) = std::fs::rename(temporary, path) {
+                remove_temporary(&written[i..]);
+
+                return Err(e);
+            }
+        }
+
+        Ok(Ok(self.collection))
+    }
+
+    /// Discards every edit and returns the collection the session was started with.
+    pub fn rollback(self) -> DbdCollection {
+        self.original
+    }
+}
+
+fn remove_temporary(written: &[(PathBuf, PathBuf)]) {
+    for (temporary, _) in written {
+        let _ = std::fs::remove_file(temporary);
+    }
+}
+
+/// Applies `operation` to the `.dbd` `contents` of `table`, keeping every unrelated line as is.
+fn patch_contents(contents: &str, table: &str, operation: &Operation) -> String {
+    let mut out = String::with_capacity(contents.len());
+    let mut in_columns = false;
+
+    for line in contents.split_inclusive('\n') {
+        let text = line.trim_end_matches(['\n', '\r']);
+        let newline = &line[text.len()..];
+        let trimmed = text.trim();
+
+        let patched = if trimmed == "COLUMNS" {
+            in_columns = true;
+            Some(text.to_string())
+        } else if trimmed.is_empty() {
+            in_columns = false;
+            Some(text.to_string())
+        } else if in_columns {
+            patch_column(text, table, operation)
+        } else if ["BUILD", "LAYOUT", "COMMENT"]
+            .iter()
+            .any(|a| trimmed.starts_with(a))
+        {
+            Some(text.to_string())
+        } else {
+            patch_entry(text, table, operation)
+        };
+
+        if let Some(patched) = patched {
+            out.push_str(&patched);
+            out.push_str(newline);
+        }
+    }
+
+    out
+}
+
+/// Splits `line` into the trimmed code and everything after it, including the comment.
+fn split_comment(line: &str) -> (&str, &str) {
+    let code = line.find("//").map_or(line, |i| &line[..i]).trim_end();
+    (code, &line[code.len()..])
+}
+
+fn patch_column(line: &str, table: &str, operation: &Operation) -> Option<String> {
+    let (code, rest) = split_comment(line);
+    let (ty, name) = match code.trim_start().split_once(char::is_whitespace) {
+        Some((ty, name)) => (ty, name.trim()),
+        None => return Some(line.to_string()),
+    };
+    let (name, unverified) = match name.strip_suffix('?') {
+        Some(name) => (name, "?"),
+        None => (name, ""),
+    };
+
+    let mut new_ty = ty.to_string();
+    let mut new_name = name;
+    match operation {
+        Operation::Rename(t) => {
+            if table == t.table && name == t.from {
+                new_name = &t.to;
+            }
+            new_ty = ty.replace(
+                &format!("<{}::{}>", t.table, t.from),
+                &format!("<{}::{}>", t.table, t.to),
+            );
+        }
+        Operation::Drop(t) => {
+            if table == t.table && name == t.column {
+                return None;
+            }
+        }
+        Operation::Retarget(t) => {
+            if table == t.table && name == t.column {
+                let base = ty.split_once('<').map_or(ty, |a| a.0);
+                new_ty = format!("{}<{}::{}>", base, t.key.database, t.key.column);
+            }
+        }
+    }
+
+    if new_ty == ty && new_name == name {
+        return Some(line.to_string());
+    }
+
+    Some(format!("{} {}{}{}", new_ty, new_name, unverified, rest))
+}
+
+fn patch_entry(line: &str, table: &str, operation: &Operation) -> Option<String> {
+    let (code, rest) = split_comment(line);
+    let code = code.trim_start();
+
+    let start = match code.strip_prefix('$') {
+        Some(tags) => match tags.find('$') {
+            Some(i) => i + 2,
+            None => return Some(line.to_string()),
+        },
+        None => 0,
+    };
+    let end = code[start..]
+        .find(['<', '['])
+        .map_or(code.len(), |i| start + i);
+    let name = &code[start..end];
+
+    match operation {
+        Operation::Rename(t) if table == t.table && name == t.from => Some(format!(
+            "{}{}{}{}",
+            &code[..start],
+            t.to,
+            &code[end..],
+            rest
+        )),
+        Operation::Drop(t) if table == t.table && name == t.column => None,
+        _ => Some(line.to_string()),
+    }
+}
+
+/// Foreign keys to tables in `collection` that do not have the column in any definition.
+fn dangling_foreign_keys(collection: &DbdCollection) -> BTreeSet<(ColumnReference, ForeignKey)> {
+    let mut dangling = BTreeSet::new();
+
+    for (table, file) in &collection.files {
+        for definition in &file.definitions {
+            for entry in &definition.entries {
+                let key = match entry.ty.foreign_key() {
+                    Some(key) => key,
+                    None => continue,
+                };
+
+                let target = match collection.get(&key.database) {
+                    Some(target) => target,
+                    None => continue,
+                };
+
+                let exists = target
+                    .definitions
+                    .iter()
+                    .any(|d| d.entries.iter().any(|e| e.name == key.column));
+                if !exists {
+                    dangling.insert((
+                        ColumnReference::new(table.as_str(), entry.name.as_str()),
+                        key.clone(),
+                    ));
+                }
+            }
+        }
+    }
+
+    dangling
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::enums::ColumnReference;
+    use crate::error::EditError;
+    use crate::error::TransformError;
+    use crate::session::{EditSession, Operation};
+    use crate::transform::DropColumn;
+    use crate::{load_directory, load_file_from_string, DbdCollection, ForeignKey};
+
+    const MAP: &str = "COLUMNS\nint ID\nint Unk0 // Comment\nint Unused?\n\nLAYOUT 0000AAAA\nBUILD 1.12.1.5875, 1.12.1.5876\nCOMMENT Definition comment\n$id$ID<32>\n$custom$Unk0<32> // Entry\n\n";
+    const AREA: &str =
+        "COLUMNS\nint ID\nint<Map::Unk0> MapID\nint Other\n\nBUILD 1.12.1.5875\n$id$ID<32>\nMapID<32>\nOther<32>\n\n";
+    const SPELL: &str = "COLUMNS\nint ID\n\nBUILD 1.12.1.5875\n$id$ID<32>\n\n";
+
+    fn collection() -> DbdCollection {
+        DbdCollection::from_files(vec![
+            load_file_from_string(MAP, "Map.dbd")
+                .unwrap()
+                .into_proper()
+                .unwrap(),
+            load_file_from_string(AREA, "AreaTable.dbd")
+                .unwrap()
+                .into_proper()
+                .unwrap(),
+            load_file_from_string(SPELL, "Spell.dbd")
+                .unwrap()
+                .into_proper()
+                .unwrap(),
+        ])
+    }
+
+    #[test]
+    fn edit_session() {
+        let mut session = EditSession::new(collection());
+        session.rename_column("Map", "Unk0", "Expansion").unwrap();
+        session
+            .retarget_foreign_key(
+                "AreaTable",
+                "Other",
+                ForeignKey::new("Spell".to_string(), "ID".to_string()),
+            )
+            .unwrap();
+        assert_eq!(session.changed_tables(), vec!["AreaTable", "Map"]);
+
+        let dir = std::env::temp_dir().join(format!("wowdbdefs-rs-session-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("Spell.dbd"), SPELL).unwrap();
+        std::fs::write(dir.join("Map.dbd"), MAP).unwrap();
+
+        let collection = session.commit(&dir).unwrap().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.join("Map.dbd")).unwrap(),
+            MAP.replace("Unk0", "Expansion")
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("AreaTable.dbd")).unwrap(),
+            "COLUMNS\nint ID\nint<Map::Expansion> MapID\nint<Spell::ID> Other\n\nBUILD 1.12.1.5875\n$id$ID<32>\nMapID<32>\nOther<32>\n\n"
+        );
+        assert_eq!(load_directory(&dir).unwrap().unwrap(), collection);
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 3);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn dangling_foreign_key() {
+        let mut session = EditSession::new(collection());
+        session.apply(DropColumn::new("Map", "Unk0")).unwrap();
+
+        assert_eq!(
+            session.validate("does-not-exist".as_ref()).unwrap(),
+            Err(EditError::DanglingForeignKey {
+                column: ColumnReference::new("AreaTable", "MapID"),
+                key: ForeignKey::new("Map".to_string(), "Unk0".to_string()),
+            })
+        );
+        assert_eq!(session.rollback(), collection());
+    }
+
+    #[test]
+    fn undo_redo() {
+        let mut session = EditSession::new(collection());
+        session.rename_column("Map", "Unk0", "Expansion").unwrap();
+        session
+            .apply_operations("retarget AreaTable Other Spell::ID\n\ndrop Map Expansion\n")
+            .unwrap();
+
+        let operations =
+            "rename Map Unk0 Expansion\nretarget AreaTable Other Spell::ID\ndrop Map Expansion\n";
+        assert_eq!(session.operations_to_string(), operations);
+        let edited = session.collection().clone();
+
+        assert_eq!(
+            session.undo().map(|a| a.to_string()),
+            Some("drop Map Expansion".to_string())
+        );
+        assert_eq!(
+            session.undo(),
+            Some(&Operation::parse("retarget AreaTable Other Spell::ID").unwrap())
+        );
+        assert!(session.redo().is_some());
+        assert!(session.redo().is_some());
+        assert_eq!(session.redo(), None);
+        assert_eq!(session.collection(), &edited);
+
+        while session.undo().is_some() {}
+        assert_eq!(session.collection(), &collection());
+        assert!(session.changed_tables().is_empty());
+
+        let mut loaded = EditSession::new(collection());
+        loaded.apply_operations(operations).unwrap();
+        assert_eq!(loaded.collection(), &edited);
+        assert_eq!(
+            loaded.patch("Map", MAP),
+            "COLUMNS\nint ID\nint Unused?\n\nLAYOUT 0000AAAA\nBUILD 1.12.1.5875, 1.12.1.5876\nCOMMENT Definition comment\n$id$ID<32>\n\n"
+        );
+        assert_eq!(
+            loaded.patch("AreaTable", AREA),
+            "COLUMNS\nint ID\nint<Map::Expansion> MapID\nint<Spell::ID> Other\n\nBUILD 1.12.1.5875\n$id$ID<32>\nMapID<32>\nOther<32>\n\n"
+        );
+
+        assert_eq!(
+            loaded.apply_operations("rename Map"),
+            Err(TransformError::InvalidOperation("rename Map".to_string()))
+        );
+        assert_eq!(loaded.operations().len(), 3);
+    }
+}
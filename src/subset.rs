@@ -0,0 +1,210 @@
+use crate::{DbdCollection, DbdFile, RawDbdFile, Version, VersionSet};
+use std::collections::BTreeMap;
+
+impl RawDbdFile {
+    /// Creates a file with only the definitions valid for at least one version in `versions`.
+    ///
+    /// The versions of the remaining definitions are limited to those in `versions`
+    /// and columns that are not used by any remaining definition are removed.
+    ///
+    /// Use [`write_to_file`](crate::write_to_file) to write the subset.
+    pub fn subset(&self, versions: &VersionSet) -> RawDbdFile {
+        let mut s = RawDbdFile::empty(self.name.clone());
+
+        for definition in &self.definitions {
+            let valid = VersionSet::from_parts(&definition.versions, &definition.version_ranges)
+                .intersection(versions);
+            if valid.is_empty() {
+                continue;
+            }
+
+            let mut definition = definition.clone();
+            definition.versions = valid.versions().collect();
+            definition.version_ranges = valid.ranges().collect();
+
+            for entry in &definition.entries {
+                if let Some(column) = self.columns.get(&entry.name) {
+                    s.add_column(column.clone());
+                }
+            }
+
+            s.add_database(definition);
+        }
+
+        s
+    }
+}
+
+impl DbdFile {
+    /// Creates a file with only the definitions valid for at least one version in `versions`.
+    ///
+    /// The versions of the remaining definitions are limited to those in `versions`.
+    pub fn subset(&self, versions: &VersionSet) -> DbdFile {
+        let definitions = self
+            .definitions
+            .iter()
+            .filter_map(|definition| {
+                let valid = definition.versions.intersection(versions);
+                if valid.is_empty() {
+                    return None;
+                }
+
+                let mut definition = definition.clone();
+                definition.versions = valid;
+                Some(definition)
+            })
+            .collect();
+
+        DbdFile {
+            name: self.name.clone(),
+            definitions,
+        }
+    }
+}
+
+impl DbdCollection {
+    /// Creates a collection with the [`DbdFile::subset`] of every file.
+    ///
+    /// Files without any definitions valid for `versions` are not included.
+    pub fn subset(&self, versions: &VersionSet) -> DbdCollection {
+        DbdCollection::from_files(
+            self.iter()
+                .map(|a| a.subset(versions))
+                .filter(|a| !a.definitions.is_empty()),
+        )
+    }
+
+    /// Creates a collection with `table` and every table reachable from it through foreign keys in `version`.
+    ///
+    /// Foreign keys are followed at most `depth` times, so a `depth` of `0` only includes `table`.
+    /// Every included file is the [`DbdFile::subset`] of `version`,
+    /// and tables without a definition for `version` or that are not in the collection are not included.
+    pub fn closure(&self, table: &str, version: &Version, depth: usize) -> DbdCollection {
+        let versions = VersionSet::from(*version);
+        let mut files = BTreeMap::new();
+        let mut current = vec![table.to_string()];
+
+        let mut level = 0;
+        while !current.is_empty() {
+            let mut next = Vec::new();
+
+            for table in current {
+                if files.contains_key(&table) {
+                    continue;
+                }
+
+                let definition = match self.get(&table).and_then(|a| a.specific_version(version)) {
+                    Some(definition) => definition,
+                    None => continue,
+                };
+
+                if level != depth {
+                    next.extend(
+                        definition
+                            .entries
+                            .iter()
+                            .filter_map(|a| a.ty.foreign_key())
+                            .map(|a| a.database.clone()),
+                    );
+                }
+
+                files.insert(table.clone(), self.files[&table].subset(&versions));
+            }
+
+            current = next;
+            level += 1;
+        }
+
+        DbdCollection::from_files(files.into_values())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{load_file_from_string, DbdCollection, Version, VersionRange, VersionSet};
+
+    const CONTENTS: &str = "COLUMNS
+int ID
+int Retail
+
+BUILD 3.3.5.12340, 1.12.1.5875
+$id$ID<32>
+
+BUILD 9.0.1.36216-10.0.0.46000
+$id$ID<32>
+Retail<32>
+";
+
+    #[test]
+    fn wrath_subset() {
+        let file = load_file_from_string(CONTENTS, "Map.dbd").unwrap();
+        let wrath = VersionSet::from(VersionRange::new(
+            Version::new(3, 0, 0, 0),
+            Version::new(3, 3, 5, u16::MAX),
+        ));
+
+        let raw = file.subset(&wrath);
+        assert_eq!(raw.definitions.len(), 1);
+        assert_eq!(raw.definitions[0].versions.len(), 1);
+        assert!(raw.definitions[0]
+            .versions
+            .contains(&Version::new(3, 3, 5, 12340)));
+        assert!(raw.columns.contains_key("ID"));
+        assert!(!raw.columns.contains_key("Retail"));
+
+        let proper = file.into_proper().unwrap().subset(&wrath);
+        assert_eq!(proper.definitions.len(), 1);
+        assert_eq!(
+            proper.definitions[0].versions,
+            Version::new(3, 3, 5, 12340).into()
+        );
+    }
+
+    #[test]
+    fn closure() {
+        let file = |name: &str, contents: &str| {
+            load_file_from_string(contents, name)
+                .unwrap()
+                .into_proper()
+                .unwrap()
+        };
+        let collection = DbdCollection::from_files(vec![
+            file(
+                "Spell.dbd",
+                "COLUMNS\nint ID\nint<SpellIcon::ID> Icon\nint<SpellVisual::ID> Visual\n\nBUILD 3.3.5.12340\n$id$ID<32>\nIcon<32>\nVisual<32>\n\nBUILD 1.12.1.5875\n$id$ID<32>\nIcon<32>\n",
+            ),
+            file("SpellIcon.dbd", "COLUMNS\nint ID\n\nBUILD 1.12.1.5875, 3.3.5.12340\n$id$ID<32>\n"),
+            file(
+                "SpellVisual.dbd",
+                "COLUMNS\nint ID\nint<SpellVisualKit::ID> Kit\n\nBUILD 3.3.5.12340\n$id$ID<32>\nKit<32>\n",
+            ),
+            file("SpellVisualKit.dbd", "COLUMNS\nint ID\n\nBUILD 3.3.5.12340\n$id$ID<32>\n"),
+            file("Map.dbd", "COLUMNS\nint ID\n\nBUILD 3.3.5.12340\n$id$ID<32>\n"),
+        ]);
+        let names = |a: &DbdCollection| {
+            a.iter()
+                .map(|a| a.table_name().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        let wrath = Version::new(3, 3, 5, 12340);
+        assert_eq!(
+            names(&collection.closure("Spell", &wrath, usize::MAX)),
+            vec!["Spell", "SpellIcon", "SpellVisual", "SpellVisualKit"]
+        );
+        assert_eq!(
+            names(&collection.closure("Spell", &wrath, 1)),
+            vec!["Spell", "SpellIcon", "SpellVisual"]
+        );
+        assert_eq!(
+            names(&collection.closure("Spell", &wrath, 0)),
+            vec!["Spell"]
+        );
+
+        let vanilla = collection.closure("Spell", &Version::new(1, 12, 1, 5875), usize::MAX);
+        assert_eq!(names(&vanilla), vec!["Spell", "SpellIcon"]);
+        assert_eq!(vanilla.get("Spell").unwrap().definitions.len(), 1);
+
+        assert!(collection.closure("Missing", &wrath, 1).is_empty());
+    }
+}
@@ -0,0 +1,293 @@
+//! Symbol and reference queries for editor tooling such as language servers.
+//!
+//! Columns are the only symbols in `.dbd` files.
+//! [`DbdFile::symbol_at`] finds the column under the cursor
+//! and [`DbdCollection::references`] finds everywhere it is used.
+//! [`DbdCollection::complete`] suggests completions while editing.
+
+use crate::enums::ColumnReference;
+use crate::highlight::{tokenize, TokenKind};
+use crate::{DbdCollection, DbdFile};
+use std::collections::BTreeSet;
+
+/// Place a column is used.
+///
+/// Created from [`DbdCollection::references`].
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum Reference {
+    /// Declaration of the column under `COLUMNS`.
+    Declaration(ColumnReference),
+    /// Entry of the column in a definition.
+    Entry {
+        /// Name of the table, without `.dbd`.
+        table: String,
+        /// Index into [`DbdFile::definitions`].
+        definition: usize,
+        /// Index into [`Definition::entries`](crate::Definition::entries).
+        entry: usize,
+    },
+    /// Column with a foreign key that points to the column.
+    ForeignKey(ColumnReference),
+}
+
+/// What is being completed with [`DbdCollection::complete`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum CompletionContext<'a> {
+    /// Name of an entry in a definition.
+    Entry {
+        /// Name of the table being edited, without `.dbd`.
+        table: &'a str,
+        /// Text typed so far.
+        prefix: &'a str,
+    },
+    /// Foreign key inside `<` and `>` of a column declaration.
+    ForeignKey {
+        /// Text typed so far, for example `Ma` or `Map::`.
+        prefix: &'a str,
+    },
+    /// Build on a `BUILD` line.
+    Build {
+        /// Text typed so far, for example `3.3`.
+        prefix: &'a str,
+    },
+}
+
+impl DbdFile {
+    /// Finds the column at `(line, column)` of `contents`.
+    ///
+    /// `contents` must be the text the file was loaded from, and `line` and `column`
+    /// are counted the same way as [`ParseError`](crate::error::ParseError), with `column` in bytes.
+    ///
+    /// Names of columns and entries refer to the column in this table,
+    /// and foreign keys refer to the column they point to.
+    pub fn symbol_at(&self, contents: &str, line: usize, column: usize) -> Option<ColumnReference> {
+        let text = contents.lines().nth(line)?;
+
+        let (range, kind) = tokenize(text)
+            .into_iter()
+            .find(|(range, _)| range.start <= column && column < range.end)?;
+        let token = &text[range];
+
+        match kind {
+            TokenKind::Identifier => Some(ColumnReference::new(self.table_name(), token)),
+            TokenKind::ForeignKey => {
+                let (table, column) = token.split_once("::")?;
+                Some(ColumnReference::new(table, column))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl DbdCollection {
+    /// Finds the declaration, entries and incoming foreign keys of `symbol`.
+    ///
+    /// The declaration comes first, then entries sorted by definition,
+    /// then foreign keys sorted by table and column.
+    /// Columns without any entries have no declaration since [`DbdFile`] only knows the columns that are used.
+    pub fn references(&self, symbol: &ColumnReference) -> Vec<Reference> {
+        let mut references = Vec::new();
+
+        if let Some(file) = self.get(&symbol.table) {
+            for (i, definition) in file.definitions.iter().enumerate() {
+                for (j, entry) in definition.entries.iter().enumerate() {
+                    if entry.name == symbol.column {
+                        references.push(Reference::Entry {
+                            table: symbol.table.clone(),
+                            definition: i,
+                            entry: j,
+                        });
+                    }
+                }
+            }
+
+            if !references.is_empty() {
+                references.insert(0, Reference::Declaration(symbol.clone()));
+            }
+        }
+
+        let mut foreign_keys = Vec::new();
+        for (table, file) in &self.files {
+            for definition in &file.definitions {
+                for entry in &definition.entries {
+                    let points_to_symbol = entry.ty.foreign_key().map_or(false, |key| {
+                        key.database == symbol.table && key.column == symbol.column
+                    });
+                    let reference = ColumnReference::new(table.as_str(), entry.name.as_str());
+
+                    if points_to_symbol && !foreign_keys.contains(&reference) {
+                        foreign_keys.push(reference);
+                    }
+                }
+            }
+        }
+        foreign_keys.sort();
+
+        references.extend(foreign_keys.into_iter().map(Reference::ForeignKey));
+
+        references
+    }
+
+    /// Suggests completions that start with the prefix of `context`, sorted and deduplicated.
+    ///
+    /// Builds are sorted by version, everything else by name.
+    ///
+    /// * [`CompletionContext::Entry`] suggests the columns used in the table.
+    /// * [`CompletionContext::ForeignKey`] suggests `Table::Column` for every column in every table.
+    /// * [`CompletionContext::Build`] suggests every build used in a `BUILD` line, including the ends of ranges.
+    pub fn complete(&self, context: CompletionContext<'_>) -> Vec<String> {
+        let mut suggestions = BTreeSet::new();
+
+        let prefix = match context {
+            CompletionContext::Entry { table, prefix } => {
+                if let Some(file) = self.get(table) {
+                    for definition in &file.definitions {
+                        for entry in &definition.entries {
+                            suggestions.insert(entry.name.clone());
+                        }
+                    }
+                }
+
+                prefix
+            }
+            CompletionContext::ForeignKey { prefix } => {
+                for (table, file) in &self.files {
+                    for definition in &file.definitions {
+                        for entry in &definition.entries {
+                            suggestions.insert(format!("{}::{}", table, entry.name));
+                        }
+                    }
+                }
+
+                prefix
+            }
+            CompletionContext::Build { prefix } => {
+                let mut versions = BTreeSet::new();
+                for file in self.iter() {
+                    for definition in &file.definitions {
+                        versions.extend(definition.versions.versions());
+                        for range in definition.versions.ranges() {
+                            versions.insert(range.from);
+                            versions.insert(range.to);
+                        }
+                    }
+                }
+
+                // Sorted by version and not by string so 10.0.0 comes after 9.0.0.
+                return versions
+                    .into_iter()
+                    .map(|a| a.to_string())
+                    .filter(|a| a.starts_with(prefix))
+                    .collect();
+            }
+        };
+
+        suggestions
+            .into_iter()
+            .filter(|a| a.starts_with(prefix))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::enums::ColumnReference;
+    use crate::symbols::{CompletionContext, Reference};
+    use crate::{load_file_from_string, DbdCollection};
+
+    const AREA: &str =
+        "COLUMNS\nint ID\nint<Map::ID> MapID\n\nBUILD 1.12.1.5875\n$id$ID<32>\nMapID<32>\n";
+    const MAP: &str =
+        "COLUMNS\nint ID\n\nBUILD 1.12.1.5875\n$id$ID<32>\n\nBUILD 3.3.5.12340\n$id$ID<32>\n";
+
+    #[test]
+    fn symbols_and_references() {
+        let area = load_file_from_string(AREA, "AreaTable.dbd")
+            .unwrap()
+            .into_proper()
+            .unwrap();
+        let map = load_file_from_string(MAP, "Map.dbd")
+            .unwrap()
+            .into_proper()
+            .unwrap();
+
+        assert_eq!(
+            area.symbol_at(AREA, 2, 6),
+            Some(ColumnReference::new("Map", "ID"))
+        );
+        assert_eq!(
+            area.symbol_at(AREA, 6, 2),
+            Some(ColumnReference::new("AreaTable", "MapID"))
+        );
+        assert_eq!(area.symbol_at(AREA, 4, 2), None);
+
+        let collection = DbdCollection::from_files(vec![area, map]);
+        assert_eq!(
+            collection.references(&ColumnReference::new("Map", "ID")),
+            vec![
+                Reference::Declaration(ColumnReference::new("Map", "ID")),
+                Reference::Entry {
+                    table: "Map".to_string(),
+                    definition: 0,
+                    entry: 0,
+                },
+                Reference::Entry {
+                    table: "Map".to_string(),
+                    definition: 1,
+                    entry: 0,
+                },
+                Reference::ForeignKey(ColumnReference::new("AreaTable", "MapID")),
+            ]
+        );
+    }
+
+    #[test]
+    fn completions() {
+        let area = load_file_from_string(AREA, "AreaTable.dbd")
+            .unwrap()
+            .into_proper()
+            .unwrap();
+        let map = load_file_from_string(MAP, "Map.dbd")
+            .unwrap()
+            .into_proper()
+            .unwrap();
+        let collection = DbdCollection::from_files(vec![area, map]);
+
+        assert_eq!(
+            collection.complete(CompletionContext::Entry {
+                table: "AreaTable",
+                prefix: "M",
+            }),
+            vec!["MapID"]
+        );
+        assert_eq!(
+            collection.complete(CompletionContext::ForeignKey { prefix: "" }),
+            vec!["AreaTable::ID", "AreaTable::MapID", "Map::ID"]
+        );
+        assert_eq!(
+            collection.complete(CompletionContext::ForeignKey { prefix: "Map::" }),
+            vec!["Map::ID"]
+        );
+        assert_eq!(
+            collection.complete(CompletionContext::Build { prefix: "" }),
+            vec!["1.12.1.5875", "3.3.5.12340"]
+        );
+        assert_eq!(
+            collection.complete(CompletionContext::Build { prefix: "3." }),
+            vec!["3.3.5.12340"]
+        );
+
+        let spell = load_file_from_string(
+            "COLUMNS\nint ID\n\nBUILD 3.3.5.12340, 3.3.5.12341, 3.3.5.12342\n$id$ID<32>\n",
+            "Spell.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+        assert_eq!(
+            DbdCollection::from_files(vec![spell])
+                .complete(CompletionContext::Build { prefix: "" }),
+            vec!["3.3.5.12340", "3.3.5.12341", "3.3.5.12342"]
+        );
+    }
+}
@@ -0,0 +1,408 @@
+//! Programmatic rewriting of a [`DbdCollection`].
+//!
+//! Local schema tweaks can be expressed as a list of [`Transform`]s and reapplied
+//! with [`DbdCollection::apply_transforms`] after every upstream update.
+//!
+//! ```rust
+//! # use wowdbdefs_rs::DbdCollection;
+//! # use wowdbdefs_rs::transform::{DropColumn, RenameColumn, Transform};
+//! # fn t(mut collection: DbdCollection) -> Result<(), Box<dyn std::error::Error>> {
+//! let transforms: Vec<Box<dyn Transform>> = vec![
+//!     Box::new(RenameColumn::new("Map", "Unk0", "Expansion")),
+//!     Box::new(DropColumn::new("Map", "Unk1")),
+//! ];
+//!
+//! collection.apply_transforms(&transforms)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::enums::ColumnReference;
+use crate::error::TransformError;
+use crate::{DbdCollection, DbdFile, ForeignKey, Type};
+use std::collections::BTreeSet;
+
+/// What [`DbdCollection::remove_column`] does with foreign keys that point to the removed column.
+#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum ForeignKeyCascade {
+    /// Foreign keys are only reported and keep pointing to the removed column.
+    Keep,
+    /// Foreign keys are reported and the columns keep their underlying integer type
+    /// but are no longer foreign keys.
+    Remove,
+}
+
+/// A single rewrite of a [`DbdCollection`].
+pub trait Transform {
+    /// Applies the rewrite to `collection`.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the tables or columns the transform refers to do not exist.
+    fn apply(&self, collection: &mut DbdCollection) -> Result<(), TransformError>;
+}
+
+impl DbdCollection {
+    /// Applies `transforms` in order.
+    ///
+    /// The collection is left with the transforms before the failing one applied if one fails.
+    ///
+    /// # Errors
+    ///
+    /// Errors with the first [`TransformError`] encountered.
+    pub fn apply_transforms(
+        &mut self,
+        transforms: &[Box<dyn Transform>],
+    ) -> Result<(), TransformError> {
+        for transform in transforms {
+            transform.apply(self)?;
+        }
+
+        Ok(())
+    }
+
+    /// Removes `column` from every definition of `table` and finds the foreign keys pointing to it.
+    ///
+    /// Returns every column in the collection with a foreign key to the removed column, sorted.
+    /// `cascade` decides whether these foreign keys are kept or removed.
+    ///
+    /// # Errors
+    ///
+    /// Errors if `table` does not exist or does not have `column` in any definition.
+    pub fn remove_column(
+        &mut self,
+        table: &str,
+        column: &str,
+        cascade: ForeignKeyCascade,
+    ) -> Result<Vec<ColumnReference>, TransformError> {
+        DropColumn::new(table, column).apply(self)?;
+
+        let mut references = BTreeSet::new();
+        for (name, file) in &mut self.files {
+            for definition in &mut file.definitions {
+                for entry in &mut definition.entries {
+                    let points_to_column = entry
+                        .ty
+                        .foreign_key()
+                        .map_or(false, |key| key.database == table && key.column == column);
+
+                    if points_to_column {
+                        references.insert(ColumnReference::new(name.as_str(), entry.name.as_str()));
+
+                        if cascade == ForeignKeyCascade::Remove {
+                            remove_foreign_key(&mut entry.ty);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(references.into_iter().collect())
+    }
+}
+
+fn table_mut<'a>(
+    collection: &'a mut DbdCollection,
+    table: &str,
+) -> Result<&'a mut DbdFile, TransformError> {
+    collection
+        .get_mut(table)
+        .ok_or_else(|| TransformError::TableNotFound(table.to_string()))
+}
+
+fn column_not_found(table: &str, column: &str) -> TransformError {
+    TransformError::ColumnNotFound {
+        table: table.to_string(),
+        column: column.to_string(),
+    }
+}
+
+fn remove_foreign_key(ty: &mut Type) {
+    match ty {
+        Type::Array { ty, .. } => remove_foreign_key(ty),
+        Type::ForeignKey { ty: inner, .. } => {
+            let inner = inner.as_ref().clone();
+            *ty = inner;
+        }
+        _ => {}
+    }
+}
+
+/// Renames a column in every definition of a table.
+///
+/// Foreign keys in all tables that point to the column are updated as well.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct RenameColumn {
+    /// Name of the table, without `.dbd`.
+    pub table: String,
+    /// Current name of the column.
+    pub from: String,
+    /// New name of the column.
+    pub to: String,
+}
+
+impl RenameColumn {
+    /// Constructor for rename.
+    pub fn new(table: impl Into<String>, from: impl Into<String>, to: impl Into<String>) -> Self {
+        Self {
+            table: table.into(),
+            from: from.into(),
+            to: to.into(),
+        }
+    }
+}
+
+impl Transform for RenameColumn {
+    fn apply(&self, collection: &mut DbdCollection) -> Result<(), TransformError> {
+        let file = table_mut(collection, &self.table)?;
+
+        let mut found = false;
+        for definition in &mut file.definitions {
+            for entry in &mut definition.entries {
+                if entry.name == self.from {
+                    entry.name = self.to.clone();
+                    found = true;
+                }
+            }
+        }
+
+        if !found {
+            return Err(column_not_found(&self.table, &self.from));
+        }
+
+        for file in collection.files.values_mut() {
+            for definition in &mut file.definitions {
+                for entry in &mut definition.entries {
+                    if let Some(key) = entry.ty.foreign_key_mut() {
+                        if key.database == self.table && key.column == self.from {
+                            key.column = self.to.clone();
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Replaces the type of a column in every definition of a table.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct OverrideType {
+    /// Name of the table, without `.dbd`.
+    pub table: String,
+    /// Name of the column.
+    pub column: String,
+    /// New type of the column.
+    pub ty: Type,
+}
+
+impl OverrideType {
+    /// Constructor for type override.
+    pub fn new(table: impl Into<String>, column: impl Into<String>, ty: Type) -> Self {
+        Self {
+            table: table.into(),
+            column: column.into(),
+            ty,
+        }
+    }
+}
+
+impl Transform for OverrideType {
+    fn apply(&self, collection: &mut DbdCollection) -> Result<(), TransformError> {
+        let file = table_mut(collection, &self.table)?;
+
+        let mut found = false;
+        for definition in &mut file.definitions {
+            for entry in &mut definition.entries {
+                if entry.name == self.column {
+                    entry.ty = self.ty.clone();
+                    found = true;
+                }
+            }
+        }
+
+        if found {
+            Ok(())
+        } else {
+            Err(column_not_found(&self.table, &self.column))
+        }
+    }
+}
+
+/// Removes a column from every definition of a table.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct DropColumn {
+    /// Name of the table, without `.dbd`.
+    pub table: String,
+    /// Name of the column.
+    pub column: String,
+}
+
+impl DropColumn {
+    /// Constructor for drop.
+    pub fn new(table: impl Into<String>, column: impl Into<String>) -> Self {
+        Self {
+            table: table.into(),
+            column: column.into(),
+        }
+    }
+}
+
+impl Transform for DropColumn {
+    fn apply(&self, collection: &mut DbdCollection) -> Result<(), TransformError> {
+        let file = table_mut(collection, &self.table)?;
+
+        let mut found = false;
+        for definition in &mut file.definitions {
+            let len = definition.entries.len();
+            definition.entries.retain(|a| a.name != self.column);
+            found |= len != definition.entries.len();
+        }
+
+        if found {
+            Ok(())
+        } else {
+            Err(column_not_found(&self.table, &self.column))
+        }
+    }
+}
+
+/// Makes a column in every definition of a table a foreign key to another column.
+///
+/// Columns that are already foreign keys point to the new column instead.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct RetargetForeignKey {
+    /// Name of the table, without `.dbd`.
+    pub table: String,
+    /// Name of the column.
+    pub column: String,
+    /// Column the foreign key points to.
+    pub key: ForeignKey,
+}
+
+impl RetargetForeignKey {
+    /// Constructor for retarget.
+    pub fn new(table: impl Into<String>, column: impl Into<String>, key: ForeignKey) -> Self {
+        Self {
+            table: table.into(),
+            column: column.into(),
+            key,
+        }
+    }
+}
+
+impl Transform for RetargetForeignKey {
+    fn apply(&self, collection: &mut DbdCollection) -> Result<(), TransformError> {
+        let file = table_mut(collection, &self.table)?;
+
+        let mut found = false;
+        for definition in &mut file.definitions {
+            for entry in &mut definition.entries {
+                if entry.name != self.column {
+                    continue;
+                }
+                found = true;
+
+                if let Some(key) = entry.ty.foreign_key_mut() {
+                    *key = self.key.clone();
+                } else {
+                    entry.ty = Type::ForeignKey {
+                        ty: Box::new(entry.ty.clone()),
+                        key: self.key.clone(),
+                    }
+                    .normalized();
+                }
+            }
+        }
+
+        if found {
+            Ok(())
+        } else {
+            Err(column_not_found(&self.table, &self.column))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::enums::ColumnReference;
+    use crate::error::TransformError;
+    use crate::transform::{DropColumn, ForeignKeyCascade, OverrideType, RenameColumn, Transform};
+    use crate::{load_file_from_string, DbdCollection, Type};
+
+    fn collection() -> DbdCollection {
+        let map = load_file_from_string(
+            "COLUMNS\nint ID\nint Unk0\nint Unk1\n\nBUILD 1.12.1.5875\n$id$ID<32>\nUnk0<32>\nUnk1<32>\n",
+            "Map.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+        let area = load_file_from_string(
+            "COLUMNS\nint ID\nint<Map::Unk0> MapID\n\nBUILD 1.12.1.5875\n$id$ID<32>\nMapID<32>\n",
+            "AreaTable.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+
+        DbdCollection::from_files(vec![map, area])
+    }
+
+    #[test]
+    fn apply_transforms() {
+        let mut collection = collection();
+
+        let transforms: Vec<Box<dyn Transform>> = vec![
+            Box::new(RenameColumn::new("Map", "Unk0", "Expansion")),
+            Box::new(DropColumn::new("Map", "Unk1")),
+            Box::new(OverrideType::new("Map", "Expansion", Type::UInt8)),
+        ];
+        collection.apply_transforms(&transforms).unwrap();
+
+        let map = &collection.get("Map").unwrap().definitions[0];
+        assert_eq!(map.entries.len(), 2);
+        assert_eq!(map.entries[1].name, "Expansion");
+        assert_eq!(map.entries[1].ty, Type::UInt8);
+
+        let area = &collection.get("AreaTable").unwrap().definitions[0];
+        match &area.entries[1].ty {
+            Type::ForeignKey { key, .. } => assert_eq!(key.column, "Expansion"),
+            ty => panic!("{:?}", ty),
+        }
+
+        assert_eq!(
+            DropColumn::new("Map", "Unk1").apply(&mut collection),
+            Err(TransformError::ColumnNotFound {
+                table: "Map".to_string(),
+                column: "Unk1".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn remove_column() {
+        let mut collection = collection();
+
+        let references = collection
+            .remove_column("Map", "Unk0", ForeignKeyCascade::Keep)
+            .unwrap();
+        assert_eq!(references, vec![ColumnReference::new("AreaTable", "MapID")]);
+        assert_eq!(
+            collection.get("Map").unwrap().definitions[0].entries.len(),
+            2
+        );
+
+        let mut collection = self::collection();
+        collection
+            .remove_column("Map", "Unk0", ForeignKeyCascade::Remove)
+            .unwrap();
+        let area = &collection.get("AreaTable").unwrap().definitions[0];
+        assert_eq!(area.entries[1].ty, Type::Int32);
+
+        assert!(collection
+            .remove_column("Map", "Unk0", ForeignKeyCascade::Remove)
+            .is_err());
+    }
+}
@@ -1,7 +1,11 @@
-use crate::error::ConversionError;
+use crate::enums::EnumDefinition;
+use crate::error::{closest_match, ConversionError, EntryConversionError};
+use crate::overrides::TypeOverride;
+use crate::VersionSet;
 use std::cmp::Ordering;
 use std::collections::{BTreeSet, HashMap};
 use std::fmt::{Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
 
 /// An entire `.dbd` file with all subtypes.
 ///
@@ -40,6 +44,10 @@ fn compare_versions(
     false
 }
 
+pub(crate) fn table_name(name: &str) -> &str {
+    name.strip_suffix(".dbd").unwrap_or(name)
+}
+
 impl RawDbdFile {
     /// Finds the definition for a specific version, if it exists.
     pub fn specific_version(&self, version: &Version) -> Option<&RawDefinition> {
@@ -68,11 +76,39 @@ impl RawDbdFile {
         })
     }
 
+    /// Same as [`RawDbdFile::into_proper`], but continues past entries that can not be converted.
+    ///
+    /// Entries that fail are left out of their [`Definition`] and returned as errors,
+    /// so every problem in the file can be reported at once.
+    pub fn into_proper_with_errors(self) -> (DbdFile, Vec<EntryConversionError>) {
+        let mut definitions = Vec::with_capacity(self.definitions.len());
+        let mut errors = Vec::new();
+
+        for def in self.definitions {
+            let (definition, e) = def.to_definition_with_errors(&self.columns);
+            definitions.push(definition);
+            errors.extend(e);
+        }
+
+        (
+            DbdFile {
+                name: self.name,
+                definitions,
+            },
+            errors,
+        )
+    }
+
     /// Find the corresponding column for an entry.
     pub fn find_column(&self, entry: &RawEntry) -> Option<&RawColumn> {
         self.columns.get(&entry.name)
     }
 
+    /// Name of the table without the `.dbd` extension.
+    pub fn table_name(&self) -> &str {
+        table_name(&self.name)
+    }
+
     pub(crate) fn empty(name: String) -> Self {
         Self {
             name,
@@ -144,6 +180,23 @@ impl ForeignKey {
     }
 }
 
+/// Where the value of an [`Entry`] is stored.
+///
+/// Created from [`Entry::storage`].
+#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum EntryStorage {
+    /// Stored in the record like any other field.
+    Record,
+    /// `$noninline,id$`, stored in the ID list instead of the record.
+    IdList,
+    /// `$noninline,relation$`, stored in the relationship map from record to parent ID instead of the record.
+    ///
+    /// The value is the ID of the parent row, and must be looked up through the relationship map.
+    RelationshipMap,
+    /// `$noninline$` without `$id$` or `$relation$`, which does not say where the value is stored.
+    Unknown,
+}
+
 /// Column definition found under `COLUMNS`.
 ///
 /// This can not know specifics like integer sizes and array status.
@@ -239,6 +292,22 @@ impl Version {
             build,
         }
     }
+
+    /// Amount of string indices in a `locstring` for this version, excluding the flags.
+    ///
+    /// Versions before Cataclysm have a string for every locale while later versions only have a single string.
+    pub const fn locstring_strings(&self) -> usize {
+        match self.major {
+            0 | 1 => 8,
+            2 | 3 => 16,
+            _ => 1,
+        }
+    }
+
+    /// Whether a `locstring` for this version is followed by a flags field.
+    pub const fn locstring_has_flags(&self) -> bool {
+        self.major < 4
+    }
 }
 
 /// Representation of version range.
@@ -302,8 +371,14 @@ pub struct RawEntry {
     pub primary_key: bool,
     /// Data is stored inline with the row and not inside [DB2](https://wowdev.wiki/DB2#Structure_8) files.
     pub inline: bool,
-    /// True if the column is a foreign key.
+    /// Column refers to the parent table, usually through a foreign key.
+    ///
+    /// See [`Entry::storage`] for how this combines with [`Entry::inline`].
     pub relation: bool,
+    /// Annotations other than `id`, `noninline` and `relation`, in the order they were written.
+    ///
+    /// These are not included in [`Entry`] but are kept by [`write_to_file`](crate::write_to_file).
+    pub unknown_tags: Vec<String>,
 }
 
 impl RawEntry {
@@ -328,12 +403,9 @@ impl RawEntry {
             primary_key,
             inline,
             relation,
+            unknown_tags: Vec::new(),
         }
     }
-
-    pub(crate) const fn has_any_tag(&self) -> bool {
-        self.primary_key || !self.inline || self.relation
-    }
 }
 
 /// Definition for specific set of versions.
@@ -349,11 +421,15 @@ pub struct RawDefinition {
     pub layouts: BTreeSet<Layout>,
     /// Entries in the definition.
     pub entries: Vec<RawEntry>,
+    /// Text of the `COMMENT` lines of the definition.
+    ///
+    /// These are not included in [`Definition`] but are kept by [`write_to_file`](crate::write_to_file).
+    pub comments: Vec<String>,
 }
 
 impl RawDefinition {
     /// Constructor for definition.
-    pub fn new(
+    pub const fn new(
         versions: BTreeSet<Version>,
         version_ranges: Vec<VersionRange>,
         layouts: BTreeSet<Layout>,
@@ -364,6 +440,7 @@ impl RawDefinition {
             version_ranges,
             entries,
             layouts,
+            comments: Vec::new(),
         }
     }
 
@@ -381,86 +458,128 @@ impl RawDefinition {
         let mut entries = Vec::with_capacity(self.entries.len());
 
         for entry in &self.entries {
-            let column = if let Some(c) = columns.get(&entry.name) {
-                c
-            } else {
-                return Err(ConversionError::ColumnNotFound(entry.name.clone()));
-            };
+            entries.push(entry.to_entry(columns)?);
+        }
+
+        Ok(self.with_entries(entries))
+    }
+
+    /// Same as [`RawDefinition::to_definition`], but continues past entries that can not be converted.
+    ///
+    /// The returned [`Definition`] only contains the entries that were converted successfully.
+    /// The errors are returned in the same order as the entries.
+    pub fn to_definition_with_errors(
+        &self,
+        columns: &HashMap<String, RawColumn>,
+    ) -> (Definition, Vec<EntryConversionError>) {
+        let mut entries = Vec::with_capacity(self.entries.len());
+        let mut errors = Vec::new();
 
-            let mut ty = match column.ty {
-                RawType::Int => match entry.integer_width {
-                    None => return Err(ConversionError::NoIntegerWidth),
-                    Some(v) => match entry.unsigned {
-                        true => match v {
-                            8 => Type::UInt8,
-                            16 => Type::UInt16,
-                            32 => Type::UInt32,
-                            64 => Type::UInt64,
-                            v => return Err(ConversionError::InvalidIntegerWidth(v.into())),
-                        },
-                        false => match v {
-                            8 => Type::Int8,
-                            16 => Type::Int16,
-                            32 => Type::Int32,
-                            64 => Type::Int64,
-                            v => return Err(ConversionError::InvalidIntegerWidth(v.into())),
-                        },
+        for entry in &self.entries {
+            match entry.to_entry(columns) {
+                Ok(e) => entries.push(e),
+                Err(error) => errors.push(EntryConversionError {
+                    entry: entry.name.clone(),
+                    error,
+                }),
+            }
+        }
+
+        (self.with_entries(entries), errors)
+    }
+
+    fn with_entries(&self, entries: Vec<Entry>) -> Definition {
+        Definition {
+            versions: VersionSet::from_parts(&self.versions, &self.version_ranges),
+            layouts: self.layouts.clone(),
+            entries,
+            filtered: false,
+        }
+    }
+}
+
+impl RawEntry {
+    fn to_entry(&self, columns: &HashMap<String, RawColumn>) -> Result<Entry, ConversionError> {
+        let column = if let Some(c) = columns.get(&self.name) {
+            c
+        } else {
+            let mut names: Vec<&str> = columns.keys().map(|a| a.as_str()).collect();
+            names.sort_unstable();
+
+            return Err(ConversionError::ColumnNotFound {
+                name: self.name.clone(),
+                suggestion: closest_match(&self.name, names),
+            });
+        };
+
+        let mut ty = match column.ty {
+            RawType::Int => match self.integer_width {
+                None => return Err(ConversionError::NoIntegerWidth),
+                Some(v) => match self.unsigned {
+                    true => match v {
+                        8 => Type::UInt8,
+                        16 => Type::UInt16,
+                        32 => Type::UInt32,
+                        64 => Type::UInt64,
+                        v => return Err(ConversionError::InvalidIntegerWidth(v.into())),
+                    },
+                    false => match v {
+                        8 => Type::Int8,
+                        16 => Type::Int16,
+                        32 => Type::Int32,
+                        64 => Type::Int64,
+                        v => return Err(ConversionError::InvalidIntegerWidth(v.into())),
                     },
                 },
-                RawType::Float => Type::Float,
-                RawType::LocString => Type::LocString,
-                RawType::String => Type::String,
-            };
+            },
+            RawType::Float => Type::Float,
+            RawType::LocString => Type::LocString,
+            RawType::String => Type::String,
+        };
 
-            if let Some(foreign_key) = &column.foreign_key {
-                match ty {
-                    Type::Array { .. }
-                    | Type::Int8
-                    | Type::Int16
-                    | Type::Int32
-                    | Type::Int64
-                    | Type::UInt8
-                    | Type::UInt16
-                    | Type::UInt32
-                    | Type::UInt64 => {
-                        ty = Type::ForeignKey {
-                            ty: Box::new(ty),
-                            key: foreign_key.clone(),
-                        };
-                    }
-                    Type::Float => return Err(ConversionError::FloatAsForeignKey),
-                    Type::LocString => return Err(ConversionError::LocStringAsForeignKey),
-                    Type::String => return Err(ConversionError::StringAsForeignKey),
-                    Type::ForeignKey { .. } => {
-                        unreachable!("ty has not been set to foreign key yet")
-                    }
+        if let Some(foreign_key) = &column.foreign_key {
+            match ty {
+                Type::Array { .. }
+                | Type::Int8
+                | Type::Int16
+                | Type::Int32
+                | Type::Int64
+                | Type::UInt8
+                | Type::UInt16
+                | Type::UInt32
+                | Type::UInt64 => {
+                    ty = Type::ForeignKey {
+                        ty: Box::new(ty),
+                        key: foreign_key.clone(),
+                    };
+                }
+                Type::Float => return Err(ConversionError::FloatAsForeignKey),
+                Type::LocString => return Err(ConversionError::LocStringAsForeignKey),
+                Type::String => return Err(ConversionError::StringAsForeignKey),
+                Type::ForeignKey { .. } => {
+                    unreachable!("ty has not been set to foreign key yet")
                 }
             }
+        }
 
-            if let Some(width) = entry.array_size {
-                ty = Type::Array {
-                    ty: Box::new(ty.clone()),
-                    width,
-                };
-            }
-
-            entries.push(Entry {
-                name: entry.name.clone(),
-                ty,
-                comment: entry.comment.clone(),
-                column_comment: column.comment.clone(),
-                verified: column.verified,
-                primary_key: entry.primary_key,
-                inline: entry.inline,
-                relation: entry.relation,
-            });
+        if let Some(width) = self.array_size {
+            ty = Type::Array {
+                ty: Box::new(ty.clone()),
+                width,
+            };
         }
 
-        Ok(Definition {
-            versions: self.versions.clone(),
-            version_ranges: self.version_ranges.clone(),
-            layouts: self.layouts.clone(),
-            entries,
+        Ok(Entry {
+            name: self.name.clone(),
+            ty,
+            comment: self.comment.clone(),
+            column_comment: column.comment.clone(),
+            verified: column.verified,
+            primary_key: self.primary_key,
+            inline: self.inline,
+            relation: self.relation,
+            enumeration: None,
+            type_override: None,
         })
     }
 }
@@ -468,16 +587,115 @@ impl RawDefinition {
 /// Parsed and validated definition.
 ///
 /// Created from [`RawDefinition::to_definition`] and [`RawDbdFile::into_proper`].
-#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct Definition {
-    /// Specific valid versions.
-    pub versions: BTreeSet<Version>,
-    /// Valid version ranges.
-    pub version_ranges: Vec<VersionRange>,
+    /// Valid versions, both specific versions and version ranges.
+    pub versions: VersionSet,
     /// Specific valid layouts.
     pub layouts: BTreeSet<Layout>,
     /// Entries in the definition.
     pub entries: Vec<Entry>,
+    pub(crate) filtered: bool,
+}
+
+impl PartialEq for Definition {
+    fn eq(&self, other: &Self) -> bool {
+        self.versions == other.versions
+            && self.layouts == other.layouts
+            && self.entries == other.entries
+    }
+}
+
+impl Eq for Definition {}
+
+impl PartialOrd for Definition {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Definition {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.versions, &self.layouts, &self.entries).cmp(&(
+            &other.versions,
+            &other.layouts,
+            &other.entries,
+        ))
+    }
+}
+
+impl Hash for Definition {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.versions.hash(state);
+        self.layouts.hash(state);
+        self.entries.hash(state);
+    }
+}
+
+impl Definition {
+    /// Returns true if entries were removed with [`DbdFile::filter_entries`],
+    /// so the entries no longer describe the full record layout.
+    ///
+    /// This is not part of the comparison of definitions.
+    pub const fn is_filtered(&self) -> bool {
+        self.filtered
+    }
+
+    /// Returns true if both definitions describe the same schema.
+    ///
+    /// Versions are compared by the versions they cover
+    /// and entries are compared with [`Entry::semantically_eq`].
+    /// The order of entries is significant since it decides the physical layout.
+    pub fn semantically_eq(&self, other: &Self) -> bool {
+        self.versions.same_versions(&other.versions)
+            && self.layouts == other.layouts
+            && self.entries.len() == other.entries.len()
+            && self
+                .entries
+                .iter()
+                .zip(&other.entries)
+                .all(|(a, b)| a.semantically_eq(b))
+    }
+
+    /// Amount of physical fields in a row for `version`, using [`Entry::expanded_field_count`].
+    ///
+    /// Entries that are not [`Entry::inline`] are not stored in the row and are not counted.
+    /// This is the same as the field count in the header of `DBC` files.
+    pub fn field_count(&self, version: &Version) -> usize {
+        self.entries
+            .iter()
+            .filter(|a| a.inline)
+            .map(|a| a.expanded_field_count(version))
+            .sum()
+    }
+
+    /// Entry that refers to the parent table, if any.
+    ///
+    /// For `DB2` files with a relationship map this is the parent ID of every record.
+    /// See [`Entry::storage`] for where the value is stored.
+    pub fn parent_relation(&self) -> Option<&Entry> {
+        self.entries.iter().find(|a| a.relation)
+    }
+
+    /// Hash of the physical structure of the row.
+    ///
+    /// Only the order, [`Type::scalar`] and [`Type::array_width`] of [`Entry::inline`] entries are included,
+    /// so definitions from different tables and builds with the same row structure have the same fingerprint
+    /// regardless of names, foreign keys and comments.
+    ///
+    /// The hash is 64 bit FNV-1a and does not change between runs,
+    /// so it can be used as a key for persistent caches.
+    pub fn structure_fingerprint(&self) -> u64 {
+        let mut hash = FNV_OFFSET_BASIS;
+        let mut write = |bytes: &[u8]| hash = fnv1a(hash, bytes);
+
+        for entry in self.entries.iter().filter(|a| a.inline) {
+            write(&[entry.ty.scalar() as u8]);
+            write(&(entry.ty.array_width().unwrap_or(0) as u64).to_le_bytes());
+        }
+
+        hash
+    }
 }
 
 /// Specific entry or column in a DBC.
@@ -504,8 +722,117 @@ pub struct Entry {
     pub primary_key: bool,
     /// Data is stored inline with the row and not inside [DB2](https://wowdev.wiki/DB2#Structure_8) files.
     pub inline: bool,
-    /// True if the column is a foreign key.
+    /// Column refers to the parent table, usually through a foreign key.
+    ///
+    /// See [`Entry::storage`] for how this combines with [`Entry::inline`].
     pub relation: bool,
+
+    /// Named values for the column.
+    ///
+    /// Set from sidecar files with [`DbdFile::attach_enums`], see [`enums`](crate::enums).
+    pub enumeration: Option<EnumDefinition>,
+    /// Domain specific type of the column.
+    ///
+    /// Set from [`TypeOverrides`](crate::overrides::TypeOverrides), see [`overrides`](crate::overrides).
+    pub type_override: Option<TypeOverride>,
+}
+
+impl Entry {
+    /// Returns true if both entries describe the same column.
+    ///
+    /// Comments, verification status and attached [`enums`](crate::enums) and
+    /// [`overrides`](crate::overrides) are ignored.
+    pub fn semantically_eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.ty == other.ty
+            && self.primary_key == other.primary_key
+            && self.inline == other.inline
+            && self.relation == other.relation
+    }
+
+    /// Where the value of the entry is stored in a `DB2` file.
+    ///
+    /// Only entries that are not [`Entry::inline`] are stored outside of the record.
+    /// A relation that is inline is stored in the record and also refers to the parent table.
+    pub const fn storage(&self) -> EntryStorage {
+        match (self.inline, self.primary_key, self.relation) {
+            (true, _, _) => EntryStorage::Record,
+            (false, true, _) => EntryStorage::IdList,
+            (false, false, true) => EntryStorage::RelationshipMap,
+            (false, false, false) => EntryStorage::Unknown,
+        }
+    }
+
+    /// Amount of physical fields the entry takes up for `version`.
+    ///
+    /// Arrays count every element and `locstring`s count [`Version::locstring_strings`]
+    /// fields plus a flags field if [`Version::locstring_has_flags`].
+    /// All other types count as a single field regardless of size.
+    pub fn expanded_field_count(&self, version: &Version) -> usize {
+        let count = match self.ty.scalar() {
+            ScalarType::LocString => {
+                version.locstring_strings() + usize::from(version.locstring_has_flags())
+            }
+            _ => 1,
+        };
+
+        count * self.ty.array_width().unwrap_or(1)
+    }
+
+    /// Splits the entry into the column declared under `COLUMNS` and the entry of the definition.
+    ///
+    /// The type is written with [`Type::normalized`], so nested arrays are flattened.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the type is a foreign key that is not an integer.
+    pub fn to_raw(&self) -> Result<(RawColumn, RawEntry), ConversionError> {
+        let array_size = self.ty.array_width();
+        let foreign_key = self.ty.foreign_key().cloned();
+
+        let (raw_ty, integer_width, unsigned) = match self.ty.scalar() {
+            ScalarType::Int8 => (RawType::Int, Some(8), false),
+            ScalarType::Int16 => (RawType::Int, Some(16), false),
+            ScalarType::Int32 => (RawType::Int, Some(32), false),
+            ScalarType::Int64 => (RawType::Int, Some(64), false),
+            ScalarType::UInt8 => (RawType::Int, Some(8), true),
+            ScalarType::UInt16 => (RawType::Int, Some(16), true),
+            ScalarType::UInt32 => (RawType::Int, Some(32), true),
+            ScalarType::UInt64 => (RawType::Int, Some(64), true),
+            ScalarType::Float => (RawType::Float, None, false),
+            ScalarType::LocString => (RawType::LocString, None, false),
+            ScalarType::String => (RawType::String, None, false),
+        };
+
+        if foreign_key.is_some() {
+            match raw_ty {
+                RawType::Int => {}
+                RawType::Float => return Err(ConversionError::FloatAsForeignKey),
+                RawType::LocString => return Err(ConversionError::LocStringAsForeignKey),
+                RawType::String => return Err(ConversionError::StringAsForeignKey),
+            }
+        }
+
+        Ok((
+            RawColumn::new(
+                self.name.clone(),
+                raw_ty,
+                foreign_key,
+                self.verified,
+                self.column_comment.clone(),
+            ),
+            RawEntry::new(
+                self.name.clone(),
+                self.comment.clone(),
+                integer_width,
+                array_size,
+                unsigned,
+                self.primary_key,
+                self.inline,
+                self.relation,
+            ),
+        ))
+    }
 }
 
 /// Type of the column.
@@ -555,6 +882,160 @@ pub enum Type {
     },
 }
 
+impl Type {
+    /// Innermost type without any arrays or foreign keys.
+    pub fn scalar(&self) -> ScalarType {
+        match self {
+            Type::Int8 => ScalarType::Int8,
+            Type::Int16 => ScalarType::Int16,
+            Type::Int32 => ScalarType::Int32,
+            Type::Int64 => ScalarType::Int64,
+            Type::UInt8 => ScalarType::UInt8,
+            Type::UInt16 => ScalarType::UInt16,
+            Type::UInt32 => ScalarType::UInt32,
+            Type::UInt64 => ScalarType::UInt64,
+            Type::Float => ScalarType::Float,
+            Type::LocString => ScalarType::LocString,
+            Type::String => ScalarType::String,
+            Type::ForeignKey { ty, .. } | Type::Array { ty, .. } => ty.scalar(),
+        }
+    }
+
+    /// Total amount of elements if the type is an array.
+    ///
+    /// Nested arrays are flattened, so an array of 2 arrays of 3 elements has a width of 6.
+    pub fn array_width(&self) -> Option<usize> {
+        match self {
+            Type::Array { ty, width } => Some(width * ty.array_width().unwrap_or(1)),
+            Type::ForeignKey { ty, .. } => ty.array_width(),
+            _ => None,
+        }
+    }
+
+    /// Foreign key of the type, regardless of whether it is inside or outside of an array.
+    pub fn foreign_key(&self) -> Option<&ForeignKey> {
+        match self {
+            Type::ForeignKey { key, .. } => Some(key),
+            Type::Array { ty, .. } => ty.foreign_key(),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn foreign_key_mut(&mut self) -> Option<&mut ForeignKey> {
+        match self {
+            Type::ForeignKey { key, .. } => Some(key),
+            Type::Array { ty, .. } => ty.foreign_key_mut(),
+            _ => None,
+        }
+    }
+
+    /// Rebuilds the type in the canonical nesting used by [`RawDefinition::to_definition`].
+    ///
+    /// The canonical nesting is an optional [`Type::Array`] containing an optional [`Type::ForeignKey`]
+    /// containing the [`Type::scalar`].
+    /// Nested arrays are flattened with [`Type::array_width`]
+    /// and only the outermost foreign key is kept if there are several.
+    pub fn normalized(&self) -> Type {
+        let mut ty = Type::from(self.scalar());
+
+        if let Some(key) = self.foreign_key() {
+            ty = Type::ForeignKey {
+                ty: Box::new(ty),
+                key: key.clone(),
+            };
+        }
+
+        if let Some(width) = self.array_width() {
+            ty = Type::Array {
+                ty: Box::new(ty),
+                width,
+            };
+        }
+
+        ty
+    }
+}
+
+/// Type of a column without any arrays or foreign keys.
+///
+/// Created from [`Type::scalar`].
+#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum ScalarType {
+    /// `i8`
+    Int8,
+    /// `i16`
+    Int16,
+    /// `i32`
+    Int32,
+    /// `i64`
+    Int64,
+
+    /// `u8`
+    UInt8,
+    /// `u16`
+    UInt16,
+    /// `u32`
+    UInt32,
+    /// `u64`
+    UInt64,
+
+    /// `f32`
+    Float,
+    /// Localized string indices.
+    ///
+    /// Specific layout depends on the version, but it's an array of indices into the string block.
+    LocString,
+    /// Index into string block.
+    String,
+}
+
+impl ScalarType {
+    /// Returns true for the signed and unsigned integer types.
+    pub const fn is_integer(&self) -> bool {
+        !matches!(
+            self,
+            ScalarType::Float | ScalarType::LocString | ScalarType::String
+        )
+    }
+
+    /// Width in bits for integer types.
+    pub const fn integer_width(&self) -> Option<u8> {
+        Some(match self {
+            ScalarType::Int8 | ScalarType::UInt8 => 8,
+            ScalarType::Int16 | ScalarType::UInt16 => 16,
+            ScalarType::Int32 | ScalarType::UInt32 => 32,
+            ScalarType::Int64 | ScalarType::UInt64 => 64,
+            ScalarType::Float | ScalarType::LocString | ScalarType::String => return None,
+        })
+    }
+
+    /// Returns true for the unsigned integer types.
+    pub const fn is_unsigned(&self) -> bool {
+        matches!(
+            self,
+            ScalarType::UInt8 | ScalarType::UInt16 | ScalarType::UInt32 | ScalarType::UInt64
+        )
+    }
+}
+
+impl From<ScalarType> for Type {
+    fn from(v: ScalarType) -> Self {
+        match v {
+            ScalarType::Int8 => Type::Int8,
+            ScalarType::Int16 => Type::Int16,
+            ScalarType::Int32 => Type::Int32,
+            ScalarType::Int64 => Type::Int64,
+            ScalarType::UInt8 => Type::UInt8,
+            ScalarType::UInt16 => Type::UInt16,
+            ScalarType::UInt32 => Type::UInt32,
+            ScalarType::UInt64 => Type::UInt64,
+            ScalarType::Float => Type::Float,
+            ScalarType::LocString => Type::LocString,
+            ScalarType::String => Type::String,
+        }
+    }
+}
+
 /// Parsed and validated file.
 ///
 /// Created from [`RawDbdFile::into_proper`].
@@ -569,10 +1050,109 @@ pub struct DbdFile {
 }
 
 impl DbdFile {
+    /// Name of the table without the `.dbd` extension.
+    pub fn table_name(&self) -> &str {
+        table_name(&self.name)
+    }
+
     /// Finds the definition for a specific version, if it exists.
     pub fn specific_version(&self, version: &Version) -> Option<&Definition> {
         self.definitions
             .iter()
-            .find(|a| compare_versions(version, &a.version_ranges, &a.versions))
+            .find(|a| a.versions.contains(version))
     }
+
+    /// Finds every definition with a version between `from` and `to`, both inclusive.
+    ///
+    /// Definitions are sorted by their first version inside the window, oldest first.
+    pub fn definitions_between(&self, from: &Version, to: &Version) -> Vec<&Definition> {
+        let window: VersionSet = VersionRange::new(*from, *to).into();
+
+        let mut definitions: Vec<(Version, &Definition)> = self
+            .definitions
+            .iter()
+            .filter_map(|a| Some((a.versions.intersection(&window).first()?, a)))
+            .collect();
+        definitions.sort_by_key(|(first, _)| *first);
+
+        definitions.into_iter().map(|(_, a)| a).collect()
+    }
+
+    /// Finds the layout of the definition with the newest version, ignoring definitions without layouts.
+    ///
+    /// The `.dbd` format does not record which layout is newest within a single definition,
+    /// so the highest layout of the definition is returned if it has several.
+    pub fn latest_layout(&self) -> Option<&Layout> {
+        self.definitions
+            .iter()
+            .filter(|a| !a.layouts.is_empty())
+            .max_by_key(|a| a.versions.last())
+            .and_then(|a| a.layouts.iter().next_back())
+    }
+
+    /// Converts the file back into the raw representation, for example to use [`write_to_file`](crate::write_to_file).
+    ///
+    /// Columns are reconstructed from the entries of every definition.
+    /// A column is only verified if it is verified in every definition
+    /// and the first column comment found is used.
+    ///
+    /// Attached [`enums`](crate::enums) and [`overrides`](crate::overrides) are not included.
+    ///
+    /// # Errors
+    ///
+    /// Errors if an entry can not be represented with [`Entry::to_raw`] or
+    /// if a column has different types or foreign keys in different definitions.
+    pub fn into_raw(self) -> Result<RawDbdFile, ConversionError> {
+        let mut file = RawDbdFile::empty(self.name);
+
+        for definition in self.definitions {
+            let mut entries = Vec::with_capacity(definition.entries.len());
+
+            for entry in &definition.entries {
+                let (column, raw_entry) = entry.to_raw()?;
+
+                if let Some(existing) = file.columns.get_mut(&column.name) {
+                    if existing.ty != column.ty || existing.foreign_key != column.foreign_key {
+                        return Err(ConversionError::ConflictingColumn(column.name));
+                    }
+
+                    existing.verified &= column.verified;
+                    if existing.comment.is_none() {
+                        existing.comment = column.comment;
+                    }
+                } else {
+                    file.add_column(column);
+                }
+
+                entries.push(raw_entry);
+            }
+
+            file.add_database(RawDefinition::new(
+                definition.versions.versions().collect(),
+                definition.versions.ranges().collect(),
+                definition.layouts,
+                entries,
+            ));
+        }
+
+        Ok(file)
+    }
+}
+
+pub(crate) const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// Continues the 64 bit FNV-1a `hash` with `bytes`.
+///
+/// Start with [`FNV_OFFSET_BASIS`].
+pub(crate) const fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u64;
+        hash = hash.wrapping_mul(PRIME);
+        i += 1;
+    }
+
+    hash
 }
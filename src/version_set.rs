@@ -0,0 +1,357 @@
+use crate::{Version, VersionRange};
+use std::collections::BTreeSet;
+
+/// Set of versions made up of both specific versions and version ranges.
+///
+/// Specific versions and ranges are kept apart so that `BUILD 3.3.5.12340, 3.3.5.12341`
+/// and `BUILD 3.3.5.12340-3.3.5.12341` stay different even though they cover the same versions.
+/// Ranges are kept sorted with overlapping and adjacent ranges merged,
+/// and specific versions that are covered by a range are removed.
+/// Use [`VersionSet::same_versions`] to compare the versions covered regardless of how they are written.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
+pub struct VersionSet {
+    versions: BTreeSet<Version>,
+    ranges: Vec<VersionRange>,
+}
+
+impl VersionSet {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        Self {
+            versions: BTreeSet::new(),
+            ranges: Vec::new(),
+        }
+    }
+
+    /// Creates a set from the specific versions and version ranges of a [`RawDefinition`](crate::RawDefinition).
+    pub fn from_parts(versions: &BTreeSet<Version>, version_ranges: &[VersionRange]) -> Self {
+        let mut s = Self {
+            versions: versions.clone(),
+            ranges: version_ranges
+                .iter()
+                .filter(|a| a.from <= a.to)
+                .copied()
+                .collect(),
+        };
+        s.normalize();
+        s
+    }
+
+    /// Adds a specific version.
+    pub fn insert(&mut self, version: Version) {
+        self.versions.insert(version);
+        self.normalize();
+    }
+
+    /// Adds all versions within `range`.
+    ///
+    /// Ranges where [`VersionRange::from`] is greater than [`VersionRange::to`] are empty and ignored.
+    pub fn insert_range(&mut self, range: VersionRange) {
+        if range.from > range.to {
+            return;
+        }
+
+        self.ranges.push(range);
+        self.normalize();
+    }
+
+    /// Returns true if `version` is in the set.
+    pub fn contains(&self, version: &Version) -> bool {
+        self.versions.contains(version) || self.ranges.iter().any(|a| a.within_range(version))
+    }
+
+    /// Returns true if the set contains no versions.
+    pub fn is_empty(&self) -> bool {
+        self.versions.is_empty() && self.ranges.is_empty()
+    }
+
+    /// Returns true if both sets cover the same versions,
+    /// regardless of whether they are specific versions or ranges.
+    pub fn same_versions(&self, other: &Self) -> bool {
+        self.is_subset(other) && other.is_subset(self)
+    }
+
+    /// All versions in either set.
+    pub fn union(&self, other: &Self) -> Self {
+        let mut s = self.clone();
+        s.versions.extend(other.versions.iter().copied());
+        s.ranges.extend_from_slice(&other.ranges);
+
+        s.normalize();
+        s
+    }
+
+    /// All versions in both sets.
+    ///
+    /// Specific versions stay specific versions.
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut s = Self::new();
+
+        for a in &self.ranges {
+            for b in &other.ranges {
+                let from = a.from.max(b.from);
+                let to = a.to.min(b.to);
+
+                if from <= to {
+                    s.ranges.push(VersionRange::new(from, to));
+                }
+            }
+        }
+
+        s.versions
+            .extend(self.versions.iter().filter(|a| other.contains(a)));
+        s.versions
+            .extend(other.versions.iter().filter(|a| self.contains(a)));
+
+        s.normalize();
+        s
+    }
+
+    /// All versions in `self` that are not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut ranges = self.ranges.clone();
+
+        let removed = other
+            .ranges
+            .iter()
+            .copied()
+            .chain(other.versions.iter().map(|a| VersionRange::new(*a, *a)));
+        for b in removed {
+            let mut new_ranges = Vec::with_capacity(ranges.len() + 1);
+
+            for a in ranges {
+                if a.to < b.from || a.from > b.to {
+                    new_ranges.push(a);
+                    continue;
+                }
+
+                if a.from < b.from {
+                    if let Some(to) = predecessor(&b.from) {
+                        new_ranges.push(VersionRange::new(a.from, to));
+                    }
+                }
+                if a.to > b.to {
+                    if let Some(from) = successor(&b.to) {
+                        new_ranges.push(VersionRange::new(from, a.to));
+                    }
+                }
+            }
+
+            ranges = new_ranges;
+        }
+
+        let mut s = Self {
+            versions: self
+                .versions
+                .iter()
+                .filter(|a| !other.contains(a))
+                .copied()
+                .collect(),
+            ranges,
+        };
+        s.normalize();
+        s
+    }
+
+    /// Returns true if every version in `self` is also in `other`.
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.difference(other).is_empty()
+    }
+
+    /// Returns true if the sets have at least one version in common.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        !self.intersection(other).is_empty()
+    }
+
+    /// Specific versions, sorted.
+    pub fn versions(&self) -> impl Iterator<Item = Version> + '_ {
+        self.versions.iter().copied()
+    }
+
+    /// Version ranges, sorted.
+    pub fn ranges(&self) -> impl Iterator<Item = VersionRange> + '_ {
+        self.ranges.iter().copied()
+    }
+
+    /// Specific versions as ranges that only cover a single version together with the ranges, sorted.
+    pub fn intervals(&self) -> Vec<VersionRange> {
+        let mut intervals: Vec<VersionRange> = self
+            .versions
+            .iter()
+            .map(|a| VersionRange::new(*a, *a))
+            .chain(self.ranges.iter().copied())
+            .collect();
+        intervals.sort();
+
+        intervals
+    }
+
+    /// Lowest version in the set.
+    pub fn first(&self) -> Option<Version> {
+        let version = self.versions.iter().next().copied();
+        let range = self.ranges.first().map(|a| a.from);
+
+        version.into_iter().chain(range).min()
+    }
+
+    /// Highest version in the set.
+    pub fn last(&self) -> Option<Version> {
+        let version = self.versions.iter().next_back().copied();
+        let range = self.ranges.last().map(|a| a.to);
+
+        version.into_iter().chain(range).max()
+    }
+
+    fn normalize(&mut self) {
+        self.ranges.sort();
+
+        let mut ranges: Vec<VersionRange> = Vec::with_capacity(self.ranges.len());
+        for range in &self.ranges {
+            if let Some(last) = ranges.last_mut() {
+                let adjacent = successor(&last.to) == Some(range.from);
+
+                if range.from <= last.to || adjacent {
+                    last.to = last.to.max(range.to);
+                    continue;
+                }
+            }
+
+            ranges.push(*range);
+        }
+
+        self.ranges = ranges;
+
+        let ranges = &self.ranges;
+        self.versions
+            .retain(|v| !ranges.iter().any(|a| a.within_range(v)));
+    }
+}
+
+impl Ord for VersionSet {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.intervals()
+            .cmp(&other.intervals())
+            .then_with(|| self.versions.cmp(&other.versions))
+    }
+}
+
+impl PartialOrd for VersionSet {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<Version> for VersionSet {
+    fn from(v: Version) -> Self {
+        let mut s = Self::new();
+        s.insert(v);
+        s
+    }
+}
+
+impl From<VersionRange> for VersionSet {
+    fn from(v: VersionRange) -> Self {
+        let mut s = Self::new();
+        s.insert_range(v);
+        s
+    }
+}
+
+fn successor(v: &Version) -> Option<Version> {
+    if let Some(build) = v.build.checked_add(1) {
+        return Some(Version::new(v.major, v.minor, v.patch, build));
+    }
+    if let Some(patch) = v.patch.checked_add(1) {
+        return Some(Version::new(v.major, v.minor, patch, 0));
+    }
+    if let Some(minor) = v.minor.checked_add(1) {
+        return Some(Version::new(v.major, minor, 0, 0));
+    }
+
+    v.major
+        .checked_add(1)
+        .map(|major| Version::new(major, 0, 0, 0))
+}
+
+fn predecessor(v: &Version) -> Option<Version> {
+    if let Some(build) = v.build.checked_sub(1) {
+        return Some(Version::new(v.major, v.minor, v.patch, build));
+    }
+    if let Some(patch) = v.patch.checked_sub(1) {
+        return Some(Version::new(v.major, v.minor, patch, u16::MAX));
+    }
+    if let Some(minor) = v.minor.checked_sub(1) {
+        return Some(Version::new(v.major, minor, u8::MAX, u16::MAX));
+    }
+
+    v.major
+        .checked_sub(1)
+        .map(|major| Version::new(major, u8::MAX, u8::MAX, u16::MAX))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Version, VersionRange, VersionSet};
+
+    const fn v(build: u16) -> Version {
+        Version::new(3, 3, 5, build)
+    }
+
+    fn range(from: u16, to: u16) -> VersionSet {
+        VersionSet::from(VersionRange::new(v(from), v(to)))
+    }
+
+    #[test]
+    fn set_algebra() {
+        let a = range(10, 20).union(&VersionSet::from(v(30)));
+        let b = range(15, 30);
+
+        assert_eq!(a.intersection(&b), range(15, 20).union(&v(30).into()));
+        assert_eq!(a.difference(&b), range(10, 14));
+        assert_eq!(b.difference(&a), range(21, 29));
+        assert_eq!(a.union(&b), range(10, 30));
+
+        assert!(range(12, 13).is_subset(&a));
+        assert!(!b.is_subset(&a));
+        assert!(a.overlaps(&b));
+        assert!(!range(21, 29).overlaps(&a));
+
+        assert_eq!(a.versions().collect::<Vec<_>>(), vec![v(30)]);
+        assert_eq!(
+            a.ranges().collect::<Vec<_>>(),
+            vec![VersionRange::new(v(10), v(20))]
+        );
+    }
+
+    #[test]
+    fn normalization() {
+        let mut s = VersionSet::new();
+        s.insert(v(5));
+        s.insert(v(6));
+        s.insert_range(VersionRange::new(v(1), v(5)));
+        s.insert_range(VersionRange::new(v(9), v(8)));
+
+        assert_ne!(s, range(1, 6));
+        assert!(s.same_versions(&range(1, 6)));
+        assert_eq!(s.versions().collect::<Vec<_>>(), vec![v(6)]);
+        assert!(s.contains(&v(3)));
+        assert!(!s.contains(&v(7)));
+
+        let wrap = VersionSet::from(VersionRange::new(
+            Version::new(3, 3, 4, 0),
+            Version::new(3, 3, 4, u16::MAX),
+        ))
+        .union(&VersionRange::new(Version::new(3, 3, 5, 0), Version::new(3, 3, 5, 1)).into());
+        assert_eq!(wrap.intervals().len(), 1);
+    }
+
+    #[test]
+    fn adjacent_builds_stay_specific() {
+        let builds = VersionSet::from(v(1)).union(&v(2).into());
+        assert_eq!(builds.versions().collect::<Vec<_>>(), vec![v(1), v(2)]);
+        assert_eq!(builds.ranges().count(), 0);
+        assert_ne!(builds, range(1, 2));
+        assert!(builds.same_versions(&range(1, 2)));
+        assert_eq!(builds.intervals().len(), 2);
+    }
+}
@@ -0,0 +1,106 @@
+use crate::{DbdFile, Definition, Entry, Type};
+
+/// Callbacks for walking a [`DbdFile`] with [`DbdFile::walk`].
+///
+/// All methods do nothing by default so only the relevant ones need to be implemented.
+///
+/// The order of calls is:
+///
+/// 1. [`Visitor::visit_file`].
+/// 2. For every definition, [`Visitor::visit_definition`].
+/// 3. For every entry in the definition, [`Visitor::visit_entry`]
+///    followed by [`Visitor::visit_type`] for the type and every type nested inside it.
+/// 4. [`Visitor::leave_definition`] after all entries of the definition.
+/// 5. [`Visitor::leave_file`] after all definitions.
+pub trait Visitor {
+    /// Called before anything else in the file.
+    fn visit_file(&mut self, _file: &DbdFile) {}
+    /// Called after everything else in the file.
+    fn leave_file(&mut self, _file: &DbdFile) {}
+
+    /// Called before the entries of the definition.
+    fn visit_definition(&mut self, _definition: &Definition) {}
+    /// Called after the entries of the definition.
+    fn leave_definition(&mut self, _definition: &Definition) {}
+
+    /// Called for every entry in a definition.
+    fn visit_entry(&mut self, _definition: &Definition, _entry: &Entry) {}
+
+    /// Called for the type of an entry and then for every type nested inside it,
+    /// for example the inner type of [`Type::Array`] and [`Type::ForeignKey`].
+    fn visit_type(&mut self, _entry: &Entry, _ty: &Type) {}
+}
+
+impl DbdFile {
+    /// Walk the file and all definitions, entries and types with `visitor`.
+    ///
+    /// See [`Visitor`] for the order of calls.
+    pub fn walk<V: Visitor + ?Sized>(&self, visitor: &mut V) {
+        visitor.visit_file(self);
+
+        for definition in &self.definitions {
+            visitor.visit_definition(definition);
+
+            for entry in &definition.entries {
+                visitor.visit_entry(definition, entry);
+
+                let mut ty = &entry.ty;
+                loop {
+                    visitor.visit_type(entry, ty);
+
+                    ty = match ty {
+                        Type::Array { ty, .. } | Type::ForeignKey { ty, .. } => ty,
+                        _ => break,
+                    };
+                }
+            }
+
+            visitor.leave_definition(definition);
+        }
+
+        visitor.leave_file(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{load_file_from_string, Entry, Type, Visitor};
+
+    #[derive(Default)]
+    struct Counter {
+        entries: usize,
+        types: usize,
+        foreign_keys: usize,
+    }
+
+    impl Visitor for Counter {
+        fn visit_entry(&mut self, _definition: &crate::Definition, _entry: &Entry) {
+            self.entries += 1;
+        }
+
+        fn visit_type(&mut self, _entry: &Entry, ty: &Type) {
+            self.types += 1;
+            if let Type::ForeignKey { .. } = ty {
+                self.foreign_keys += 1;
+            }
+        }
+    }
+
+    #[test]
+    fn walk() {
+        let file = load_file_from_string(
+            "COLUMNS\nint ID\nint<Map::ID> MapID\n\nBUILD 1.12.1.5875\n$id$ID<32>\nMapID<32>[2]\n\nBUILD 3.3.5.12340\n$id$ID<32>\n",
+            "AreaTable.dbd",
+        )
+        .unwrap()
+        .into_proper()
+        .unwrap();
+
+        let mut counter = Counter::default();
+        file.walk(&mut counter);
+
+        assert_eq!(counter.entries, 3);
+        assert_eq!(counter.types, 5);
+        assert_eq!(counter.foreign_keys, 1);
+    }
+}
@@ -1,14 +1,65 @@
 use crate::writer::Writer;
-use crate::RawDbdFile;
+use crate::{RawDbdFile, RawDefinition, Version, VersionRange, VersionSet};
+
+/// Options for [`write_to_file_with_options`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct WriteOptions {
+    /// Collapse runs of specific builds into a single `BUILD` range
+    /// when no other definition in the file covers a version between them.
+    ///
+    /// This changes which unlisted builds a definition is valid for.
+    /// Enabled by default.
+    pub collapse_builds: bool,
+    /// Maximum amount of specific builds written on a single `BUILD` line.
+    ///
+    /// `None` writes all specific builds on the same line.
+    pub builds_per_line: Option<usize>,
+}
+
+impl WriteOptions {
+    /// Creates the default options,
+    /// specific builds are collapsed into ranges and the remaining builds are all written on the same line.
+    pub const fn new() -> Self {
+        Self {
+            collapse_builds: true,
+            builds_per_line: None,
+        }
+    }
+}
+
+impl Default for WriteOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 /// Write the [`RawDbdFile`] to a string in the `.dbd` format.
 ///
 /// Versions will be written deduplicated.
+/// Specific builds covered by a range are removed and overlapping or adjacent ranges are merged.
+/// Runs of specific builds are collapsed into ranges, see [`WriteOptions::collapse_builds`].
+///
+/// Columns are written sorted by name.
+/// `COMMENT` lines and unknown annotations are written as they were parsed,
+/// so files can be parsed, edited and written without going through [`DbdFile`](crate::DbdFile).
+///
+/// [`RawDbdFile`] also implements [`Display`](std::fmt::Display) through this function.
 pub fn write_to_file(file: &RawDbdFile) -> String {
+    write_to_file_with_options(file, &WriteOptions::new())
+}
+
+/// Write the [`RawDbdFile`] to a string in the `.dbd` format using `options`.
+///
+/// Versions will be written deduplicated.
+/// Specific builds covered by a range are removed and overlapping or adjacent ranges are merged.
+pub fn write_to_file_with_options(file: &RawDbdFile, options: &WriteOptions) -> String {
     let mut s = Writer::new();
     s.wln("COLUMNS");
 
-    for column in file.columns.values() {
+    let mut columns: Vec<_> = file.columns.values().collect();
+    columns.sort_by(|a, b| a.name.cmp(&b.name));
+
+    for column in columns {
         let foreign_key = if let Some(foreign_key) = &column.foreign_key {
             foreign_key.to_string()
         } else {
@@ -34,10 +85,14 @@ pub fn write_to_file(file: &RawDbdFile) -> String {
     s.newline();
 
     for definition in &file.definitions {
-        if !definition.versions.is_empty() {
+        let versions = compact_versions(file, definition, options);
+
+        let builds: Vec<Version> = versions.versions().collect();
+        let builds_per_line = options.builds_per_line.unwrap_or(usize::MAX).max(1);
+        for line in builds.chunks(builds_per_line) {
             s.w("BUILD ");
 
-            for (i, v) in definition.versions.iter().enumerate() {
+            for (i, v) in line.iter().enumerate() {
                 if i != 0 {
                     s.w(", ");
                 }
@@ -47,50 +102,43 @@ pub fn write_to_file(file: &RawDbdFile) -> String {
             s.newline();
         }
 
-        for v in &definition.version_ranges {
+        for v in versions.ranges() {
             s.wln(format!("BUILD {}-{}", v.from, v.to));
         }
 
         for l in &definition.layouts {
-            s.wln(format!("LAYOUT {}", l));
+            s.wln(format!("LAYOUT {:08X}", l.inner));
+        }
+
+        for comment in &definition.comments {
+            s.wln(format!("COMMENT {}", comment));
         }
 
         for entry in &definition.entries {
             let name = entry.name.as_str();
 
-            let tags = if entry.has_any_tag() {
-                let mut s = "$".to_string();
-                if entry.primary_key {
-                    s += "id";
-                }
-
-                if !entry.inline {
-                    if entry.primary_key {
-                        s += ",";
-                    }
-
-                    s += "noninline";
-                }
-
-                if entry.relation {
-                    if entry.primary_key || !entry.inline {
-                        s += ",";
-                    }
-
-                    s += "relation";
-                }
-
-                s += "$";
+            let mut tags = Vec::new();
+            if entry.primary_key {
+                tags.push("id");
+            }
+            if !entry.inline {
+                tags.push("noninline");
+            }
+            if entry.relation {
+                tags.push("relation");
+            }
+            tags.extend(entry.unknown_tags.iter().map(|a| a.as_str()));
 
-                s
-            } else {
+            let tags = if tags.is_empty() {
                 "".to_string()
+            } else {
+                format!("${}$", tags.join(","))
             };
 
             let integer_width = if let Some(width) = entry.integer_width {
                 let unsigned = if entry.unsigned { "u" } else { "" };
 
-                format!("<{width}{unsigned}>")
+                format!("<{unsigned}{width}>")
             } else {
                 "".to_string()
             };
@@ -101,7 +149,12 @@ pub fn write_to_file(file: &RawDbdFile) -> String {
                 "".to_string()
             };
 
-            s.wln(format!("{tags}{name}{integer_width}{array_size}"));
+            s.w(format!("{tags}{name}{integer_width}{array_size}"));
+            if let Some(comment) = &entry.comment {
+                s.wln(format!(" // {}", comment));
+            } else {
+                s.newline();
+            }
         }
 
         s.newline();
@@ -109,3 +162,125 @@ pub fn write_to_file(file: &RawDbdFile) -> String {
 
     s.inner
 }
+
+fn compact_versions(
+    file: &RawDbdFile,
+    definition: &RawDefinition,
+    options: &WriteOptions,
+) -> VersionSet {
+    let versions = VersionSet::from_parts(&definition.versions, &definition.version_ranges);
+    if !options.collapse_builds {
+        return versions;
+    }
+
+    let others = file
+        .definitions
+        .iter()
+        .filter(|a| !std::ptr::eq(*a, definition))
+        .fold(VersionSet::new(), |acc, a| {
+            acc.union(&VersionSet::from_parts(&a.versions, &a.version_ranges))
+        });
+
+    let mut collapsed = versions.clone();
+    for w in versions.intervals().windows(2) {
+        let gap = VersionSet::from(VersionRange::new(w[0].to, w[1].from)).difference(&versions);
+
+        if !gap.overlaps(&others) {
+            collapsed = collapsed.union(&VersionRange::new(w[0].from, w[1].to).into());
+        }
+    }
+
+    collapsed
+}
+
+impl std::fmt::Display for RawDbdFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&write_to_file(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{load_file_from_string, write_to_file, write_to_file_with_options, WriteOptions};
+
+    const CONTENTS: &str = "COLUMNS
+int ID
+
+BUILD 1.12.1.5875, 1.12.1.5876, 1.12.2.6005
+BUILD 3.3.5.12340, 3.3.5.12341
+BUILD 3.3.5.12300-3.3.5.12340
+$id$ID<32>
+
+BUILD 2.4.3.8606
+$id$ID<32>
+";
+
+    #[test]
+    fn compact_builds() {
+        let file = load_file_from_string(CONTENTS, "Map.dbd").unwrap();
+
+        let s = write_to_file(&file);
+        assert!(s.contains("BUILD 1.12.1.5875-1.12.2.6005\nBUILD 3.3.5.12300-3.3.5.12341\n"));
+
+        let s = write_to_file_with_options(
+            &file,
+            &WriteOptions {
+                collapse_builds: false,
+                builds_per_line: None,
+            },
+        );
+        assert!(s.contains(
+            "BUILD 1.12.1.5875, 1.12.1.5876, 1.12.2.6005, 3.3.5.12341\nBUILD 3.3.5.12300-3.3.5.12340\n"
+        ));
+
+        let file = load_file_from_string("COLUMNS\nint ID\n\nBUILD 1.12.1.5875, 1.12.2.6005, 2.4.3.8606\nID\n\nBUILD 2.0.0.6080\nID\n", "Map.dbd").unwrap();
+        let s = write_to_file_with_options(
+            &file,
+            &WriteOptions {
+                collapse_builds: true,
+                builds_per_line: Some(1),
+            },
+        );
+        assert!(s.contains("BUILD 2.4.3.8606\nBUILD 1.12.1.5875-1.12.2.6005\n"));
+    }
+
+    #[test]
+    fn raw_constructs() {
+        const CONTENTS: &str = "COLUMNS
+int ID
+int Unknown // column
+
+BUILD 1.12.1.5875
+COMMENT Only client side
+$id,future$ID<32>
+Unknown<u8> // entry
+";
+        let file = load_file_from_string(CONTENTS, "Map.dbd").unwrap();
+        assert_eq!(file.definitions[0].entries[0].unknown_tags, vec!["future"]);
+
+        let s = file.to_string();
+        assert!(s.contains("COMMENT Only client side\n$id,future$ID<32>\nUnknown<u8> // entry\n"));
+        assert_eq!(load_file_from_string(&s, "Map.dbd").unwrap(), file);
+    }
+
+    #[test]
+    fn layouts_widths_and_comments() {
+        const CONTENTS: &str = "COLUMNS
+int ID
+int Flags
+int Unsigned
+
+LAYOUT 0000ABCD, 0B14E4AA
+BUILD 3.3.5.12340
+$id$ID<32>
+Flags<16> // signed
+Unsigned<u64>[2] // unsigned array
+";
+        let file = load_file_from_string(CONTENTS, "Map.dbd").unwrap();
+
+        let s = write_to_file(&file);
+        assert!(s.contains("LAYOUT 0000ABCD\nLAYOUT 0B14E4AA\n"));
+        assert!(s.contains("\nFlags<16> // signed\nUnsigned<u64>[2] // unsigned array\n"));
+        assert_eq!(load_file_from_string(&s, "Map.dbd").unwrap(), file);
+    }
+}